@@ -0,0 +1,441 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write as _},
+    ops::RangeInclusive,
+    path::Path,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use serde::Serialize;
+
+use crate::{
+    block::{BlockAPI as _, BlockRef, BlockTimestampMs, Round, VerifiedBlock, GENESIS_ROUND},
+    commit::{CommitAPI as _, CommitIndex, CommitRange, CommittedSubDag, TrustedCommit},
+    storage::{rocksdb_store::RocksDBStore, Store},
+};
+
+/// Read-only access to the blocks and commits in the consensus store of an authority, for
+/// debugging and visualizing the DAG.
+pub struct DagInspector {
+    store: Arc<dyn Store>,
+}
+
+/// Identifies a block, with the full base64 encoded digest.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct BlockId {
+    pub round: Round,
+    pub author: usize,
+    pub digest: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockSummary {
+    pub id: BlockId,
+    pub timestamp_ms: BlockTimestampMs,
+    pub ancestors: Vec<BlockId>,
+    pub transactions: usize,
+    /// Indices of the commits the block votes for.
+    pub commit_votes: Vec<CommitIndex>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CommitSummary {
+    pub index: CommitIndex,
+    pub digest: String,
+    pub timestamp_ms: BlockTimestampMs,
+    pub leader: BlockId,
+    /// Blocks of the committed sub-dag, in the linearized order.
+    pub blocks: Vec<BlockId>,
+    /// Blocks voting for the commit.
+    pub votes: Vec<BlockId>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MissingAncestor {
+    pub block: BlockId,
+    pub ancestor: BlockId,
+}
+
+/// The blocks in a range of rounds, with the commits led by blocks in these rounds.
+#[derive(Clone, Debug, Serialize)]
+pub struct DagExport {
+    pub blocks: Vec<BlockSummary>,
+    pub commits: Vec<CommitSummary>,
+}
+
+impl DagInspector {
+    /// Opens the consensus store at `path` as a RocksDB secondary instance, so that the store of
+    /// a running authority can be inspected.
+    pub fn open(path: &Path, secondary_path: Option<&Path>) -> anyhow::Result<Self> {
+        let store = RocksDBStore::new_secondary(path, secondary_path)?;
+        Ok(Self::new(Arc::new(store)))
+    }
+
+    pub(crate) fn new(store: Arc<dyn Store>) -> Self {
+        Self { store }
+    }
+
+    /// Returns the blocks in `rounds`, optionally only those proposed by `author`, ordered by
+    /// round and author.
+    pub fn blocks(
+        &self,
+        rounds: RangeInclusive<Round>,
+        author: Option<usize>,
+    ) -> anyhow::Result<Vec<BlockSummary>> {
+        Ok(self
+            .store
+            .scan_blocks_by_round(*rounds.start(), *rounds.end())?
+            .iter()
+            .filter(|block| author.map_or(true, |author| block.author().value() == author))
+            .map(BlockSummary::new)
+            .collect())
+    }
+
+    /// Returns the serialized blocks in `rounds`, ordered by round and author, e.g. to replay them.
+    pub fn serialized_blocks(&self, rounds: RangeInclusive<Round>) -> anyhow::Result<Vec<Bytes>> {
+        Ok(self
+            .store
+            .scan_blocks_by_round(*rounds.start(), *rounds.end())?
+            .iter()
+            .map(|block| block.serialized().clone())
+            .collect())
+    }
+
+    /// Returns the index of the last commit, or 0 if nothing has been committed yet.
+    pub fn last_commit_index(&self) -> anyhow::Result<CommitIndex> {
+        Ok(self
+            .store
+            .read_last_commit()?
+            .map_or(0, |commit| commit.index()))
+    }
+
+    /// Returns the commits with indices in `indices`.
+    pub fn commits(
+        &self,
+        indices: RangeInclusive<CommitIndex>,
+    ) -> anyhow::Result<Vec<CommitSummary>> {
+        self.store
+            .scan_commits(CommitRange::new(indices))?
+            .iter()
+            .map(|commit| self.commit_summary(commit))
+            .collect()
+    }
+
+    /// Returns the commits whose leaders are in `rounds`.
+    pub fn commits_in_rounds(
+        &self,
+        rounds: RangeInclusive<Round>,
+    ) -> anyhow::Result<Vec<CommitSummary>> {
+        // Leader rounds increase with commit indices, so the first and last commits in the range
+        // can be found with binary searches.
+        let last_index = self.last_commit_index()?;
+        let start = self.first_commit_from_round(*rounds.start(), last_index)?;
+        let end = match rounds.end().checked_add(1) {
+            Some(round) => self.first_commit_from_round(round, last_index)?,
+            None => last_index + 1,
+        };
+        if start >= end {
+            return Ok(vec![]);
+        }
+        self.commits(start..=end - 1)
+    }
+
+    /// Returns the ancestors of the blocks in `rounds` that are not in the store. Genesis
+    /// ancestors are never stored, and are not reported.
+    pub fn missing_ancestors(
+        &self,
+        rounds: RangeInclusive<Round>,
+    ) -> anyhow::Result<Vec<MissingAncestor>> {
+        let blocks = self
+            .store
+            .scan_blocks_by_round(*rounds.start(), *rounds.end())?;
+        let ancestors: Vec<BlockRef> = blocks
+            .iter()
+            .flat_map(|block| block.ancestors())
+            .filter(|ancestor| ancestor.round > GENESIS_ROUND)
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let found = self.store.contains_blocks(&ancestors)?;
+        let missing: BTreeSet<BlockRef> = ancestors
+            .into_iter()
+            .zip(found)
+            .filter_map(|(ancestor, found)| (!found).then_some(ancestor))
+            .collect();
+
+        Ok(blocks
+            .iter()
+            .flat_map(|block| {
+                block
+                    .ancestors()
+                    .iter()
+                    .filter(|ancestor| missing.contains(ancestor))
+                    .map(|ancestor| MissingAncestor {
+                        block: block.reference().into(),
+                        ancestor: (*ancestor).into(),
+                    })
+            })
+            .collect())
+    }
+
+    /// Exports the blocks in `rounds` and the commits they lead.
+    pub fn export(&self, rounds: RangeInclusive<Round>) -> anyhow::Result<DagExport> {
+        Ok(DagExport {
+            blocks: self.blocks(rounds.clone(), None)?,
+            commits: self.commits_in_rounds(rounds)?,
+        })
+    }
+
+    fn commit_summary(&self, commit: &TrustedCommit) -> anyhow::Result<CommitSummary> {
+        let votes = self.store.read_commit_votes(commit.index())?;
+        Ok(CommitSummary {
+            index: commit.index(),
+            digest: format!("{:?}", commit.digest()),
+            timestamp_ms: commit.timestamp_ms(),
+            leader: commit.leader().into(),
+            blocks: commit.blocks().iter().map(|r| (*r).into()).collect(),
+            votes: votes.into_iter().map(BlockId::from).collect(),
+        })
+    }
+
+    /// The lowest commit index in `1..=last_index` with a leader at or after `round`, or
+    /// `last_index + 1` if there is none.
+    fn first_commit_from_round(
+        &self,
+        round: Round,
+        last_index: CommitIndex,
+    ) -> anyhow::Result<CommitIndex> {
+        let (mut low, mut high) = (1, last_index + 1);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let commit = self
+                .store
+                .scan_commits(CommitRange::new(mid..=mid))?
+                .pop()
+                .ok_or_else(|| anyhow::anyhow!("Commit {mid} is missing from the store"))?;
+            if commit.leader().round < round {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+}
+
+impl From<BlockRef> for BlockId {
+    fn from(block_ref: BlockRef) -> Self {
+        Self {
+            round: block_ref.round,
+            author: block_ref.author.value(),
+            digest: format!("{:?}", block_ref.digest),
+        }
+    }
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "B{}({},{})",
+            self.round,
+            self.author,
+            self.digest.get(0..4).unwrap_or(&self.digest)
+        )
+    }
+}
+
+impl BlockSummary {
+    fn new(block: &VerifiedBlock) -> Self {
+        Self {
+            id: block.reference().into(),
+            timestamp_ms: block.timestamp_ms(),
+            ancestors: block.ancestors().iter().map(|r| (*r).into()).collect(),
+            transactions: block.transactions().len(),
+            commit_votes: block.commit_votes().iter().map(|vote| vote.index).collect(),
+        }
+    }
+}
+
+impl CommitSummary {
+    /// Summarizes a commit as it is sent to the consumer. Votes are not known at that point.
+    pub(crate) fn from_sub_dag(sub_dag: &CommittedSubDag) -> Self {
+        Self {
+            index: sub_dag.commit_ref.index,
+            digest: format!("{:?}", sub_dag.commit_ref.digest),
+            timestamp_ms: sub_dag.timestamp_ms,
+            leader: sub_dag.leader.into(),
+            blocks: sub_dag
+                .blocks
+                .iter()
+                .map(|block| block.reference().into())
+                .collect(),
+            votes: vec![],
+        }
+    }
+}
+
+impl DagExport {
+    /// Renders the DAG in the Graphviz DOT format, with one rank per round, edges from blocks to
+    /// their ancestors within the exported rounds, and committed leaders highlighted.
+    pub fn to_dot(&self) -> String {
+        let leaders: BTreeMap<&BlockId, CommitIndex> = self
+            .commits
+            .iter()
+            .map(|commit| (&commit.leader, commit.index))
+            .collect();
+        let exported: BTreeSet<&BlockId> = self.blocks.iter().map(|block| &block.id).collect();
+        let node = |id: &BlockId| format!("\"{}/{}/{}\"", id.round, id.author, id.digest);
+
+        let mut dot = String::from("digraph dag {\n  rankdir=BT;\n  node [shape=box];\n");
+        let mut rounds: BTreeMap<Round, Vec<&BlockSummary>> = BTreeMap::new();
+        for block in &self.blocks {
+            rounds.entry(block.id.round).or_default().push(block);
+        }
+        for (round, blocks) in rounds {
+            writeln!(dot, "  subgraph round_{round} {{\n    rank=same;").unwrap();
+            for block in blocks {
+                let mut label = format!("{}\\n{} txs", block.id, block.transactions);
+                let mut style = "";
+                if let Some(index) = leaders.get(&block.id) {
+                    write!(label, "\\nleader of commit {index}").unwrap();
+                    style = ", style=filled, fillcolor=gold";
+                }
+                writeln!(dot, "    {} [label=\"{label}\"{style}];", node(&block.id)).unwrap();
+            }
+            dot.push_str("  }\n");
+        }
+        for block in &self.blocks {
+            for ancestor in block.ancestors.iter().filter(|a| exported.contains(a)) {
+                writeln!(dot, "  {} -> {};", node(&block.id), node(ancestor)).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        storage::{mem_store::MemStore, WriteBatch},
+        test_dag_builder::DagBuilder,
+    };
+
+    fn build_dag(num_rounds: Round) -> (DagBuilder, Arc<MemStore>, Vec<TrustedCommit>) {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let mut dag_builder = DagBuilder::new(context);
+        dag_builder.layers(1..=num_rounds).build();
+
+        let mut commits = vec![];
+        let mut last_committed_rounds = vec![0; 4];
+        for (index, leader) in dag_builder
+            .leader_blocks(1..=num_rounds)
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let (sub_dag, commit) = dag_builder.get_sub_dag_and_commit(
+                leader,
+                last_committed_rounds.clone(),
+                index as CommitIndex + 1,
+            );
+            for block in &sub_dag.blocks {
+                let round = &mut last_committed_rounds[block.author().value()];
+                *round = (*round).max(block.round());
+            }
+            commits.push(commit);
+        }
+
+        let store = Arc::new(MemStore::new());
+        store
+            .write(
+                WriteBatch::default()
+                    .blocks(dag_builder.blocks(1..=num_rounds))
+                    .commits(commits.clone()),
+            )
+            .unwrap();
+        (dag_builder, store, commits)
+    }
+
+    #[tokio::test]
+    async fn test_blocks_and_commits() {
+        let (dag_builder, store, commits) = build_dag(5);
+        let inspector = DagInspector::new(store);
+
+        let blocks = inspector.blocks(2..=3, None).unwrap();
+        assert_eq!(blocks.len(), 8);
+        assert!(blocks.windows(2).all(|w| w[0].id < w[1].id));
+        let blocks = inspector.blocks(2..=3, Some(1)).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks.iter().all(|block| block.id.author == 1));
+        assert_eq!(blocks[0].ancestors.len(), 4);
+
+        assert_eq!(inspector.last_commit_index().unwrap(), 5);
+        let summaries = inspector.commits(2..=3).unwrap();
+        assert_eq!(summaries.len(), 2);
+        for (summary, commit) in summaries.iter().zip(&commits[1..3]) {
+            assert_eq!(summary.index, commit.index());
+            assert_eq!(summary.leader, BlockId::from(commit.leader()));
+            assert_eq!(
+                summary.blocks,
+                commit
+                    .blocks()
+                    .iter()
+                    .map(|r| BlockId::from(*r))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        // One leader per round.
+        let summaries = inspector.commits_in_rounds(2..=4).unwrap();
+        assert_eq!(
+            summaries.iter().map(|c| c.index).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+        assert!(inspector.commits_in_rounds(6..=10).unwrap().is_empty());
+
+        let export = inspector.export(4..=5).unwrap();
+        assert_eq!(export.blocks.len(), 8);
+        assert_eq!(export.commits.len(), 2);
+        let dot = export.to_dot();
+        let leader = dag_builder.leader_block(5).unwrap().reference();
+        assert!(dot.contains(&format!(
+            "\"5/{}/{:?}\"",
+            leader.author.value(),
+            leader.digest
+        )));
+        assert!(dot.contains("leader of commit 5"));
+        // Edges from round 5 to round 4 only.
+        assert_eq!(dot.matches(" -> ").count(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_missing_ancestors() {
+        let (dag_builder, _, _) = build_dag(3);
+        let store = Arc::new(MemStore::new());
+        // Leave out round 2.
+        let blocks = dag_builder
+            .blocks(1..=3)
+            .into_iter()
+            .filter(|block| block.round() != 2)
+            .collect();
+        store.write(WriteBatch::default().blocks(blocks)).unwrap();
+        let inspector = DagInspector::new(store);
+
+        // Genesis ancestors of round 1 are not reported.
+        assert!(inspector.missing_ancestors(1..=1).unwrap().is_empty());
+        let missing = inspector.missing_ancestors(1..=3).unwrap();
+        assert_eq!(missing.len(), 16);
+        assert!(missing
+            .iter()
+            .all(|m| m.block.round == 3 && m.ancestor.round == 2));
+    }
+}
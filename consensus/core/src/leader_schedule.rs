@@ -16,10 +16,7 @@ use crate::{
     context::Context,
     dag_state::DagState,
     leader_scoring::{ReputationScoreCalculator, ReputationScores},
-    leader_scoring_strategy::{
-        CertificateScoringStrategy, CertifiedVoteScoringStrategyV1, CertifiedVoteScoringStrategyV2,
-        ScoringStrategy, VoteScoringStrategy,
-    },
+    leader_scoring_strategy::{ScoringStrategy, ScoringStrategyKind},
     CommitIndex, Round,
 };
 
@@ -39,9 +36,9 @@ impl LeaderSchedule {
     /// number of committed sub dags.
     /// TODO: move this to protocol config
     #[cfg(not(msim))]
-    const CONSENSUS_COMMITS_PER_SCHEDULE: u64 = 300;
+    pub(crate) const CONSENSUS_COMMITS_PER_SCHEDULE: u64 = 300;
     #[cfg(msim)]
-    const CONSENSUS_COMMITS_PER_SCHEDULE: u64 = 10;
+    pub(crate) const CONSENSUS_COMMITS_PER_SCHEDULE: u64 = 10;
 
     pub(crate) fn new(context: Arc<Context>, leader_swap_table: LeaderSwapTable) -> Self {
         Self {
@@ -52,7 +49,6 @@ impl LeaderSchedule {
         }
     }
 
-    #[cfg(test)]
     pub(crate) fn with_num_commits_per_schedule(mut self, num_commits_per_schedule: u64) -> Self {
         self.num_commits_per_schedule = num_commits_per_schedule;
         self
@@ -81,24 +77,21 @@ impl LeaderSchedule {
         Self::new(context, leader_swap_table)
     }
 
+    /// Uses `scoring_strategy` instead of the strategy chosen from the environment, e.g. to
+    /// compare strategies offline.
+    pub(crate) fn with_scoring_strategy(mut self, scoring_strategy: ScoringStrategyKind) -> Self {
+        self.scoring_strategy = scoring_strategy.strategy();
+        self
+    }
+
     // TODO: remove this once scoring strategy is finalized
     fn choose_scoring_strategy() -> Arc<dyn ScoringStrategy> {
-        if let Ok(scoring_strategy) = std::env::var("CONSENSUS_SCORING_STRATEGY") {
-            tracing::info!(
-                "Using scoring strategy {scoring_strategy} for ReputationScoreCalculator"
-            );
-            let scoring_strategy: Arc<dyn ScoringStrategy> = match scoring_strategy.as_str() {
-                "vote" => Arc::new(VoteScoringStrategy {}),
-                "certified_vote_v1" => Arc::new(CertifiedVoteScoringStrategyV1 {}),
-                "certified_vote_v2" => Arc::new(CertifiedVoteScoringStrategyV2 {}),
-                "certificate" => Arc::new(CertificateScoringStrategy {}),
-                _ => Arc::new(VoteScoringStrategy {}),
-            };
-            scoring_strategy
-        } else {
-            tracing::info!("Using scoring strategy vote for ReputationScoreCalculator");
-            Arc::new(VoteScoringStrategy {})
-        }
+        let scoring_strategy = std::env::var("CONSENSUS_SCORING_STRATEGY")
+            .ok()
+            .and_then(|scoring_strategy| scoring_strategy.parse::<ScoringStrategyKind>().ok())
+            .unwrap_or_default();
+        tracing::info!("Using scoring strategy {scoring_strategy} for ReputationScoreCalculator");
+        scoring_strategy.strategy()
     }
 
     pub(crate) fn commits_until_leader_schedule_update(
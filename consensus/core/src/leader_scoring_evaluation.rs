@@ -0,0 +1,369 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use bytes::Bytes;
+use consensus_config::Committee;
+use serde::Serialize;
+use sui_protocol_config::ProtocolConfig;
+
+use crate::{
+    block::{BlockAPI, VerifiedBlock},
+    commit::CommitIndex,
+    leader_schedule::{LeaderSchedule, LeaderSwapTable},
+    leader_scoring_strategy::ScoringStrategyKind,
+    replay::{deserialize_blocks, ConsensusReplay},
+    Round,
+};
+
+/// Compares leader scoring strategies offline. The same blocks are replayed through the commit
+/// rule once per strategy, with leader scoring and schedule changes enabled, and the leader
+/// schedules each strategy leads to are measured.
+///
+/// The blocks can be recorded, e.g. read from the consensus store of a validator, or simulated.
+/// Since the blocks are fixed, a strategy only affects which leaders are elected, and hence
+/// which leader slots are skipped and how long committing takes.
+pub struct ScoringEvaluator {
+    committee: Committee,
+    protocol_config: ProtocolConfig,
+    commits_per_schedule: u64,
+}
+
+impl ScoringEvaluator {
+    pub fn new(committee: Committee, mut protocol_config: ProtocolConfig) -> Self {
+        protocol_config.set_mysticeti_leader_scoring_and_schedule_for_testing(true);
+        Self {
+            committee,
+            protocol_config,
+            commits_per_schedule: LeaderSchedule::CONSENSUS_COMMITS_PER_SCHEDULE,
+        }
+    }
+
+    /// Set the number of commits the scores of each leader schedule are calculated from
+    pub fn with_commits_per_schedule(mut self, commits_per_schedule: u64) -> Self {
+        self.commits_per_schedule = commits_per_schedule.max(1);
+        self
+    }
+
+    /// Set the stake, in percent of the total stake, of the authorities that are swapped out of
+    /// the leader schedule, instead of the threshold of the protocol config.
+    pub fn with_bad_nodes_stake_threshold(mut self, threshold: u64) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            threshold <= 33,
+            "The bad nodes stake threshold must be at most 33, got {threshold}"
+        );
+        self.protocol_config
+            .set_consensus_bad_nodes_stake_threshold_for_testing(threshold);
+        Ok(self)
+    }
+
+    /// Evaluates every scoring strategy over the serialized blocks, processed in order.
+    pub fn evaluate_all(
+        &self,
+        serialized_blocks: Vec<Bytes>,
+    ) -> anyhow::Result<Vec<ScoringEvaluation>> {
+        let blocks = deserialize_blocks(serialized_blocks)?;
+        ScoringStrategyKind::ALL
+            .iter()
+            .map(|strategy| self.evaluate_blocks(*strategy, &blocks))
+            .collect()
+    }
+
+    /// Evaluates one scoring strategy over the serialized blocks, processed in order.
+    pub fn evaluate(
+        &self,
+        strategy: ScoringStrategyKind,
+        serialized_blocks: Vec<Bytes>,
+    ) -> anyhow::Result<ScoringEvaluation> {
+        self.evaluate_blocks(strategy, &deserialize_blocks(serialized_blocks)?)
+    }
+
+    fn evaluate_blocks(
+        &self,
+        strategy: ScoringStrategyKind,
+        blocks: &[VerifiedBlock],
+    ) -> anyhow::Result<ScoringEvaluation> {
+        let commits_per_schedule = self.commits_per_schedule;
+        let mut replay = ConsensusReplay::new_with_leader_schedule(
+            self.committee.clone(),
+            0,
+            self.protocol_config.clone(),
+            |schedule| {
+                schedule
+                    .with_scoring_strategy(strategy)
+                    .with_num_commits_per_schedule(commits_per_schedule)
+            },
+        )?;
+
+        let mut evaluation = ScoringEvaluation {
+            strategy,
+            commits: 0,
+            skipped_leader_rounds: 0,
+            leaders_per_authority: vec![0; self.committee.size()],
+            mean_commit_latency_rounds: 0.0,
+            max_commit_latency_rounds: 0,
+            mean_commit_latency_ms: 0.0,
+            schedule_updates: vec![],
+        };
+        let mut leader_rounds = BTreeSet::new();
+        let mut total_latency_rounds = 0_u64;
+        let mut total_latency_ms = 0_u64;
+        let mut highest_round = 0;
+        let mut highest_timestamp_ms = 0;
+        let mut swap_table = replay.leader_swap_table();
+
+        for block in blocks {
+            highest_round = highest_round.max(block.round());
+            highest_timestamp_ms = highest_timestamp_ms.max(block.timestamp_ms());
+            for sub_dag in replay.add_verified_blocks(vec![block.clone()])? {
+                let latency_rounds = highest_round.saturating_sub(sub_dag.leader.round);
+                evaluation.commits += 1;
+                evaluation.leaders_per_authority[sub_dag.leader.author.value()] += 1;
+                evaluation.max_commit_latency_rounds =
+                    evaluation.max_commit_latency_rounds.max(latency_rounds);
+                total_latency_rounds += latency_rounds as u64;
+                total_latency_ms += highest_timestamp_ms.saturating_sub(sub_dag.timestamp_ms);
+                leader_rounds.insert(sub_dag.leader.round);
+            }
+
+            let table = replay.leader_swap_table();
+            if table.reputation_scores.commit_range != swap_table.reputation_scores.commit_range {
+                evaluation
+                    .schedule_updates
+                    .push(ScheduleUpdate::from_swap_table(&table));
+                swap_table = table;
+            }
+        }
+
+        if evaluation.commits > 0 {
+            evaluation.mean_commit_latency_rounds =
+                total_latency_rounds as f64 / evaluation.commits as f64;
+            evaluation.mean_commit_latency_ms = total_latency_ms as f64 / evaluation.commits as f64;
+        }
+        if let Some(last_leader_round) = leader_rounds.last() {
+            evaluation.skipped_leader_rounds = *last_leader_round - leader_rounds.len() as Round;
+        }
+        Ok(evaluation)
+    }
+}
+
+/// How the leader schedule of one scoring strategy performed over a DAG.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScoringEvaluation {
+    pub strategy: ScoringStrategyKind,
+    pub commits: usize,
+    /// Rounds up to the last committed leader without a committed leader
+    pub skipped_leader_rounds: u32,
+    /// Number of commits led by each authority, by authority index
+    pub leaders_per_authority: Vec<usize>,
+    /// Rounds between a leader and the highest round processed when it was committed
+    pub mean_commit_latency_rounds: f64,
+    pub max_commit_latency_rounds: Round,
+    /// Time between a leader's timestamp and the highest timestamp processed when it was
+    /// committed
+    pub mean_commit_latency_ms: f64,
+    /// The leader schedules, in the order the strategy switched to them
+    pub schedule_updates: Vec<ScheduleUpdate>,
+}
+
+/// A leader schedule change: the scores calculated from a range of commits, and the authorities
+/// swapped in and out of the schedule as a result.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScheduleUpdate {
+    pub first_commit: CommitIndex,
+    pub last_commit: CommitIndex,
+    /// Score per authority, by authority index
+    pub scores: Vec<u64>,
+    /// The authorities that take the leader slots of the bad nodes
+    pub good_nodes: Vec<usize>,
+    /// The authorities swapped out of the leader schedule
+    pub bad_nodes: Vec<usize>,
+}
+
+impl ScheduleUpdate {
+    fn from_swap_table(table: &LeaderSwapTable) -> Self {
+        Self {
+            first_commit: table.reputation_scores.commit_range.start(),
+            last_commit: table.reputation_scores.commit_range.end(),
+            scores: table.reputation_scores.scores_per_authority.clone(),
+            good_nodes: table
+                .good_nodes
+                .iter()
+                .map(|(index, _, _)| index.value())
+                .collect(),
+            bad_nodes: table.bad_nodes.keys().map(|index| index.value()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use consensus_config::AuthorityIndex;
+
+    use super::*;
+    use crate::{context::Context, test_dag_builder::DagBuilder};
+
+    /// Builds a DAG where authority 3 has crashed and proposes no blocks.
+    fn crashed_authority_dag(num_rounds: Round) -> (Committee, Vec<Bytes>) {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let mut dag_builder = DagBuilder::new(context.clone());
+        dag_builder
+            .layers(1..=num_rounds)
+            .authorities(vec![AuthorityIndex::new_for_test(3)])
+            .skip_block()
+            .build();
+        let blocks = dag_builder
+            .blocks(1..=num_rounds)
+            .into_iter()
+            .map(|block| block.serialized().clone())
+            .collect();
+        (context.committee.clone(), blocks)
+    }
+
+    /// Builds a DAG where authority 3 is slow: its blocks arrive after the other authorities
+    /// propose their next blocks, which only include them as ancestors once every
+    /// `inclusion_period` rounds.
+    fn slow_authority_dag(num_rounds: Round, inclusion_period: Round) -> (Committee, Vec<Bytes>) {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let mut dag_builder = DagBuilder::new(context.clone());
+        let slow_authority = AuthorityIndex::new_for_test(3);
+        for round in 1..=num_rounds {
+            let ancestors = dag_builder.last_ancestors.clone();
+            let connections = context
+                .committee
+                .authorities()
+                .map(|(authority, _)| {
+                    let ancestors = if authority == slow_authority || round % inclusion_period == 0
+                    {
+                        ancestors.clone()
+                    } else {
+                        ancestors
+                            .iter()
+                            .filter(|ancestor| ancestor.author != slow_authority)
+                            .cloned()
+                            .collect()
+                    };
+                    (authority, ancestors)
+                })
+                .collect();
+            dag_builder.layer_with_connections(connections, round);
+        }
+        let blocks = dag_builder
+            .blocks(1..=num_rounds)
+            .into_iter()
+            .map(|block| block.serialized().clone())
+            .collect();
+        (context.committee.clone(), blocks)
+    }
+
+    #[tokio::test]
+    async fn test_crashed_authority_is_swapped_out() {
+        let (committee, blocks) = crashed_authority_dag(50);
+        let protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+
+        let evaluations = ScoringEvaluator::new(committee.clone(), protocol_config.clone())
+            .with_commits_per_schedule(5)
+            .with_bad_nodes_stake_threshold(33)
+            .unwrap()
+            .evaluate_all(blocks.clone())
+            .unwrap();
+        assert_eq!(evaluations.len(), ScoringStrategyKind::ALL.len());
+        for evaluation in &evaluations {
+            assert!(evaluation.commits > 0);
+            assert_eq!(evaluation.leaders_per_authority[3], 0);
+            assert!(!evaluation.schedule_updates.is_empty());
+            for update in &evaluation.schedule_updates {
+                assert_eq!(update.scores[3], 0, "{:?}", evaluation.strategy);
+                assert_eq!(update.bad_nodes, vec![3], "{:?}", evaluation.strategy);
+            }
+        }
+
+        // Without swapping leaders, every leader slot of the crashed authority is skipped.
+        let without_swaps = ScoringEvaluator::new(committee, protocol_config)
+            .with_commits_per_schedule(5)
+            .with_bad_nodes_stake_threshold(0)
+            .unwrap()
+            .evaluate(ScoringStrategyKind::Vote, blocks)
+            .unwrap();
+        assert!(without_swaps
+            .schedule_updates
+            .iter()
+            .all(|update| update.bad_nodes.is_empty()));
+        for evaluation in &evaluations {
+            assert!(evaluation.skipped_leader_rounds < without_swaps.skipped_leader_rounds);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_authority_is_swapped_out() {
+        let (committee, blocks) = slow_authority_dag(50, 5);
+        let protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+
+        let evaluations = ScoringEvaluator::new(committee.clone(), protocol_config.clone())
+            .with_commits_per_schedule(5)
+            .with_bad_nodes_stake_threshold(33)
+            .unwrap()
+            .evaluate_all(blocks.clone())
+            .unwrap();
+        assert_eq!(evaluations.len(), ScoringStrategyKind::ALL.len());
+        for evaluation in &evaluations {
+            assert!(evaluation.commits > 0);
+            // The slow authority still proposes blocks, but scores the lowest and leads the
+            // fewest commits.
+            let others = &evaluation.leaders_per_authority[..3];
+            assert!(
+                others
+                    .iter()
+                    .all(|leaders| evaluation.leaders_per_authority[3] < *leaders),
+                "{:?}: {:?}",
+                evaluation.strategy,
+                evaluation.leaders_per_authority
+            );
+            assert!(!evaluation.schedule_updates.is_empty());
+            for update in &evaluation.schedule_updates {
+                assert!(
+                    update.scores[..3]
+                        .iter()
+                        .all(|score| update.scores[3] < *score),
+                    "{:?}: {:?}",
+                    evaluation.strategy,
+                    update.scores
+                );
+                assert_eq!(update.bad_nodes, vec![3], "{:?}", evaluation.strategy);
+            }
+        }
+
+        // Without swapping leaders, the leader slots of the slow authority are mostly skipped.
+        let without_swaps = ScoringEvaluator::new(committee, protocol_config)
+            .with_commits_per_schedule(5)
+            .with_bad_nodes_stake_threshold(0)
+            .unwrap()
+            .evaluate(ScoringStrategyKind::Vote, blocks)
+            .unwrap();
+        assert!(without_swaps
+            .schedule_updates
+            .iter()
+            .all(|update| update.bad_nodes.is_empty()));
+        for evaluation in &evaluations {
+            assert!(evaluation.skipped_leader_rounds < without_swaps.skipped_leader_rounds);
+        }
+    }
+
+    #[test]
+    fn test_scoring_strategy_kind_names() {
+        for strategy in ScoringStrategyKind::ALL {
+            assert_eq!(
+                strategy.to_string().parse::<ScoringStrategyKind>(),
+                Ok(strategy)
+            );
+        }
+        assert_eq!(
+            "certified_vote_v2".parse::<ScoringStrategyKind>(),
+            Ok(ScoringStrategyKind::CertifiedVoteV2)
+        );
+        assert!("unknown".parse::<ScoringStrategyKind>().is_err());
+    }
+}
@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::HashMap, ops::Range};
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+use serde::Serialize;
+use strum_macros::{Display, EnumString};
 
 use crate::{
     block::{BlockAPI, BlockRef, Slot},
@@ -19,6 +22,43 @@ pub(crate) trait ScoringStrategy: Send + Sync {
     fn leader_scoring_round_range(&self, min_round: u32, max_round: u32) -> Range<u32>;
 }
 
+/// The available scoring strategies, named as in the `CONSENSUS_SCORING_STRATEGY`
+/// environment variable that selects the strategy of the leader schedule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize)]
+pub enum ScoringStrategyKind {
+    #[default]
+    #[strum(serialize = "vote")]
+    #[serde(rename = "vote")]
+    Vote,
+    #[strum(serialize = "certificate")]
+    #[serde(rename = "certificate")]
+    Certificate,
+    #[strum(serialize = "certified_vote_v1")]
+    #[serde(rename = "certified_vote_v1")]
+    CertifiedVoteV1,
+    #[strum(serialize = "certified_vote_v2")]
+    #[serde(rename = "certified_vote_v2")]
+    CertifiedVoteV2,
+}
+
+impl ScoringStrategyKind {
+    pub const ALL: [ScoringStrategyKind; 4] = [
+        ScoringStrategyKind::Vote,
+        ScoringStrategyKind::Certificate,
+        ScoringStrategyKind::CertifiedVoteV1,
+        ScoringStrategyKind::CertifiedVoteV2,
+    ];
+
+    pub(crate) fn strategy(self) -> Arc<dyn ScoringStrategy> {
+        match self {
+            ScoringStrategyKind::Vote => Arc::new(VoteScoringStrategy {}),
+            ScoringStrategyKind::Certificate => Arc::new(CertificateScoringStrategy {}),
+            ScoringStrategyKind::CertifiedVoteV1 => Arc::new(CertifiedVoteScoringStrategyV1 {}),
+            ScoringStrategyKind::CertifiedVoteV2 => Arc::new(CertifiedVoteScoringStrategyV2 {}),
+        }
+    }
+}
+
 /// This scoring strategy is like `CertifiedVoteScoringStrategyV1` but instead of
 /// only giving one point for each vote that is included in 2f+1 certificates. We
 /// give a score equal to the amount of stake of all certificates that included
@@ -14,15 +14,18 @@ mod commit_syncer;
 mod context;
 mod core;
 mod core_thread;
+mod dag_inspector;
 mod dag_state;
 mod error;
 mod leader_schedule;
 mod leader_scoring;
+mod leader_scoring_evaluation;
 mod leader_scoring_strategy;
 mod leader_timeout;
 mod linearizer;
 mod metrics;
 mod network;
+mod replay;
 mod stake_aggregator;
 mod storage;
 mod subscriber;
@@ -41,6 +44,12 @@ mod test_dag_parser;
 pub use authority_node::ConsensusAuthority;
 pub use block::{BlockAPI, Round};
 pub use commit::{CommitConsumer, CommitDigest, CommitIndex, CommitRef, CommittedSubDag};
+pub use dag_inspector::{
+    BlockId, BlockSummary, CommitSummary, DagExport, DagInspector, MissingAncestor,
+};
+pub use leader_scoring_evaluation::{ScheduleUpdate, ScoringEvaluation, ScoringEvaluator};
+pub use leader_scoring_strategy::ScoringStrategyKind;
+pub use replay::{first_divergent_commit, ConsensusReplay};
 pub use transaction::{TransactionClient, TransactionVerifier, ValidationError};
 
 #[cfg(test)]
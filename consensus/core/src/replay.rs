@@ -0,0 +1,264 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use consensus_config::{Committee, Parameters, ProtocolKeyPair};
+use mysten_metrics::monitored_mpsc::{unbounded_channel, UnboundedReceiver};
+use parking_lot::RwLock;
+use prometheus::Registry;
+use rand::{rngs::StdRng, SeedableRng as _};
+use sui_protocol_config::ProtocolConfig;
+
+use crate::{
+    block::{SignedBlock, VerifiedBlock},
+    block_manager::BlockManager,
+    block_verifier::NoopBlockVerifier,
+    commit::{CommitIndex, CommittedSubDag},
+    commit_observer::CommitObserver,
+    context::{Clock, Context},
+    core::{Core, CoreSignals, CoreSignalsReceivers},
+    dag_inspector::{BlockId, CommitSummary},
+    dag_state::DagState,
+    error::{ConsensusError, ConsensusResult},
+    leader_schedule::{LeaderSchedule, LeaderSwapTable},
+    metrics::initialise_metrics,
+    storage::mem_store::MemStore,
+    transaction::{TransactionClient, TransactionConsumer},
+    CommitConsumer,
+};
+
+/// Replays recorded blocks through `Core` offline, with the committee and protocol config of
+/// the epoch they were recorded in, to reproduce the commits an authority made.
+///
+/// Blocks are processed in the order they are added, as if they had been received in that
+/// order. They are assumed to have been verified already, and the replaying authority never
+/// proposes blocks of its own. All blocks are kept in memory.
+pub struct ConsensusReplay {
+    core: Core,
+    leader_schedule: Arc<LeaderSchedule>,
+    commit_receiver: UnboundedReceiver<CommittedSubDag>,
+    // Core expects a subscriber to its signals.
+    _signal_receivers: CoreSignalsReceivers,
+    _transaction_client: TransactionClient,
+}
+
+impl ConsensusReplay {
+    /// Creates a replay of the authority with index `own_index` in `committee`.
+    pub fn new(
+        committee: Committee,
+        own_index: usize,
+        protocol_config: ProtocolConfig,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_leader_schedule(committee, own_index, protocol_config, |schedule| schedule)
+    }
+
+    /// Creates a replay whose leader schedule is adjusted by `configure_schedule`, e.g. to use
+    /// another scoring strategy.
+    pub(crate) fn new_with_leader_schedule(
+        committee: Committee,
+        own_index: usize,
+        protocol_config: ProtocolConfig,
+        configure_schedule: impl FnOnce(LeaderSchedule) -> LeaderSchedule,
+    ) -> anyhow::Result<Self> {
+        let own_index = committee
+            .to_authority_index(own_index)
+            .ok_or_else(|| anyhow::anyhow!("Authority {own_index} is not in the committee"))?;
+        let context = Arc::new(Context::new(
+            own_index,
+            committee,
+            Parameters::default(),
+            protocol_config,
+            initialise_metrics(Registry::new()),
+            Arc::new(Clock::new()),
+        ));
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let block_manager = BlockManager::new(
+            context.clone(),
+            dag_state.clone(),
+            Arc::new(NoopBlockVerifier),
+        );
+        let leader_schedule = if context
+            .protocol_config
+            .mysticeti_leader_scoring_and_schedule()
+        {
+            LeaderSchedule::from_store(context.clone(), dag_state.clone())
+        } else {
+            LeaderSchedule::new(context.clone(), LeaderSwapTable::default())
+        };
+        let leader_schedule = Arc::new(configure_schedule(leader_schedule));
+        let (commit_sender, commit_receiver) = unbounded_channel("consensus_replay_output");
+        let commit_observer = CommitObserver::new(
+            context.clone(),
+            CommitConsumer::new(commit_sender, 0, 0),
+            dag_state.clone(),
+            store,
+            leader_schedule.clone(),
+        );
+        let (transaction_client, tx_receiver) = TransactionClient::new(context.clone());
+        let transaction_consumer = TransactionConsumer::new(tx_receiver, context.clone(), None);
+        let (signals, signal_receivers) = CoreSignals::new(context.clone());
+        // The signer is never used, as blocks are not proposed while the consumer is unavailable.
+        let block_signer = ProtocolKeyPair::generate(&mut StdRng::from_seed([0; 32]));
+
+        let core = Core::new(
+            context,
+            leader_schedule.clone(),
+            transaction_consumer,
+            block_manager,
+            false,
+            commit_observer,
+            signals,
+            block_signer,
+            dag_state,
+        );
+
+        Ok(Self {
+            core,
+            leader_schedule,
+            commit_receiver,
+            _signal_receivers: signal_receivers,
+            _transaction_client: transaction_client,
+        })
+    }
+
+    /// Processes the serialized blocks in order, and returns the commits they led to.
+    pub fn add_blocks(
+        &mut self,
+        serialized_blocks: Vec<Bytes>,
+    ) -> anyhow::Result<Vec<CommitSummary>> {
+        let blocks = deserialize_blocks(serialized_blocks)?;
+        Ok(self
+            .add_verified_blocks(blocks)?
+            .iter()
+            .map(CommitSummary::from_sub_dag)
+            .collect())
+    }
+
+    pub(crate) fn add_verified_blocks(
+        &mut self,
+        blocks: Vec<VerifiedBlock>,
+    ) -> ConsensusResult<Vec<CommittedSubDag>> {
+        self.core.add_blocks(blocks)?;
+
+        let mut commits = vec![];
+        while let Ok(sub_dag) = self.commit_receiver.try_recv() {
+            commits.push(sub_dag);
+        }
+        Ok(commits)
+    }
+
+    /// Returns the swap table of the current leader schedule.
+    pub(crate) fn leader_swap_table(&self) -> LeaderSwapTable {
+        self.leader_schedule.leader_swap_table.read().clone()
+    }
+
+    /// Returns the ancestors of added blocks that have not been added yet.
+    pub fn missing_blocks(&self) -> Vec<BlockId> {
+        self.core
+            .get_missing_blocks()
+            .into_iter()
+            .map(BlockId::from)
+            .collect()
+    }
+}
+
+pub(crate) fn deserialize_blocks(
+    serialized_blocks: Vec<Bytes>,
+) -> ConsensusResult<Vec<VerifiedBlock>> {
+    serialized_blocks
+        .into_iter()
+        .map(|serialized| {
+            let signed_block: SignedBlock =
+                bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedBlock)?;
+            Ok(VerifiedBlock::new_verified(signed_block, serialized))
+        })
+        .collect()
+}
+
+/// Returns the index of the first commit that differs between `replayed` and `recorded`, both
+/// ordered by index, among the indices they both contain.
+pub fn first_divergent_commit(
+    replayed: &[CommitSummary],
+    recorded: &[CommitSummary],
+) -> Option<CommitIndex> {
+    let mut recorded = recorded.iter().peekable();
+    for commit in replayed {
+        while recorded.next_if(|r| r.index < commit.index).is_some() {}
+        match recorded.peek() {
+            Some(r) if r.index == commit.index && r.digest != commit.digest => {
+                return Some(commit.index)
+            }
+            Some(_) => {}
+            None => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_dag_builder::DagBuilder, Round};
+
+    fn build_blocks(num_rounds: Round) -> (Committee, Vec<Bytes>) {
+        let context = Arc::new(Context::new_for_test(4).0);
+        let mut dag_builder = DagBuilder::new(context.clone());
+        dag_builder.layers(1..=num_rounds).build();
+        let blocks = dag_builder
+            .blocks(1..=num_rounds)
+            .into_iter()
+            .map(|block| block.serialized().clone())
+            .collect();
+        (context.committee.clone(), blocks)
+    }
+
+    fn replay(committee: Committee) -> ConsensusReplay {
+        ConsensusReplay::new(committee, 0, ProtocolConfig::get_for_max_version_UNSAFE()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replay_is_independent_of_arrival_order() {
+        let (committee, blocks) = build_blocks(10);
+
+        let mut in_order = replay(committee.clone());
+        let mut commits = vec![];
+        for block in &blocks {
+            commits.extend(in_order.add_blocks(vec![block.clone()]).unwrap());
+        }
+        assert!(!commits.is_empty());
+        assert!(commits
+            .iter()
+            .enumerate()
+            .all(|(i, commit)| commit.index == i as CommitIndex + 1));
+        assert!(in_order.missing_blocks().is_empty());
+
+        // Blocks arriving before their ancestors are suspended until the ancestors arrive.
+        let mut reversed = replay(committee);
+        let reversed_blocks: Vec<_> = blocks.into_iter().rev().collect();
+        assert!(reversed
+            .add_blocks(reversed_blocks[..4].to_vec())
+            .unwrap()
+            .is_empty());
+        assert_eq!(reversed.missing_blocks().len(), 4);
+        let mut reversed_commits = vec![];
+        for block in reversed_blocks.into_iter().skip(4) {
+            reversed_commits.extend(reversed.add_blocks(vec![block]).unwrap());
+        }
+
+        let digests = |commits: &[CommitSummary]| {
+            commits
+                .iter()
+                .map(|c| (c.index, c.digest.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(digests(&commits), digests(&reversed_commits));
+        assert_eq!(first_divergent_commit(&commits, &reversed_commits), None);
+
+        let mut diverged = commits.clone();
+        diverged[1].digest = "changed".to_string();
+        assert_eq!(first_divergent_commit(&diverged, &commits[1..]), Some(2));
+    }
+}
@@ -19,7 +19,7 @@ use crate::{
     error::ConsensusResult,
 };
 
-/// In-memory storage for testing.
+/// In-memory storage for testing and offline replay.
 pub(crate) struct MemStore {
     inner: RwLock<Inner>,
 }
@@ -33,7 +33,6 @@ struct Inner {
 }
 
 impl MemStore {
-    #[cfg(test)]
     pub(crate) fn new() -> Self {
         MemStore {
             inner: RwLock::new(Inner {
@@ -127,6 +126,23 @@ impl Store for MemStore {
         Ok(blocks)
     }
 
+    fn scan_blocks_by_round(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        let inner = self.inner.read();
+        let blocks = inner
+            .blocks
+            .range((
+                Included((start_round, AuthorityIndex::MIN, BlockDigest::MIN)),
+                Included((end_round, AuthorityIndex::MAX, BlockDigest::MAX)),
+            ))
+            .map(|(_, block)| block.clone())
+            .collect();
+        Ok(blocks)
+    }
+
     fn contains_block_at_slot(&self, slot: Slot) -> ConsensusResult<bool> {
         let inner = self.inner.read();
         let found = inner
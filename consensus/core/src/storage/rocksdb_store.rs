@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::VecDeque, ops::Bound::Included, time::Duration};
+use std::{collections::VecDeque, ops::Bound::Included, path::Path, sync::Arc, time::Duration};
 
 use bytes::Bytes;
 use consensus_config::AuthorityIndex;
@@ -9,7 +9,11 @@ use sui_macros::fail_point;
 use typed_store::{
     metrics::SamplingInterval,
     reopen,
-    rocks::{default_db_options, open_cf_opts, DBMap, MetricConf, ReadWriteOptions},
+    rocks::{
+        default_db_options, open_cf_opts, open_cf_opts_secondary, DBMap, MetricConf,
+        ReadWriteOptions, RocksDB,
+    },
+    rocksdb::Options,
     Map as _,
 };
 
@@ -47,10 +51,42 @@ impl RocksDBStore {
         // Consensus data has high write throughput (all transactions) and is rarely read
         // (only during recovery and when helping peers catch up).
         let db_options = default_db_options().optimize_db_for_write_throughput(2);
+        let rocksdb = open_cf_opts(
+            path,
+            Some(db_options.options),
+            Self::metrics_conf(),
+            &Self::column_family_options(),
+        )
+        .expect("Cannot open database");
+        Self::from_db(&rocksdb)
+    }
+
+    /// Opens the storage at `path` as a RocksDB secondary instance, which can read the storage of
+    /// a running authority without interfering with it. The secondary instance keeps its own
+    /// files in `secondary_path`, or in a `SECONDARY` directory next to `path` by default.
+    pub(crate) fn new_secondary(
+        path: &Path,
+        secondary_path: Option<&Path>,
+    ) -> ConsensusResult<Self> {
+        let rocksdb = open_cf_opts_secondary(
+            path,
+            secondary_path,
+            None,
+            Self::metrics_conf(),
+            &Self::column_family_options(),
+        )?;
+        Ok(Self::from_db(&rocksdb))
+    }
+
+    fn metrics_conf() -> MetricConf {
         let mut metrics_conf = MetricConf::new("consensus");
         metrics_conf.read_sample_interval = SamplingInterval::new(Duration::from_secs(60), 0);
+        metrics_conf
+    }
+
+    fn column_family_options() -> Vec<(&'static str, Options)> {
         let cf_options = default_db_options().optimize_for_write_throughput().options;
-        let column_family_options = vec![
+        vec![
             (
                 Self::BLOCKS_CF,
                 default_db_options()
@@ -64,16 +100,11 @@ impl RocksDBStore {
             (Self::COMMITS_CF, cf_options.clone()),
             (Self::COMMIT_VOTES_CF, cf_options.clone()),
             (Self::COMMIT_INFO_CF, cf_options.clone()),
-        ];
-        let rocksdb = open_cf_opts(
-            path,
-            Some(db_options.options),
-            metrics_conf,
-            &column_family_options,
-        )
-        .expect("Cannot open database");
+        ]
+    }
 
-        let (blocks, digests_by_authorities, commits, commit_votes, commit_info) = reopen!(&rocksdb,
+    fn from_db(rocksdb: &Arc<RocksDB>) -> Self {
+        let (blocks, digests_by_authorities, commits, commit_votes, commit_info) = reopen!(rocksdb,
             Self::BLOCKS_CF;<(Round, AuthorityIndex, BlockDigest), bytes::Bytes>,
             Self::DIGESTS_BY_AUTHORITIES_CF;<(AuthorityIndex, Round, BlockDigest), ()>,
             Self::COMMITS_CF;<(CommitIndex, CommitDigest), Bytes>,
@@ -213,6 +244,26 @@ impl Store for RocksDBStore {
         Ok(blocks)
     }
 
+    fn scan_blocks_by_round(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        let mut blocks = vec![];
+        for kv in self.blocks.safe_range_iter((
+            Included((start_round, AuthorityIndex::MIN, BlockDigest::MIN)),
+            Included((end_round, AuthorityIndex::MAX, BlockDigest::MAX)),
+        )) {
+            let ((round, author, digest), serialized) = kv?;
+            let signed_block: SignedBlock =
+                bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedBlock)?;
+            let block = VerifiedBlock::new_verified(signed_block, serialized);
+            assert_eq!(BlockRef::new(round, author, digest), block.reference());
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
     // The method returns the last `num_of_rounds` rounds blocks by author in round ascending order.
     // When a `before_round` is defined then the blocks of round `<=before_round` are returned. If not
     // then the max value for round will be used as cut off.
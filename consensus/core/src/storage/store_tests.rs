@@ -156,6 +156,27 @@ async fn scan_blocks(
         );
     }
 
+    {
+        let scanned_blocks = store
+            .scan_blocks_by_round(11, 13)
+            .expect("Scan blocks should not fail");
+        assert_eq!(
+            scanned_blocks,
+            vec![
+                written_blocks[3].clone(),
+                written_blocks[4].clone(),
+                written_blocks[5].clone(),
+                written_blocks[7].clone(),
+                written_blocks[6].clone(),
+            ]
+        );
+
+        let scanned_blocks = store
+            .scan_blocks_by_round(20, 30)
+            .expect("Scan blocks should not fail");
+        assert!(scanned_blocks.is_empty(), "{:?}", scanned_blocks);
+    }
+
     let additional_blocks = vec![
         VerifiedBlock::new_for_test(TestBlock::new(14, 2).build()),
         VerifiedBlock::new_for_test(TestBlock::new(15, 0).build()),
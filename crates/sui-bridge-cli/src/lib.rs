@@ -121,6 +121,19 @@ pub enum BridgeCommand {
         #[clap(long, default_value = "false")]
         ping: bool,
     },
+    /// View the history of bridge actions processed by a bridge node
+    #[clap(name = "view-action-history")]
+    ViewActionHistory {
+        /// Base url of the bridge node's admin server, see `admin-listen-port`
+        #[clap(long = "node-url")]
+        node_url: String,
+        /// Base58 encoded digest of the action to look up.
+        /// If not set, the latest `limit` actions are shown.
+        #[clap(long = "action-digest")]
+        action_digest: Option<String>,
+        #[clap(long = "limit", default_value = "20")]
+        limit: usize,
+    },
     /// Client to facilitate and execute Bridge actions
     #[clap(name = "client")]
     Client {
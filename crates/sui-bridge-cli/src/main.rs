@@ -17,7 +17,7 @@ use sui_bridge::crypto::{BridgeAuthorityPublicKey, BridgeAuthorityPublicKeyBytes
 use sui_bridge::eth_transaction_builder::build_eth_transaction;
 use sui_bridge::sui_client::SuiClient;
 use sui_bridge::sui_transaction_builder::build_sui_transaction;
-use sui_bridge::types::BridgeActionType;
+use sui_bridge::types::{BridgeActionHistoryRecord, BridgeActionType};
 use sui_bridge::utils::{
     examine_key, generate_bridge_authority_key_and_write_to_file,
     generate_bridge_client_key_and_write_to_file, generate_bridge_node_config_and_write_to_file,
@@ -501,6 +501,41 @@ async fn main() -> anyhow::Result<()> {
             output_wrapper.inner = output;
             println!("{}", serde_json::to_string_pretty(&output_wrapper).unwrap());
         }
+        BridgeCommand::ViewActionHistory {
+            node_url,
+            action_digest,
+            limit,
+        } => {
+            let base_url = reqwest::Url::parse(&node_url)?;
+            // Important: the paths need to match the ones in sui-bridge/src/server/mod.rs
+            let url = match &action_digest {
+                Some(digest) => base_url.join(&format!("history/action/{}", digest))?,
+                None => base_url.join(&format!("history/latest/{}", limit))?,
+            };
+            let resp = reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(10))
+                .timeout(Duration::from_secs(10))
+                .build()?
+                .get(url)
+                .send()
+                .await?;
+            if !resp.status().is_success() {
+                return Err(anyhow::anyhow!(
+                    "Failed to query action history, status {}: {}",
+                    resp.status(),
+                    resp.text().await?
+                ));
+            }
+            let records = if action_digest.is_some() {
+                resp.json::<Option<BridgeActionHistoryRecord>>()
+                    .await?
+                    .into_iter()
+                    .collect()
+            } else {
+                resp.json::<Vec<BridgeActionHistoryRecord>>().await?
+            };
+            println!("{}", serde_json::to_string_pretty(&records).unwrap());
+        }
         BridgeCommand::Client { config_path, cmd } => {
             let config = BridgeCliConfig::load(config_path).expect("Couldn't load BridgeCliConfig");
             let config = LoadedBridgeCliConfig::load(config).await?;
@@ -88,6 +88,15 @@ gen_eth_events!(
 
 gen_eth_events!(EthBridgeVault, "abi/bridge_vault.json");
 
+// The subset of ERC20 that is needed to read the balances locked in the vault.
+abigen!(
+    EthErc20,
+    r#"[
+        function balanceOf(address account) external view returns (uint256)
+        function decimals() external view returns (uint8)
+    ]"#,
+);
+
 impl EthBridgeEvent {
     pub fn try_into_bridge_action(
         self,
@@ -16,6 +16,7 @@ use sui_types::transaction::ObjectArg;
 use sui_types::TypeTag;
 use sui_types::{
     base_types::{ObjectID, ObjectRef, SuiAddress},
+    bridge::BridgeSummary,
     crypto::{Signature, SuiKeyPair},
     digests::TransactionDigest,
     gas_coin::GasCoin,
@@ -34,7 +35,10 @@ use crate::{
     storage::BridgeOrchestratorTables,
     sui_client::{SuiClient, SuiClientInner},
     sui_transaction_builder::build_sui_transaction,
-    types::{BridgeAction, BridgeActionStatus, VerifiedCertifiedBridgeAction},
+    types::{
+        BridgeAction, BridgeActionStatus, BridgeActionType, EmergencyAction, EmergencyActionType,
+        VerifiedCertifiedBridgeAction,
+    },
 };
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -294,6 +298,20 @@ where
         store: &Arc<BridgeOrchestratorTables>,
         metrics: &Arc<BridgeMetrics>,
     ) -> bool {
+        if let BridgeAction::EmergencyAction(emergency_action) = action {
+            return Self::handle_stale_emergency_action_maybe(
+                sui_client,
+                action,
+                emergency_action,
+                store,
+                metrics,
+            )
+            .await;
+        }
+        // Other governance actions do not have a token transfer status on chain.
+        if action.is_governace_action() {
+            return false;
+        }
         let status = sui_client
             .get_token_transfer_action_onchain_status_until_success(
                 action.chain_id() as u8,
@@ -307,6 +325,11 @@ where
                     action
                 );
                 metrics.action_executor_already_processed_actions.inc();
+                store
+                    .record_action_status(action, status, None)
+                    .unwrap_or_else(|e| {
+                        panic!("Write to DB should not fail: {:?}", e);
+                    });
                 store
                     .remove_pending_actions(&[action.digest()])
                     .unwrap_or_else(|e| {
@@ -320,6 +343,34 @@ where
         }
     }
 
+    // Checks if an emergency action can no longer be executed on chain, because the
+    // emergency op sequence number has moved past its nonce or the bridge is already
+    // paused or unpaused. If so, remove it from the pending log without recording a
+    // status, as the action itself was never approved. Returns true if it is stale.
+    async fn handle_stale_emergency_action_maybe(
+        sui_client: &Arc<SuiClient<C>>,
+        action: &BridgeAction,
+        emergency_action: &EmergencyAction,
+        store: &Arc<BridgeOrchestratorTables>,
+        metrics: &Arc<BridgeMetrics>,
+    ) -> bool {
+        let summary = sui_client.get_bridge_summary_until_success().await;
+        if !is_stale_emergency_action(emergency_action, &summary) {
+            return false;
+        }
+        info!(
+            "Emergency action is stale, removing action from pending logs: {:?}",
+            action
+        );
+        metrics.action_executor_already_processed_actions.inc();
+        store
+            .remove_pending_actions(&[action.digest()])
+            .unwrap_or_else(|e| {
+                panic!("Write to DB should not fail: {:?}", e);
+            });
+        true
+    }
+
     // TODO: introduce a way to properly stagger the handling
     // for various validators.
     async fn request_signatures(
@@ -341,10 +392,13 @@ where
         info!("requesting signatures");
         let BridgeActionExecutionWrapper(action, attempt_times) = action;
 
-        // Only token transfer action should reach here
+        // Only token transfer actions and emergency actions proposed by the
+        // supply reconciler should reach here
         match &action {
-            BridgeAction::SuiToEthBridgeAction(_) | BridgeAction::EthToSuiBridgeAction(_) => (),
-            _ => unreachable!("Non token transfer action should not reach here"),
+            BridgeAction::SuiToEthBridgeAction(_)
+            | BridgeAction::EthToSuiBridgeAction(_)
+            | BridgeAction::EmergencyAction(_) => (),
+            _ => unreachable!("Non token transfer or emergency action should not reach here"),
         };
 
         // If the action is already processed, skip it.
@@ -364,6 +418,11 @@ where
             .await
         {
             Ok(certificate) => {
+                store
+                    .record_action_certificate(&certificate)
+                    .unwrap_or_else(|e| {
+                        panic!("Write to DB should not fail: {:?}", e);
+                    });
                 info!("Sending certificate to execution");
                 execution_queue_sender
                     .send(CertifiedBridgeActionExecutionWrapper(certificate, 0))
@@ -581,9 +640,9 @@ where
         match status {
             SuiExecutionStatus::Success => {
                 let events = response.events.expect("We requested events but got None.");
-                // If the transaction is successful, there must be either
+                // If a token transfer transaction is successful, there must be either
                 // TokenTransferAlreadyClaimed or TokenTransferClaimed event.
-                assert!(events
+                assert!(action.is_governace_action() || events
                     .data
                     .iter()
                     .any(|e| e.type_ == *TokenTransferAlreadyClaimed.get().unwrap()
@@ -594,6 +653,20 @@ where
                     events,
                     );
                 info!(?tx_digest, "Sui transaction executed successfully");
+                let claimed = events.data.iter().any(|e| {
+                    e.type_ == *TokenTransferClaimed.get().unwrap()
+                        || e.type_ == *TokenTransferAlreadyClaimed.get().unwrap()
+                });
+                let action_status = if claimed {
+                    BridgeActionStatus::Claimed
+                } else {
+                    BridgeActionStatus::Approved
+                };
+                store
+                    .record_action_status(action, action_status, Some(tx_digest))
+                    .unwrap_or_else(|e| {
+                        panic!("Write to DB should not fail: {:?}", e);
+                    });
                 store
                     .remove_pending_actions(&[action.digest()])
                     .unwrap_or_else(|e| {
@@ -636,6 +709,20 @@ where
     }
 }
 
+fn is_stale_emergency_action(action: &EmergencyAction, summary: &BridgeSummary) -> bool {
+    let next_nonce = summary
+        .sequence_nums
+        .iter()
+        .find(|(message_type, _)| *message_type == BridgeActionType::EmergencyButton as u8)
+        .map(|(_, seq_num)| *seq_num)
+        .unwrap_or_default();
+    let already_applied = match action.action_type {
+        EmergencyActionType::Pause => summary.is_frozen,
+        EmergencyActionType::Unpause => !summary.is_frozen,
+    };
+    action.nonce < next_nonce || already_applied
+}
+
 pub async fn submit_to_executor(
     tx: &mysten_metrics::metered_channel::Sender<BridgeActionExecutionWrapper>,
     action: BridgeAction,
@@ -657,6 +744,7 @@ mod tests {
     use sui_json_rpc_types::SuiTransactionBlockEffects;
     use sui_json_rpc_types::SuiTransactionBlockEvents;
     use sui_json_rpc_types::{SuiEvent, SuiTransactionBlockResponse};
+    use sui_types::bridge::BridgeChainId;
     use sui_types::crypto::get_key_pair;
     use sui_types::gas_coin::GasCoin;
     use sui_types::TypeTag;
@@ -752,6 +840,20 @@ mod tests {
         tx_subscription.recv().await.unwrap();
         assert!(store.get_all_pending_actions().is_empty());
 
+        // The action history keeps the signatures, the tx digest and the status transitions
+        let record = store.get_action_history(&action.digest()).unwrap().unwrap();
+        assert_eq!(record.action, action);
+        assert!(!record.signatures.is_empty());
+        assert_eq!(record.sui_tx_digest, Some(tx_digest));
+        assert_eq!(
+            record
+                .transitions
+                .iter()
+                .map(|t| t.status.clone())
+                .collect::<Vec<_>>(),
+            vec![BridgeActionStatus::Pending, BridgeActionStatus::Claimed]
+        );
+
         /////////////////////////////////////////////////////////////////////////////////////////////////
         ////////////////////////////////////// Test execution failure ///////////////////////////////////
         /////////////////////////////////////////////////////////////////////////////////////////////////
@@ -1065,6 +1167,96 @@ mod tests {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
         tx_subscription.try_recv().unwrap_err();
+        let record = store.get_action_history(&action_digest).unwrap().unwrap();
+        assert_eq!(record.status(), Some(&BridgeActionStatus::Approved));
+        assert!(record.signatures.is_empty());
+        assert_eq!(record.sui_tx_digest, None);
+    }
+
+    #[tokio::test]
+    async fn test_skip_stale_emergency_action() {
+        let (
+            signing_tx,
+            _execution_tx,
+            sui_client_mock,
+            mut tx_subscription,
+            store,
+            _secrets,
+            _dummy_sui_key,
+            _mock0,
+            _mock1,
+            _mock2,
+            _mock3,
+            _handles,
+            _gas_object_ref,
+            _sui_address,
+            _sui_token_type_tags,
+            _bridge_pause_tx,
+        ) = setup().await;
+
+        // Another emergency action was executed since this one was proposed
+        sui_client_mock.set_sequence_nums(vec![(BridgeActionType::EmergencyButton as u8, 2)]);
+        let action = BridgeAction::EmergencyAction(EmergencyAction {
+            nonce: 1,
+            chain_id: BridgeChainId::SuiCustom,
+            action_type: EmergencyActionType::Pause,
+        });
+        let action_digest = action.digest();
+        store.insert_pending_actions(&[action.clone()]).unwrap();
+        submit_to_executor(&signing_tx, action).await.unwrap();
+
+        // The action is removed from WAL without being signed, executed or recorded
+        let now = std::time::Instant::now();
+        while store.get_all_pending_actions().contains_key(&action_digest) {
+            if now.elapsed().as_secs() > 10 {
+                panic!("Timeout waiting for action to be removed from WAL");
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+        tx_subscription.try_recv().unwrap_err();
+        assert!(store.get_action_history(&action_digest).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_is_stale_emergency_action() {
+        let mut summary = BridgeSummary {
+            bridge_version: 0,
+            message_version: 0,
+            chain_id: BridgeChainId::SuiCustom as u8,
+            sequence_nums: vec![(BridgeActionType::EmergencyButton as u8, 3)],
+            committee: Default::default(),
+            treasury: Default::default(),
+            bridge_records_id: ObjectID::random(),
+            limiter: Default::default(),
+            is_frozen: false,
+        };
+        let action = |nonce, action_type| EmergencyAction {
+            nonce,
+            chain_id: BridgeChainId::SuiCustom,
+            action_type,
+        };
+        assert!(!is_stale_emergency_action(
+            &action(3, EmergencyActionType::Pause),
+            &summary
+        ));
+        assert!(is_stale_emergency_action(
+            &action(2, EmergencyActionType::Pause),
+            &summary
+        ));
+        assert!(is_stale_emergency_action(
+            &action(3, EmergencyActionType::Unpause),
+            &summary
+        ));
+
+        summary.is_frozen = true;
+        assert!(is_stale_emergency_action(
+            &action(3, EmergencyActionType::Pause),
+            &summary
+        ));
+        assert!(!is_stale_emergency_action(
+            &action(3, EmergencyActionType::Unpause),
+            &summary
+        ));
     }
 
     #[tokio::test]
@@ -21,6 +21,7 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_config::Config;
 use sui_json_rpc_types::Coin;
 use sui_keys::keypair_file::read_key;
@@ -92,6 +93,30 @@ pub struct SuiConfig {
     pub sui_bridge_module_last_processed_event_id_override: Option<EventID>,
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SupplyReconcilerConfig {
+    /// How often observed transfers are reconciled with on-chain supplies, in seconds.
+    #[serde(default = "default_reconciler_interval_secs")]
+    pub interval_secs: u64,
+    /// The divergence (sui adjusted amount) per token that is tolerated before an
+    /// anomaly is raised.
+    #[serde(default)]
+    pub tolerance: u64,
+    /// Whether to propose an `EmergencyButton` pause action on Sui when bridged tokens
+    /// on Sui are no longer backed by tokens locked on Eth. The action is only certified
+    /// if enough committee members have it in `approved_governance_actions`.
+    #[serde(default)]
+    pub propose_emergency_pause: bool,
+}
+
+fn default_reconciler_interval_secs() -> u64 {
+    60
+}
+
+const DEFAULT_ACTION_HISTORY_RETENTION_DAYS: u64 = 30;
+
 #[serde_as]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -100,6 +125,10 @@ pub struct BridgeNodeConfig {
     pub server_listen_port: u16,
     /// The port that for metrics server.
     pub metrics_port: u16,
+    /// The port that the admin server listens on, on localhost only. The admin server
+    /// serves the action history recorded by the client, and is not started if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admin_listen_port: Option<u16>,
     /// Path of the file where bridge authority key (Secp256k1) is stored.
     pub bridge_authority_key_path: PathBuf,
     /// Whether to run client. If true, `sui.bridge_client_key_path`
@@ -108,12 +137,20 @@ pub struct BridgeNodeConfig {
     /// Path of the client storage. Required when `run_client` is true.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub db_path: Option<PathBuf>,
+    /// How many days the client keeps the history of actions it processed. Pending
+    /// actions are always kept. Defaults to 30 days.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_history_retention_days: Option<u64>,
     /// A list of approved governance actions. Action in this list will be signed when requested by client.
     pub approved_governance_actions: Vec<BridgeAction>,
     /// Sui configuration
     pub sui: SuiConfig,
     /// Eth configuration
     pub eth: EthConfig,
+    /// Supply reconciler configuration. The reconciler only runs when `run_client`
+    /// is true and this is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supply_reconciler: Option<SupplyReconcilerConfig>,
 }
 
 impl Config for BridgeNodeConfig {}
@@ -152,7 +189,8 @@ impl BridgeNodeConfig {
             ));
         }
 
-        let (eth_client, eth_contracts) = self.prepare_for_eth(metrics).await?;
+        let (eth_client, eth_contracts, eth_contract_addresses) =
+            self.prepare_for_eth(metrics).await?;
         let bridge_summary = sui_client
             .get_bridge_summary()
             .await
@@ -180,6 +218,7 @@ impl BridgeNodeConfig {
             key: bridge_authority_key,
             metrics_port: self.metrics_port,
             server_listen_port: self.server_listen_port,
+            admin_listen_port: self.admin_listen_port,
             sui_client: sui_client.clone(),
             eth_client: eth_client.clone(),
             approved_governance_actions,
@@ -206,6 +245,7 @@ impl BridgeNodeConfig {
             eth_client: eth_client.clone(),
             db_path,
             eth_contracts,
+            eth_contract_addresses,
             // in `prepare_for_eth` we check if this is None when `run_client` is true. Safe to unwrap here.
             eth_contracts_start_block_fallback: self
                 .eth
@@ -215,6 +255,14 @@ impl BridgeNodeConfig {
             sui_bridge_module_last_processed_event_id_override: self
                 .sui
                 .sui_bridge_module_last_processed_event_id_override,
+            supply_reconciler: self.supply_reconciler.clone(),
+            action_history_retention: Duration::from_secs(
+                self.action_history_retention_days
+                    .unwrap_or(DEFAULT_ACTION_HISTORY_RETENTION_DAYS)
+                    * 24
+                    * 60
+                    * 60,
+            ),
         };
 
         Ok((bridge_server_config, Some(bridge_client_config)))
@@ -223,7 +271,11 @@ impl BridgeNodeConfig {
     async fn prepare_for_eth(
         &self,
         metrics: Arc<BridgeMetrics>,
-    ) -> anyhow::Result<(Arc<EthClient<MeteredEthHttpProvier>>, Vec<EthAddress>)> {
+    ) -> anyhow::Result<(
+        Arc<EthClient<MeteredEthHttpProvier>>,
+        Vec<EthAddress>,
+        EthContractAddresses,
+    )> {
         let bridge_proxy_address = EthAddress::from_str(&self.eth.eth_bridge_proxy_address)?;
         let provider = Arc::new(
             new_metered_eth_provider(&self.eth.eth_rpc_url, metrics.clone())
@@ -290,7 +342,14 @@ impl BridgeNodeConfig {
             limiter_address,
             vault_address,
         ];
-        Ok((eth_client, contract_addresses))
+        let eth_contract_addresses = EthContractAddresses {
+            sui_bridge: bridge_proxy_address,
+            bridge_committee: committee_address,
+            bridge_config: config_address,
+            bridge_limiter: limiter_address,
+            bridge_vault: vault_address,
+        };
+        Ok((eth_client, contract_addresses, eth_contract_addresses))
     }
 
     async fn prepare_for_sui(
@@ -366,6 +425,7 @@ impl BridgeNodeConfig {
 pub struct BridgeServerConfig {
     pub key: BridgeAuthorityKeyPair,
     pub server_listen_port: u16,
+    pub admin_listen_port: Option<u16>,
     pub metrics_port: u16,
     pub sui_client: Arc<SuiClient<SuiSdkClient>>,
     pub eth_client: Arc<EthClient<MeteredEthHttpProvier>>,
@@ -383,10 +443,13 @@ pub struct BridgeClientConfig {
     pub eth_client: Arc<EthClient<MeteredEthHttpProvier>>,
     pub db_path: PathBuf,
     pub eth_contracts: Vec<EthAddress>,
+    pub eth_contract_addresses: EthContractAddresses,
     // See `BridgeNodeConfig` for the explanation of following two fields.
     pub eth_contracts_start_block_fallback: u64,
     pub eth_contracts_start_block_override: Option<u64>,
     pub sui_bridge_module_last_processed_event_id_override: Option<EventID>,
+    pub supply_reconciler: Option<SupplyReconcilerConfig>,
+    pub action_history_retention: Duration,
 }
 
 #[serde_as]
@@ -735,6 +735,9 @@ pub(crate) async fn start_bridge_cluster(
                 bridge_client_gas_object: None,
                 sui_bridge_module_last_processed_event_id_override: None,
             },
+            supply_reconciler: None,
+            admin_listen_port: None,
+            action_history_retention_days: None,
         };
         // Spawn bridge node in memory
         let config_clone = config.clone();
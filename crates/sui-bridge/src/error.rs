@@ -63,6 +63,8 @@ pub enum BridgeError {
     SuiTxFailureGeneric(String),
     // Zero value bridge transfer should not be allowed
     ZeroValueBridgeTransfer(String),
+    // Action history is not recorded on this node
+    ActionHistoryUnavailable,
     // Storage Error
     StorageError(String),
     // Rest API Error
@@ -9,9 +9,11 @@ use crate::error::{BridgeError, BridgeResult};
 use crate::metered_eth_provider::{new_metered_eth_provider, MeteredEthHttpProvier};
 use crate::metrics::BridgeMetrics;
 use crate::types::{BridgeAction, EthLog, RawEthLog};
+use ethers::abi::{AbiDecode, AbiEncode};
+use ethers::contract::EthCall;
 use ethers::providers::{JsonRpcClient, Middleware, Provider};
 use ethers::types::TxHash;
-use ethers::types::{Block, Filter};
+use ethers::types::{Block, Bytes, Filter, TransactionRequest};
 use tap::TapFallible;
 
 #[cfg(test)]
@@ -122,6 +124,29 @@ where
         Ok(number.as_u64())
     }
 
+    /// Calls a view function of `contract` against the last finalized block
+    /// and decodes its return value.
+    pub async fn call_finalized<C, R>(&self, contract: EthAddress, call: C) -> BridgeResult<R>
+    where
+        C: EthCall,
+        R: AbiDecode,
+    {
+        let tx = TransactionRequest::new().to(contract).data(call.encode());
+        let result: Bytes = self
+            .provider
+            .request("eth_call", (tx, "finalized"))
+            .await
+            .map_err(BridgeError::from)?;
+        R::decode(result).map_err(|e| {
+            BridgeError::ProviderError(format!(
+                "Failed to decode result of {} on {:?}: {:?}",
+                C::function_name(),
+                contract,
+                e
+            ))
+        })
+    }
+
     // Note: query may fail if range is too big. Callsite is responsible
     // for chunking the query.
     pub async fn get_events_in_range(
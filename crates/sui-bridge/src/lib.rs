@@ -22,6 +22,7 @@ pub mod storage;
 pub mod sui_client;
 pub mod sui_syncer;
 pub mod sui_transaction_builder;
+pub mod supply_reconciler;
 pub mod types;
 pub mod utils;
 
@@ -50,6 +50,14 @@ pub struct BridgeMetrics {
     pub(crate) eth_rpc_queries_latency: HistogramVec,
 
     pub(crate) gas_coin_balance: IntGauge,
+
+    pub(crate) reconciler_observed_transfer_amount: IntGaugeVec,
+    pub(crate) reconciler_sui_token_supply: IntGaugeVec,
+    pub(crate) reconciler_eth_locked_amount: IntGaugeVec,
+    pub(crate) reconciler_supply_divergence: IntGaugeVec,
+    pub(crate) reconciler_anomalies: IntCounterVec,
+    pub(crate) reconciler_emergency_proposals: IntCounter,
+    pub(crate) err_reconciler_queries: IntCounter,
 }
 
 impl BridgeMetrics {
@@ -232,6 +240,53 @@ impl BridgeMetrics {
                 registry,
             )
             .unwrap(),
+            reconciler_observed_transfer_amount: register_int_gauge_vec_with_registry!(
+                "bridge_reconciler_observed_transfer_amount",
+                "Sum of token transfer amounts (sui adjusted) observed by the syncers, by route and token",
+                &["source_chain", "destination_chain", "token_id"],
+                registry,
+            )
+            .unwrap(),
+            reconciler_sui_token_supply: register_int_gauge_vec_with_registry!(
+                "bridge_reconciler_sui_token_supply",
+                "Total supply of bridged tokens on Sui, by token",
+                &["token_id"],
+                registry,
+            )
+            .unwrap(),
+            reconciler_eth_locked_amount: register_int_gauge_vec_with_registry!(
+                "bridge_reconciler_eth_locked_amount",
+                "Amount of tokens (sui adjusted) locked in the Eth bridge vault, by token",
+                &["token_id"],
+                registry,
+            )
+            .unwrap(),
+            reconciler_supply_divergence: register_int_gauge_vec_with_registry!(
+                "bridge_reconciler_supply_divergence",
+                "Amount by which a supply invariant is violated (sui adjusted), by token and check",
+                &["token_id", "check"],
+                registry,
+            )
+            .unwrap(),
+            reconciler_anomalies: register_int_counter_vec_with_registry!(
+                "bridge_reconciler_anomalies",
+                "Total number of supply anomalies detected by the reconciler, by check",
+                &["check"],
+                registry,
+            )
+            .unwrap(),
+            reconciler_emergency_proposals: register_int_counter_with_registry!(
+                "bridge_reconciler_emergency_proposals",
+                "Total number of emergency pause actions proposed by the reconciler",
+                registry,
+            )
+            .unwrap(),
+            err_reconciler_queries: register_int_counter_with_registry!(
+                "bridge_err_reconciler_queries",
+                "Total number of failed on-chain queries in the reconciler",
+                registry,
+            )
+            .unwrap(),
         }
     }
 
@@ -10,9 +10,12 @@ use crate::{
     metrics::BridgeMetrics,
     monitor::BridgeMonitor,
     orchestrator::BridgeOrchestrator,
-    server::{handler::BridgeRequestHandler, run_server, BridgeNodePublicMetadata},
-    storage::BridgeOrchestratorTables,
+    server::{
+        handler::BridgeRequestHandler, run_admin_server, run_server, BridgeNodePublicMetadata,
+    },
+    storage::{now_ms, BridgeOrchestratorTables},
     sui_syncer::SuiSyncer,
+    supply_reconciler::BridgeSupplyReconciler,
 };
 use arc_swap::ArcSwap;
 use ethers::types::Address as EthAddress;
@@ -29,7 +32,10 @@ use sui_types::{
     Identifier,
 };
 use tokio::task::JoinHandle;
-use tracing::info;
+use tracing::{info, warn};
+
+/// How often the action history is pruned of records older than the retention period.
+const ACTION_HISTORY_PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 pub async fn run_bridge_node(
     config: BridgeNodeConfig,
@@ -41,38 +47,54 @@ pub async fn run_bridge_node(
     let (server_config, client_config) = config.validate(metrics.clone()).await?;
 
     // Start Client
-    let _handles = if let Some(client_config) = client_config {
-        start_client_components(client_config, metrics.clone()).await
+    let (_handles, store) = if let Some(client_config) = client_config {
+        let store: std::sync::Arc<BridgeOrchestratorTables> =
+            BridgeOrchestratorTables::new(&client_config.db_path.join("client"));
+        (
+            start_client_components(client_config, store.clone(), metrics.clone()).await?,
+            Some(store),
+        )
     } else {
-        Ok(vec![])
-    }?;
+        (vec![], None)
+    };
 
     // Start Server
     let socket_address = SocketAddr::new(
         IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
         server_config.server_listen_port,
     );
-    Ok(run_server(
-        &socket_address,
-        BridgeRequestHandler::new(
-            server_config.key,
-            server_config.sui_client,
-            server_config.eth_client,
-            server_config.approved_governance_actions,
+    let mut handler = BridgeRequestHandler::new(
+        server_config.key,
+        server_config.sui_client,
+        server_config.eth_client,
+        server_config.approved_governance_actions,
+        metrics.clone(),
+    );
+    if let Some(store) = store {
+        handler = handler.with_action_history(store);
+    }
+    let handler = Arc::new(handler);
+    let metadata = Arc::new(metadata);
+    if let Some(admin_listen_port) = server_config.admin_listen_port {
+        let admin_socket_address =
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), admin_listen_port);
+        info!("Starting admin server at {}", admin_socket_address);
+        run_admin_server(
+            &admin_socket_address,
+            handler.clone(),
             metrics.clone(),
-        ),
-        metrics,
-        Arc::new(metadata),
-    ))
+            metadata.clone(),
+        );
+    }
+    Ok(run_server(&socket_address, handler, metrics, metadata))
 }
 
 // TODO: is there a way to clean up the overrides after it's stored in DB?
 async fn start_client_components(
     client_config: BridgeClientConfig,
+    store: Arc<BridgeOrchestratorTables>,
     metrics: Arc<BridgeMetrics>,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
-    let store: std::sync::Arc<BridgeOrchestratorTables> =
-        BridgeOrchestratorTables::new(&client_config.db_path.join("client"));
     let sui_modules_to_watch = get_sui_modules_to_watch(
         &store,
         client_config.sui_bridge_module_last_processed_event_id_override,
@@ -145,20 +167,67 @@ async fn start_client_components(
         sui_token_type_tags,
     );
     all_handles.push(spawn_logged_monitored_task!(monitor.run()));
+    all_handles.push(spawn_logged_monitored_task!(prune_action_history(
+        store.clone(),
+        client_config.action_history_retention,
+    )));
 
-    let orchestrator = BridgeOrchestrator::new(
-        sui_client,
+    let mut orchestrator = BridgeOrchestrator::new(
+        sui_client.clone(),
         sui_events_rx,
         eth_events_rx,
         store.clone(),
         monitor_tx,
-        metrics,
+        metrics.clone(),
     );
 
+    if let Some(reconciler_config) = client_config.supply_reconciler {
+        let (reconciler_tx, reconciler_rx) = mysten_metrics::metered_channel::channel(
+            10000,
+            &mysten_metrics::get_metrics()
+                .unwrap()
+                .channel_inflight
+                .with_label_values(&["supply_reconciler_queue"]),
+        );
+        let (proposed_actions_tx, proposed_actions_rx) = mysten_metrics::metered_channel::channel(
+            100,
+            &mysten_metrics::get_metrics()
+                .unwrap()
+                .channel_inflight
+                .with_label_values(&["proposed_actions_queue"]),
+        );
+        let reconciler = BridgeSupplyReconciler::new(
+            sui_client,
+            client_config.eth_client.clone(),
+            client_config.eth_contract_addresses.clone(),
+            reconciler_rx,
+            Some(proposed_actions_tx),
+            reconciler_config,
+            metrics,
+        );
+        all_handles.push(spawn_logged_monitored_task!(reconciler.run()));
+        orchestrator = orchestrator.with_supply_reconciler(reconciler_tx, proposed_actions_rx);
+    }
+
     all_handles.extend(orchestrator.run(bridge_action_executor).await);
     Ok(all_handles)
 }
 
+async fn prune_action_history(store: Arc<BridgeOrchestratorTables>, retention: Duration) {
+    let mut interval = tokio::time::interval(ACTION_HISTORY_PRUNING_INTERVAL);
+    loop {
+        interval.tick().await;
+        let cutoff_ms = now_ms().saturating_sub(retention.as_millis() as u64);
+        let store = store.clone();
+        match tokio::task::spawn_blocking(move || store.prune_action_history(cutoff_ms)).await {
+            Ok(Ok(0)) => (),
+            Ok(Ok(pruned)) => info!("Pruned {} actions from the action history", pruned),
+            Ok(Err(e)) => warn!("Failed to prune the action history: {:?}", e),
+            Err(e) => warn!("Action history pruning task failed: {:?}", e),
+        }
+    }
+}
+
 fn get_sui_modules_to_watch(
     store: &std::sync::Arc<BridgeOrchestratorTables>,
     sui_bridge_module_last_processed_event_id_override: Option<EventID>,
@@ -417,6 +486,9 @@ mod tests {
             approved_governance_actions: vec![],
             run_client: false,
             db_path: None,
+            supply_reconciler: None,
+            admin_listen_port: None,
+            action_history_retention_days: None,
         };
         // Spawn bridge node in memory
         let _handle = run_bridge_node(
@@ -480,6 +552,9 @@ mod tests {
             approved_governance_actions: vec![],
             run_client: true,
             db_path: Some(db_path),
+            supply_reconciler: None,
+            admin_listen_port: None,
+            action_history_retention_days: None,
         };
         // Spawn bridge node in memory
         let _handle = run_bridge_node(
@@ -554,6 +629,9 @@ mod tests {
             approved_governance_actions: vec![],
             run_client: true,
             db_path: Some(db_path),
+            supply_reconciler: None,
+            admin_listen_port: None,
+            action_history_retention_days: None,
         };
         // Spawn bridge node in memory
         let _handle = run_bridge_node(
@@ -5,6 +5,8 @@
 //! 1. monitors Sui and Ethereum events with the help of `SuiSyncer` and `EthSyncer`
 //! 2. updates WAL table and cursor tables
 //! 2. hands actions to `BridgeExecutor` for execution
+//! 3. forwards token transfers to `BridgeSupplyReconciler` and executes the
+//!    actions it proposes, when a reconciler is attached
 
 use crate::abi::EthBridgeEvent;
 use crate::action_executor::{
@@ -15,7 +17,7 @@ use crate::events::SuiBridgeEvent;
 use crate::metrics::BridgeMetrics;
 use crate::storage::BridgeOrchestratorTables;
 use crate::sui_client::{SuiClient, SuiClientInner};
-use crate::types::EthLog;
+use crate::types::{BridgeAction, EthLog};
 use ethers::types::Address as EthAddress;
 use mysten_metrics::spawn_logged_monitored_task;
 use std::sync::Arc;
@@ -30,6 +32,8 @@ pub struct BridgeOrchestrator<C> {
     eth_events_rx: mysten_metrics::metered_channel::Receiver<(EthAddress, u64, Vec<EthLog>)>,
     store: Arc<BridgeOrchestratorTables>,
     monitor_tx: mysten_metrics::metered_channel::Sender<SuiBridgeEvent>,
+    reconciler_tx: Option<mysten_metrics::metered_channel::Sender<BridgeAction>>,
+    proposed_actions_rx: Option<mysten_metrics::metered_channel::Receiver<BridgeAction>>,
     metrics: Arc<BridgeMetrics>,
 }
 
@@ -51,10 +55,24 @@ where
             eth_events_rx,
             store,
             monitor_tx,
+            reconciler_tx: None,
+            proposed_actions_rx: None,
             metrics,
         }
     }
 
+    /// Forwards observed token transfers to `reconciler_tx` and submits actions
+    /// received from `proposed_actions_rx` for execution.
+    pub fn with_supply_reconciler(
+        mut self,
+        reconciler_tx: mysten_metrics::metered_channel::Sender<BridgeAction>,
+        proposed_actions_rx: mysten_metrics::metered_channel::Receiver<BridgeAction>,
+    ) -> Self {
+        self.reconciler_tx = Some(reconciler_tx);
+        self.proposed_actions_rx = Some(proposed_actions_rx);
+        self
+    }
+
     pub async fn run(
         self,
         bridge_action_executor: impl BridgeActionExecutorTrait,
@@ -73,6 +91,7 @@ where
             executor_sender_clone,
             self.sui_events_rx,
             self.monitor_tx,
+            self.reconciler_tx.clone(),
             metrics_clone,
         )));
         let store_clone = self.store.clone();
@@ -88,11 +107,22 @@ where
                 .expect("Submit to executor should not fail");
         }
 
+        if let Some(proposed_actions_rx) = self.proposed_actions_rx {
+            task_handles.push(spawn_logged_monitored_task!(
+                Self::run_proposed_actions_watcher(
+                    store_clone.clone(),
+                    executor_sender.clone(),
+                    proposed_actions_rx,
+                )
+            ));
+        }
+
         let metrics_clone = self.metrics.clone();
         task_handles.push(spawn_logged_monitored_task!(Self::run_eth_watcher(
             store_clone,
             executor_sender,
             self.eth_events_rx,
+            self.reconciler_tx,
             metrics_clone,
         )));
 
@@ -104,6 +134,7 @@ where
         executor_tx: mysten_metrics::metered_channel::Sender<BridgeActionExecutionWrapper>,
         mut sui_events_rx: mysten_metrics::metered_channel::Receiver<(Identifier, Vec<SuiEvent>)>,
         monitor_tx: mysten_metrics::metered_channel::Sender<SuiBridgeEvent>,
+        reconciler_tx: Option<mysten_metrics::metered_channel::Sender<BridgeAction>>,
         metrics: Arc<BridgeMetrics>,
     ) {
         info!("Starting sui watcher task");
@@ -169,6 +200,7 @@ where
                 store
                     .insert_pending_actions(&actions)
                     .expect("Store operation should not fail");
+                forward_to_reconciler(&reconciler_tx, &actions).await;
                 for action in actions {
                     submit_to_executor(&executor_tx, action)
                         .await
@@ -193,6 +225,7 @@ where
             u64,
             Vec<EthLog>,
         )>,
+        reconciler_tx: Option<mysten_metrics::metered_channel::Sender<BridgeAction>>,
         metrics: Arc<BridgeMetrics>,
     ) {
         info!("Starting eth watcher task");
@@ -244,6 +277,7 @@ where
                 store
                     .insert_pending_actions(&actions)
                     .expect("Store operation should not fail");
+                forward_to_reconciler(&reconciler_tx, &actions).await;
                 // Execution will remove the pending actions from DB when the action is completed.
                 for action in actions {
                     submit_to_executor(&executor_tx, action)
@@ -258,6 +292,40 @@ where
         }
         panic!("Eth event channel was closed");
     }
+
+    async fn run_proposed_actions_watcher(
+        store: Arc<BridgeOrchestratorTables>,
+        executor_tx: mysten_metrics::metered_channel::Sender<BridgeActionExecutionWrapper>,
+        mut proposed_actions_rx: mysten_metrics::metered_channel::Receiver<BridgeAction>,
+    ) {
+        info!("Starting proposed actions watcher task");
+        while let Some(action) = proposed_actions_rx.recv().await {
+            info!("Received proposed action: {:?}", action);
+            // Write action to pending WAL
+            store
+                .insert_pending_actions(&[action.clone()])
+                .expect("Store operation should not fail");
+            submit_to_executor(&executor_tx, action)
+                .await
+                .expect("Submit to executor should not fail");
+        }
+        panic!("Proposed actions channel was closed unexpectedly");
+    }
+}
+
+async fn forward_to_reconciler(
+    reconciler_tx: &Option<mysten_metrics::metered_channel::Sender<BridgeAction>>,
+    actions: &[BridgeAction],
+) {
+    let Some(reconciler_tx) = reconciler_tx else {
+        return;
+    };
+    for action in actions {
+        reconciler_tx
+            .send(action.clone())
+            .await
+            .expect("Sending action to reconciler channel should not fail");
+    }
 }
 
 #[cfg(test)]
@@ -7,12 +7,16 @@ use crate::crypto::{BridgeAuthorityKeyPair, BridgeAuthoritySignInfo};
 use crate::error::{BridgeError, BridgeResult};
 use crate::eth_client::EthClient;
 use crate::metrics::BridgeMetrics;
+use crate::storage::BridgeOrchestratorTables;
 use crate::sui_client::{SuiClient, SuiClientInner};
-use crate::types::{BridgeAction, SignedBridgeAction};
+use crate::types::{
+    BridgeAction, BridgeActionDigest, BridgeActionHistoryRecord, SignedBridgeAction,
+};
 use async_trait::async_trait;
 use axum::Json;
 use ethers::providers::JsonRpcClient;
 use ethers::types::TxHash;
+use fastcrypto::encoding::{Base58, Encoding};
 use lru::LruCache;
 use std::num::NonZeroUsize;
 use std::str::FromStr;
@@ -48,6 +52,20 @@ pub trait BridgeRequestHandlerTrait {
         &self,
         action: BridgeAction,
     ) -> Result<Json<SignedBridgeAction>, BridgeError>;
+
+    /// Handles a request for the history of a BridgeAction processed by
+    /// this node. The input is the base58 encoded BridgeActionDigest.
+    async fn handle_action_history(
+        &self,
+        action_digest_base58: String,
+    ) -> Result<Json<Option<BridgeActionHistoryRecord>>, BridgeError>;
+
+    /// Handles a request for the latest `limit` BridgeActions processed
+    /// by this node, most recently observed first.
+    async fn handle_latest_action_history(
+        &self,
+        limit: usize,
+    ) -> Result<Json<Vec<BridgeActionHistoryRecord>>, BridgeError>;
 }
 
 #[async_trait::async_trait]
@@ -225,6 +243,7 @@ pub struct BridgeRequestHandler {
         BridgeAction,
         oneshot::Sender<BridgeResult<SignedBridgeAction>>,
     )>,
+    action_history: Option<Arc<BridgeOrchestratorTables>>,
 }
 
 impl BridgeRequestHandler {
@@ -284,8 +303,21 @@ impl BridgeRequestHandler {
             sui_signer_tx,
             eth_signer_tx,
             governance_signer_tx,
+            action_history: None,
         }
     }
+
+    /// Serves action history queries from the bridge client's store.
+    pub fn with_action_history(mut self, store: Arc<BridgeOrchestratorTables>) -> Self {
+        self.action_history = Some(store);
+        self
+    }
+
+    fn action_history_store(&self) -> BridgeResult<&Arc<BridgeOrchestratorTables>> {
+        self.action_history
+            .as_ref()
+            .ok_or(BridgeError::ActionHistoryUnavailable)
+    }
 }
 
 #[async_trait]
@@ -343,6 +375,38 @@ impl BridgeRequestHandlerTrait for BridgeRequestHandler {
         })?;
         Ok(Json(signed_action))
     }
+
+    async fn handle_action_history(
+        &self,
+        action_digest_base58: String,
+    ) -> Result<Json<Option<BridgeActionHistoryRecord>>, BridgeError> {
+        let digest = parse_action_digest(&action_digest_base58)?;
+        let record = self.action_history_store()?.get_action_history(&digest)?;
+        Ok(Json(record))
+    }
+
+    async fn handle_latest_action_history(
+        &self,
+        limit: usize,
+    ) -> Result<Json<Vec<BridgeActionHistoryRecord>>, BridgeError> {
+        let records = self
+            .action_history_store()?
+            .get_latest_action_history(limit)?;
+        Ok(Json(records))
+    }
+}
+
+/// Parses a base58 encoded BridgeActionDigest, as used in action history requests.
+pub(crate) fn parse_action_digest(action_digest_base58: &str) -> BridgeResult<BridgeActionDigest> {
+    Base58::decode(action_digest_base58)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(BridgeActionDigest::new)
+        .ok_or_else(|| {
+            BridgeError::InvalidBridgeClientRequest(format!(
+                "Invalid action digest: {action_digest_base58}"
+            ))
+        })
 }
 
 #[cfg(test)]
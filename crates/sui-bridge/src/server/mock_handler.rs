@@ -15,13 +15,13 @@ use crate::error::BridgeError;
 use crate::error::BridgeResult;
 use crate::metrics::BridgeMetrics;
 use crate::server::BridgeNodePublicMetadata;
-use crate::types::SignedBridgeAction;
+use crate::types::{BridgeActionHistoryRecord, SignedBridgeAction};
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use axum::Json;
 use sui_types::digests::TransactionDigest;
 
-use super::handler::BridgeRequestHandlerTrait;
+use super::handler::{parse_action_digest, BridgeRequestHandlerTrait};
 use super::make_router;
 
 #[allow(clippy::type_complexity)]
@@ -31,6 +31,8 @@ pub struct BridgeRequestMockHandler {
     sui_token_events:
         Arc<Mutex<HashMap<(TransactionDigest, u16), BridgeResult<SignedBridgeAction>>>>,
     sui_token_events_requested: Arc<Mutex<HashMap<(TransactionDigest, u16), u64>>>,
+    /// Most recently observed first
+    action_history: Arc<Mutex<Vec<BridgeActionHistoryRecord>>>,
 }
 
 impl BridgeRequestMockHandler {
@@ -39,9 +41,15 @@ impl BridgeRequestMockHandler {
             signer: Arc::new(ArcSwap::new(Arc::new(None))),
             sui_token_events: Arc::new(Mutex::new(HashMap::new())),
             sui_token_events_requested: Arc::new(Mutex::new(HashMap::new())),
+            action_history: Arc::new(Mutex::new(vec![])),
         }
     }
 
+    /// Adds a record to the action history, as the most recently observed action.
+    pub fn add_action_history_record(&self, record: BridgeActionHistoryRecord) {
+        self.action_history.lock().unwrap().insert(0, record);
+    }
+
     pub fn add_sui_event_response(
         &self,
         tx_digest: TransactionDigest,
@@ -126,6 +134,28 @@ impl BridgeRequestHandlerTrait for BridgeRequestMockHandler {
         let signed_action = SignedBridgeAction::new_from_data_and_sig(action, sig);
         Ok(Json(signed_action))
     }
+
+    async fn handle_action_history(
+        &self,
+        action_digest_base58: String,
+    ) -> Result<Json<Option<BridgeActionHistoryRecord>>, BridgeError> {
+        let digest = parse_action_digest(&action_digest_base58)?;
+        let history = self.action_history.lock().unwrap();
+        Ok(Json(
+            history
+                .iter()
+                .find(|record| record.digest == digest)
+                .cloned(),
+        ))
+    }
+
+    async fn handle_latest_action_history(
+        &self,
+        limit: usize,
+    ) -> Result<Json<Vec<BridgeActionHistoryRecord>>, BridgeError> {
+        let history = self.action_history.lock().unwrap();
+        Ok(Json(history.iter().take(limit).cloned().collect()))
+    }
 }
 
 pub fn run_mock_server(
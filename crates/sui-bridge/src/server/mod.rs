@@ -10,8 +10,9 @@ use crate::{
     server::handler::{BridgeRequestHandler, BridgeRequestHandlerTrait},
     types::{
         AddTokensOnEvmAction, AddTokensOnSuiAction, AssetPriceUpdateAction,
-        BlocklistCommitteeAction, BlocklistType, BridgeAction, EmergencyAction,
-        EmergencyActionType, EvmContractUpgradeAction, LimitUpdateAction, SignedBridgeAction,
+        BlocklistCommitteeAction, BlocklistType, BridgeAction, BridgeActionHistoryRecord,
+        EmergencyAction, EmergencyActionType, EvmContractUpgradeAction, LimitUpdateAction,
+        SignedBridgeAction,
     },
 };
 use axum::{
@@ -57,6 +58,12 @@ pub const ADD_TOKENS_ON_SUI_PATH: &str =
     "/sign/add_tokens_on_sui/:chain_id/:nonce/:native/:token_ids/:token_type_names/:token_prices";
 pub const ADD_TOKENS_ON_EVM_PATH: &str =
     "/sign/add_tokens_on_evm/:chain_id/:nonce/:native/:token_ids/:token_addresses/:token_sui_decimals/:token_prices";
+// Served by the admin server only, see `run_admin_server`.
+pub const ACTION_HISTORY_PATH: &str = "/history/action/:action_digest";
+pub const LATEST_ACTION_HISTORY_PATH: &str = "/history/latest/:limit";
+
+/// The maximal number of records returned by one `LATEST_ACTION_HISTORY_PATH` request.
+pub const MAX_ACTION_HISTORY_QUERY_LIMIT: usize = 1000;
 
 /// BridgeNode's public metadata that is acceesible via the `/ping` endpoint.
 // Be careful with what to put here, as it is public.
@@ -79,7 +86,7 @@ impl BridgeNodePublicMetadata {
 
 pub fn run_server(
     socket_address: &SocketAddr,
-    handler: BridgeRequestHandler,
+    handler: Arc<BridgeRequestHandler>,
     metrics: Arc<BridgeMetrics>,
     metadata: Arc<BridgeNodePublicMetadata>,
 ) -> tokio::task::JoinHandle<()> {
@@ -88,7 +95,28 @@ pub fn run_server(
         let listener = tokio::net::TcpListener::bind(socket_address).await.unwrap();
         axum::serve(
             listener,
-            make_router(Arc::new(handler), metrics, metadata).into_make_service(),
+            make_router(handler, metrics, metadata).into_make_service(),
+        )
+        .await
+        .unwrap();
+    })
+}
+
+/// Runs the admin server, which serves the action history recorded by the
+/// bridge client. Unlike the signing server, it is meant for the operator
+/// only, so it should not be exposed publicly.
+pub fn run_admin_server(
+    socket_address: &SocketAddr,
+    handler: Arc<BridgeRequestHandler>,
+    metrics: Arc<BridgeMetrics>,
+    metadata: Arc<BridgeNodePublicMetadata>,
+) -> tokio::task::JoinHandle<()> {
+    let socket_address = *socket_address;
+    tokio::spawn(async move {
+        let listener = tokio::net::TcpListener::bind(socket_address).await.unwrap();
+        axum::serve(
+            listener,
+            make_admin_router(handler, metrics, metadata).into_make_service(),
         )
         .await
         .unwrap();
@@ -125,6 +153,21 @@ pub(crate) fn make_router(
         .with_state((handler, metrics, metadata))
 }
 
+pub(crate) fn make_admin_router(
+    handler: Arc<impl BridgeRequestHandlerTrait + Sync + Send + 'static>,
+    metrics: Arc<BridgeMetrics>,
+    metadata: Arc<BridgeNodePublicMetadata>,
+) -> Router {
+    Router::new()
+        .route("/", get(health_check))
+        .route(ACTION_HISTORY_PATH, get(handle_action_history))
+        .route(
+            LATEST_ACTION_HISTORY_PATH,
+            get(handle_latest_action_history),
+        )
+        .with_state((handler, metrics, metadata))
+}
+
 impl axum::response::IntoResponse for BridgeError {
     // TODO: distinguish client error.
     fn into_response(self) -> axum::response::Response {
@@ -572,6 +615,44 @@ async fn handle_add_tokens_on_evm(
     with_metrics!(metrics.clone(), "handle_add_tokens_on_evm", future).await
 }
 
+#[instrument(level = "error", skip_all, fields(action_digest=action_digest))]
+async fn handle_action_history(
+    Path(action_digest): Path<String>,
+    State((handler, metrics, _metadata)): State<(
+        Arc<impl BridgeRequestHandlerTrait + Sync + Send>,
+        Arc<BridgeMetrics>,
+        Arc<BridgeNodePublicMetadata>,
+    )>,
+) -> Result<Json<Option<BridgeActionHistoryRecord>>, BridgeError> {
+    let future = async {
+        let record = handler.handle_action_history(action_digest).await?;
+        Ok(record)
+    };
+    with_metrics!(metrics.clone(), "handle_action_history", future).await
+}
+
+#[instrument(level = "error", skip_all, fields(limit=limit))]
+async fn handle_latest_action_history(
+    Path(limit): Path<usize>,
+    State((handler, metrics, _metadata)): State<(
+        Arc<impl BridgeRequestHandlerTrait + Sync + Send>,
+        Arc<BridgeMetrics>,
+        Arc<BridgeNodePublicMetadata>,
+    )>,
+) -> Result<Json<Vec<BridgeActionHistoryRecord>>, BridgeError> {
+    let future = async {
+        if limit > MAX_ACTION_HISTORY_QUERY_LIMIT {
+            return Err(BridgeError::InvalidBridgeClientRequest(format!(
+                "Limit {} exceeds the maximum of {}",
+                limit, MAX_ACTION_HISTORY_QUERY_LIMIT
+            )));
+        }
+        let records = handler.handle_latest_action_history(limit).await?;
+        Ok(records)
+    };
+    with_metrics!(metrics.clone(), "handle_latest_action_history", future).await
+}
+
 #[macro_export]
 macro_rules! with_metrics {
     ($metrics:expr, $type_:expr, $func:expr) => {
@@ -615,7 +696,9 @@ mod tests {
     use super::*;
     use crate::client::bridge_client::BridgeClient;
     use crate::server::mock_handler::BridgeRequestMockHandler;
-    use crate::test_utils::get_test_authorities_and_run_mock_bridge_server;
+    use crate::test_utils::{
+        get_test_authorities_and_run_mock_bridge_server, get_test_sui_to_eth_bridge_action,
+    };
     use crate::types::BridgeCommittee;
 
     #[tokio::test]
@@ -736,6 +819,99 @@ mod tests {
         client.request_sign_bridge_action(action).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_bridge_server_handle_action_history_paths() {
+        let mock = BridgeRequestMockHandler::new();
+        let actions = (0..2)
+            .map(|i| {
+                get_test_sui_to_eth_bridge_action(
+                    None,
+                    Some(i),
+                    Some(i as u64),
+                    Some(100),
+                    None,
+                    None,
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+        for action in &actions {
+            mock.add_action_history_record(BridgeActionHistoryRecord::new(action.clone()));
+        }
+        let public_url = serve(make_router(
+            Arc::new(mock.clone()),
+            Arc::new(BridgeMetrics::new_for_testing()),
+            Arc::new(BridgeNodePublicMetadata::empty_for_testing()),
+        ))
+        .await;
+        let admin_url = serve(make_admin_router(
+            Arc::new(mock),
+            Arc::new(BridgeMetrics::new_for_testing()),
+            Arc::new(BridgeNodePublicMetadata::empty_for_testing()),
+        ))
+        .await;
+        let client = reqwest::Client::new();
+        let get = |url: String| {
+            let request = client.get(url);
+            async move { request.send().await.unwrap() }
+        };
+        let digest = |action: &BridgeAction| {
+            serde_json::to_value(action.digest())
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        // History is only served by the admin server
+        let resp = get(format!("{public_url}/history/latest/10")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = get(format!(
+            "{public_url}/history/action/{}",
+            digest(&actions[0])
+        ))
+        .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = get(format!(
+            "{admin_url}/history/action/{}",
+            digest(&actions[0])
+        ))
+        .await;
+        let record: Option<BridgeActionHistoryRecord> = resp.json().await.unwrap();
+        assert_eq!(record.unwrap().digest, actions[0].digest());
+        let unknown = get_test_sui_to_eth_bridge_action(None, None, None, None, None, None, None);
+        let resp = get(format!("{admin_url}/history/action/{}", digest(&unknown))).await;
+        let record: Option<BridgeActionHistoryRecord> = resp.json().await.unwrap();
+        assert!(record.is_none());
+        let resp = get(format!("{admin_url}/history/action/not_a_digest")).await;
+        assert!(!resp.status().is_success());
+
+        // Latest observed first
+        let resp = get(format!("{admin_url}/history/latest/10")).await;
+        let records: Vec<BridgeActionHistoryRecord> = resp.json().await.unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.digest).collect::<Vec<_>>(),
+            vec![actions[1].digest(), actions[0].digest()]
+        );
+        let resp = get(format!("{admin_url}/history/latest/1")).await;
+        let records: Vec<BridgeActionHistoryRecord> = resp.json().await.unwrap();
+        assert_eq!(records.len(), 1);
+        let resp = get(format!(
+            "{admin_url}/history/latest/{}",
+            MAX_ACTION_HISTORY_QUERY_LIMIT + 1
+        ))
+        .await;
+        assert!(!resp.status().is_success());
+    }
+
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        url
+    }
+
     fn setup() -> BridgeClient {
         let mock = BridgeRequestMockHandler::new();
         let (_handles, authorities, mut secrets) =
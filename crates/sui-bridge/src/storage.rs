@@ -3,9 +3,11 @@
 
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 use sui_types::Identifier;
 
+use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
 use typed_store::rocks::{DBMap, MetricConf};
 use typed_store::traits::TableSummary;
@@ -14,7 +16,15 @@ use typed_store::DBMapUtils;
 use typed_store::Map;
 
 use crate::error::{BridgeError, BridgeResult};
-use crate::types::{BridgeAction, BridgeActionDigest};
+use crate::types::{
+    BridgeAction, BridgeActionDigest, BridgeActionHistoryRecord, BridgeActionStatus,
+    BridgeActionStatusTransition, VerifiedCertifiedBridgeAction,
+};
+
+/// Serializes the read-modify-write updates of `action_history`, which the
+/// orchestrator and the executor's signing and execution tasks make concurrently
+/// for the same action. Tables can't hold anything but `DBMap`s.
+static ACTION_HISTORY_LOCK: Mutex<()> = Mutex::new(());
 
 #[derive(DBMapUtils)]
 pub struct BridgeOrchestratorTables {
@@ -24,6 +34,10 @@ pub struct BridgeOrchestratorTables {
     pub(crate) sui_syncer_cursors: DBMap<Identifier, EventID>,
     /// contract address to the last processed block
     pub(crate) eth_syncer_cursors: DBMap<ethers::types::Address, u64>,
+    /// every BridgeAction the orchestrator has received, with its status transitions
+    pub(crate) action_history: DBMap<BridgeActionDigest, BridgeActionHistoryRecord>,
+    /// (first observed timestamp in ms, action digest) to order `action_history`
+    pub(crate) action_history_index: DBMap<(u64, BridgeActionDigest), ()>,
 }
 
 impl BridgeOrchestratorTables {
@@ -36,7 +50,10 @@ impl BridgeOrchestratorTables {
         ))
     }
 
+    /// Inserts actions into the pending WAL and records them as `Pending`
+    /// in the action history.
     pub(crate) fn insert_pending_actions(&self, actions: &[BridgeAction]) -> BridgeResult<()> {
+        let _guard = ACTION_HISTORY_LOCK.lock().unwrap();
         let mut batch = self.pending_actions.batch();
         batch
             .insert_batch(
@@ -46,6 +63,33 @@ impl BridgeOrchestratorTables {
             .map_err(|e| {
                 BridgeError::StorageError(format!("Couldn't insert into pending_actions: {:?}", e))
             })?;
+        let now = now_ms();
+        let mut records = HashMap::new();
+        let mut new_index_keys = vec![];
+        for action in actions {
+            if records.contains_key(&action.digest()) {
+                continue;
+            }
+            let (mut record, is_new) = self.get_or_new_history_record(action)?;
+            if is_new {
+                new_index_keys.push(((now, record.digest), ()));
+            }
+            push_transition(&mut record, BridgeActionStatus::Pending, now);
+            records.insert(record.digest, record);
+        }
+        batch
+            .insert_batch(&self.action_history, records)
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't insert into action_history: {:?}", e))
+            })?;
+        batch
+            .insert_batch(&self.action_history_index, new_index_keys)
+            .map_err(|e| {
+                BridgeError::StorageError(format!(
+                    "Couldn't insert into action_history_index: {:?}",
+                    e
+                ))
+            })?;
         batch
             .write()
             .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))
@@ -106,6 +150,74 @@ impl BridgeOrchestratorTables {
             .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))
     }
 
+    /// Records the committee signatures collected for an action.
+    pub(crate) fn record_action_certificate(
+        &self,
+        certificate: &VerifiedCertifiedBridgeAction,
+    ) -> BridgeResult<()> {
+        let _guard = ACTION_HISTORY_LOCK.lock().unwrap();
+        let (mut record, is_new) = self.get_or_new_history_record(certificate.data())?;
+        record.signatures = certificate.auth_sig().signatures.clone();
+        self.write_history_record(record, is_new)
+    }
+
+    /// Records that an action reached `status` on chain, optionally with the
+    /// digest of the Sui transaction this node used to get it there.
+    pub(crate) fn record_action_status(
+        &self,
+        action: &BridgeAction,
+        status: BridgeActionStatus,
+        sui_tx_digest: Option<TransactionDigest>,
+    ) -> BridgeResult<()> {
+        let _guard = ACTION_HISTORY_LOCK.lock().unwrap();
+        let (mut record, is_new) = self.get_or_new_history_record(action)?;
+        push_transition(&mut record, status, now_ms());
+        if sui_tx_digest.is_some() {
+            record.sui_tx_digest = sui_tx_digest;
+        }
+        self.write_history_record(record, is_new)
+    }
+
+    /// Must be called with `ACTION_HISTORY_LOCK` held until the record is written.
+    fn get_or_new_history_record(
+        &self,
+        action: &BridgeAction,
+    ) -> BridgeResult<(BridgeActionHistoryRecord, bool)> {
+        Ok(match self.get_action_history(&action.digest())? {
+            Some(record) => (record, false),
+            None => (BridgeActionHistoryRecord::new(action.clone()), true),
+        })
+    }
+
+    fn write_history_record(
+        &self,
+        record: BridgeActionHistoryRecord,
+        is_new: bool,
+    ) -> BridgeResult<()> {
+        let mut batch = self.action_history.batch();
+        if is_new {
+            batch
+                .insert_batch(
+                    &self.action_history_index,
+                    [((now_ms(), record.digest), ())],
+                )
+                .map_err(|e| {
+                    BridgeError::StorageError(format!(
+                        "Couldn't insert into action_history_index: {:?}",
+                        e
+                    ))
+                })?;
+        }
+        batch
+            .insert_batch(&self.action_history, [(record.digest, record)])
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't insert into action_history: {:?}", e))
+            })?;
+        batch
+            .write()
+            .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))
+    }
+
     pub fn get_all_pending_actions(&self) -> HashMap<BridgeActionDigest, BridgeAction> {
         self.pending_actions.unbounded_iter().collect()
     }
@@ -129,6 +241,95 @@ impl BridgeOrchestratorTables {
                 BridgeError::StorageError(format!("Couldn't get sui_syncer_cursors: {:?}", e))
             })
     }
+
+    pub fn get_action_history(
+        &self,
+        digest: &BridgeActionDigest,
+    ) -> BridgeResult<Option<BridgeActionHistoryRecord>> {
+        self.action_history
+            .get(digest)
+            .map_err(|e| BridgeError::StorageError(format!("Couldn't get action_history: {:?}", e)))
+    }
+
+    /// Returns up to `limit` history records, most recently observed first.
+    pub fn get_latest_action_history(
+        &self,
+        limit: usize,
+    ) -> BridgeResult<Vec<BridgeActionHistoryRecord>> {
+        let digests = self
+            .action_history_index
+            .unbounded_iter()
+            .skip_to_last()
+            .reverse()
+            .take(limit)
+            .map(|((_, digest), _)| digest)
+            .collect::<Vec<_>>();
+        let records = self.action_history.multi_get(&digests).map_err(|e| {
+            BridgeError::StorageError(format!("Couldn't get action_history: {:?}", e))
+        })?;
+        Ok(records.into_iter().flatten().collect())
+    }
+
+    /// Removes the history of actions first observed before `cutoff_ms`, except
+    /// for actions that are still pending. Returns the number of removed records.
+    pub(crate) fn prune_action_history(&self, cutoff_ms: u64) -> BridgeResult<usize> {
+        let _guard = ACTION_HISTORY_LOCK.lock().unwrap();
+        let mut index_keys = vec![];
+        let mut digests = vec![];
+        for ((timestamp_ms, digest), _) in self.action_history_index.unbounded_iter() {
+            if timestamp_ms >= cutoff_ms {
+                break;
+            }
+            let pending = self.pending_actions.contains_key(&digest).map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't get pending_actions: {:?}", e))
+            })?;
+            if !pending {
+                index_keys.push((timestamp_ms, digest));
+                digests.push(digest);
+            }
+        }
+        let mut batch = self.action_history.batch();
+        batch
+            .delete_batch(&self.action_history_index, index_keys)
+            .map_err(|e| {
+                BridgeError::StorageError(format!(
+                    "Couldn't delete from action_history_index: {:?}",
+                    e
+                ))
+            })?;
+        batch
+            .delete_batch(&self.action_history, &digests)
+            .map_err(|e| {
+                BridgeError::StorageError(format!("Couldn't delete from action_history: {:?}", e))
+            })?;
+        batch
+            .write()
+            .map_err(|e| BridgeError::StorageError(format!("Couldn't write batch: {:?}", e)))?;
+        Ok(digests.len())
+    }
+}
+
+/// Appends a transition to `status` unless the action has reached it before,
+/// e.g. when an event is replayed after a restart.
+fn push_transition(
+    record: &mut BridgeActionHistoryRecord,
+    status: BridgeActionStatus,
+    timestamp_ms: u64,
+) {
+    if record.transitions.iter().any(|t| t.status == status) {
+        return;
+    }
+    record.transitions.push(BridgeActionStatusTransition {
+        status,
+        timestamp_ms,
+    });
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
 }
 
 #[cfg(test)]
@@ -244,4 +445,165 @@ mod tests {
             sui_cursor
         );
     }
+
+    #[tokio::test]
+    async fn test_bridge_action_history() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = BridgeOrchestratorTables::new(temp_dir.path());
+
+        let action1 = get_test_sui_to_eth_bridge_action(
+            None,
+            Some(0),
+            Some(99),
+            Some(10000),
+            None,
+            None,
+            None,
+        );
+        let action2 = get_test_sui_to_eth_bridge_action(
+            None,
+            Some(1),
+            Some(100),
+            Some(10000),
+            None,
+            None,
+            None,
+        );
+        assert!(store
+            .get_action_history(&action1.digest())
+            .unwrap()
+            .is_none());
+        assert!(store.get_latest_action_history(10).unwrap().is_empty());
+
+        store.insert_pending_actions(&[action1.clone()]).unwrap();
+        // make sure action2 is observed strictly later
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        store
+            .insert_pending_actions(&[action2.clone(), action2.clone()])
+            .unwrap();
+
+        let record = store
+            .get_action_history(&action1.digest())
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.action, action1);
+        assert_eq!(record.status(), Some(&BridgeActionStatus::Pending));
+        assert!(record.signatures.is_empty());
+        assert!(record.sui_tx_digest.is_none());
+
+        // Latest observed first, duplicates are collapsed
+        let latest = store.get_latest_action_history(10).unwrap();
+        assert_eq!(
+            latest.iter().map(|r| r.digest).collect::<Vec<_>>(),
+            vec![action2.digest(), action1.digest()]
+        );
+        let latest = store.get_latest_action_history(1).unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].digest, action2.digest());
+
+        // Removing from the WAL does not remove history
+        store.remove_pending_actions(&[action1.digest()]).unwrap();
+        let tx_digest = TransactionDigest::random();
+        store
+            .record_action_status(&action1, BridgeActionStatus::Approved, Some(tx_digest))
+            .unwrap();
+        // Replaying a status that was already reached is a no-op
+        store.insert_pending_actions(&[action1.clone()]).unwrap();
+        store
+            .record_action_status(&action1, BridgeActionStatus::Approved, None)
+            .unwrap();
+
+        let record = store
+            .get_action_history(&action1.digest())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            record
+                .transitions
+                .iter()
+                .map(|t| t.status.clone())
+                .collect::<Vec<_>>(),
+            vec![BridgeActionStatus::Pending, BridgeActionStatus::Approved]
+        );
+        assert!(record.transitions[0].timestamp_ms <= record.transitions[1].timestamp_ms);
+        assert_eq!(record.sui_tx_digest, Some(tx_digest));
+        assert_eq!(store.get_latest_action_history(10).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_bridge_action_history_updates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = BridgeOrchestratorTables::new(temp_dir.path());
+
+        for i in 0..20 {
+            let action = get_test_sui_to_eth_bridge_action(
+                None,
+                Some(i),
+                Some(i as u64),
+                Some(10000),
+                None,
+                None,
+                None,
+            );
+            store.insert_pending_actions(&[action.clone()]).unwrap();
+            // Transitions recorded at the same time must not overwrite each other
+            std::thread::scope(|scope| {
+                for status in [BridgeActionStatus::Approved, BridgeActionStatus::Claimed] {
+                    let (store, action) = (&store, &action);
+                    scope.spawn(move || store.record_action_status(action, status, None).unwrap());
+                }
+            });
+            let record = store.get_action_history(&action.digest()).unwrap().unwrap();
+            assert_eq!(record.transitions.len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prune_bridge_action_history() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = BridgeOrchestratorTables::new(temp_dir.path());
+
+        let actions = (0..3)
+            .map(|i| {
+                get_test_sui_to_eth_bridge_action(
+                    None,
+                    Some(i),
+                    Some(i as u64),
+                    Some(10000),
+                    None,
+                    None,
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // action 0 is processed and action 1 is still pending before the cutoff,
+        // action 2 is observed after it
+        store.insert_pending_actions(&actions[..2]).unwrap();
+        store
+            .remove_pending_actions(&[actions[0].digest()])
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        let cutoff_ms = now_ms();
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        store.insert_pending_actions(&actions[2..]).unwrap();
+
+        assert_eq!(store.prune_action_history(cutoff_ms).unwrap(), 1);
+        assert!(store
+            .get_action_history(&actions[0].digest())
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            store
+                .get_latest_action_history(10)
+                .unwrap()
+                .iter()
+                .map(|r| r.digest)
+                .collect::<Vec<_>>(),
+            vec![actions[2].digest(), actions[1].digest()]
+        );
+
+        // Pruning again is a no-op
+        assert_eq!(store.prune_action_history(cutoff_ms).unwrap(), 0);
+    }
 }
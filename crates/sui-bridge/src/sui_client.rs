@@ -167,6 +167,20 @@ where
             .map_err(|e| BridgeError::InternalError(format!("Can't get bridge committee: {e}")))
     }
 
+    pub async fn get_bridge_summary_until_success(&self) -> BridgeSummary {
+        loop {
+            let Ok(Ok(summary)) = retry_with_max_elapsed_time!(
+                self.inner.get_bridge_summary(),
+                Duration::from_secs(30)
+            ) else {
+                // TODO: add metrics and fire alert
+                error!("Failed to get bridge summary");
+                continue;
+            };
+            return summary;
+        }
+    }
+
     pub async fn is_bridge_paused(&self) -> BridgeResult<bool> {
         self.get_bridge_summary()
             .await
@@ -195,6 +209,26 @@ where
             .collect()
     }
 
+    /// Returns the total supply on Sui of every token registered in the bridge treasury,
+    /// keyed by token id.
+    pub async fn get_token_total_supplies(&self) -> BridgeResult<HashMap<u8, u64>> {
+        let treasury = self.get_treasury_summary().await?;
+        let mut supplies = HashMap::new();
+        for (id, type_name) in treasury.id_token_type_map {
+            let supply = self
+                .inner
+                .get_total_supply(format!("0x{type_name}"))
+                .await
+                .map_err(|e| {
+                    BridgeError::InternalError(format!(
+                        "Can't get total supply of {type_name}: {e}"
+                    ))
+                })?;
+            supplies.insert(id, supply);
+        }
+        Ok(supplies)
+    }
+
     pub async fn get_notional_values(&self) -> BridgeResult<HashMap<u8, u64>> {
         let bridge_summary = self.get_bridge_summary().await?;
         bridge_summary
@@ -384,6 +418,8 @@ pub trait SuiClientInner: Send + Sync {
 
     async fn get_bridge_summary(&self) -> Result<BridgeSummary, Self::Error>;
 
+    async fn get_total_supply(&self, coin_type: String) -> Result<u64, Self::Error>;
+
     async fn execute_transaction_block_with_effects(
         &self,
         tx: Transaction,
@@ -467,6 +503,13 @@ impl SuiClientInner for SuiSdkClient {
         self.http().get_latest_bridge().await.map_err(|e| e.into())
     }
 
+    async fn get_total_supply(&self, coin_type: String) -> Result<u64, Self::Error> {
+        self.coin_read_api()
+            .get_total_supply(coin_type)
+            .await
+            .map(|supply| supply.value)
+    }
+
     async fn get_token_transfer_action_onchain_status(
         &self,
         bridge_object_arg: ObjectArg,
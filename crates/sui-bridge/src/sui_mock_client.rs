@@ -13,7 +13,8 @@ use sui_json_rpc_types::{EventFilter, EventPage, SuiEvent};
 use sui_types::base_types::ObjectID;
 use sui_types::base_types::ObjectRef;
 use sui_types::bridge::{
-    BridgeCommitteeSummary, BridgeSummary, MoveTypeParsedTokenTransferMessage,
+    BridgeCommitteeSummary, BridgeLimiterSummary, BridgeSummary, BridgeTreasurySummary,
+    MoveTypeParsedTokenTransferMessage,
 };
 use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
@@ -44,6 +45,10 @@ pub struct SuiMockClient {
     onchain_status: Arc<Mutex<HashMap<(u8, u64), BridgeActionStatus>>>,
     bridge_committee_summary: Arc<Mutex<Option<BridgeCommitteeSummary>>>,
     is_paused: Arc<Mutex<Option<IsBridgePaused>>>,
+    treasury_summary: Arc<Mutex<Option<BridgeTreasurySummary>>>,
+    limiter_summary: Arc<Mutex<Option<BridgeLimiterSummary>>>,
+    sequence_nums: Arc<Mutex<Vec<(u8, u64)>>>,
+    total_supplies: Arc<Mutex<HashMap<String, u64>>>,
     requested_transactions_tx: tokio::sync::broadcast::Sender<TransactionDigest>,
 }
 
@@ -61,6 +66,10 @@ impl SuiMockClient {
             onchain_status: Default::default(),
             bridge_committee_summary: Default::default(),
             is_paused: Default::default(),
+            treasury_summary: Default::default(),
+            limiter_summary: Default::default(),
+            sequence_nums: Default::default(),
+            total_supplies: Default::default(),
             requested_transactions_tx: tokio::sync::broadcast::channel(10000).0,
         }
     }
@@ -121,6 +130,25 @@ impl SuiMockClient {
         self.is_paused.lock().unwrap().replace(value);
     }
 
+    pub fn set_treasury_summary(&self, treasury: BridgeTreasurySummary) {
+        self.treasury_summary.lock().unwrap().replace(treasury);
+    }
+
+    pub fn set_limiter_summary(&self, limiter: BridgeLimiterSummary) {
+        self.limiter_summary.lock().unwrap().replace(limiter);
+    }
+
+    pub fn set_sequence_nums(&self, sequence_nums: Vec<(u8, u64)>) {
+        *self.sequence_nums.lock().unwrap() = sequence_nums;
+    }
+
+    pub fn set_total_supply(&self, coin_type: &str, supply: u64) {
+        self.total_supplies
+            .lock()
+            .unwrap()
+            .insert(coin_type.to_string(), supply);
+    }
+
     pub fn set_wildcard_transaction_response(
         &self,
         response: BridgeResult<SuiTransactionBlockResponse>,
@@ -212,20 +240,39 @@ impl SuiClientInner for SuiMockClient {
             bridge_version: 0,
             message_version: 0,
             chain_id: 0,
-            sequence_nums: vec![],
+            sequence_nums: self.sequence_nums.lock().unwrap().clone(),
             bridge_records_id: ObjectID::random(),
             is_frozen: self.is_paused.lock().unwrap().unwrap_or_default(),
-            limiter: Default::default(),
+            limiter: self
+                .limiter_summary
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or_default(),
             committee: self
                 .bridge_committee_summary
                 .lock()
                 .unwrap()
                 .clone()
                 .unwrap_or_default(),
-            treasury: Default::default(),
+            treasury: self
+                .treasury_summary
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or_default(),
         })
     }
 
+    async fn get_total_supply(&self, coin_type: String) -> Result<u64, Self::Error> {
+        Ok(*self
+            .total_supplies
+            .lock()
+            .unwrap()
+            .get(&coin_type)
+            .unwrap_or_else(|| panic!("No preset total supply found for {}", coin_type)))
+    }
+
     async fn get_token_transfer_action_onchain_status(
         &self,
         _bridge_object_arg: ObjectArg,
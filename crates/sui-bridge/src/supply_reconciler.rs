@@ -0,0 +1,776 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! `BridgeSupplyReconciler` cross-checks the token transfers observed by the
+//! syncers against on-chain state. It:
+//! 1. sums `TokenTransfer` actions per route and token
+//! 2. checks that bridged tokens on Sui are backed by tokens locked in the Eth vault
+//! 3. checks that Sui supplies do not grow faster than the observed Eth -> Sui transfers
+//! 4. checks that the limiter transfer records are internally consistent
+//!
+//! Anomalies are reported through metrics and logs. Optionally an `EmergencyButton`
+//! pause action is proposed when bridged tokens are no longer backed.
+
+use crate::abi::{eth_bridge_config, eth_erc20};
+use crate::config::{EthContractAddresses, SupplyReconcilerConfig};
+use crate::error::BridgeResult;
+use crate::eth_client::EthClient;
+use crate::metrics::BridgeMetrics;
+use crate::sui_client::{SuiClient, SuiClientInner};
+use crate::types::{BridgeAction, BridgeActionType, EmergencyAction, EmergencyActionType};
+use ethers::providers::JsonRpcClient;
+use ethers::types::{Address as EthAddress, U256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_types::bridge::{BridgeChainId, BridgeSummary};
+use tracing::{error, info, warn};
+
+const CHECK_BACKING: &str = "backing";
+const CHECK_FLOW: &str = "flow";
+const CHECK_LIMITER: &str = "limiter";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupplyAnomaly {
+    /// The supply of a bridged token on Sui exceeds what is locked in the Eth vault.
+    UnbackedSupply {
+        token_id: u8,
+        sui_supply: u128,
+        eth_locked: u128,
+    },
+    /// The supply of a token on Sui grew by more than the net Eth -> Sui transfers
+    /// observed since the baseline was taken.
+    UnexplainedSupplyGrowth {
+        token_id: u8,
+        supply_growth: i128,
+        observed_inflow: i128,
+    },
+    /// The total of a limiter transfer record does not match its per hour amounts.
+    InconsistentLimiterRecord {
+        source: BridgeChainId,
+        destination: BridgeChainId,
+        total_amount: u64,
+        per_hour_sum: u128,
+    },
+}
+
+impl SupplyAnomaly {
+    fn check(&self) -> &'static str {
+        match self {
+            SupplyAnomaly::UnbackedSupply { .. } => CHECK_BACKING,
+            SupplyAnomaly::UnexplainedSupplyGrowth { .. } => CHECK_FLOW,
+            SupplyAnomaly::InconsistentLimiterRecord { .. } => CHECK_LIMITER,
+        }
+    }
+}
+
+/// Running sums of the token transfer amounts (sui adjusted) observed by the syncers.
+#[derive(Debug, Default, Clone)]
+pub struct ObservedTransfers {
+    totals: HashMap<(BridgeChainId, BridgeChainId, u8), u128>,
+}
+
+impl ObservedTransfers {
+    /// Adds the amount of a token transfer action to the sums. Returns false
+    /// and ignores the action if it is not a token transfer.
+    pub fn record(&mut self, action: &BridgeAction) -> bool {
+        let Some((source, destination, token_id, amount)) = get_token_transfer(action) else {
+            return false;
+        };
+        *self
+            .totals
+            .entry((source, destination, token_id))
+            .or_default() += amount as u128;
+        true
+    }
+
+    pub fn total(&self, source: BridgeChainId, destination: BridgeChainId, token_id: u8) -> u128 {
+        self.totals
+            .get(&(source, destination, token_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the net amount of `token_id` observed moving from Eth to Sui.
+    pub fn net_inflow_to_sui(&self, token_id: u8) -> i128 {
+        self.totals
+            .iter()
+            .filter(|((_, _, id), _)| *id == token_id)
+            .map(|((source, _, _), amount)| {
+                if source.is_sui_chain() {
+                    -(*amount as i128)
+                } else {
+                    *amount as i128
+                }
+            })
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EthTokenInfo {
+    address: EthAddress,
+    eth_decimals: u8,
+    sui_decimals: u8,
+}
+
+/// Sui supplies and observed inflows at the time of the first reconciliation.
+#[derive(Debug)]
+struct SupplyBaseline {
+    supplies: HashMap<u8, u64>,
+    inflows: HashMap<u8, i128>,
+}
+
+pub struct BridgeSupplyReconciler<C, P> {
+    sui_client: Arc<SuiClient<C>>,
+    eth_client: Arc<EthClient<P>>,
+    eth_contracts: EthContractAddresses,
+    transfers_rx: mysten_metrics::metered_channel::Receiver<BridgeAction>,
+    proposed_actions_tx: Option<mysten_metrics::metered_channel::Sender<BridgeAction>>,
+    config: SupplyReconcilerConfig,
+    metrics: Arc<BridgeMetrics>,
+    observed: ObservedTransfers,
+    eth_tokens: HashMap<u8, EthTokenInfo>,
+    baseline: Option<SupplyBaseline>,
+    emergency_pause_proposed: bool,
+}
+
+impl<C, P> BridgeSupplyReconciler<C, P>
+where
+    C: SuiClientInner + 'static,
+    P: JsonRpcClient + 'static,
+{
+    pub fn new(
+        sui_client: Arc<SuiClient<C>>,
+        eth_client: Arc<EthClient<P>>,
+        eth_contracts: EthContractAddresses,
+        transfers_rx: mysten_metrics::metered_channel::Receiver<BridgeAction>,
+        proposed_actions_tx: Option<mysten_metrics::metered_channel::Sender<BridgeAction>>,
+        config: SupplyReconcilerConfig,
+        metrics: Arc<BridgeMetrics>,
+    ) -> Self {
+        Self {
+            sui_client,
+            eth_client,
+            eth_contracts,
+            transfers_rx,
+            proposed_actions_tx,
+            config,
+            metrics,
+            observed: ObservedTransfers::default(),
+            eth_tokens: HashMap::new(),
+            baseline: None,
+            emergency_pause_proposed: false,
+        }
+    }
+
+    pub async fn run(mut self) {
+        info!("Starting BridgeSupplyReconciler");
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                action = self.transfers_rx.recv() => {
+                    let Some(action) = action else {
+                        break;
+                    };
+                    self.record_transfer(&action);
+                }
+                _ = interval.tick() => {
+                    match self.reconcile().await {
+                        Ok(anomalies) => self.handle_anomalies(anomalies).await,
+                        Err(e) => {
+                            self.metrics.err_reconciler_queries.inc();
+                            warn!("Failed to reconcile bridge supplies: {:?}", e);
+                        }
+                    }
+                }
+            }
+        }
+        panic!("BridgeSupplyReconciler channel was closed unexpectedly");
+    }
+
+    fn record_transfer(&mut self, action: &BridgeAction) {
+        let Some((source, destination, token_id, _)) = get_token_transfer(action) else {
+            return;
+        };
+        self.observed.record(action);
+        self.metrics
+            .reconciler_observed_transfer_amount
+            .with_label_values(&[
+                &(source as u8).to_string(),
+                &(destination as u8).to_string(),
+                &token_id.to_string(),
+            ])
+            .set(to_gauge(self.observed.total(source, destination, token_id)));
+    }
+
+    async fn reconcile(&mut self) -> BridgeResult<Vec<SupplyAnomaly>> {
+        let summary = self.sui_client.get_bridge_summary().await?;
+        let supplies = self.sui_client.get_token_total_supplies().await?;
+        let native_tokens = get_sui_native_tokens(&summary);
+
+        let mut anomalies = vec![];
+        for (token_id, supply) in &supplies {
+            let token_label = token_id.to_string();
+            self.metrics
+                .reconciler_sui_token_supply
+                .with_label_values(&[&token_label])
+                .set(to_gauge(*supply as u128));
+            // Tokens native to Sui are locked on Sui rather than in the Eth vault.
+            if native_tokens.contains(token_id) {
+                continue;
+            }
+            let eth_locked = self.get_eth_locked_amount(*token_id).await?;
+            self.metrics
+                .reconciler_eth_locked_amount
+                .with_label_values(&[&token_label])
+                .set(to_gauge(eth_locked));
+            let divergence = (*supply as u128).saturating_sub(eth_locked);
+            self.metrics
+                .reconciler_supply_divergence
+                .with_label_values(&[&token_label, CHECK_BACKING])
+                .set(to_gauge(divergence));
+            if divergence > self.config.tolerance as u128 {
+                anomalies.push(SupplyAnomaly::UnbackedSupply {
+                    token_id: *token_id,
+                    sui_supply: *supply as u128,
+                    eth_locked,
+                });
+            }
+        }
+
+        // Transfers observed while the syncers are catching up may predate the baseline,
+        // so the flow check only alerts and never proposes an emergency pause.
+        match &self.baseline {
+            None => {
+                let inflows = supplies
+                    .keys()
+                    .map(|id| (*id, self.observed.net_inflow_to_sui(*id)))
+                    .collect();
+                info!("Taking supply baseline: {:?}", supplies);
+                self.baseline = Some(SupplyBaseline {
+                    supplies: supplies.clone(),
+                    inflows,
+                });
+            }
+            Some(baseline) => {
+                for (token_id, supply) in &supplies {
+                    let Some(baseline_supply) = baseline.supplies.get(token_id) else {
+                        continue;
+                    };
+                    let supply_growth = *supply as i128 - *baseline_supply as i128;
+                    let observed_inflow = self.observed.net_inflow_to_sui(*token_id)
+                        - baseline.inflows.get(token_id).copied().unwrap_or_default();
+                    let divergence = (supply_growth - observed_inflow).max(0) as u128;
+                    self.metrics
+                        .reconciler_supply_divergence
+                        .with_label_values(&[&token_id.to_string(), CHECK_FLOW])
+                        .set(to_gauge(divergence));
+                    if divergence > self.config.tolerance as u128 {
+                        anomalies.push(SupplyAnomaly::UnexplainedSupplyGrowth {
+                            token_id: *token_id,
+                            supply_growth,
+                            observed_inflow,
+                        });
+                    }
+                }
+            }
+        }
+
+        for (source, destination, record) in &summary.limiter.transfer_records {
+            let per_hour_sum = record
+                .per_hour_amounts
+                .iter()
+                .map(|amount| *amount as u128)
+                .sum::<u128>();
+            if per_hour_sum != record.total_amount as u128 {
+                anomalies.push(SupplyAnomaly::InconsistentLimiterRecord {
+                    source: *source,
+                    destination: *destination,
+                    total_amount: record.total_amount,
+                    per_hour_sum,
+                });
+            }
+        }
+
+        Ok(anomalies)
+    }
+
+    async fn handle_anomalies(&mut self, anomalies: Vec<SupplyAnomaly>) {
+        let mut unbacked = false;
+        for anomaly in &anomalies {
+            error!("Bridge supply anomaly detected: {:?}", anomaly);
+            self.metrics
+                .reconciler_anomalies
+                .with_label_values(&[anomaly.check()])
+                .inc();
+            unbacked |= matches!(anomaly, SupplyAnomaly::UnbackedSupply { .. });
+        }
+        if !unbacked || !self.config.propose_emergency_pause || self.emergency_pause_proposed {
+            return;
+        }
+        let Some(proposed_actions_tx) = &self.proposed_actions_tx else {
+            return;
+        };
+        let summary = match self.sui_client.get_bridge_summary().await {
+            Ok(summary) => summary,
+            Err(e) => {
+                self.metrics.err_reconciler_queries.inc();
+                warn!(
+                    "Failed to get bridge summary for emergency proposal: {:?}",
+                    e
+                );
+                return;
+            }
+        };
+        if summary.is_frozen {
+            info!("Bridge is already paused, skip proposing emergency pause");
+            return;
+        }
+        let action = match build_emergency_pause_action(&summary) {
+            Ok(action) => action,
+            Err(e) => {
+                error!("Failed to build emergency pause action: {:?}", e);
+                return;
+            }
+        };
+        error!("Proposing emergency pause action: {:?}", action);
+        proposed_actions_tx
+            .send(action)
+            .await
+            .expect("Sending proposed action should not fail");
+        self.metrics.reconciler_emergency_proposals.inc();
+        self.emergency_pause_proposed = true;
+    }
+
+    /// Returns the amount of `token_id` locked in the Eth vault, in sui adjusted units.
+    async fn get_eth_locked_amount(&mut self, token_id: u8) -> BridgeResult<u128> {
+        let info = match self.eth_tokens.get(&token_id) {
+            Some(info) => *info,
+            None => {
+                let info = self.get_eth_token_info(token_id).await?;
+                self.eth_tokens.insert(token_id, info);
+                info
+            }
+        };
+        let balance: U256 = self
+            .eth_client
+            .call_finalized(
+                info.address,
+                eth_erc20::BalanceOfCall {
+                    account: self.eth_contracts.bridge_vault,
+                },
+            )
+            .await?;
+        Ok(to_sui_adjusted_amount(
+            balance,
+            info.eth_decimals,
+            info.sui_decimals,
+        ))
+    }
+
+    async fn get_eth_token_info(&self, token_id: u8) -> BridgeResult<EthTokenInfo> {
+        let token: eth_bridge_config::SupportedTokensReturn = self
+            .eth_client
+            .call_finalized(
+                self.eth_contracts.bridge_config,
+                eth_bridge_config::SupportedTokensCall { token_id },
+            )
+            .await?;
+        let eth_decimals: u8 = self
+            .eth_client
+            .call_finalized(token.token_address, eth_erc20::DecimalsCall)
+            .await?;
+        Ok(EthTokenInfo {
+            address: token.token_address,
+            eth_decimals,
+            sui_decimals: token.sui_decimal,
+        })
+    }
+}
+
+/// Returns the source chain, destination chain, token id and sui adjusted amount
+/// of a token transfer action.
+fn get_token_transfer(action: &BridgeAction) -> Option<(BridgeChainId, BridgeChainId, u8, u64)> {
+    match action {
+        BridgeAction::SuiToEthBridgeAction(action) => {
+            let event = &action.sui_bridge_event;
+            Some((
+                event.sui_chain_id,
+                event.eth_chain_id,
+                event.token_id,
+                event.amount_sui_adjusted,
+            ))
+        }
+        BridgeAction::EthToSuiBridgeAction(action) => {
+            let event = &action.eth_bridge_event;
+            Some((
+                event.eth_chain_id,
+                event.sui_chain_id,
+                event.token_id,
+                event.sui_adjusted_amount,
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn get_sui_native_tokens(summary: &BridgeSummary) -> HashSet<u8> {
+    summary
+        .treasury
+        .id_token_type_map
+        .iter()
+        .filter(|(_, type_name)| {
+            summary
+                .treasury
+                .supported_tokens
+                .iter()
+                .any(|(name, metadata)| name == type_name && metadata.native_token)
+        })
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+fn build_emergency_pause_action(summary: &BridgeSummary) -> BridgeResult<BridgeAction> {
+    let nonce = summary
+        .sequence_nums
+        .iter()
+        .find(|(message_type, _)| *message_type == BridgeActionType::EmergencyButton as u8)
+        .map(|(_, seq_num)| *seq_num)
+        .unwrap_or_default();
+    Ok(BridgeAction::EmergencyAction(EmergencyAction {
+        nonce,
+        chain_id: BridgeChainId::try_from(summary.chain_id)?,
+        action_type: EmergencyActionType::Pause,
+    }))
+}
+
+fn to_sui_adjusted_amount(amount: U256, eth_decimals: u8, sui_decimals: u8) -> u128 {
+    let adjusted = if eth_decimals >= sui_decimals {
+        amount / U256::exp10((eth_decimals - sui_decimals) as usize)
+    } else {
+        amount.saturating_mul(U256::exp10((sui_decimals - eth_decimals) as usize))
+    };
+    u128::try_from(adjusted).unwrap_or(u128::MAX)
+}
+
+fn to_gauge(amount: u128) -> i64 {
+    i64::try_from(amount).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth_mock_provider::EthMockProvider;
+    use crate::sui_mock_client::SuiMockClient;
+    use crate::test_utils::{
+        get_test_eth_to_sui_bridge_action, get_test_sui_to_eth_bridge_action, mock_eth_call,
+    };
+    use ethers::abi::AbiEncode;
+    use ethers::types::Bytes;
+    use sui_types::bridge::{
+        BridgeLimiterSummary, BridgeTokenMetadata, BridgeTreasurySummary,
+        MoveTypeBridgeTransferRecord, TOKEN_ID_ETH,
+    };
+
+    const ETH_TYPE: &str =
+        "0x0000000000000000000000000000000000000000000000000000000000000abc::eth::ETH";
+
+    fn setup(
+        config: SupplyReconcilerConfig,
+    ) -> (
+        BridgeSupplyReconciler<SuiMockClient, EthMockProvider>,
+        SuiMockClient,
+        EthMockProvider,
+        EthContractAddresses,
+        mysten_metrics::metered_channel::Receiver<BridgeAction>,
+    ) {
+        telemetry_subscribers::init_for_testing();
+        let registry = prometheus::Registry::new();
+        mysten_metrics::init_metrics(&registry);
+
+        let sui_client_mock = SuiMockClient::default();
+        sui_client_mock.set_treasury_summary(BridgeTreasurySummary {
+            supported_tokens: vec![(
+                ETH_TYPE[2..].to_string(),
+                BridgeTokenMetadata {
+                    id: TOKEN_ID_ETH,
+                    decimal_multiplier: 100_000_000,
+                    notional_value: 10,
+                    native_token: false,
+                },
+            )],
+            id_token_type_map: vec![(TOKEN_ID_ETH, ETH_TYPE[2..].to_string())],
+        });
+        let eth_mock_provider = EthMockProvider::default();
+        let eth_contracts = EthContractAddresses {
+            sui_bridge: EthAddress::repeat_byte(1),
+            bridge_committee: EthAddress::repeat_byte(2),
+            bridge_config: EthAddress::repeat_byte(3),
+            bridge_limiter: EthAddress::repeat_byte(4),
+            bridge_vault: EthAddress::repeat_byte(5),
+        };
+        let weth = EthAddress::repeat_byte(6);
+        mock_eth_call(
+            &eth_mock_provider,
+            eth_contracts.bridge_config,
+            eth_bridge_config::SupportedTokensCall {
+                token_id: TOKEN_ID_ETH,
+            },
+            Bytes::from(
+                eth_bridge_config::SupportedTokensReturn {
+                    token_address: weth,
+                    sui_decimal: 8,
+                    native: false,
+                }
+                .encode(),
+            ),
+        );
+        mock_eth_call(
+            &eth_mock_provider,
+            weth,
+            eth_erc20::DecimalsCall,
+            Bytes::from(18u8.encode()),
+        );
+
+        let (proposed_actions_tx, proposed_actions_rx) = mysten_metrics::metered_channel::channel(
+            100,
+            &mysten_metrics::get_metrics()
+                .unwrap()
+                .channel_inflight
+                .with_label_values(&["unit_test_proposed_actions"]),
+        );
+        let (_transfers_tx, transfers_rx) = mysten_metrics::metered_channel::channel(
+            100,
+            &mysten_metrics::get_metrics()
+                .unwrap()
+                .channel_inflight
+                .with_label_values(&["unit_test_reconciler_transfers"]),
+        );
+        let reconciler = BridgeSupplyReconciler::new(
+            Arc::new(SuiClient::new_for_testing(sui_client_mock.clone())),
+            Arc::new(EthClient::new_mocked(
+                eth_mock_provider.clone(),
+                HashSet::new(),
+            )),
+            eth_contracts.clone(),
+            transfers_rx,
+            Some(proposed_actions_tx),
+            config,
+            Arc::new(BridgeMetrics::new_for_testing()),
+        );
+        (
+            reconciler,
+            sui_client_mock,
+            eth_mock_provider,
+            eth_contracts,
+            proposed_actions_rx,
+        )
+    }
+
+    fn mock_vault_balance(
+        eth_mock_provider: &EthMockProvider,
+        eth_contracts: &EthContractAddresses,
+        balance: U256,
+    ) {
+        mock_eth_call(
+            eth_mock_provider,
+            EthAddress::repeat_byte(6),
+            eth_erc20::BalanceOfCall {
+                account: eth_contracts.bridge_vault,
+            },
+            Bytes::from(balance.encode()),
+        );
+    }
+
+    fn test_config() -> SupplyReconcilerConfig {
+        SupplyReconcilerConfig {
+            interval_secs: 1,
+            tolerance: 0,
+            propose_emergency_pause: true,
+        }
+    }
+
+    #[test]
+    fn test_observed_transfers() {
+        let mut observed = ObservedTransfers::default();
+        let sui_to_eth = get_test_sui_to_eth_bridge_action(
+            None,
+            None,
+            None,
+            Some(100),
+            None,
+            None,
+            Some(TOKEN_ID_ETH),
+        );
+        let eth_to_sui = get_test_eth_to_sui_bridge_action(None, None, None, Some(TOKEN_ID_ETH));
+        assert!(observed.record(&sui_to_eth));
+        assert!(observed.record(&eth_to_sui));
+        assert!(observed.record(&eth_to_sui));
+        assert!(
+            !observed.record(&BridgeAction::EmergencyAction(EmergencyAction {
+                nonce: 0,
+                chain_id: BridgeChainId::SuiCustom,
+                action_type: EmergencyActionType::Pause,
+            }))
+        );
+
+        let BridgeAction::EthToSuiBridgeAction(eth_action) = &eth_to_sui else {
+            unreachable!()
+        };
+        let inflow = eth_action.eth_bridge_event.sui_adjusted_amount as i128;
+        assert_eq!(
+            observed.total(
+                eth_action.eth_bridge_event.eth_chain_id,
+                eth_action.eth_bridge_event.sui_chain_id,
+                TOKEN_ID_ETH
+            ),
+            2 * inflow as u128
+        );
+        assert_eq!(observed.net_inflow_to_sui(TOKEN_ID_ETH), 2 * inflow - 100);
+        assert_eq!(observed.net_inflow_to_sui(TOKEN_ID_ETH + 1), 0);
+    }
+
+    #[test]
+    fn test_to_sui_adjusted_amount() {
+        assert_eq!(to_sui_adjusted_amount(U256::exp10(18), 18, 8), 100_000_000);
+        assert_eq!(to_sui_adjusted_amount(U256::from(5), 6, 8), 500);
+        assert_eq!(to_sui_adjusted_amount(U256::from(7), 8, 8), 7);
+        assert_eq!(to_sui_adjusted_amount(U256::MAX, 8, 8), u128::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_backing_check() {
+        let (mut reconciler, sui_client_mock, eth_mock_provider, eth_contracts, mut proposed_rx) =
+            setup(test_config());
+
+        // 1 ETH locked on Eth, 1 ETH minted on Sui
+        sui_client_mock.set_total_supply(ETH_TYPE, 100_000_000);
+        mock_vault_balance(&eth_mock_provider, &eth_contracts, U256::exp10(18));
+        let anomalies = reconciler.reconcile().await.unwrap();
+        assert!(anomalies.is_empty());
+
+        // More ETH on Sui than locked on Eth
+        sui_client_mock.set_total_supply(ETH_TYPE, 100_000_001);
+        let anomalies = reconciler.reconcile().await.unwrap();
+        assert!(anomalies.contains(&SupplyAnomaly::UnbackedSupply {
+            token_id: TOKEN_ID_ETH,
+            sui_supply: 100_000_001,
+            eth_locked: 100_000_000,
+        }));
+
+        sui_client_mock.set_sequence_nums(vec![(BridgeActionType::EmergencyButton as u8, 3)]);
+        reconciler.handle_anomalies(anomalies.clone()).await;
+        assert_eq!(
+            proposed_rx.try_recv().unwrap(),
+            BridgeAction::EmergencyAction(EmergencyAction {
+                nonce: 3,
+                chain_id: BridgeChainId::SuiMainnet,
+                action_type: EmergencyActionType::Pause,
+            })
+        );
+        // The pause is only proposed once
+        reconciler.handle_anomalies(anomalies).await;
+        assert!(proposed_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_backing_check_without_proposal() {
+        let (mut reconciler, sui_client_mock, eth_mock_provider, eth_contracts, mut proposed_rx) =
+            setup(SupplyReconcilerConfig {
+                propose_emergency_pause: false,
+                tolerance: 10,
+                ..test_config()
+            });
+        mock_vault_balance(&eth_mock_provider, &eth_contracts, U256::exp10(18));
+
+        // Within tolerance
+        sui_client_mock.set_total_supply(ETH_TYPE, 100_000_010);
+        assert!(reconciler.reconcile().await.unwrap().is_empty());
+
+        sui_client_mock.set_total_supply(ETH_TYPE, 100_000_011);
+        let anomalies = reconciler.reconcile().await.unwrap();
+        assert_eq!(anomalies.len(), 1);
+        reconciler.handle_anomalies(anomalies).await;
+        assert!(proposed_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_flow_check() {
+        let (mut reconciler, sui_client_mock, eth_mock_provider, eth_contracts, _proposed_rx) =
+            setup(test_config());
+        mock_vault_balance(&eth_mock_provider, &eth_contracts, U256::exp10(20));
+
+        // First reconciliation takes the baseline
+        sui_client_mock.set_total_supply(ETH_TYPE, 1000);
+        assert!(reconciler.reconcile().await.unwrap().is_empty());
+
+        // Observe a deposit on Eth that is claimed on Sui
+        let eth_to_sui = get_test_eth_to_sui_bridge_action(None, None, None, Some(TOKEN_ID_ETH));
+        let BridgeAction::EthToSuiBridgeAction(eth_action) = &eth_to_sui else {
+            unreachable!()
+        };
+        let amount = eth_action.eth_bridge_event.sui_adjusted_amount;
+        reconciler.record_transfer(&eth_to_sui);
+        sui_client_mock.set_total_supply(ETH_TYPE, 1000 + amount);
+        assert!(reconciler.reconcile().await.unwrap().is_empty());
+
+        // Supply grows without any observed deposit
+        sui_client_mock.set_total_supply(ETH_TYPE, 1000 + amount + 1);
+        let anomalies = reconciler.reconcile().await.unwrap();
+        assert_eq!(
+            anomalies,
+            vec![SupplyAnomaly::UnexplainedSupplyGrowth {
+                token_id: TOKEN_ID_ETH,
+                supply_growth: amount as i128 + 1,
+                observed_inflow: amount as i128,
+            }]
+        );
+        // Flow anomalies do not trigger an emergency proposal
+        reconciler.handle_anomalies(anomalies).await;
+        assert!(!reconciler.emergency_pause_proposed);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_limiter_check() {
+        let (mut reconciler, sui_client_mock, eth_mock_provider, eth_contracts, _proposed_rx) =
+            setup(test_config());
+        mock_vault_balance(&eth_mock_provider, &eth_contracts, U256::exp10(18));
+        sui_client_mock.set_total_supply(ETH_TYPE, 100);
+        sui_client_mock.set_limiter_summary(BridgeLimiterSummary {
+            transfer_limit: vec![],
+            transfer_records: vec![
+                (
+                    BridgeChainId::EthMainnet,
+                    BridgeChainId::SuiMainnet,
+                    MoveTypeBridgeTransferRecord {
+                        hour_head: 10,
+                        hour_tail: 9,
+                        per_hour_amounts: vec![1, 2],
+                        total_amount: 3,
+                    },
+                ),
+                (
+                    BridgeChainId::SuiMainnet,
+                    BridgeChainId::EthMainnet,
+                    MoveTypeBridgeTransferRecord {
+                        hour_head: 10,
+                        hour_tail: 9,
+                        per_hour_amounts: vec![1, 2],
+                        total_amount: 4,
+                    },
+                ),
+            ],
+        });
+        assert_eq!(
+            reconciler.reconcile().await.unwrap(),
+            vec![SupplyAnomaly::InconsistentLimiterRecord {
+                source: BridgeChainId::SuiMainnet,
+                destination: BridgeChainId::EthMainnet,
+                total_amount: 4,
+                per_hour_sum: 3,
+            }]
+        );
+    }
+}
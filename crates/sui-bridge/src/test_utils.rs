@@ -19,11 +19,12 @@ use crate::{
         SuiToEthBridgeAction,
     },
 };
-use ethers::abi::{long_signature, ParamType};
+use ethers::abi::{long_signature, AbiEncode, ParamType};
+use ethers::contract::EthCall;
 use ethers::types::Address as EthAddress;
 use ethers::types::{
-    Block, BlockNumber, Filter, FilterBlockOption, Log, TransactionReceipt, TxHash, ValueOrArray,
-    U64,
+    Block, BlockNumber, Bytes, Filter, FilterBlockOption, Log, TransactionReceipt,
+    TransactionRequest, TxHash, ValueOrArray, U64,
 };
 use fastcrypto::encoding::{Encoding, Hex};
 use fastcrypto::traits::KeyPair;
@@ -178,6 +179,20 @@ pub fn mock_last_finalized_block(mock_provider: &EthMockProvider, block_number:
         .unwrap();
 }
 
+// Mocks eth_call of `call` on `contract` against the last finalized block,
+// see `EthClient::call_finalized`. `result` is the abi encoded return value.
+pub fn mock_eth_call<C: EthCall>(
+    mock_provider: &EthMockProvider,
+    contract: EthAddress,
+    call: C,
+    result: Bytes,
+) {
+    let tx = TransactionRequest::new().to(contract).data(call.encode());
+    mock_provider
+        .add_response("eth_call", (tx, "finalized"), result)
+        .unwrap();
+}
+
 // Mocks eth_getLogs and eth_getTransactionReceipt for the given address and block range.
 // The input log needs to have transaction_hash set.
 pub fn mock_get_logs(
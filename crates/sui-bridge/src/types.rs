@@ -203,7 +203,7 @@ impl Debug for BridgeActionKey {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, TryFromPrimitive)]
+#[derive(Debug, PartialEq, Eq, Clone, TryFromPrimitive, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum BridgeActionStatus {
     Pending = 0,
@@ -482,6 +482,42 @@ impl Message for BridgeAction {
     }
 }
 
+/// A status change of a BridgeAction as observed by this node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeActionStatusTransition {
+    pub status: BridgeActionStatus,
+    pub timestamp_ms: u64,
+}
+
+/// Everything this node knows about a BridgeAction it has processed:
+/// its status transitions, the committee signatures it collected and
+/// the Sui transaction that executed it (if this node executed it).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeActionHistoryRecord {
+    pub digest: BridgeActionDigest,
+    pub action: BridgeAction,
+    pub transitions: Vec<BridgeActionStatusTransition>,
+    pub signatures: BTreeMap<BridgeAuthorityPublicKeyBytes, BridgeAuthorityRecoverableSignature>,
+    pub sui_tx_digest: Option<TransactionDigest>,
+}
+
+impl BridgeActionHistoryRecord {
+    pub fn new(action: BridgeAction) -> Self {
+        Self {
+            digest: action.digest(),
+            action,
+            transitions: vec![],
+            signatures: BTreeMap::new(),
+            sui_tx_digest: None,
+        }
+    }
+
+    /// The latest known status, `None` if no transition was recorded yet.
+    pub fn status(&self) -> Option<&BridgeActionStatus> {
+        self.transitions.last().map(|t| &t.status)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EthLog {
     pub block_number: u64,
@@ -195,6 +195,9 @@ pub fn generate_bridge_node_config_and_write_to_file(
         approved_governance_actions: vec![],
         run_client,
         db_path: None,
+        supply_reconciler: None,
+        admin_listen_port: None,
+        action_history_retention_days: None,
     };
     if run_client {
         config.sui.bridge_client_key_path = Some(PathBuf::from("/path/to/your/bridge_client_key"));
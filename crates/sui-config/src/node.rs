@@ -183,6 +183,11 @@ pub struct NodeConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub firewall_config: Option<RemoteFirewallConfig>,
 
+    /// Maximum number of requests in a JSON-RPC batch request. Defaults to
+    /// `sui_json_rpc::DEFAULT_MAX_BATCH_SIZE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_rpc_max_batch_size: Option<usize>,
+
     #[serde(default)]
     pub execution_cache: ExecutionCacheConfig,
 
@@ -204,7 +209,17 @@ pub enum ExecutionCacheConfig {
     #[default]
     PassthroughCache,
     WritebackCache {
+        /// Maximum number of entries in each cache
         max_cache_size: Option<usize>,
+        /// Memory, in bytes, shared by the object, object-by-id, marker and package caches.
+        /// When set, these caches are sized by the estimated size of their entries instead of
+        /// by `max_cache_size`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memory_budget_bytes: Option<u64>,
+        /// Whether to periodically move memory from caches with high hit rates to caches with
+        /// low hit rates. Only applies when `memory_budget_bytes` is set.
+        #[serde(default = "bool_true")]
+        adaptive_sizing: bool,
     },
 }
 
@@ -746,6 +761,10 @@ pub struct StateSnapshotConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
+    /// If set, a full snapshot is only written for epochs that are a multiple of this interval.
+    /// Snapshots of other epochs are deltas of the preceding full snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_snapshot_interval_epochs: Option<u64>,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
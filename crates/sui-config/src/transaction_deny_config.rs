@@ -1,12 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt, str::FromStr};
 
+use anyhow::anyhow;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use sui_types::base_types::{ObjectID, SuiAddress};
 
+use crate::Config;
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TransactionDenyConfig {
@@ -33,6 +37,18 @@ pub struct TransactionDenyConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     address_deny_list: Vec<SuiAddress>,
 
+    /// A list of Move functions, written as `package::module::function`, that are not allowed to
+    /// be called directly from a programmable transaction. Unlike `package_deny_list`, this only
+    /// applies to the exact package ID, and not to calls made from within Move.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    function_deny_list: Vec<MoveQualifiedName>,
+
+    /// A list of Move struct types, written as `package::module::Name`, that are not allowed to
+    /// appear in the type arguments of a programmable transaction command, including nested
+    /// inside other type arguments (e.g. `vector<T>` or `Coin<T>`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    type_deny_list: Vec<MoveQualifiedName>,
+
     /// Whether publishing new packages is disabled.
     #[serde(default)]
     package_publish_disabled: bool,
@@ -60,6 +76,12 @@ pub struct TransactionDenyConfig {
     #[serde(skip)]
     address_deny_set: OnceCell<HashSet<SuiAddress>>,
 
+    #[serde(skip)]
+    function_deny_set: OnceCell<HashSet<MoveQualifiedName>>,
+
+    #[serde(skip)]
+    type_deny_set: OnceCell<HashSet<MoveQualifiedName>>,
+
     /// Whether receiving objects transferred to other objects is allowed
     #[serde(default)]
     receiving_objects_disabled: bool,
@@ -91,6 +113,16 @@ impl TransactionDenyConfig {
             .get_or_init(|| self.address_deny_list.iter().cloned().collect())
     }
 
+    pub fn get_function_deny_set(&self) -> &HashSet<MoveQualifiedName> {
+        self.function_deny_set
+            .get_or_init(|| self.function_deny_list.iter().cloned().collect())
+    }
+
+    pub fn get_type_deny_set(&self) -> &HashSet<MoveQualifiedName> {
+        self.type_deny_set
+            .get_or_init(|| self.type_deny_list.iter().cloned().collect())
+    }
+
     pub fn package_publish_disabled(&self) -> bool {
         self.package_publish_disabled
     }
@@ -120,6 +152,50 @@ impl TransactionDenyConfig {
     }
 }
 
+/// Deny configs can be loaded on their own from a YAML file, so that they can be reloaded
+/// while the node is running.
+impl Config for TransactionDenyConfig {}
+
+/// A fully qualified Move function or struct name, `package::module::name`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub struct MoveQualifiedName {
+    pub package: ObjectID,
+    pub module: String,
+    pub name: String,
+}
+
+impl MoveQualifiedName {
+    pub fn new(package: ObjectID, module: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            package,
+            module: module.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl fmt::Display for MoveQualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}::{}", self.package, self.module, self.name)
+    }
+}
+
+impl FromStr for MoveQualifiedName {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split("::");
+        let (Some(package), Some(module), Some(name), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(anyhow!(
+                "Invalid Move name '{s}', expected 'package::module::name'"
+            ));
+        };
+        Ok(Self::new(ObjectID::from_str(package)?, module, name))
+    }
+}
+
 #[derive(Default)]
 pub struct TransactionDenyConfigBuilder {
     config: TransactionDenyConfig,
@@ -174,6 +250,16 @@ impl TransactionDenyConfigBuilder {
         self
     }
 
+    pub fn add_denied_function(mut self, function: MoveQualifiedName) -> Self {
+        self.config.function_deny_list.push(function);
+        self
+    }
+
+    pub fn add_denied_type(mut self, struct_type: MoveQualifiedName) -> Self {
+        self.config.type_deny_list.push(struct_type);
+        self
+    }
+
     pub fn disable_zklogin_sig(mut self) -> Self {
         self.config.zklogin_sig_disabled = true;
         self
@@ -43,6 +43,7 @@ use std::{
     vec,
 };
 use sui_config::node::{AuthorityOverloadConfig, StateDebugDumpConfig};
+use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_config::NodeConfig;
 use sui_types::crypto::RandomnessRound;
 use sui_types::execution_status::ExecutionStatus;
@@ -799,6 +800,10 @@ pub struct AuthorityState {
 
     pub config: NodeConfig,
 
+    /// The transaction deny config currently in effect. Initialized from `config`, and can be
+    /// replaced while the node is running with `reload_transaction_deny_config`.
+    transaction_deny_config: ArcSwap<TransactionDenyConfig>,
+
     /// Current overload status in this authority. Updated periodically.
     pub overload_info: AuthorityOverloadInfo,
 
@@ -861,7 +866,7 @@ impl AuthorityState {
             transaction.tx_signatures(),
             &input_object_kinds,
             &receiving_objects_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -1732,7 +1737,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -1951,7 +1956,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -2712,6 +2717,7 @@ impl AuthorityState {
             _pruner,
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
+            transaction_deny_config: ArcSwap::from_pointee(config.transaction_deny_config.clone()),
             config,
             overload_info: AuthorityOverloadInfo::default(),
             validator_tx_finalizer,
@@ -2733,6 +2739,25 @@ impl AuthorityState {
         state
     }
 
+    pub fn transaction_deny_config(&self) -> Arc<TransactionDenyConfig> {
+        self.transaction_deny_config.load_full()
+    }
+
+    /// Replace the transaction deny config. Transactions signed after this returns are checked
+    /// against the new config.
+    pub fn reload_transaction_deny_config(&self, config: TransactionDenyConfig) {
+        info!(
+            "Reloading transaction deny config: {} denied objects, {} denied packages, \
+             {} denied addresses, {} denied functions, {} denied types",
+            config.get_object_deny_set().len(),
+            config.get_package_deny_set().len(),
+            config.get_address_deny_set().len(),
+            config.get_function_deny_set().len(),
+            config.get_type_deny_set().len(),
+        );
+        self.transaction_deny_config.store(Arc::new(config));
+    }
+
     // TODO: Consolidate our traits to reduce the number of methods here.
     pub fn get_object_cache_reader(&self) -> &Arc<dyn ObjectCacheRead> {
         &self.execution_cache_trait_pointers.object_cache_reader
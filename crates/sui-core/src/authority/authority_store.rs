@@ -777,6 +777,27 @@ impl AuthorityStore {
         Ok(())
     }
 
+    /// Removes object versions that are no longer live, e.g. when applying a delta state
+    /// snapshot on top of the snapshot it is based on.
+    pub fn bulk_remove_live_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        object_refs: impl Iterator<Item = ObjectRef>,
+    ) -> SuiResult<()> {
+        let mut batch = perpetual_db.objects.batch();
+        for object_ref in object_refs {
+            batch.delete_batch(
+                &perpetual_db.objects,
+                std::iter::once(ObjectKey::from(object_ref)),
+            )?;
+            batch.delete_batch(
+                &perpetual_db.live_owned_object_markers,
+                std::iter::once(object_ref),
+            )?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
     pub fn set_epoch_start_configuration(
         &self,
         epoch_start_configuration: &EpochStartConfiguration,
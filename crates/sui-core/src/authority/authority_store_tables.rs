@@ -449,6 +449,46 @@ impl AuthorityPerpetualTables {
         wb.write()?;
         Ok(())
     }
+
+    /// Writes a tombstone for `object_key`, as if the object was deleted, or wrapped if `wrapped`
+    /// is set, at that version.
+    pub fn insert_tombstone_test_only(&self, object_key: ObjectKey, wrapped: bool) -> SuiResult {
+        let tombstone = if wrapped {
+            StoreObject::Wrapped
+        } else {
+            StoreObject::Deleted
+        };
+        self.objects
+            .insert(&object_key, &StoreObjectWrapper::from(tombstone))?;
+        Ok(())
+    }
+}
+
+impl AuthorityPerpetualTablesReadOnly {
+    /// Constructs `sui_types::object::Object` from `StoreObjectWrapper`, like
+    /// `AuthorityPerpetualTables::object`, for tables opened in read only mode.
+    /// Returns `None` if object was deleted/wrapped
+    pub fn object(
+        &self,
+        object_key: &ObjectKey,
+        store_object: StoreObjectWrapper,
+    ) -> Result<Option<Object>, SuiError> {
+        let StoreObject::Value(store_object) = store_object.migrate().into_inner() else {
+            return Ok(None);
+        };
+        let indirect_object = match store_object.data {
+            StoreData::IndirectObject(ref metadata) => self
+                .indirect_move_objects
+                .get(&metadata.digest)?
+                .map(|o| o.migrate().into_inner()),
+            _ => None,
+        };
+        Ok(Some(try_construct_object(
+            object_key,
+            store_object,
+            indirect_object,
+        )?))
+    }
 }
 
 impl ObjectStore for AuthorityPerpetualTables {
@@ -37,6 +37,7 @@ use sui_types::{
 };
 use tracing::instrument;
 
+pub mod cache_sizing;
 pub(crate) mod cache_types;
 pub mod metrics;
 mod object_locks;
@@ -48,6 +49,7 @@ pub use passthrough_cache::PassthroughCache;
 pub use proxy_cache::ProxyCache;
 pub use writeback_cache::WritebackCache;
 
+use cache_sizing::CacheSizingSummary;
 use metrics::ExecutionCacheMetrics;
 
 // If you have Arc<ExecutionCache>, you cannot return a reference to it as
@@ -140,13 +142,20 @@ pub fn choose_execution_cache(config: &ExecutionCacheConfig) -> ExecutionCacheCo
 }
 
 pub fn build_execution_cache(
+    cache_config: &ExecutionCacheConfig,
     epoch_start_config: &EpochStartConfiguration,
     prometheus_registry: &Registry,
     store: &Arc<AuthorityStore>,
 ) -> ExecutionCacheTraitPointers {
     let execution_cache_metrics = Arc::new(ExecutionCacheMetrics::new(prometheus_registry));
     ExecutionCacheTraitPointers::new(
-        ProxyCache::new(epoch_start_config, store.clone(), execution_cache_metrics).into(),
+        ProxyCache::new(
+            cache_config,
+            epoch_start_config,
+            store.clone(),
+            execution_cache_metrics,
+        )
+        .into(),
     )
 }
 
@@ -697,6 +706,9 @@ pub trait ExecutionCacheReconfigAPI: Send + Sync {
         &'a self,
         epoch_start_config: &'a EpochStartConfiguration,
     ) -> BoxFuture<'a, ()>;
+
+    /// The current sizing of the caches, if they are sized by memory.
+    fn cache_sizing(&self) -> Option<CacheSizingSummary>;
 }
 
 // StateSyncAPI is for writing any data that was not the result of transaction execution,
@@ -907,6 +919,10 @@ macro_rules! implement_passthrough_traits {
                     stringify!($implementor)
                 );
             }
+
+            fn cache_sizing(&self) -> Option<CacheSizingSummary> {
+                self.cache_sizing_impl()
+            }
         }
 
         impl StateSyncAPI for $implementor {
@@ -0,0 +1,390 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sizing of the writeback cache's object, object-by-id, marker and package caches by memory.
+//!
+//! The caches share a memory budget, of which each cache gets a share. Every cache is built with
+//! the whole budget as its capacity, but weighs its entries by their estimated size divided by
+//! its share, so that it holds about its share of the budget. Shares can therefore change
+//! without rebuilding the caches: entries inserted afterwards are weighed by the new share, and
+//! a cache converges to its new size as its entries are replaced.
+//!
+//! With adaptive sizing, the budget is periodically rebalanced according to the requests
+//! recorded in `ExecutionCacheMetrics` since the previous rebalance: a step of the share of the
+//! cache with the highest hit rate is moved to the cache with the most misses.
+
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use moka::sync::Cache as MokaCache;
+use parking_lot::Mutex;
+use serde::Serialize;
+use sui_types::storage::{MarkerValue, PackageObject};
+use tracing::info;
+
+use super::cache_types::CachedVersionMap;
+use super::metrics::ExecutionCacheMetrics;
+
+/// Entries are weighed in units of this many bytes, so that large entries fit in u32 weights.
+const WEIGHT_UNIT_BYTES: u64 = 64;
+/// Estimated memory used by an entry besides its value, e.g. by its key and by the cache.
+const ENTRY_OVERHEAD_BYTES: usize = 128;
+
+const PERMILLE: u32 = 1000;
+const MIN_SHARE_PERMILLE: u32 = 50;
+const MAX_SHARE_PERMILLE: u32 = 700;
+const REBALANCE_STEP_PERMILLE: u32 = 25;
+const REBALANCE_INTERVAL: Duration = Duration::from_secs(30);
+/// Caches with fewer requests since the previous rebalance keep their share.
+const MIN_REBALANCE_REQUESTS: u64 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SizedCache {
+    Object,
+    ObjectById,
+    Marker,
+    Package,
+}
+
+impl SizedCache {
+    const ALL: [SizedCache; 4] = [
+        SizedCache::Object,
+        SizedCache::ObjectById,
+        SizedCache::Marker,
+        SizedCache::Package,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            SizedCache::Object => "object",
+            SizedCache::ObjectById => "object_by_id",
+            SizedCache::Marker => "marker",
+            SizedCache::Package => "package",
+        }
+    }
+
+    fn initial_share_permille(self) -> u32 {
+        match self {
+            SizedCache::Object => 400,
+            SizedCache::ObjectById => 250,
+            SizedCache::Marker => 100,
+            SizedCache::Package => 250,
+        }
+    }
+
+    /// Whether requests of the given type and level, as recorded in `ExecutionCacheMetrics`,
+    /// are served by this cache.
+    fn serves(self, request_type: &str, level: &str) -> bool {
+        match self {
+            // The "committed" level is shared with the transaction caches.
+            SizedCache::Object => {
+                level == "committed"
+                    && !request_type.starts_with("marker")
+                    && !request_type.starts_with("transaction")
+                    && !request_type.starts_with("executed_effects")
+            }
+            SizedCache::ObjectById => level == "object_by_id",
+            SizedCache::Marker => level == "committed" && request_type.starts_with("marker"),
+            SizedCache::Package => level == "package_cache",
+        }
+    }
+}
+
+/// The estimated memory used by a cached value.
+pub(crate) trait EstimatedSize {
+    fn estimated_size(&self) -> usize;
+}
+
+impl<T: EstimatedSize> EstimatedSize for Arc<Mutex<T>> {
+    fn estimated_size(&self) -> usize {
+        self.lock().estimated_size()
+    }
+}
+
+impl<V: EstimatedSize> EstimatedSize for CachedVersionMap<V> {
+    fn estimated_size(&self) -> usize {
+        self.values().map(|value| value.estimated_size()).sum()
+    }
+}
+
+impl EstimatedSize for MarkerValue {
+    fn estimated_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+}
+
+impl EstimatedSize for PackageObject {
+    fn estimated_size(&self) -> usize {
+        self.object().object_size_for_gas_metering()
+    }
+}
+
+/// Builds a cache of `cache` sized by `sizing`, or holding up to `max_entries` entries when the
+/// caches are not sized by memory.
+pub(crate) fn build_cache<K, V>(
+    max_entries: u64,
+    sizing: Option<&Arc<CacheSizing>>,
+    cache: SizedCache,
+) -> MokaCache<K, V>
+where
+    K: Hash + Eq + Send + Sync + 'static,
+    V: EstimatedSize + Clone + Send + Sync + 'static,
+{
+    match sizing {
+        Some(sizing) => {
+            let weigher_sizing = sizing.clone();
+            MokaCache::builder()
+                .max_capacity((sizing.budget_bytes / WEIGHT_UNIT_BYTES).max(1))
+                .weigher(move |_key, value: &V| weigher_sizing.weigh(cache, value.estimated_size()))
+                .build()
+        }
+        None => MokaCache::builder().max_capacity(max_entries).build(),
+    }
+}
+
+/// The memory budget of the caches that are sized by memory, and how it is shared between them.
+pub struct CacheSizing {
+    budget_bytes: u64,
+    adaptive: bool,
+    // Share of the budget of each cache, indexed by `SizedCache`.
+    shares_permille: [AtomicU32; 4],
+    rebalance_state: Mutex<RebalanceState>,
+}
+
+struct RebalanceState {
+    last_rebalance: Instant,
+    // Requests and hits of each cache recorded up to the last rebalance.
+    last_counts: [(u64, u64); 4],
+    // Hit rate of each cache between the last two rebalances, if it had enough requests.
+    hit_rates: [Option<f64>; 4],
+}
+
+impl CacheSizing {
+    pub fn new(budget_bytes: u64, adaptive: bool) -> Self {
+        Self {
+            budget_bytes,
+            adaptive,
+            shares_permille: SizedCache::ALL
+                .map(|cache| AtomicU32::new(cache.initial_share_permille())),
+            rebalance_state: Mutex::new(RebalanceState {
+                last_rebalance: Instant::now(),
+                last_counts: [(0, 0); 4],
+                hit_rates: [None; 4],
+            }),
+        }
+    }
+
+    fn share_permille(&self, cache: SizedCache) -> u32 {
+        self.shares_permille[cache as usize].load(Ordering::Relaxed)
+    }
+
+    fn cache_budget_bytes(&self, cache: SizedCache) -> u64 {
+        self.budget_bytes * self.share_permille(cache) as u64 / PERMILLE as u64
+    }
+
+    /// Weighs an entry so that the entries of a cache fill the whole budget when they use the
+    /// cache's share of it.
+    pub(crate) fn weigh(&self, cache: SizedCache, size_bytes: usize) -> u32 {
+        let units = ((size_bytes + ENTRY_OVERHEAD_BYTES) as u64).div_ceil(WEIGHT_UNIT_BYTES);
+        let weight = units * PERMILLE as u64 / self.share_permille(cache).max(1) as u64;
+        weight.clamp(1, u32::MAX as u64) as u32
+    }
+
+    /// Rebalances the budget if adaptive sizing is enabled and the last rebalance was long
+    /// enough ago.
+    pub(crate) fn maybe_rebalance(&self, metrics: &ExecutionCacheMetrics) {
+        if !self.adaptive {
+            return;
+        }
+        let mut state = self.rebalance_state.lock();
+        if state.last_rebalance.elapsed() < REBALANCE_INTERVAL {
+            return;
+        }
+        self.rebalance(&mut state, metrics);
+    }
+
+    fn rebalance(&self, state: &mut RebalanceState, metrics: &ExecutionCacheMetrics) {
+        state.last_rebalance = Instant::now();
+        let mut misses = [0; 4];
+        for cache in SizedCache::ALL {
+            let (requests, hits) =
+                metrics.requests_and_hits(|request_type, level| cache.serves(request_type, level));
+            let (last_requests, last_hits) =
+                std::mem::replace(&mut state.last_counts[cache as usize], (requests, hits));
+            let requests = requests.saturating_sub(last_requests);
+            let hits = hits.saturating_sub(last_hits);
+            state.hit_rates[cache as usize] =
+                (requests >= MIN_REBALANCE_REQUESTS).then(|| hits as f64 / requests as f64);
+            misses[cache as usize] = requests.saturating_sub(hits);
+        }
+
+        let hit_rate = |cache: SizedCache| state.hit_rates[cache as usize];
+        let Some(receiver) = SizedCache::ALL
+            .into_iter()
+            .filter(|cache| hit_rate(*cache).is_some())
+            .filter(|cache| {
+                self.share_permille(*cache) + REBALANCE_STEP_PERMILLE <= MAX_SHARE_PERMILLE
+            })
+            .max_by_key(|cache| misses[*cache as usize])
+        else {
+            return;
+        };
+        let Some(donor) = SizedCache::ALL
+            .into_iter()
+            .filter(|cache| *cache != receiver)
+            .filter(|cache| {
+                self.share_permille(*cache) >= MIN_SHARE_PERMILLE + REBALANCE_STEP_PERMILLE
+            })
+            .filter_map(|cache| Some((cache, hit_rate(cache)?)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(cache, _)| cache)
+        else {
+            return;
+        };
+        if misses[receiver as usize] == 0 || hit_rate(donor) <= hit_rate(receiver) {
+            return;
+        }
+
+        self.shares_permille[donor as usize].fetch_sub(REBALANCE_STEP_PERMILLE, Ordering::Relaxed);
+        self.shares_permille[receiver as usize]
+            .fetch_add(REBALANCE_STEP_PERMILLE, Ordering::Relaxed);
+        info!(
+            "Moved {REBALANCE_STEP_PERMILLE} permille of the execution cache budget from the {} cache (hit rate {:.3}) to the {} cache (hit rate {:.3})",
+            donor.name(),
+            hit_rate(donor).unwrap_or_default(),
+            receiver.name(),
+            hit_rate(receiver).unwrap_or_default(),
+        );
+        self.update_metrics(metrics);
+    }
+
+    pub(crate) fn update_metrics(&self, metrics: &ExecutionCacheMetrics) {
+        for cache in SizedCache::ALL {
+            metrics
+                .cache_budget_bytes
+                .with_label_values(&[cache.name()])
+                .set(self.cache_budget_bytes(cache) as i64);
+        }
+    }
+
+    /// Summarizes the sizing, given the number of entries and the weighted size of each cache.
+    pub(crate) fn summary(&self, usage: impl Fn(SizedCache) -> (u64, u64)) -> CacheSizingSummary {
+        let hit_rates = self.rebalance_state.lock().hit_rates;
+        let caches = SizedCache::ALL
+            .into_iter()
+            .map(|cache| {
+                let (entries, weighted_size) = usage(cache);
+                let share_permille = self.share_permille(cache);
+                SizedCacheSummary {
+                    cache: cache.name(),
+                    share_permille,
+                    budget_bytes: self.cache_budget_bytes(cache),
+                    entries,
+                    estimated_bytes: weighted_size * WEIGHT_UNIT_BYTES * share_permille as u64
+                        / PERMILLE as u64,
+                    hit_rate: hit_rates[cache as usize],
+                }
+            })
+            .collect();
+        CacheSizingSummary {
+            budget_bytes: self.budget_bytes,
+            adaptive: self.adaptive,
+            caches,
+        }
+    }
+}
+
+/// The current sizing of the caches that are sized by memory.
+#[derive(Clone, Debug, Serialize)]
+pub struct CacheSizingSummary {
+    pub budget_bytes: u64,
+    pub adaptive: bool,
+    pub caches: Vec<SizedCacheSummary>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SizedCacheSummary {
+    pub cache: &'static str,
+    /// Share of the budget, in thousandths
+    pub share_permille: u32,
+    pub budget_bytes: u64,
+    pub entries: u64,
+    /// Estimated memory used by the entries, which is approximate while the cache converges to
+    /// a new share
+    pub estimated_bytes: u64,
+    /// Hit rate between the last two rebalances, if the cache had enough requests
+    pub hit_rate: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::Registry;
+
+    fn record_requests(
+        metrics: &ExecutionCacheMetrics,
+        request_type: &'static str,
+        level: &'static str,
+        requests: u64,
+        hits: u64,
+    ) {
+        for i in 0..requests {
+            metrics.record_cache_request(request_type, level);
+            if i < hits {
+                metrics.record_cache_hit(request_type, level);
+            } else {
+                metrics.record_cache_miss(request_type, level);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weight_scales_with_share() {
+        let sizing = CacheSizing::new(1 << 30, true);
+        // 160 weight units, including the entry overhead.
+        let size = 160 * WEIGHT_UNIT_BYTES as usize - ENTRY_OVERHEAD_BYTES;
+        let object_weight = sizing.weigh(SizedCache::Object, size);
+        let marker_weight = sizing.weigh(SizedCache::Marker, size);
+        assert_eq!(object_weight, 400);
+        // The marker cache has a quarter of the share of the object cache.
+        assert_eq!(marker_weight, object_weight * 4);
+        assert!(sizing.weigh(SizedCache::Object, 0) >= 1);
+    }
+
+    #[test]
+    fn test_rebalance_moves_budget_to_missing_cache() {
+        let metrics = ExecutionCacheMetrics::new(&Registry::new());
+        let sizing = CacheSizing::new(1 << 30, true);
+
+        // The package cache always hits, the object cache mostly misses.
+        record_requests(&metrics, "package", "package_cache", 2000, 2000);
+        record_requests(&metrics, "object_by_version", "committed", 2000, 200);
+        // Transaction requests are not served by the sized caches.
+        record_requests(&metrics, "transaction_effects", "committed", 5000, 0);
+        sizing.rebalance(&mut sizing.rebalance_state.lock(), &metrics);
+        assert_eq!(
+            sizing.share_permille(SizedCache::Object),
+            SizedCache::Object.initial_share_permille() + REBALANCE_STEP_PERMILLE
+        );
+        assert_eq!(
+            sizing.share_permille(SizedCache::Package),
+            SizedCache::Package.initial_share_permille() - REBALANCE_STEP_PERMILLE
+        );
+
+        // Only requests since the previous rebalance count, and there are too few of them.
+        record_requests(&metrics, "object_by_version", "committed", 10, 0);
+        sizing.rebalance(&mut sizing.rebalance_state.lock(), &metrics);
+        assert_eq!(
+            sizing.share_permille(SizedCache::Object),
+            SizedCache::Object.initial_share_permille() + REBALANCE_STEP_PERMILLE
+        );
+
+        let summary = sizing.summary(|_| (0, 0));
+        let total: u32 = summary.caches.iter().map(|c| c.share_permille).sum();
+        assert_eq!(total, PERMILLE);
+        assert_eq!(summary.caches[0].cache, "object");
+        assert_eq!(summary.caches[0].hit_rate, None);
+    }
+}
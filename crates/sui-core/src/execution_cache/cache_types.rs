@@ -74,6 +74,11 @@ impl<V> CachedVersionMap<V> {
         None
     }
 
+    /// returns the values in the map, from the oldest to the newest version
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.values.iter().map(|(_, value)| value)
+    }
+
     /// returns the newest (highest) version in the map
     pub fn get_highest(&self) -> Option<&(SequenceNumber, V)> {
         self.values.back()
@@ -4,8 +4,8 @@
 use tracing::trace;
 
 use prometheus::{
-    register_int_counter_vec_with_registry, register_int_gauge_with_registry, IntCounterVec,
-    IntGauge, Registry,
+    core::Collector, register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 
 pub struct ExecutionCacheMetrics {
@@ -15,6 +15,7 @@ pub struct ExecutionCacheMetrics {
     pub(crate) cache_negative_hits: IntCounterVec,
     pub(crate) cache_misses: IntCounterVec,
     pub(crate) cache_writes: IntCounterVec,
+    pub(crate) cache_budget_bytes: IntGaugeVec,
 }
 
 impl ExecutionCacheMetrics {
@@ -65,6 +66,15 @@ impl ExecutionCacheMetrics {
                 registry,
             )
             .unwrap(),
+
+            // `cache` is "object", "object_by_id", "marker" or "package"
+            cache_budget_bytes: register_int_gauge_vec_with_registry!(
+                "execution_cache_budget_bytes",
+                "Memory budget of the execution caches that are sized by memory",
+                &["cache"],
+                registry,
+            )
+            .unwrap(),
         }
     }
 
@@ -121,4 +131,32 @@ impl ExecutionCacheMetrics {
     pub(crate) fn record_cache_write(&self, collection: &'static str) {
         self.cache_writes.with_label_values(&[collection]).inc();
     }
+
+    /// Returns the number of requests and of hits, including negative hits, recorded so far
+    /// for the request types and levels accepted by `filter`.
+    pub(crate) fn requests_and_hits(&self, filter: impl Fn(&str, &str) -> bool) -> (u64, u64) {
+        let sum = |counters: &IntCounterVec| -> u64 {
+            counters
+                .collect()
+                .iter()
+                .flat_map(|family| family.get_metric())
+                .filter(|metric| {
+                    let label = |name: &str| {
+                        metric
+                            .get_label()
+                            .iter()
+                            .find(|label| label.get_name() == name)
+                            .map(|label| label.get_value())
+                            .unwrap_or_default()
+                    };
+                    filter(label("request_type"), label("level"))
+                })
+                .map(|metric| metric.get_counter().get_value() as u64)
+                .sum()
+        };
+        (
+            sum(&self.cache_requests),
+            sum(&self.cache_hits) + sum(&self.cache_negative_hits),
+        )
+    }
 }
@@ -33,9 +33,9 @@ use tracing::instrument;
 use typed_store::Map;
 
 use super::{
-    implement_passthrough_traits, CheckpointCache, ExecutionCacheCommit, ExecutionCacheMetrics,
-    ExecutionCacheReconfigAPI, ExecutionCacheWrite, ObjectCacheRead, StateSyncAPI, TestingAPI,
-    TransactionCacheRead,
+    cache_sizing::CacheSizingSummary, implement_passthrough_traits, CheckpointCache,
+    ExecutionCacheCommit, ExecutionCacheMetrics, ExecutionCacheReconfigAPI, ExecutionCacheWrite,
+    ObjectCacheRead, StateSyncAPI, TestingAPI, TransactionCacheRead,
 };
 
 pub struct PassthroughCache {
@@ -76,6 +76,10 @@ impl PassthroughCache {
             })
             .ok();
     }
+
+    fn cache_sizing_impl(&self) -> Option<CacheSizingSummary> {
+        None
+    }
 }
 
 impl ObjectCacheRead for PassthroughCache {
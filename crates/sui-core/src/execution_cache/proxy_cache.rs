@@ -14,6 +14,7 @@ use futures::FutureExt;
 use parking_lot::RwLock;
 use std::sync::Arc;
 use std::time::Duration;
+use sui_config::ExecutionCacheConfig;
 use sui_protocol_config::ProtocolVersion;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::VerifiedExecutionData;
@@ -29,9 +30,10 @@ use sui_types::sui_system_state::SuiSystemState;
 use sui_types::transaction::{VerifiedSignedTransaction, VerifiedTransaction};
 
 use super::{
-    CheckpointCache, ExecutionCacheCommit, ExecutionCacheConfigType, ExecutionCacheMetrics,
-    ExecutionCacheReconfigAPI, ExecutionCacheWrite, ObjectCacheRead, PassthroughCache,
-    StateSyncAPI, TestingAPI, TransactionCacheRead, WritebackCache,
+    cache_sizing::CacheSizingSummary, CheckpointCache, ExecutionCacheCommit,
+    ExecutionCacheConfigType, ExecutionCacheMetrics, ExecutionCacheReconfigAPI,
+    ExecutionCacheWrite, ObjectCacheRead, PassthroughCache, StateSyncAPI, TestingAPI,
+    TransactionCacheRead, WritebackCache,
 };
 
 macro_rules! delegate_method {
@@ -57,6 +59,7 @@ pub struct ProxyCache {
 
 impl ProxyCache {
     pub fn new(
+        cache_config: &ExecutionCacheConfig,
         epoch_start_config: &EpochStartConfiguration,
         store: Arc<AuthorityStore>,
         metrics: Arc<ExecutionCacheMetrics>,
@@ -64,7 +67,8 @@ impl ProxyCache {
         let cache_type = epoch_start_config.execution_cache_type();
         tracing::info!("using cache impl {:?}", cache_type);
         let passthrough_cache = PassthroughCache::new(store.clone(), metrics.clone());
-        let writeback_cache = WritebackCache::new(store.clone(), metrics.clone());
+        let writeback_cache =
+            WritebackCache::new_with_config(cache_config, store.clone(), metrics.clone());
 
         Self {
             passthrough_cache,
@@ -400,6 +404,10 @@ impl ExecutionCacheReconfigAPI for ProxyCache {
     ) -> BoxFuture<'a, ()> {
         self.reconfigure_cache_impl(epoch_start_config).boxed()
     }
+
+    fn cache_sizing(&self) -> Option<CacheSizingSummary> {
+        delegate_method!(self.cache_sizing())
+    }
 }
 
 impl StateSyncAPI for ProxyCache {
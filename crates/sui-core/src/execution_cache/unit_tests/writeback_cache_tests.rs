@@ -364,6 +364,20 @@ impl Scenario {
         self.cache().clear_state_end_of_epoch(&lock);
     }
 
+    // Replace the cache with one whose object, object-by-id, marker and package caches share a
+    // memory budget of `budget_bytes`.
+    pub fn with_memory_budget(&mut self, budget_bytes: u64) {
+        self.cache = Arc::new(WritebackCache::new_with_config(
+            &ExecutionCacheConfig::WritebackCache {
+                max_cache_size: None,
+                memory_budget_bytes: Some(budget_bytes),
+                adaptive_sizing: false,
+            },
+            self.store.clone(),
+            self.cache.metrics.clone(),
+        ));
+    }
+
     pub fn evict_caches(&self) {
         self.cache.clear_caches_and_assert_empty();
     }
@@ -978,6 +992,61 @@ async fn test_invalidate_package_cache_on_revert() {
     .await;
 }
 
+#[tokio::test]
+async fn test_memory_budget_evicts_objects() {
+    telemetry_subscribers::init_for_testing();
+    let mut s = Scenario::new(None, Arc::new(AtomicU32::new(0))).await;
+    s.with_memory_budget(64 * 1024);
+
+    let ids: Vec<u32> = (1..=1000).collect();
+    s.with_created(&ids);
+    let tx = s.do_tx().await;
+    s.commit(tx).await.unwrap();
+
+    let cache = s.cache.clone();
+    let object_cache = &cache.cached.object_cache;
+    object_cache.run_pending_tasks();
+    assert!(object_cache.entry_count() > 0);
+    assert!(object_cache.entry_count() < ids.len() as u64);
+    assert!(object_cache.weighted_size() <= object_cache.policy().max_capacity().unwrap());
+
+    // Evicted objects are read from the db
+    s.assert_live(&ids);
+}
+
+#[tokio::test]
+async fn test_memory_budget_reweighs_moved_objects() {
+    telemetry_subscribers::init_for_testing();
+    let mut s = Scenario::new(None, Arc::new(AtomicU32::new(0))).await;
+    s.with_memory_budget(1024 * 1024);
+    let cache = s.cache.clone();
+    let sizing = cache.sizing.clone().unwrap();
+    let object_cache = &cache.cached.object_cache;
+
+    // The entry of an object is created empty when its first version is moved to the cache, and
+    // is weighed again with the versions it holds.
+    s.with_created(&[1]);
+    let tx = s.do_tx().await;
+    s.commit(tx).await.unwrap();
+    let first_version = ObjectEntry::Object(s.object(1)).estimated_size();
+    object_cache.run_pending_tasks();
+    assert_eq!(
+        object_cache.weighted_size(),
+        sizing.weigh(SizedCache::Object, first_version) as u64
+    );
+
+    s.with_mutated(&[1]);
+    let tx = s.do_tx().await;
+    s.commit(tx).await.unwrap();
+    let second_version = ObjectEntry::Object(s.object(1)).estimated_size();
+    object_cache.run_pending_tasks();
+    assert_eq!(object_cache.entry_count(), 1);
+    assert_eq!(
+        object_cache.weighted_size(),
+        sizing.weigh(SizedCache::Object, first_version + second_version) as u64
+    );
+}
+
 #[sim_test]
 async fn test_concurrent_readers() {
     telemetry_subscribers::init_for_testing();
@@ -57,6 +57,7 @@ use prometheus::Registry;
 use std::collections::{BTreeMap, BTreeSet};
 use std::hash::Hash;
 use std::sync::Arc;
+use sui_config::ExecutionCacheConfig;
 use sui_macros::fail_point_async;
 use sui_protocol_config::ProtocolVersion;
 use sui_types::accumulator::Accumulator;
@@ -78,7 +79,10 @@ use tracing::{debug, info, instrument, trace, warn};
 
 use super::ExecutionCacheAPI;
 use super::{
-    cache_types::CachedVersionMap, implement_passthrough_traits, object_locks::ObjectLocks,
+    cache_sizing::{build_cache, CacheSizing, CacheSizingSummary, EstimatedSize, SizedCache},
+    cache_types::CachedVersionMap,
+    implement_passthrough_traits,
+    object_locks::ObjectLocks,
     CheckpointCache, ExecutionCacheCommit, ExecutionCacheMetrics, ExecutionCacheReconfigAPI,
     ExecutionCacheWrite, ObjectCacheRead, StateSyncAPI, TestingAPI, TransactionCacheRead,
 };
@@ -111,6 +115,15 @@ impl ObjectEntry {
     }
 }
 
+impl EstimatedSize for ObjectEntry {
+    fn estimated_size(&self) -> usize {
+        match self {
+            ObjectEntry::Object(object) => object.object_size_for_gas_metering(),
+            ObjectEntry::Deleted | ObjectEntry::Wrapped => 0,
+        }
+    }
+}
+
 impl std::fmt::Debug for ObjectEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -152,6 +165,15 @@ enum LatestObjectCacheEntry {
     NonExistent,
 }
 
+impl EstimatedSize for LatestObjectCacheEntry {
+    fn estimated_size(&self) -> usize {
+        match self {
+            LatestObjectCacheEntry::Object(_, entry) => entry.estimated_size(),
+            LatestObjectCacheEntry::NonExistent => 0,
+        }
+    }
+}
+
 impl LatestObjectCacheEntry {
     fn is_newer_than(&self, other: &LatestObjectCacheEntry) -> bool {
         match (self, other) {
@@ -258,7 +280,7 @@ impl UncommittedData {
     }
 }
 
-// TODO: set this via the config
+// The default number of entries of each cache, when the caches are not sized by memory.
 static MAX_CACHE_SIZE: u64 = 10000;
 
 /// CachedData stores data that has been committed to the db, but is likely to be read soon.
@@ -290,39 +312,15 @@ struct CachedCommittedData {
 }
 
 impl CachedCommittedData {
-    fn new() -> Self {
-        let object_cache = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
-        let object_by_id_cache = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
-        let marker_cache = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
-        let transactions = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
-        let transaction_effects = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
-        let transaction_events = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
-        let executed_effects_digests = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
-        let transaction_objects = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
+    fn new(max_entries: u64, sizing: Option<&Arc<CacheSizing>>) -> Self {
+        let object_cache = build_cache(max_entries, sizing, SizedCache::Object);
+        let object_by_id_cache = build_cache(max_entries, sizing, SizedCache::ObjectById);
+        let marker_cache = build_cache(max_entries, sizing, SizedCache::Marker);
+        let transactions = MokaCache::builder().max_capacity(max_entries).build();
+        let transaction_effects = MokaCache::builder().max_capacity(max_entries).build();
+        let transaction_events = MokaCache::builder().max_capacity(max_entries).build();
+        let executed_effects_digests = MokaCache::builder().max_capacity(max_entries).build();
+        let transaction_objects = MokaCache::builder().max_capacity(max_entries).build();
 
         Self {
             object_cache,
@@ -388,6 +386,9 @@ pub struct WritebackCache {
     executed_effects_digests_notify_read: NotifyRead<TransactionDigest, TransactionEffectsDigest>,
     store: Arc<AuthorityStore>,
     metrics: Arc<ExecutionCacheMetrics>,
+
+    // Set when the object, object-by-id, marker and package caches are sized by memory.
+    sizing: Option<Arc<CacheSizing>>,
 }
 
 macro_rules! check_cache_entry_by_version {
@@ -429,21 +430,70 @@ macro_rules! check_cache_entry_by_latest {
 
 impl WritebackCache {
     pub fn new(store: Arc<AuthorityStore>, metrics: Arc<ExecutionCacheMetrics>) -> Self {
-        let packages = MokaCache::builder()
-            .max_capacity(MAX_CACHE_SIZE)
-            .max_capacity(MAX_CACHE_SIZE)
-            .build();
+        Self::new_with_config(
+            &ExecutionCacheConfig::WritebackCache {
+                max_cache_size: None,
+                memory_budget_bytes: None,
+                adaptive_sizing: false,
+            },
+            store,
+            metrics,
+        )
+    }
+
+    pub fn new_with_config(
+        config: &ExecutionCacheConfig,
+        store: Arc<AuthorityStore>,
+        metrics: Arc<ExecutionCacheMetrics>,
+    ) -> Self {
+        let (max_entries, sizing) = match config {
+            ExecutionCacheConfig::WritebackCache {
+                max_cache_size,
+                memory_budget_bytes,
+                adaptive_sizing,
+            } => (
+                max_cache_size.map_or(MAX_CACHE_SIZE, |size| size as u64),
+                memory_budget_bytes
+                    .map(|budget| Arc::new(CacheSizing::new(budget, *adaptive_sizing))),
+            ),
+            ExecutionCacheConfig::PassthroughCache => (MAX_CACHE_SIZE, None),
+        };
+        if let Some(sizing) = &sizing {
+            sizing.update_metrics(&metrics);
+        }
+
+        let packages = build_cache(max_entries, sizing.as_ref(), SizedCache::Package);
         Self {
             dirty: UncommittedData::new(),
-            cached: CachedCommittedData::new(),
+            cached: CachedCommittedData::new(max_entries, sizing.as_ref()),
             packages,
             object_locks: ObjectLocks::new(),
             executed_effects_digests_notify_read: NotifyRead::new(),
             store,
             metrics,
+            sizing,
         }
     }
 
+    fn cache_sizing_impl(&self) -> Option<CacheSizingSummary> {
+        let sizing = self.sizing.as_ref()?;
+        Some(sizing.summary(|cache| match cache {
+            SizedCache::Object => (
+                self.cached.object_cache.entry_count(),
+                self.cached.object_cache.weighted_size(),
+            ),
+            SizedCache::ObjectById => (
+                self.cached.object_by_id_cache.entry_count(),
+                self.cached.object_by_id_cache.weighted_size(),
+            ),
+            SizedCache::Marker => (
+                self.cached.marker_cache.entry_count(),
+                self.cached.marker_cache.weighted_size(),
+            ),
+            SizedCache::Package => (self.packages.entry_count(), self.packages.weighted_size()),
+        }))
+    }
+
     pub fn new_for_tests(store: Arc<AuthorityStore>, registry: &Registry) -> Self {
         Self::new(store, ExecutionCacheMetrics::new(registry).into())
     }
@@ -893,6 +943,10 @@ impl WritebackCache {
             self.flush_transactions_from_dirty_to_cached(epoch, *tx_digest, outputs);
         }
 
+        if let Some(sizing) = &self.sizing {
+            sizing.maybe_rebalance(&self.metrics);
+        }
+
         Ok(())
     }
 
@@ -964,6 +1018,7 @@ impl WritebackCache {
                 (epoch, object_key.0),
                 object_key.1,
                 marker_value,
+                self.sizing.is_some(),
             );
         }
 
@@ -974,6 +1029,7 @@ impl WritebackCache {
                 *object_id,
                 object.version(),
                 &ObjectEntry::Object(object.clone()),
+                self.sizing.is_some(),
             );
         }
 
@@ -984,6 +1040,7 @@ impl WritebackCache {
                 *object_id,
                 *version,
                 &ObjectEntry::Deleted,
+                self.sizing.is_some(),
             );
         }
 
@@ -994,6 +1051,7 @@ impl WritebackCache {
                 *object_id,
                 *version,
                 &ObjectEntry::Wrapped,
+                self.sizing.is_some(),
             );
         }
     }
@@ -1033,6 +1091,7 @@ impl WritebackCache {
         key: K,
         version: SequenceNumber,
         value: &V,
+        reweigh: bool,
     ) where
         K: Eq + std::hash::Hash + Clone + Send + Sync + Copy + 'static,
         V: Send + Sync + Clone + Eq + std::fmt::Debug + 'static,
@@ -1049,6 +1108,14 @@ impl WritebackCache {
         cache_map.insert(version, value.clone());
         // TODO: make this automatic by giving CachedVersionMap an optional max capacity
         cache_map.truncate_to(MAX_VERSIONS);
+        drop(cache_map);
+
+        // A cache sized by memory weighs an entry when it is inserted, which may have been when
+        // its map was empty. Insert the entry again to weigh it with its current versions. The
+        // dirty entry is still locked, so no other writer can replace the cache entry meanwhile.
+        if reweigh {
+            cache.insert(key, cache_entry.into_value());
+        }
 
         let DashMapEntry::Occupied(mut occupied_dirty_entry) = dirty_entry else {
             panic!("dirty map must exist");
@@ -551,6 +551,33 @@ impl StateAccumulator {
         }
     }
 
+    /// Accumulates a live object given only its reference, e.g. as listed by a state snapshot.
+    /// Wrapped tombstones are recognized by their digest, and accumulated the same way as by
+    /// `accumulate_live_object`.
+    pub fn accumulate_live_object_ref(acc: &mut Accumulator, object_ref: &ObjectRef) {
+        if object_ref.2 == ObjectDigest::OBJECT_DIGEST_WRAPPED {
+            acc.insert(
+                bcs::to_bytes(&WrappedObject::new(object_ref.0, object_ref.1))
+                    .expect("Failed to serialize WrappedObject"),
+            );
+        } else {
+            acc.insert(object_ref.2);
+        }
+    }
+
+    /// Removes a live object that was accumulated by `accumulate_live_object` or
+    /// `accumulate_live_object_ref`, given its reference.
+    pub fn remove_live_object_ref(acc: &mut Accumulator, object_ref: &ObjectRef) {
+        if object_ref.2 == ObjectDigest::OBJECT_DIGEST_WRAPPED {
+            acc.remove(
+                bcs::to_bytes(&WrappedObject::new(object_ref.0, object_ref.1))
+                    .expect("Failed to serialize WrappedObject"),
+            );
+        } else {
+            acc.remove(object_ref.2);
+        }
+    }
+
     pub fn digest_live_object_set(
         &self,
         include_wrapped_tombstone: bool,
@@ -15,6 +15,8 @@ use crate::quorum_driver::reconfig_observer::{OnsiteReconfigObserver, ReconfigOb
 use crate::quorum_driver::{QuorumDriverHandler, QuorumDriverHandlerBuilder, QuorumDriverMetrics};
 use futures::future::{select, Either, Future};
 use futures::FutureExt;
+use moka::ops::compute::Op;
+use moka::sync::Cache as MokaCache;
 use mysten_common::sync::notify_read::NotifyRead;
 use mysten_metrics::histogram::{Histogram, HistogramVec};
 use mysten_metrics::{spawn_logged_monitored_task, spawn_monitored_task};
@@ -37,7 +39,7 @@ use sui_types::executable_transaction::VerifiedExecutableTransaction;
 use sui_types::quorum_driver_types::{
     ExecuteTransactionRequestType, ExecuteTransactionRequestV3, ExecuteTransactionResponseV3,
     FinalizedEffects, IsTransactionExecutedLocally, QuorumDriverEffectsQueueResult,
-    QuorumDriverError, QuorumDriverResponse, QuorumDriverResult,
+    QuorumDriverError, QuorumDriverResponse, QuorumDriverResult, TransactionSubmissionStatus,
 };
 use sui_types::sui_system_state::SuiSystemState;
 use sui_types::transaction::VerifiedTransaction;
@@ -53,12 +55,18 @@ const LOCAL_EXECUTION_TIMEOUT: Duration = Duration::from_secs(10);
 
 const WAIT_FOR_FINALITY_TIMEOUT: Duration = Duration::from_secs(30);
 
+// How long the status of a submitted transaction is kept after its last update, and how many
+// statuses are kept at most.
+const SUBMISSION_STATUS_RETENTION: Duration = Duration::from_secs(10 * 60);
+const MAX_TRACKED_SUBMISSIONS: u64 = 100_000;
+
 pub struct TransactiondOrchestrator<A: Clone> {
     quorum_driver_handler: Arc<QuorumDriverHandler<A>>,
     validator_state: Arc<AuthorityState>,
     _local_executor_handle: JoinHandle<()>,
     pending_tx_log: Arc<WritePathPendingTransactionLog>,
     notifier: Arc<NotifyRead<TransactionDigest, QuorumDriverResult>>,
+    submission_statuses: SubmissionStatusTracker,
     metrics: Arc<TransactionOrchestratorMetrics>,
 }
 
@@ -118,24 +126,33 @@ where
             parent_path.join("fullnode_pending_transactions"),
         ));
         let pending_tx_log_clone = pending_tx_log.clone();
+        let submission_statuses =
+            SubmissionStatusTracker::new(SUBMISSION_STATUS_RETENTION, MAX_TRACKED_SUBMISSIONS);
+        let submission_statuses_clone = submission_statuses.clone();
         let _local_executor_handle = {
             spawn_monitored_task!(async move {
                 Self::loop_execute_finalized_tx_locally(
                     state_clone,
                     effects_receiver,
                     pending_tx_log_clone,
+                    submission_statuses_clone,
                     metrics_clone,
                 )
                 .await;
             })
         };
-        Self::schedule_txes_in_log(pending_tx_log.clone(), quorum_driver_handler.clone());
+        Self::schedule_txes_in_log(
+            pending_tx_log.clone(),
+            quorum_driver_handler.clone(),
+            submission_statuses.clone(),
+        );
         Self {
             quorum_driver_handler,
             validator_state,
             _local_executor_handle,
             pending_tx_log,
             notifier,
+            submission_statuses,
             metrics,
         }
     }
@@ -273,12 +290,17 @@ where
             in_flight.dec();
         });
 
+        self.submission_statuses
+            .record(tx_digest, TransactionSubmissionStatus::Pending);
         let ticket = self
             .submit(transaction.clone(), request, client_addr)
             .await
             .map_err(|e| {
                 warn!(?tx_digest, "QuorumDriverInternalError: {e:?}");
-                QuorumDriverError::QuorumDriverInternalError(e)
+                let err = QuorumDriverError::QuorumDriverInternalError(e);
+                self.submission_statuses
+                    .record(tx_digest, TransactionSubmissionStatus::Failed(err.clone()));
+                err
             })?;
 
         let Ok(result) = timeout(WAIT_FOR_FINALITY_TIMEOUT, ticket).await else {
@@ -296,9 +318,19 @@ where
                 warn!(?tx_digest, "QuorumDriverInternalError: {err:?}");
                 Err(QuorumDriverError::QuorumDriverInternalError(err))
             }
-            Ok(Err(err)) => Err(err),
+            Ok(Err(err)) => {
+                self.submission_statuses
+                    .record(tx_digest, TransactionSubmissionStatus::Failed(err.clone()));
+                Err(err)
+            }
             Ok(Ok(response)) => {
                 good_response_metrics.inc();
+                // Also recorded when the effects are received from Quorum Driver, but that may
+                // happen after the response is returned.
+                self.submission_statuses.record(
+                    tx_digest,
+                    TransactionSubmissionStatus::Certified(*response.effects_cert.digest()),
+                );
                 Ok((transaction, response))
             }
         }
@@ -431,12 +463,17 @@ where
         validator_state: Arc<AuthorityState>,
         mut effects_receiver: Receiver<QuorumDriverEffectsQueueResult>,
         pending_transaction_log: Arc<WritePathPendingTransactionLog>,
+        submission_statuses: SubmissionStatusTracker,
         metrics: Arc<TransactionOrchestratorMetrics>,
     ) {
         loop {
             match effects_receiver.recv().await {
                 Ok(Ok((transaction, QuorumDriverResponse { effects_cert, .. }))) => {
                     let tx_digest = transaction.digest();
+                    submission_statuses.record(
+                        *tx_digest,
+                        TransactionSubmissionStatus::Certified(*effects_cert.digest()),
+                    );
                     if let Err(err) = pending_transaction_log.finish_transaction(tx_digest) {
                         panic!(
                             "Failed to finish transaction {tx_digest} in pending transaction log: {err}"
@@ -480,7 +517,8 @@ where
                     )
                     .await;
                 }
-                Ok(Err((tx_digest, _err))) => {
+                Ok(Err((tx_digest, err))) => {
+                    submission_statuses.record(tx_digest, TransactionSubmissionStatus::Failed(err));
                     if let Err(err) = pending_transaction_log.finish_transaction(&tx_digest) {
                         error!(
                             ?tx_digest,
@@ -544,6 +582,7 @@ where
     fn schedule_txes_in_log(
         pending_tx_log: Arc<WritePathPendingTransactionLog>,
         quorum_driver: Arc<QuorumDriverHandler<A>>,
+        submission_statuses: SubmissionStatusTracker,
     ) {
         spawn_logged_monitored_task!(async move {
             if std::env::var("SKIP_LOADING_FROM_PENDING_TX_LOG").is_ok() {
@@ -560,6 +599,7 @@ where
                 // requires a migration.
                 let tx = tx.into_inner();
                 let tx_digest = *tx.digest();
+                submission_statuses.record(tx_digest, TransactionSubmissionStatus::Pending);
                 // It's not impossible we fail to enqueue a task but that's not the end of world.
                 // TODO(william) correctly extract client_addr from logs
                 if let Err(err) = quorum_driver
@@ -594,6 +634,75 @@ where
     pub fn load_all_pending_transactions(&self) -> Vec<VerifiedTransaction> {
         self.pending_tx_log.load_all_pending_transactions()
     }
+
+    /// Returns where a transaction is, if it was submitted to this node within the retention
+    /// window, or if this node executed it.
+    pub fn transaction_submission_status(
+        &self,
+        digest: &TransactionDigest,
+    ) -> SuiResult<Option<TransactionSubmissionStatus>> {
+        // Local execution and checkpoints are read from the stores, so they are also reported
+        // for transactions that were not submitted here or whose status was dropped.
+        let cache_reader = self.validator_state.get_transaction_cache_reader();
+        let effects_digest = cache_reader
+            .multi_get_executed_effects_digests(&[*digest])?
+            .pop()
+            .expect("multi-get must return correct number of items");
+        if let Some(effects_digest) = effects_digest {
+            let status = match cache_reader.deprecated_get_transaction_checkpoint(digest)? {
+                Some((epoch, checkpoint)) => {
+                    TransactionSubmissionStatus::Checkpointed(effects_digest, epoch, checkpoint)
+                }
+                None => TransactionSubmissionStatus::ExecutedLocally(effects_digest),
+            };
+            return Ok(Some(status));
+        }
+        Ok(self.submission_statuses.get(digest))
+    }
+}
+
+/// Statuses of recently submitted transactions, as reported by Quorum Driver. Statuses expire
+/// once they have not been updated for the retention window.
+#[derive(Clone)]
+struct SubmissionStatusTracker {
+    statuses: MokaCache<TransactionDigest, TransactionSubmissionStatus>,
+}
+
+impl SubmissionStatusTracker {
+    fn new(retention: Duration, max_entries: u64) -> Self {
+        Self {
+            statuses: MokaCache::builder()
+                .max_capacity(max_entries)
+                .time_to_live(retention)
+                .build(),
+        }
+    }
+
+    /// Records the status of a transaction, unless it already progressed further. A failed
+    /// transaction can be submitted again, so a failure is replaced by a new submission.
+    fn record(&self, digest: TransactionDigest, status: TransactionSubmissionStatus) {
+        self.statuses
+            .entry(digest)
+            .and_compute_with(|current| match current {
+                Some(current) if Self::progress(current.value()) > Self::progress(&status) => {
+                    Op::Nop
+                }
+                _ => Op::Put(status),
+            });
+    }
+
+    fn get(&self, digest: &TransactionDigest) -> Option<TransactionSubmissionStatus> {
+        self.statuses.get(digest)
+    }
+
+    fn progress(status: &TransactionSubmissionStatus) -> u8 {
+        match status {
+            TransactionSubmissionStatus::Pending | TransactionSubmissionStatus::Failed(_) => 0,
+            TransactionSubmissionStatus::Certified(_) => 1,
+            TransactionSubmissionStatus::ExecutedLocally(_) => 2,
+            TransactionSubmissionStatus::Checkpointed(..) => 3,
+        }
+    }
 }
 
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on
@@ -770,4 +879,11 @@ where
     > {
         self.execute_transaction_v3(request, client_addr).await
     }
+
+    fn transaction_submission_status(
+        &self,
+        digest: TransactionDigest,
+    ) -> SuiResult<Option<TransactionSubmissionStatus>> {
+        TransactiondOrchestrator::transaction_submission_status(self, &digest)
+    }
 }
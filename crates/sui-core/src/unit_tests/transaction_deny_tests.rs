@@ -13,11 +13,14 @@ use move_core_types::ident_str;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_config::certificate_deny_config::CertificateDenyConfigBuilder;
-use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionDenyConfigBuilder};
+use sui_config::transaction_deny_config::{
+    MoveQualifiedName, TransactionDenyConfig, TransactionDenyConfigBuilder,
+};
 use sui_swarm_config::genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_test_transaction_builder::TestTransactionBuilder;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::coin::COIN_MODULE_NAME;
 use sui_types::effects::TransactionEffectsAPI;
 use sui_types::error::{SuiError, SuiResult, UserInputError};
 use sui_types::execution_status::{ExecutionFailureStatus, ExecutionStatus};
@@ -30,6 +33,7 @@ use sui_types::utils::get_zklogin_user_address;
 use sui_types::utils::{
     make_zklogin_tx, to_sender_signed_transaction, to_sender_signed_transaction_with_multi_signers,
 };
+use sui_types::{gas_coin::GAS, SUI_FRAMEWORK_PACKAGE_ID};
 
 const ACCOUNT_NUM: usize = 5;
 const GAS_OBJECT_COUNT: usize = 15;
@@ -430,6 +434,69 @@ async fn test_package_denied() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_function_denied() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let (package_c, _) = publish_package_on_single_authority(
+        &path.join("src/unit_tests/data/package_deny/c"),
+        accounts[0].0,
+        &accounts[0].1,
+        accounts[0].2[0],
+        [("c", ObjectID::ZERO)],
+        vec![],
+        &state,
+    )
+    .await
+    .unwrap();
+
+    // Deny the function without restarting the authority.
+    state.reload_transaction_deny_config(
+        TransactionDenyConfigBuilder::new()
+            .add_denied_function(MoveQualifiedName::new(package_c, "c", "c"))
+            .build(),
+    );
+    let result =
+        handle_move_call_transaction(&state, package_c, "c", "c", vec![], &accounts[0], 1).await;
+    assert_denied(&result);
+
+    // Reloading an empty config lifts the restriction.
+    state.reload_transaction_deny_config(TransactionDenyConfig::default());
+    let result =
+        handle_move_call_transaction(&state, package_c, "c", "c", vec![], &accounts[0], 2).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_type_argument_denied() {
+    let (network_config, state) = setup_test(
+        TransactionDenyConfigBuilder::new()
+            .add_denied_type("0x2::sui::SUI".parse().unwrap())
+            .build(),
+    )
+    .await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let account = &accounts[0];
+    let rgp = state.reference_gas_price_for_testing().unwrap();
+    let data = TransactionData::new_move_call(
+        account.0,
+        SUI_FRAMEWORK_PACKAGE_ID,
+        COIN_MODULE_NAME.to_owned(),
+        ident_str!("zero").to_owned(),
+        vec![GAS::type_tag()],
+        account.2[0],
+        vec![],
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER * rgp,
+        rgp,
+    )
+    .unwrap();
+    let epoch_store = state.epoch_store_for_testing();
+    let tx = to_sender_signed_transaction(data, &account.1);
+    let tx = epoch_store.verify_transaction(tx).unwrap();
+    assert_denied(&state.handle_transaction(&epoch_store, tx).await);
+}
+
 #[tokio::test]
 async fn test_certificate_deny() {
     let (network_config, state) = setup_test(TransactionDenyConfig::default()).await;
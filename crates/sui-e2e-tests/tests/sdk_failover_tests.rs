@@ -0,0 +1,268 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::StreamExt;
+use std::time::Duration;
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
+use sui_sdk::{SuiClientBuilder, SUI_COIN_TYPE};
+use sui_types::object::Owner;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use test_cluster::TestClusterBuilder;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Forward,
+    /// Hold requests until the mode changes.
+    Stall,
+    /// Close connections as soon as a request arrives.
+    Reject,
+    /// Answer JSON-RPC batches with an error, like fullnodes behind proxies that disallow them.
+    RejectBatches,
+}
+
+const BATCH_REJECTION: &str = r#"{"jsonrpc":"2.0","error":{"code":-32600,"message":"Batch requests are not supported"},"id":null}"#;
+
+/// A TCP proxy in front of an RPC endpoint, that can stop forwarding requests on demand.
+struct Proxy {
+    url: String,
+    mode: watch::Sender<Mode>,
+}
+
+impl Proxy {
+    async fn start(target: &str) -> Self {
+        let target = target.trim_start_matches("http://").to_string();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (mode, _) = watch::channel(Mode::Forward);
+        let modes = mode.clone();
+        tokio::spawn(async move {
+            while let Ok((client, _)) = listener.accept().await {
+                tokio::spawn(Self::forward(client, target.clone(), modes.subscribe()));
+            }
+        });
+        Self { url, mode }
+    }
+
+    fn set(&self, mode: Mode) {
+        self.mode.send_replace(mode);
+    }
+
+    async fn forward(client: TcpStream, target: String, mut mode: watch::Receiver<Mode>) {
+        let Ok(upstream) = TcpStream::connect(&target).await else {
+            return;
+        };
+        let (mut client_read, mut client_write) = client.into_split();
+        let (mut upstream_read, mut upstream_write) = upstream.into_split();
+        // Resolves to whether a batch has to be rejected.
+        let requests = async {
+            let mut buf = vec![0; 64 * 1024];
+            loop {
+                let n = client_read.read(&mut buf).await?;
+                if n == 0 {
+                    return Ok::<_, std::io::Error>(false);
+                }
+                let mode = match mode.wait_for(|mode| *mode != Mode::Stall).await {
+                    Ok(mode) => *mode,
+                    Err(_) => return Ok(false),
+                };
+                match mode {
+                    Mode::Reject => return Ok(false),
+                    Mode::RejectBatches if is_batch(&buf[..n]) => return Ok(true),
+                    _ => upstream_write.write_all(&buf[..n]).await?,
+                }
+            }
+        };
+        let responses = tokio::io::copy(&mut upstream_read, &mut client_write);
+        let reject_batch = tokio::select! {
+            r = requests => r.unwrap_or(false),
+            _ = responses => false,
+        };
+        if reject_batch {
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{BATCH_REJECTION}",
+                BATCH_REJECTION.len()
+            );
+            let _ = client_write.write_all(response.as_bytes()).await;
+        }
+        // Dropping both halves of the connection closes it.
+    }
+}
+
+/// Whether an HTTP request carries a JSON-RPC batch, assuming its body arrived with its headers.
+fn is_batch(request: &[u8]) -> bool {
+    request
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .and_then(|end| request[end + 4..].iter().find(|b| !b.is_ascii_whitespace()))
+        == Some(&b'[')
+}
+
+#[tokio::test]
+async fn test_client_failover() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let rpc_url = test_cluster.rpc_url();
+
+    // Nothing listens on the first endpoint, so the client has to fall back to the fullnode.
+    let client = SuiClientBuilder::default()
+        .request_timeout(Duration::from_secs(5))
+        .fallback_urls([rpc_url])
+        .health_check_interval(Duration::from_millis(100))
+        .hedge_reads_after(Duration::from_millis(50))
+        .consistent_reads(true)
+        .build("http://127.0.0.1:1")
+        .await?;
+
+    let checkpoint = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    let coins = client
+        .coin_read_api()
+        .get_coins_stream(address, Some(SUI_COIN_TYPE.to_string()))
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(5, coins.len());
+
+    // Reads never go back to an earlier checkpoint.
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(
+        client
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await?
+            >= checkpoint
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hedged_reads() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let proxy = Proxy::start(test_cluster.rpc_url()).await;
+
+    // The proxy stays the active endpoint, as it never fails a request.
+    let client = SuiClientBuilder::default()
+        .request_timeout(Duration::from_secs(60))
+        .fallback_urls([test_cluster.rpc_url()])
+        .health_check_interval(Duration::from_secs(3600))
+        .hedge_reads_after(Duration::from_millis(100))
+        .build(&proxy.url)
+        .await?;
+
+    // Without hedging, reads would wait for the request timeout.
+    proxy.set(Mode::Stall);
+    for _ in 0..3 {
+        tokio::time::timeout(
+            Duration::from_secs(20),
+            client.read_api().get_latest_checkpoint_sequence_number(),
+        )
+        .await??;
+    }
+
+    // Stalled requests complete once the proxy forwards them again.
+    proxy.set(Mode::Forward);
+    client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_consistent_reads_after_failover() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let fallback = test_cluster.spawn_new_fullnode().await;
+    let proxy = Proxy::start(test_cluster.rpc_url()).await;
+    let client = SuiClientBuilder::default()
+        .request_timeout(Duration::from_secs(5))
+        .fallback_urls([&fallback.rpc_url])
+        .health_check_interval(Duration::from_secs(3600))
+        .consistent_reads(true)
+        .build(&proxy.url)
+        .await?;
+
+    // Transfer a coin through the first endpoint, and read back the checkpoint that includes it.
+    let (sender, gas) = test_cluster.wallet.get_one_gas_object().await?.unwrap();
+    let recipient = test_cluster.get_address_1();
+    let tx = test_cluster
+        .test_transaction_builder_with_gas_object(sender, gas)
+        .await
+        .transfer_sui(None, recipient)
+        .build();
+    let response = client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            test_cluster.sign_transaction(&tx),
+            SuiTransactionBlockResponseOptions::new(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    while client
+        .read_api()
+        .get_transaction_with_options(response.digest, SuiTransactionBlockResponseOptions::new())
+        .await?
+        .checkpoint
+        .is_none()
+    {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // Reads fail over to the other fullnode, and must observe the transfer once they succeed.
+    proxy.set(Mode::Reject);
+    let object = tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            match client
+                .read_api()
+                .get_object_with_options(gas.0, SuiObjectDataOptions::new().with_owner())
+                .await
+            {
+                Ok(object) => return object,
+                Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+            }
+        }
+    })
+    .await?;
+    let object = object.data.unwrap();
+    assert!(object.version > gas.1);
+    assert_eq!(object.owner, Some(Owner::AddressOwner(recipient)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_consistent_reads_without_batches() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let proxy = Proxy::start(test_cluster.rpc_url()).await;
+    proxy.set(Mode::RejectBatches);
+    // Without another endpoint to fail over to, reads only succeed if the client stops batching.
+    let client = SuiClientBuilder::default()
+        .request_timeout(Duration::from_secs(5))
+        .consistent_reads(true)
+        .build(&proxy.url)
+        .await?;
+
+    // Reads don't go backwards in time.
+    let mut checkpoint = 0;
+    for _ in 0..3 {
+        let latest = client
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await?;
+        assert!(latest >= checkpoint);
+        checkpoint = latest;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    let coins = client
+        .coin_read_api()
+        .get_coins(test_cluster.get_address_0(), None, None, None)
+        .await?;
+    assert!(!coins.data.is_empty());
+
+    Ok(())
+}
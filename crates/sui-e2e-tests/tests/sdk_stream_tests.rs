@@ -2,9 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use futures::StreamExt;
+use std::collections::BTreeSet;
 use std::future;
+use std::time::Duration;
+use sui_keys::keystore::AccountKeystore;
+use sui_sdk::checkpoint_stream::CheckpointStreamBuilder;
+use sui_sdk::gas_pool::{GasPool, GasPoolConfig};
 use sui_sdk::{SuiClientBuilder, SUI_COIN_TYPE};
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
+use sui_types::base_types::SuiAddress;
+use sui_types::transaction::TransactionData;
 use test_cluster::TestClusterBuilder;
 
 // TODO: rewrite the tests after the removal of DevNet NFT
@@ -134,3 +141,86 @@ async fn test_coins_stream() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_checkpoint_stream() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let rest_url = format!("{}/v2", test_cluster.rpc_url());
+
+    let mut checkpoints = CheckpointStreamBuilder::new(&rest_url)
+        .max_in_flight(2)
+        .poll_interval(Duration::from_millis(100))
+        .build();
+    for expected in 0..3 {
+        let checkpoint = checkpoints.next().await.unwrap()?;
+        assert_eq!(expected, checkpoint.checkpoint_summary.sequence_number);
+    }
+    assert_eq!(3, checkpoints.cursor());
+
+    // Resume from where the first stream stopped.
+    let mut checkpoints = CheckpointStreamBuilder::new(&rest_url)
+        .start_from(checkpoints.cursor())
+        .poll_interval(Duration::from_millis(100))
+        .build();
+    let checkpoint = checkpoints.next().await.unwrap()?;
+    assert_eq!(3, checkpoint.checkpoint_summary.sequence_number);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gas_pool() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let keypair = test_cluster
+        .wallet
+        .config
+        .keystore
+        .get_key(&address)?
+        .copy();
+
+    // The account starts with 5 coins, the pool splits off the rest.
+    let pool = GasPool::new(
+        test_cluster.sui_client().clone(),
+        keypair,
+        GasPoolConfig {
+            target_coin_count: 8,
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(8, pool.available_coins());
+
+    let budget = 10_000_000;
+    let mut leases = futures::future::try_join_all((0..8).map(|_| pool.lease(budget))).await?;
+    let leased: BTreeSet<_> = leases.iter().map(|lease| lease.object_ref().0).collect();
+    assert_eq!(8, leased.len());
+    assert_eq!(0, pool.available_coins());
+
+    // Pay for a transaction with a leased coin, and return it with the transaction's effects.
+    let lease = leases.pop().unwrap();
+    let gas = lease.object_ref();
+    let data = TransactionData::new_transfer_sui(
+        SuiAddress::random_for_testing_only(),
+        address,
+        Some(1),
+        gas,
+        budget,
+        test_cluster.get_reference_gas_price().await,
+    );
+    let response = test_cluster
+        .wallet
+        .execute_transaction_must_succeed(test_cluster.wallet.sign_transaction(&data))
+        .await;
+    lease.release(response.effects.as_ref().unwrap());
+    let lease = pool.lease(budget).await?;
+    assert_eq!(gas.0, lease.object_ref().0);
+    assert!(lease.object_ref().1 > gas.1);
+
+    for lease in leases {
+        lease.release_unused();
+    }
+    assert_eq!(7, pool.available_coins());
+
+    Ok(())
+}
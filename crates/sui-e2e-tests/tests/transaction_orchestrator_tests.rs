@@ -11,10 +11,12 @@ use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
 use sui_test_transaction_builder::{
     batch_make_transfer_transactions, make_staking_transaction, make_transfer_sui_transaction,
 };
+use sui_types::base_types::TransactionDigest;
 use sui_types::effects::TransactionEffectsAPI;
+use sui_types::message_envelope::Message;
 use sui_types::quorum_driver_types::{
     ExecuteTransactionRequestType, ExecuteTransactionRequestV3, ExecuteTransactionResponseV3,
-    FinalizedEffects, IsTransactionExecutedLocally, QuorumDriverError,
+    FinalizedEffects, IsTransactionExecutedLocally, QuorumDriverError, TransactionSubmissionStatus,
 };
 use sui_types::transaction::Transaction;
 use test_cluster::TestClusterBuilder;
@@ -89,6 +91,58 @@ async fn test_blocking_execution() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_transaction_submission_status() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let handle = &test_cluster.fullnode_handle.sui_node;
+    let orchestrator = handle.with(|n| n.transaction_orchestrator().as_ref().unwrap().clone());
+
+    // Transactions that were never submitted are unknown.
+    assert_eq!(
+        orchestrator.transaction_submission_status(&TransactionDigest::random())?,
+        None
+    );
+
+    let txn = make_transfer_sui_transaction(&test_cluster.wallet, None, None).await;
+    let digest = *txn.digest();
+    let (response, _) = execute_with_orchestrator(
+        &orchestrator,
+        txn,
+        ExecuteTransactionRequestType::WaitForEffectsCert,
+    )
+    .await?;
+    let effects_digest = response.effects.effects.digest();
+
+    // Once finalized, the transaction is certified, and it is eventually executed and
+    // checkpointed by the fullnode.
+    match orchestrator.transaction_submission_status(&digest)? {
+        Some(TransactionSubmissionStatus::Certified(status_effects_digest))
+        | Some(TransactionSubmissionStatus::ExecutedLocally(status_effects_digest))
+        | Some(TransactionSubmissionStatus::Checkpointed(status_effects_digest, _, _)) => {
+            assert_eq!(status_effects_digest, effects_digest)
+        }
+        other => panic!("unexpected status: {:?}", other),
+    }
+    timeout(Duration::from_secs(30), async {
+        loop {
+            if let Some(TransactionSubmissionStatus::Checkpointed(
+                status_effects_digest,
+                epoch,
+                _,
+            )) = orchestrator.transaction_submission_status(&digest).unwrap()
+            {
+                assert_eq!(status_effects_digest, effects_digest);
+                assert_eq!(epoch, 0);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await?;
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_fullnode_wal_log() -> Result<(), anyhow::Error> {
     #[cfg(msim)]
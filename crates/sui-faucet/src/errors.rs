@@ -40,6 +40,15 @@ pub enum FaucetError {
     #[error("Coin amounts sent are incorrect:`{0}`")]
     CoinAmountTransferredIncorrect(String),
 
+    #[error("Coin type `{0}` is not supported by this faucet")]
+    UnsupportedCoinType(String),
+
+    #[error("Timed out waiting for a `{0}` coin from the coin pool")]
+    NoCoinAvailable(String),
+
+    #[error("Too many requests, please try again in {0} seconds.")]
+    TooManyRequests(u64),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -3,14 +3,22 @@
 use crate::FaucetError;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::{parse_sui_type_tag, TypeTag};
 use uuid::Uuid;
 
+mod request_quotas;
 mod simple_faucet;
 mod write_ahead_log;
+pub use self::request_quotas::{RecordedRequest, RequestQuotas};
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf, sync::Arc};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+    sync::Arc,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -46,12 +54,20 @@ pub enum BatchSendStatusType {
 
 pub struct AppState<F = Arc<SimpleFaucet>> {
     pub faucet: F,
+    /// Only set when a per-IP or per-recipient quota is configured.
+    pub quotas: Option<RequestQuotas>,
     pub config: FaucetConfig,
 }
 
 impl<F> AppState<F> {
     pub fn new(faucet: F, config: FaucetConfig) -> Self {
-        Self { faucet, config }
+        Self {
+            faucet,
+            quotas: config
+                .has_request_quotas()
+                .then(|| RequestQuotas::new(&config)),
+            config,
+        }
     }
 }
 
@@ -65,6 +81,15 @@ pub trait Faucet {
         amounts: &[u64],
     ) -> Result<FaucetReceipt, FaucetError>;
 
+    /// Send coins of `coin_type`, other than SUI, of the specified amount to the recipient
+    async fn send_coins(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &TypeTag,
+        amounts: &[u64],
+    ) -> Result<FaucetReceipt, FaucetError>;
+
     /// Send `Coin<SUI>` of the specified amount to the recipient in a batch request
     async fn batch_send(
         &self,
@@ -80,6 +105,28 @@ pub trait Faucet {
 pub const DEFAULT_AMOUNT: u64 = 1_000_000_000;
 pub const DEFAULT_NUM_OF_COINS: usize = 1;
 
+/// A coin type other than SUI that the faucet sends, and the amount of each coin it sends, parsed
+/// from `<coin type>=<amount>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinTypeConfig {
+    pub coin_type: TypeTag,
+    pub amount: u64,
+}
+
+impl FromStr for CoinTypeConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (coin_type, amount) = s
+            .rsplit_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected <coin type>=<amount>, got {s}"))?;
+        Ok(Self {
+            coin_type: parse_sui_type_tag(coin_type.trim())?,
+            amount: amount.trim().parse()?,
+        })
+    }
+}
+
 #[derive(Parser, Clone)]
 #[clap(
     name = "Sui Faucet",
@@ -125,6 +172,60 @@ pub struct FaucetConfig {
 
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
+
+    /// Maximum number of requests from a single client IP within the quota window. Unlimited if
+    /// not set.
+    #[clap(long)]
+    pub max_requests_per_ip: Option<u64>,
+
+    /// Maximum number of requests to a single recipient address within the quota window.
+    /// Unlimited if not set.
+    #[clap(long)]
+    pub max_requests_per_recipient: Option<u64>,
+
+    /// Length in seconds of the sliding window the per-IP and per-recipient quotas apply to.
+    #[clap(long, default_value_t = 86400)]
+    pub quota_window_secs: u64,
+
+    /// Client IPs exempt from the per-IP quota.
+    #[clap(long, value_delimiter = ',')]
+    pub ip_allowlist: Vec<IpAddr>,
+
+    /// Recipient addresses exempt from the per-recipient quota.
+    #[clap(long, value_delimiter = ',')]
+    pub recipient_allowlist: Vec<SuiAddress>,
+
+    /// Take the client IP from the `X-Forwarded-For` header, for faucets behind a trusted proxy.
+    #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
+    pub use_forwarded_for: bool,
+
+    /// Number of trusted proxies in front of the faucet, each appending the address it received a
+    /// request from to `X-Forwarded-For`. The client IP is the entry that many positions from the
+    /// right, as entries further left are set by the client.
+    #[clap(long, default_value_t = 1)]
+    pub trusted_proxy_hops: usize,
+
+    /// Where request quotas are persisted. Defaults to next to the write ahead log.
+    #[clap(long)]
+    pub request_quota_store: Option<PathBuf>,
+
+    /// A coin type other than SUI to send, as `<coin type>=<amount>`, where amount is the value of
+    /// each coin sent. The faucet's active address must own pre-funded coins of that type. Can be
+    /// repeated.
+    #[clap(long = "coin-type")]
+    pub coin_types: Vec<CoinTypeConfig>,
+}
+
+impl FaucetConfig {
+    pub fn has_request_quotas(&self) -> bool {
+        self.max_requests_per_ip.is_some() || self.max_requests_per_recipient.is_some()
+    }
+
+    pub fn request_quota_store_path(&self) -> PathBuf {
+        self.request_quota_store
+            .clone()
+            .unwrap_or_else(|| self.write_ahead_log.with_extension("quotas"))
+    }
 }
 
 impl Default for FaucetConfig {
@@ -143,6 +244,15 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            max_requests_per_ip: None,
+            max_requests_per_recipient: None,
+            quota_window_secs: 86400,
+            ip_allowlist: vec![],
+            recipient_allowlist: vec![],
+            use_forwarded_for: false,
+            trusted_proxy_hops: 1,
+            request_quota_store: None,
+            coin_types: vec![],
         }
     }
 }
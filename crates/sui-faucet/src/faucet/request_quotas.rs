@@ -0,0 +1,404 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use sui_types::base_types::SuiAddress;
+use typed_store::rocks::DBMap;
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::DBMapUtils;
+use typed_store::Map;
+
+use crate::{FaucetConfig, FaucetError};
+
+/// Persistent record of the requests served recently, keyed by client IP and by recipient
+/// address. Each entry holds the timestamps (in milliseconds since the epoch) of the requests
+/// still inside the quota window, so quotas survive the faucet restarting.
+#[derive(DBMapUtils)]
+pub struct RequestQuotaStore {
+    pub requests_by_ip: DBMap<IpAddr, Vec<u64>>,
+    pub requests_by_recipient: DBMap<SuiAddress, Vec<u64>>,
+}
+
+impl RequestQuotaStore {
+    pub(crate) fn open(path: &Path) -> Self {
+        Self::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::new("faucet_request_quotas"),
+            None,
+            None,
+        )
+    }
+}
+
+/// A request counted against the quotas, to give back with [`RequestQuotas::release`] if it is
+/// not served.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RecordedRequest {
+    ip: Option<IpAddr>,
+    recipient: Option<SuiAddress>,
+    timestamp: u64,
+}
+
+/// Per-IP and per-recipient request quotas over a sliding window.
+pub struct RequestQuotas {
+    store: RequestQuotaStore,
+    max_requests_per_ip: Option<u64>,
+    max_requests_per_recipient: Option<u64>,
+    window: Duration,
+    ip_allowlist: HashSet<IpAddr>,
+    recipient_allowlist: HashSet<SuiAddress>,
+    // Serializes checking and recording requests, so concurrent requests cannot both take the
+    // last slot of a quota.
+    lock: Mutex<()>,
+}
+
+impl RequestQuotas {
+    pub fn new(config: &FaucetConfig) -> Self {
+        Self {
+            store: RequestQuotaStore::open(&config.request_quota_store_path()),
+            max_requests_per_ip: config.max_requests_per_ip,
+            max_requests_per_recipient: config.max_requests_per_recipient,
+            window: Duration::from_secs(config.quota_window_secs),
+            ip_allowlist: config.ip_allowlist.iter().copied().collect(),
+            recipient_allowlist: config.recipient_allowlist.iter().copied().collect(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Count a request from `ip` to `recipient` against their quotas. Fails with
+    /// `FaucetError::TooManyRequests` without counting the request if either quota is used up.
+    /// Allowlisted IPs and recipients are exempt from their respective quota.
+    pub fn check_and_record(
+        &self,
+        ip: Option<IpAddr>,
+        recipient: SuiAddress,
+    ) -> Result<RecordedRequest, FaucetError> {
+        self.check_and_record_at(ip, recipient, now_ms())
+    }
+
+    fn check_and_record_at(
+        &self,
+        ip: Option<IpAddr>,
+        recipient: SuiAddress,
+        now: u64,
+    ) -> Result<RecordedRequest, FaucetError> {
+        let (ip, recipient) = self.limited(ip, recipient);
+
+        let _guard = self.lock.lock();
+        let ip_requests = ip
+            .map(|(ip, limit)| self.recent_requests(&self.store.requests_by_ip, &ip, limit, now))
+            .transpose()?;
+        let recipient_requests = recipient
+            .map(|(recipient, limit)| {
+                self.recent_requests(&self.store.requests_by_recipient, &recipient, limit, now)
+            })
+            .transpose()?;
+
+        if let (Some((ip, _)), Some(mut requests)) = (ip, ip_requests) {
+            requests.push(now);
+            self.store
+                .requests_by_ip
+                .insert(&ip, &requests)
+                .map_err(FaucetError::internal)?;
+        }
+        if let (Some((recipient, _)), Some(mut requests)) = (recipient, recipient_requests) {
+            requests.push(now);
+            self.store
+                .requests_by_recipient
+                .insert(&recipient, &requests)
+                .map_err(FaucetError::internal)?;
+        }
+        Ok(RecordedRequest {
+            ip: ip.map(|(ip, _)| ip),
+            recipient: recipient.map(|(recipient, _)| recipient),
+            timestamp: now,
+        })
+    }
+
+    /// Stop counting a request that was not served against the quotas.
+    pub fn release(&self, request: RecordedRequest) -> Result<(), FaucetError> {
+        let _guard = self.lock.lock();
+        if let Some(ip) = request.ip {
+            release_request(&self.store.requests_by_ip, &ip, request.timestamp)?;
+        }
+        if let Some(recipient) = request.recipient {
+            release_request(
+                &self.store.requests_by_recipient,
+                &recipient,
+                request.timestamp,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The IP and recipient of a request that have a quota, with their limits.
+    fn limited(
+        &self,
+        ip: Option<IpAddr>,
+        recipient: SuiAddress,
+    ) -> (Option<(IpAddr, u64)>, Option<(SuiAddress, u64)>) {
+        let ip = ip
+            .filter(|ip| !self.ip_allowlist.contains(ip))
+            .zip(self.max_requests_per_ip);
+        let recipient = Some(recipient)
+            .filter(|recipient| !self.recipient_allowlist.contains(recipient))
+            .zip(self.max_requests_per_recipient);
+        (ip, recipient)
+    }
+
+    /// The requests made by `key` inside the window ending at `now`, or an error carrying how long
+    /// until the oldest of them leaves the window if there are already `limit` of them.
+    fn recent_requests<K: Serialize + DeserializeOwned>(
+        &self,
+        table: &DBMap<K, Vec<u64>>,
+        key: &K,
+        limit: u64,
+        now: u64,
+    ) -> Result<Vec<u64>, FaucetError> {
+        let window_start = now.saturating_sub(self.window.as_millis() as u64);
+        let mut requests = table
+            .get(key)
+            .map_err(FaucetError::internal)?
+            .unwrap_or_default();
+        requests.retain(|timestamp| *timestamp > window_start);
+
+        if requests.len() as u64 >= limit {
+            // Requests are appended in order, so the first one is the oldest.
+            let retry_after_ms = requests.first().map_or(0, |oldest| oldest - window_start);
+            return Err(FaucetError::TooManyRequests(
+                retry_after_ms.div_ceil(1000).max(1),
+            ));
+        }
+        Ok(requests)
+    }
+
+    /// Drop the entries whose requests have all left the window, so the store does not keep
+    /// growing with one-off clients. This scans the whole store, so it blocks and should run on
+    /// a blocking thread.
+    pub fn remove_expired(&self) -> Result<(), FaucetError> {
+        self.remove_expired_at(now_ms())
+    }
+
+    fn remove_expired_at(&self, now: u64) -> Result<(), FaucetError> {
+        let window_start = now.saturating_sub(self.window.as_millis() as u64);
+        self.remove_expired_entries(&self.store.requests_by_ip, window_start)?;
+        self.remove_expired_entries(&self.store.requests_by_recipient, window_start)
+    }
+
+    /// Scans `table` without holding the lock, so requests are not held up by the scan, and only
+    /// takes it to remove the entries found expired that no request has been recorded to since.
+    fn remove_expired_entries<K: Serialize + DeserializeOwned>(
+        &self,
+        table: &DBMap<K, Vec<u64>>,
+        window_start: u64,
+    ) -> Result<(), FaucetError> {
+        let is_expired =
+            |requests: &[u64]| requests.iter().all(|timestamp| *timestamp <= window_start);
+        let mut candidates = vec![];
+        for item in table.safe_iter() {
+            let (key, requests) = item.map_err(FaucetError::internal)?;
+            if is_expired(&requests) {
+                candidates.push(key);
+            }
+        }
+
+        let _guard = self.lock.lock();
+        let expired = table
+            .multi_get(&candidates)
+            .map_err(FaucetError::internal)?
+            .into_iter()
+            .zip(candidates)
+            .filter_map(|(requests, key)| {
+                requests
+                    .filter(|requests| is_expired(requests))
+                    .map(|_| key)
+            });
+        table.multi_remove(expired).map_err(FaucetError::internal)
+    }
+}
+
+/// Remove one request made at `timestamp` from the requests made by `key`.
+fn release_request<K: Serialize + DeserializeOwned>(
+    table: &DBMap<K, Vec<u64>>,
+    key: &K,
+    timestamp: u64,
+) -> Result<(), FaucetError> {
+    let Some(mut requests) = table.get(key).map_err(FaucetError::internal)? else {
+        return Ok(());
+    };
+    let Some(position) = requests.iter().rposition(|t| *t == timestamp) else {
+        return Ok(());
+    };
+    requests.remove(position);
+    if requests.is_empty() {
+        table.remove(key).map_err(FaucetError::internal)
+    } else {
+        table.insert(key, &requests).map_err(FaucetError::internal)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the epoch")
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const WINDOW_MS: u64 = 60_000;
+    const START: u64 = 1_700_000_000_000;
+
+    fn open_quotas(config: FaucetConfig) -> RequestQuotas {
+        RequestQuotas::new(&FaucetConfig {
+            quota_window_secs: WINDOW_MS / 1000,
+            ..config
+        })
+    }
+
+    fn config(path: &Path) -> FaucetConfig {
+        FaucetConfig {
+            request_quota_store: Some(path.join("quotas")),
+            max_requests_per_ip: Some(2),
+            max_requests_per_recipient: Some(2),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn per_ip_quota_over_sliding_window() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = open_quotas(config(tmp.path()));
+        let ip = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+
+        quotas
+            .check_and_record_at(ip, SuiAddress::random_for_testing_only(), START)
+            .unwrap();
+        quotas
+            .check_and_record_at(ip, SuiAddress::random_for_testing_only(), START + 10_000)
+            .unwrap();
+
+        // The first request only leaves the window after 60 seconds.
+        assert_eq!(
+            quotas.check_and_record_at(ip, SuiAddress::random_for_testing_only(), START + 30_000),
+            Err(FaucetError::TooManyRequests(30))
+        );
+        quotas
+            .check_and_record_at(
+                ip,
+                SuiAddress::random_for_testing_only(),
+                START + WINDOW_MS + 1,
+            )
+            .unwrap();
+
+        // Another IP has its own quota.
+        quotas
+            .check_and_record_at(
+                Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))),
+                SuiAddress::random_for_testing_only(),
+                START + WINDOW_MS + 1,
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn per_recipient_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = open_quotas(config(tmp.path()));
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas.check_and_record_at(None, recipient, START).unwrap();
+        quotas.check_and_record_at(None, recipient, START).unwrap();
+        assert_eq!(
+            quotas.check_and_record_at(None, recipient, START + 1),
+            Err(FaucetError::TooManyRequests(60))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejected_requests_are_not_counted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = open_quotas(FaucetConfig {
+            max_requests_per_ip: Some(1),
+            ..config(tmp.path())
+        });
+        let ip = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas
+            .check_and_record_at(ip, SuiAddress::random_for_testing_only(), START)
+            .unwrap();
+        // Rejected by the IP quota, so the recipient keeps both of its requests.
+        assert!(quotas.check_and_record_at(ip, recipient, START).is_err());
+        quotas.check_and_record_at(None, recipient, START).unwrap();
+        quotas.check_and_record_at(None, recipient, START).unwrap();
+    }
+
+    #[tokio::test]
+    async fn released_requests_are_not_counted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = open_quotas(config(tmp.path()));
+        let ip = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas.check_and_record_at(ip, recipient, START).unwrap();
+        let failed = quotas.check_and_record_at(ip, recipient, START).unwrap();
+        assert!(quotas.check_and_record_at(ip, recipient, START).is_err());
+
+        // A request that was not served gives its slot back to both quotas.
+        quotas.release(failed).unwrap();
+        quotas.check_and_record_at(ip, recipient, START).unwrap();
+        assert!(quotas.check_and_record_at(ip, recipient, START).is_err());
+    }
+
+    #[tokio::test]
+    async fn allowlists_are_exempt() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let recipient = SuiAddress::random_for_testing_only();
+        let quotas = open_quotas(FaucetConfig {
+            ip_allowlist: vec![ip],
+            recipient_allowlist: vec![recipient],
+            ..config(tmp.path())
+        });
+
+        for _ in 0..5 {
+            quotas
+                .check_and_record_at(Some(ip), SuiAddress::random_for_testing_only(), START)
+                .unwrap();
+            quotas.check_and_record_at(None, recipient, START).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn quotas_persist_across_restarts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let recipient = SuiAddress::random_for_testing_only();
+        {
+            let quotas = open_quotas(config(tmp.path()));
+            quotas.check_and_record_at(None, recipient, START).unwrap();
+            quotas.check_and_record_at(None, recipient, START).unwrap();
+        }
+
+        let quotas = open_quotas(config(tmp.path()));
+        assert!(quotas.check_and_record_at(None, recipient, START).is_err());
+
+        quotas.remove_expired_at(START + WINDOW_MS).unwrap();
+        assert!(quotas
+            .store
+            .requests_by_recipient
+            .get(&recipient)
+            .unwrap()
+            .is_none());
+    }
+}
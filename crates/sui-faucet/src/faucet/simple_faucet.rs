@@ -5,12 +5,11 @@ use crate::faucet::write_ahead_log;
 use crate::metrics::FaucetMetrics;
 use async_recursion::async_recursion;
 use async_trait::async_trait;
+use futures::StreamExt;
 use mysten_metrics::spawn_monitored_task;
 use prometheus::Registry;
 use shared_crypto::intent::Intent;
-use std::collections::HashMap;
-#[cfg(test)]
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::path::Path;
 use std::sync::{Arc, Weak};
@@ -21,7 +20,7 @@ use ttl_cache::TtlCache;
 use typed_store::Map;
 
 use sui_json_rpc_types::{
-    OwnedObjectRef, SuiObjectDataOptions, SuiTransactionBlockEffectsAPI,
+    OwnedObjectRef, SuiObjectDataOptions, SuiRawData, SuiTransactionBlockEffectsAPI,
     SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_keys::keystore::AccountKeystore;
@@ -30,8 +29,10 @@ use sui_types::object::Owner;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::{
     base_types::{ObjectID, SuiAddress, TransactionDigest},
+    coin::Coin,
     gas_coin::GasCoin,
-    transaction::{Transaction, TransactionData},
+    transaction::{Transaction, TransactionData, TransactionDataAPI},
+    TypeTag,
 };
 use tokio::sync::{
     mpsc::{self, Receiver, Sender},
@@ -43,8 +44,8 @@ use uuid::Uuid;
 
 use super::write_ahead_log::WriteAheadLog;
 use crate::{
-    BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, Faucet, FaucetConfig,
-    FaucetError, FaucetReceipt,
+    BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, CoinTypeConfig, Faucet,
+    FaucetConfig, FaucetError, FaucetReceipt,
 };
 
 pub struct SimpleFaucet {
@@ -61,6 +62,10 @@ pub struct SimpleFaucet {
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
     ttl_expiration: u64,
     coin_amount: u64,
+    /// Pools of pre-funded coins for each coin type other than SUI the faucet sends.
+    coin_pools: HashMap<TypeTag, CoinPool>,
+    /// The type of every coin in `coin_pools`, including the ones currently taken out of a pool.
+    pool_coin_types: HashMap<ObjectID, TypeTag>,
     /// Shuts down the batch transfer task. Used only in testing.
     #[allow(unused)]
     batch_transfer_shutdown: parking_lot::Mutex<Option<oneshot::Sender<()>>>,
@@ -81,6 +86,12 @@ impl fmt::Debug for SimpleFaucet {
     }
 }
 
+/// Coins of a type other than SUI, handed out to one request at a time like gas coins.
+struct CoinPool {
+    producer: Mutex<Sender<ObjectID>>,
+    consumer: Mutex<Receiver<ObjectID>>,
+}
+
 enum GasCoinResponse {
     GasCoinWithInsufficientBalance(ObjectID),
     InvalidGasCoin(ObjectID),
@@ -170,6 +181,51 @@ impl SimpleFaucet {
                     .unwrap();
             }
         }
+
+        // Coins used by transactions still in the WAL are kept out of their pool until those
+        // transactions are retried, so they are not spent twice.
+        let mut wal_coins = HashSet::new();
+        for item in wal.log.safe_iter() {
+            let (_, entry) = item.map_err(FaucetError::internal)?;
+            let inputs = entry.tx.input_objects().unwrap_or_default();
+            wal_coins.extend(inputs.iter().map(|input| input.object_id()));
+        }
+
+        let mut coin_pools = HashMap::new();
+        let mut pool_coin_types = HashMap::new();
+        let client = wallet
+            .get_client()
+            .await
+            .map_err(|e| FaucetError::Wallet(e.to_string()))?;
+        for CoinTypeConfig { coin_type, amount } in &config.coin_types {
+            let coins = client
+                .coin_read_api()
+                .get_coins_stream(active_address, Some(coin_type.to_canonical_string(true)))
+                .filter(|coin| {
+                    futures::future::ready(coin.balance >= amount * config.num_coins as u64)
+                })
+                .collect::<Vec<_>>()
+                .await;
+            info!(%coin_type, "Found {} coins for coin pool", coins.len());
+
+            let (producer, consumer) = mpsc::channel(coins.len().max(1));
+            for coin in coins {
+                pool_coin_types.insert(coin.coin_object_id, coin_type.clone());
+                if !wal_coins.contains(&coin.coin_object_id) {
+                    producer
+                        .try_send(coin.coin_object_id)
+                        .expect("unexpected - queue is large enough to hold all coins");
+                }
+            }
+            coin_pools.insert(
+                coin_type.clone(),
+                CoinPool {
+                    producer: Mutex::new(producer),
+                    consumer: Mutex::new(consumer),
+                },
+            );
+        }
+
         let (batch_transfer_shutdown, mut rx_batch_transfer_shutdown) = oneshot::channel();
 
         let faucet = Self {
@@ -188,6 +244,8 @@ impl SimpleFaucet {
             task_id_cache: TtlCache::new(config.max_request_per_second as usize * 60 * 10).into(),
             ttl_expiration: config.ttl_expiration,
             coin_amount: config.amount,
+            coin_pools,
+            pool_coin_types,
             batch_transfer_shutdown: parking_lot::Mutex::new(Some(batch_transfer_shutdown)),
         };
 
@@ -414,6 +472,19 @@ impl SimpleFaucet {
         tx_data: TransactionData,
         for_batch: bool,
     ) -> Result<SuiTransactionBlockResponse, FaucetError> {
+        // Coins from the coin pools spent by the transaction go back to their pool with the gas
+        // coin.
+        let pool_coins: Vec<_> = tx_data
+            .input_objects()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|input| {
+                let coin_id = input.object_id();
+                self.pool_coin_types
+                    .get(&coin_id)
+                    .map(|coin_type| (coin_id, coin_type))
+            })
+            .collect();
         let signature = self
             .wallet
             .config
@@ -481,6 +552,9 @@ impl SimpleFaucet {
                 } else {
                     self.recycle_gas_coin(coin_id, uuid).await;
                 }
+                for (pool_coin_id, coin_type) in pool_coins {
+                    self.recycle_pool_coin(coin_type, pool_coin_id, uuid).await;
+                }
                 Ok(result)
             }
         }
@@ -547,6 +621,158 @@ impl SimpleFaucet {
         }
     }
 
+    /// Sends coins of `coin_type` from its coin pool, paying for gas with a coin from the gas pool.
+    async fn transfer_coins(
+        &self,
+        coin_type: &TypeTag,
+        amounts: &[u64],
+        recipient: SuiAddress,
+        uuid: Uuid,
+    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+        let total_amount: u64 = amounts.iter().sum();
+        let pool_coin_id = self
+            .prepare_pool_coin(coin_type, total_amount, uuid)
+            .await?;
+
+        let gas_cost = match self.get_gas_cost().await {
+            Ok(gas_cost) => gas_cost,
+            Err(e) => {
+                self.recycle_pool_coin(coin_type, pool_coin_id, uuid).await;
+                return Err(e);
+            }
+        };
+        let gas_coin_id = loop {
+            match self.prepare_gas_coin(gas_cost, uuid, false).await {
+                GasCoinResponse::ValidGasCoin(coin_id) => break coin_id,
+                GasCoinResponse::UnknownGasCoin(coin_id) => {
+                    self.recycle_gas_coin(coin_id, uuid).await;
+                    self.recycle_pool_coin(coin_type, pool_coin_id, uuid).await;
+                    return Err(FaucetError::FullnodeReadingError(format!(
+                        "unknown gas coin {coin_id:?}"
+                    )));
+                }
+                GasCoinResponse::GasCoinWithInsufficientBalance(coin_id)
+                | GasCoinResponse::InvalidGasCoin(coin_id) => {
+                    warn!(?uuid, ?coin_id, "Unusable gas coin, removing from pool");
+                    self.metrics.total_discarded_coins.inc();
+                }
+                GasCoinResponse::NoGasCoinAvailable => {
+                    self.recycle_pool_coin(coin_type, pool_coin_id, uuid).await;
+                    return Err(FaucetError::NoGasCoinAvailable);
+                }
+            }
+        };
+
+        let tx_data = match self
+            .build_pay_coin_txn(pool_coin_id, gas_coin_id, recipient, amounts, gas_cost)
+            .await
+        {
+            Ok(tx_data) => tx_data,
+            Err(e) => {
+                self.recycle_gas_coin(gas_coin_id, uuid).await;
+                self.recycle_pool_coin(coin_type, pool_coin_id, uuid).await;
+                return Err(FaucetError::internal(e));
+            }
+        };
+
+        {
+            // As for SUI, register the transaction in the WAL (keyed by its gas coin) before
+            // sending it, so it is retried if the faucet goes down before it gets a response.
+            let mut wal = self.wal.lock().await;
+            wal.reserve(uuid, gas_coin_id, recipient, tx_data.clone())
+                .map_err(FaucetError::internal)?;
+        }
+        let response = self
+            .sign_and_execute_txn(uuid, recipient, gas_coin_id, tx_data, false)
+            .await?;
+        self.metrics.total_coin_requests_succeeded.inc();
+        self.check_and_map_transfer_gas_result(response, amounts.len(), recipient)
+            .await
+    }
+
+    /// Pulls a coin from the pool of `coin_type`, discarding coins that no longer belong to the
+    /// faucet or do not have enough balance, until it finds one worth `total_amount`.
+    async fn prepare_pool_coin(
+        &self,
+        coin_type: &TypeTag,
+        total_amount: u64,
+        uuid: Uuid,
+    ) -> Result<ObjectID, FaucetError> {
+        let pool = self
+            .coin_pools
+            .get(coin_type)
+            .ok_or_else(|| FaucetError::UnsupportedCoinType(coin_type.to_string()))?;
+        loop {
+            let coin_id = {
+                let Ok(mut consumer) = timeout(LOCK_TIMEOUT, pool.consumer.lock()).await else {
+                    error!(?uuid, %coin_type, "Timeout when getting coin pool consumer lock");
+                    return Err(FaucetError::NoCoinAvailable(coin_type.to_string()));
+                };
+                let Ok(coin_id) = timeout(RECV_TIMEOUT, consumer.recv()).await else {
+                    error!(?uuid, %coin_type, "Timeout when getting coin from the coin pool");
+                    return Err(FaucetError::NoCoinAvailable(coin_type.to_string()));
+                };
+                let Some(coin_id) = coin_id else {
+                    unreachable!("channel is closed");
+                };
+                coin_id
+            };
+
+            match self.get_pool_coin_balance(coin_type, coin_id).await {
+                Ok(Some(balance)) if balance >= total_amount => return Ok(coin_id),
+                Ok(_) => {
+                    warn!(?uuid, ?coin_id, %coin_type, "Unusable coin, removing from coin pool");
+                }
+                Err(e) => {
+                    error!(?uuid, ?coin_id, "Fullnode read error: {e:?}");
+                    self.recycle_pool_coin(coin_type, coin_id, uuid).await;
+                    return Err(FaucetError::FullnodeReadingError(format!(
+                        "unknown coin {coin_id:?}"
+                    )));
+                }
+            }
+        }
+    }
+
+    /// The balance of `coin_id` if it is a coin of `coin_type` owned by the faucet, `None` if it
+    /// is not.
+    async fn get_pool_coin_balance(
+        &self,
+        coin_type: &TypeTag,
+        coin_id: ObjectID,
+    ) -> anyhow::Result<Option<u64>> {
+        let client = self.wallet.get_client().await?;
+        let Some(object) = client
+            .read_api()
+            .get_object_with_options(coin_id, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .data
+        else {
+            return Ok(None);
+        };
+        let Some(SuiRawData::MoveObject(raw)) = &object.bcs else {
+            return Ok(None);
+        };
+        if raw.type_ != Coin::type_(coin_type.clone())
+            || object.owner != Some(Owner::AddressOwner(self.active_address))
+        {
+            return Ok(None);
+        }
+        Ok(Some(raw.deserialize::<Coin>()?.value()))
+    }
+
+    async fn recycle_pool_coin(&self, coin_type: &TypeTag, coin_id: ObjectID, uuid: Uuid) {
+        let Some(pool) = self.coin_pools.get(coin_type) else {
+            return;
+        };
+        pool.producer
+            .lock()
+            .await
+            .try_send(coin_id)
+            .expect("unexpected - queue is large enough to hold all coins");
+        info!(?uuid, ?coin_id, %coin_type, "Recycled pool coin");
+    }
+
     async fn recycle_gas_coin(&self, coin_id: ObjectID, uuid: Uuid) {
         // Once transactions are done, in despite of success or failure,
         // we put back the coins. The producer should never wait indefinitely,
@@ -678,6 +904,31 @@ impl SimpleFaucet {
             })
     }
 
+    async fn build_pay_coin_txn(
+        &self,
+        coin_id: ObjectID,
+        gas_coin_id: ObjectID,
+        recipient: SuiAddress,
+        amounts: &[u64],
+        budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let coin = self.wallet.get_object_ref(coin_id).await?;
+        let gas_payment = self.wallet.get_object_ref(gas_coin_id).await?;
+        let gas_price = self.wallet.get_reference_gas_price().await?;
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.pay(vec![coin], vec![recipient; amounts.len()], amounts.to_vec())?;
+            builder.finish()
+        };
+        Ok(TransactionData::new_programmable(
+            self.active_address,
+            vec![gas_payment],
+            pt,
+            budget,
+            gas_price,
+        ))
+    }
+
     async fn check_and_map_transfer_gas_result(
         &self,
         res: SuiTransactionBlockResponse,
@@ -837,6 +1088,17 @@ impl SimpleFaucet {
         self.wallet
     }
 
+    /// The coins in the pool of `coin_type`, in the order they are queued.
+    #[cfg(test)]
+    async fn drain_coin_pool(&self, coin_type: &TypeTag) -> Vec<ObjectID> {
+        let mut consumer = self.coin_pools[coin_type].consumer.lock().await;
+        let mut coins = vec![];
+        while let Ok(coin_id) = consumer.try_recv() {
+            coins.push(coin_id);
+        }
+        coins
+    }
+
     #[cfg(test)]
     async fn drain_gas_queue(&mut self, expected_gas_count: usize) -> HashSet<ObjectID> {
         use tokio::sync::mpsc::error::TryRecvError;
@@ -909,6 +1171,45 @@ impl Faucet for SimpleFaucet {
         Ok(faucet_receipt)
     }
 
+    async fn send_coins(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &TypeTag,
+        amounts: &[u64],
+    ) -> Result<FaucetReceipt, FaucetError> {
+        info!(?recipient, uuid = ?id, %coin_type, ?amounts, "Getting faucet coin requests");
+
+        let (digest, coin_ids) = self
+            .transfer_coins(coin_type, amounts, recipient, id)
+            .await?;
+
+        info!(uuid = ?id, ?recipient, ?digest, "Pay txn succeeded");
+        // Every coin split off is worth the amount requested for it.
+        let sent = coin_ids
+            .into_iter()
+            .zip(amounts)
+            .map(|(id, amount)| CoinInfo {
+                transfer_tx_digest: digest,
+                amount: *amount,
+                id,
+            })
+            .collect();
+
+        let faucet_receipt = FaucetReceipt { sent };
+        let mut task_map = self.task_id_cache.lock().await;
+        task_map.insert(
+            id,
+            BatchSendStatus {
+                status: BatchSendStatusType::SUCCEEDED,
+                transferred_gas_objects: Some(faucet_receipt.clone()),
+            },
+            Duration::from_secs(self.ttl_expiration),
+        );
+
+        Ok(faucet_receipt)
+    }
+
     async fn batch_send(
         &self,
         id: Uuid,
@@ -1089,12 +1390,16 @@ mod tests {
     use super::*;
     use anyhow::*;
     use shared_crypto::intent::Intent;
+    use sui_json_rpc_types::ObjectChange;
     use sui_json_rpc_types::SuiExecutionStatus;
     use sui_json_rpc_types::SuiTransactionBlockEffects;
     use sui_sdk::wallet_context::WalletContext;
+    use sui_types::parse_sui_type_tag;
+    use sui_types::transaction::ObjectArg;
     use sui_types::transaction::SenderSignedData;
     use sui_types::transaction::TransactionDataAPI;
-    use test_cluster::TestClusterBuilder;
+    use sui_types::Identifier;
+    use test_cluster::{TestCluster, TestClusterBuilder};
 
     async fn execute_tx(
         ctx: &mut WalletContext,
@@ -1126,6 +1431,82 @@ mod tests {
         }
     }
 
+    /// Publishes the `my_coin` example package, and mints `count` coins worth `amount` each to
+    /// the active address of the cluster's wallet. Returns the coin type.
+    async fn publish_and_mint_coins(
+        test_cluster: &TestCluster,
+        count: usize,
+        amount: u64,
+    ) -> TypeTag {
+        let tx = test_cluster
+            .test_transaction_builder()
+            .await
+            .publish_examples("coin")
+            .build();
+        let changes = test_cluster
+            .sign_and_execute_transaction(&tx)
+            .await
+            .object_changes
+            .unwrap();
+        let package = changes
+            .iter()
+            .find_map(|change| match change {
+                ObjectChange::Published { package_id, .. } => Some(*package_id),
+                _ => None,
+            })
+            .unwrap();
+        let mut treasury = changes
+            .iter()
+            .find_map(|change| match change {
+                ObjectChange::Created {
+                    object_type,
+                    object_id,
+                    version,
+                    digest,
+                    ..
+                } if object_type.name.as_str() == "TreasuryCap" => {
+                    Some((*object_id, *version, *digest))
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        let recipient = test_cluster.wallet.active_address().unwrap();
+        for _ in 0..count {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            let args = vec![
+                builder.obj(ObjectArg::ImmOrOwnedObject(treasury)).unwrap(),
+                builder.pure(amount).unwrap(),
+                builder.pure(recipient).unwrap(),
+            ];
+            builder.programmable_move_call(
+                package,
+                Identifier::new("my_coin").unwrap(),
+                Identifier::new("mint").unwrap(),
+                vec![],
+                args,
+            );
+            let tx = test_cluster
+                .test_transaction_builder()
+                .await
+                .programmable(builder.finish())
+                .build();
+            let effects = test_cluster
+                .sign_and_execute_transaction(&tx)
+                .await
+                .effects
+                .unwrap();
+            treasury = effects
+                .mutated()
+                .iter()
+                .find(|object| object.object_id() == treasury.0)
+                .unwrap()
+                .reference
+                .to_object_ref();
+        }
+        parse_sui_type_tag(&format!("{package}::my_coin::MY_COIN")).unwrap()
+    }
+
     #[tokio::test]
     async fn simple_faucet_basic_interface_should_work() {
         telemetry_subscribers::init_for_testing();
@@ -1214,6 +1595,192 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_send_coins_of_unsupported_type() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let context = test_cluster.wallet;
+        let coin_type = parse_sui_type_tag("0x2::test_coin::TEST_COIN").unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig {
+            coin_types: vec!["0x3::staked_coin::STAKED_COIN=100".parse().unwrap()],
+            ..Default::default()
+        };
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+        faucet.shutdown_batch_send_task();
+
+        let result = faucet
+            .send_coins(
+                Uuid::new_v4(),
+                SuiAddress::random_for_testing_only(),
+                &coin_type,
+                &[100],
+            )
+            .await;
+        assert_eq!(
+            result.unwrap_err(),
+            FaucetError::UnsupportedCoinType(coin_type.to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_coins() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let coin_type = publish_and_mint_coins(&test_cluster, 2, 1_000).await;
+        let client = test_cluster.wallet.get_client().await.unwrap();
+        let context = test_cluster.wallet;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig {
+            coin_types: vec![CoinTypeConfig {
+                coin_type: coin_type.clone(),
+                amount: 100,
+            }],
+            ..Default::default()
+        };
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+        faucet.shutdown_batch_send_task();
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let receipt = faucet
+            .send_coins(Uuid::new_v4(), recipient, &coin_type, &[100, 200])
+            .await
+            .unwrap();
+        assert_eq!(
+            receipt
+                .sent
+                .iter()
+                .map(|coin| coin.amount)
+                .collect::<Vec<_>>(),
+            vec![100, 200]
+        );
+        let balance = client
+            .coin_read_api()
+            .get_balance(recipient, Some(coin_type.to_canonical_string(true)))
+            .await
+            .unwrap();
+        assert_eq!(balance.total_balance, 300);
+
+        // Both pool coins are back in the pool.
+        let pool = faucet.drain_coin_pool(&coin_type).await;
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_faucet_restart_retries_pay_coin_txn_from_wal() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let coin_type = publish_and_mint_coins(&test_cluster, 2, 1_000).await;
+        let client = test_cluster.wallet.get_client().await.unwrap();
+        let context = test_cluster.wallet;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let config = FaucetConfig {
+            coin_types: vec![CoinTypeConfig {
+                coin_type: coin_type.clone(),
+                amount: 100,
+            }],
+            ..Default::default()
+        };
+        let faucet = SimpleFaucet::new(
+            context,
+            &Registry::new(),
+            &tmp.path().join("faucet.wal"),
+            config.clone(),
+        )
+        .await
+        .unwrap();
+
+        // Reserve a transaction paying a pool coin in the WAL, as if the faucet went down before
+        // sending it.
+        let recipient = SuiAddress::random_for_testing_only();
+        let uuid = Uuid::new_v4();
+        let pool_coin_id = faucet
+            .prepare_pool_coin(&coin_type, 100, uuid)
+            .await
+            .unwrap();
+        let gas_cost = faucet.get_gas_cost().await.unwrap();
+        let GasCoinResponse::ValidGasCoin(gas_coin_id) =
+            faucet.prepare_gas_coin(gas_cost, uuid, false).await
+        else {
+            panic!("prepare_gas_coin did not give a valid coin.")
+        };
+        let tx_data = faucet
+            .build_pay_coin_txn(pool_coin_id, gas_coin_id, recipient, &[100], gas_cost)
+            .await
+            .unwrap();
+        faucet
+            .wal
+            .lock()
+            .await
+            .reserve(uuid, gas_coin_id, recipient, tx_data)
+            .unwrap();
+        faucet.shutdown_batch_send_task();
+        let kept_context = Arc::try_unwrap(faucet).unwrap().teardown();
+
+        // The restarted faucet holds the pool coin out of its pool until it retried the
+        // transaction, then puts it back once.
+        let faucet = SimpleFaucet::new(
+            kept_context,
+            &Registry::new(),
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+        faucet.shutdown_batch_send_task();
+        assert!(faucet.wal.lock().await.log.is_empty());
+
+        let balance = client
+            .coin_read_api()
+            .get_balance(recipient, Some(coin_type.to_canonical_string(true)))
+            .await
+            .unwrap();
+        assert_eq!(balance.total_balance, 100);
+
+        let pool = faucet.drain_coin_pool(&coin_type).await;
+        assert_eq!(pool.len(), 2);
+        assert_eq!(
+            pool.iter()
+                .filter(|coin_id| **coin_id == pool_coin_id)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_parse_coin_type_config() {
+        let config: CoinTypeConfig = "0x2::test_coin::TEST_COIN=100".parse().unwrap();
+        assert_eq!(
+            config,
+            CoinTypeConfig {
+                coin_type: parse_sui_type_tag("0x2::test_coin::TEST_COIN").unwrap(),
+                amount: 100,
+            }
+        );
+        assert!("0x2::test_coin::TEST_COIN"
+            .parse::<CoinTypeConfig>()
+            .is_err());
+        assert!("0x2::test_coin::TEST_COIN=a lot"
+            .parse::<CoinTypeConfig>()
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_transfer_state() {
         let test_cluster = TestClusterBuilder::new().build().await;
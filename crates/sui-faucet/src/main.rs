@@ -38,8 +38,8 @@ async fn main() -> Result<(), anyhow::Error> {
     info!("Starting Prometheus HTTP endpoint at {}", prom_binding);
     let registry_service = mysten_metrics::start_prometheus_server(prom_binding);
     let prometheus_registry = registry_service.default_registry();
-    let app_state = Arc::new(AppState {
-        faucet: SimpleFaucet::new(
+    let app_state = Arc::new(AppState::new(
+        SimpleFaucet::new(
             context,
             &prometheus_registry,
             write_ahead_log,
@@ -48,7 +48,7 @@ async fn main() -> Result<(), anyhow::Error> {
         .await
         .unwrap(),
         config,
-    });
+    ));
 
     start_faucet(app_state, max_concurrency, &prometheus_registry).await
 }
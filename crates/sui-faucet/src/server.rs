@@ -3,20 +3,21 @@
 
 use crate::{
     AppState, BatchFaucetResponse, BatchStatusFaucetResponse, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, RequestMetricsLayer,
+    FaucetRequest, FaucetResponse, RecordedRequest, RequestMetricsLayer,
 };
 
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::Path,
+    extract::{ConnectInfo, Path},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{get, post},
     BoxError, Extension, Json, Router,
 };
-use http::Method;
+use http::{header, HeaderMap, Method};
 use mysten_metrics::spawn_monitored_task;
 use prometheus::Registry;
+use serde::Serialize;
 use std::{
     borrow::Cow,
     net::{IpAddr, SocketAddr},
@@ -26,6 +27,7 @@ use std::{
 };
 use sui_config::SUI_CLIENT_CONFIG;
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::{base_types::SuiAddress, gas_coin::GAS, parse_sui_type_tag, TypeTag};
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
@@ -50,6 +52,7 @@ pub async fn start_faucet(
         request_buffer_size,
         max_request_per_second,
         wal_retry_interval,
+        quota_window_secs,
         ..
     } = app_state.config;
 
@@ -74,6 +77,27 @@ pub async fn start_faucet(
                 .into_inner(),
         );
 
+    if app_state.quotas.is_some() {
+        let quotas_state = app_state.clone();
+        spawn_monitored_task!(async move {
+            info!("Starting task to remove expired request quotas.");
+            loop {
+                tokio::time::sleep(Duration::from_secs(quota_window_secs)).await;
+                let state = quotas_state.clone();
+                // Removing expired quotas scans the whole store.
+                let result = tokio::task::spawn_blocking(move || {
+                    state.quotas.as_ref().map_or(Ok(()), |q| q.remove_expired())
+                })
+                .await;
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => warn!("Failed to remove expired request quotas: {:?}", e),
+                    Err(e) => warn!("Failed to remove expired request quotas: {:?}", e),
+                }
+            }
+        });
+    }
+
     spawn_monitored_task!(async move {
         info!("Starting task to clear WAL.");
         loop {
@@ -86,7 +110,11 @@ pub async fn start_faucet(
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
 
@@ -98,8 +126,10 @@ async fn health() -> &'static str {
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
     let id = Uuid::new_v4();
     // ID for traceability
     info!(uuid = ?id, "Got new gas request.");
@@ -110,19 +140,34 @@ async fn batch_request_gas(
             Json(BatchFaucetResponse::from(FaucetError::Internal(
                 "Input Error.".to_string(),
             ))),
-        );
+        )
+            .into_response();
     };
 
-    if state.config.batch_enabled {
+    let (coin_type, amounts) = match requested_coins(&state.config, request.coin_type.as_deref()) {
+        Ok(coins) => coins,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(BatchFaucetResponse::from(e))).into_response()
+        }
+    };
+
+    let ip = client_ip(&state.config, addr, &headers);
+    let recorded = match record_request(&state, ip, request.recipient) {
+        Ok(recorded) => recorded,
+        Err(e) => return quota_error_response::<BatchFaucetResponse>(id, e),
+    };
+
+    // Only SUI is sent in batches.
+    if state.config.batch_enabled && coin_type.is_none() {
         let result = spawn_monitored_task!(async move {
-            state
+            let result = state
                 .faucet
-                .batch_send(
-                    id,
-                    request.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
-                )
-                .await
+                .batch_send(id, request.recipient, &amounts)
+                .await;
+            if result.is_err() {
+                release_request(&state, recorded);
+            }
+            result
         })
         .await
         .unwrap();
@@ -130,7 +175,7 @@ async fn batch_request_gas(
         match result {
             Ok(v) => {
                 info!(uuid =?id, "Request is successfully served");
-                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(v)))
+                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(v))).into_response()
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
@@ -138,20 +183,26 @@ async fn batch_request_gas(
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
                 )
+                    .into_response()
             }
         }
     } else {
         // TODO (jian): remove this feature gate when batch has proven to be baked long enough
         info!(uuid = ?id, "Falling back to v1 implementation");
         let result = spawn_monitored_task!(async move {
-            state
-                .faucet
-                .send(
-                    id,
-                    request.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
-                )
-                .await
+            let result = match coin_type {
+                Some(coin_type) => {
+                    state
+                        .faucet
+                        .send_coins(id, request.recipient, &coin_type, &amounts)
+                        .await
+                }
+                None => state.faucet.send(id, request.recipient, &amounts).await,
+            };
+            if result.is_err() {
+                release_request(&state, recorded);
+            }
+            result
         })
         .await
         .unwrap();
@@ -159,7 +210,7 @@ async fn batch_request_gas(
         match result {
             Ok(_) => {
                 info!(uuid =?id, "Request is successfully served");
-                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id)))
+                (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id))).into_response()
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
@@ -167,6 +218,7 @@ async fn batch_request_gas(
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
                 )
+                    .into_response()
             }
         }
     }
@@ -203,24 +255,44 @@ async fn request_status(
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            let (coin_type, amounts) =
+                match requested_coins(&state.config, requests.coin_type.as_deref()) {
+                    Ok(coins) => coins,
+                    Err(e) => {
+                        return (StatusCode::BAD_REQUEST, Json(FaucetResponse::from(e)))
+                            .into_response()
+                    }
+                };
+            let ip = client_ip(&state.config, addr, &headers);
+            let recorded = match record_request(&state, ip, requests.recipient) {
+                Ok(recorded) => recorded,
+                Err(e) => return quota_error_response::<FaucetResponse>(id, e),
+            };
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the recycling of coins
             spawn_monitored_task!(async move {
-                state
-                    .faucet
-                    .send(
-                        id,
-                        requests.recipient,
-                        &vec![state.config.amount; state.config.num_coins],
-                    )
-                    .await
+                let result = match coin_type {
+                    Some(coin_type) => {
+                        state
+                            .faucet
+                            .send_coins(id, requests.recipient, &coin_type, &amounts)
+                            .await
+                    }
+                    None => state.faucet.send(id, requests.recipient, &amounts).await,
+                };
+                if result.is_err() {
+                    release_request(&state, recorded);
+                }
+                result
             })
             .await
             .unwrap()
@@ -232,12 +304,13 @@ async fn request_gas(
                     "Input Error.".to_string(),
                 ))),
             )
+                .into_response()
         }
     };
     match result {
         Ok(v) => {
             info!(uuid =?id, "Request is successfully served");
-            (StatusCode::CREATED, Json(FaucetResponse::from(v)))
+            (StatusCode::CREATED, Json(FaucetResponse::from(v))).into_response()
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
@@ -245,10 +318,97 @@ async fn request_gas(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(FaucetResponse::from(v)),
             )
+                .into_response()
         }
     }
 }
 
+/// The coin type to send for a request and the amount of each coin, with no coin type standing
+/// for SUI.
+fn requested_coins(
+    config: &FaucetConfig,
+    coin_type: Option<&str>,
+) -> Result<(Option<TypeTag>, Vec<u64>), FaucetError> {
+    let sui_coins = (None, vec![config.amount; config.num_coins]);
+    let Some(coin_type) = coin_type else {
+        return Ok(sui_coins);
+    };
+    let unsupported = || FaucetError::UnsupportedCoinType(coin_type.to_string());
+    let coin_type = parse_sui_type_tag(coin_type).map_err(|_| unsupported())?;
+    if coin_type == GAS::type_tag() {
+        return Ok(sui_coins);
+    }
+    let coin = config
+        .coin_types
+        .iter()
+        .find(|coin| coin.coin_type == coin_type)
+        .ok_or_else(unsupported)?;
+    Ok((Some(coin_type), vec![coin.amount; config.num_coins]))
+}
+
+/// The IP of the client making a request, taken from `X-Forwarded-For` if the faucet is
+/// configured to trust it, and from the connection otherwise. Only the entries appended by the
+/// trusted proxies are used, counting from the right, as a client can send any value of its own.
+fn client_ip(config: &FaucetConfig, addr: SocketAddr, headers: &HeaderMap) -> IpAddr {
+    if config.use_forwarded_for && config.trusted_proxy_hops > 0 {
+        // Repeated headers are equivalent to a single comma separated one.
+        let forwarded_for: Vec<_> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+        let ip = forwarded_for
+            .len()
+            .checked_sub(config.trusted_proxy_hops)
+            .and_then(|index| forwarded_for[index].trim().parse().ok());
+        if let Some(ip) = ip {
+            return ip;
+        }
+    }
+    addr.ip()
+}
+
+/// Counts a request against the per-IP and per-recipient quotas, if any are configured.
+fn record_request(
+    state: &AppState,
+    ip: IpAddr,
+    recipient: SuiAddress,
+) -> Result<Option<RecordedRequest>, FaucetError> {
+    state
+        .quotas
+        .as_ref()
+        .map(|quotas| quotas.check_and_record(Some(ip), recipient))
+        .transpose()
+}
+
+/// Gives back the quota used by a request that could not be served.
+fn release_request(state: &AppState, recorded: Option<RecordedRequest>) {
+    if let (Some(quotas), Some(recorded)) = (&state.quotas, recorded) {
+        if let Err(e) = quotas.release(recorded) {
+            warn!("Failed to release request quota: {:?}", e);
+        }
+    }
+}
+
+/// Responds to a request rejected by the per-IP or per-recipient quotas, telling the client when
+/// to retry.
+fn quota_error_response<R: Serialize + From<FaucetError>>(
+    id: Uuid,
+    error: FaucetError,
+) -> Response {
+    warn!(uuid =?id, "Request rejected: {:?}", error);
+    match error {
+        FaucetError::TooManyRequests(retry_after) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            Json(R::from(error)),
+        )
+            .into_response(),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(R::from(error))).into_response(),
+    }
+}
+
 pub fn create_wallet_context(
     timeout_secs: u64,
     config_dir: PathBuf,
@@ -275,3 +435,44 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
         Cow::from(format!("Unhandled internal error: {}", error)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(forwarded_for: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in forwarded_for {
+            headers.append("x-forwarded-for", value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_client_ip_from_forwarded_for() {
+        let addr: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        let ip = |ip: &str| ip.parse::<IpAddr>().unwrap();
+        let mut config = FaucetConfig {
+            use_forwarded_for: true,
+            ..Default::default()
+        };
+
+        // The leftmost entry is chosen by the client, the rightmost one by the proxy.
+        let spoofed = headers(&["1.1.1.1, 2.2.2.2"]);
+        assert_eq!(client_ip(&config, addr, &spoofed), ip("2.2.2.2"));
+        assert_eq!(
+            client_ip(&config, addr, &headers(&["1.1.1.1", "2.2.2.2"])),
+            ip("2.2.2.2")
+        );
+
+        config.trusted_proxy_hops = 2;
+        assert_eq!(client_ip(&config, addr, &spoofed), ip("1.1.1.1"));
+        // Fewer entries than trusted proxies
+        assert_eq!(client_ip(&config, addr, &headers(&["2.2.2.2"])), addr.ip());
+
+        config.use_forwarded_for = false;
+        assert_eq!(client_ip(&config, addr, &spoofed), addr.ip());
+        config.use_forwarded_for = true;
+        assert_eq!(client_ip(&config, addr, &HeaderMap::new()), addr.ip());
+    }
+}
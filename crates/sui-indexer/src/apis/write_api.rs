@@ -11,10 +11,10 @@ use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_api::{WriteApiClient, WriteApiServer};
 use sui_json_rpc_types::{
     DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseOptions, SuiTransactionSubmissionStatus,
 };
 use sui_open_rpc::Module;
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::sui_serde::BigInt;
 
@@ -52,6 +52,15 @@ impl WriteApiServer for WriteApi {
         .into())
     }
 
+    async fn get_transaction_submission_status(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<SuiTransactionSubmissionStatus>> {
+        self.fullnode
+            .get_transaction_submission_status(digest)
+            .await
+    }
+
     async fn dev_inspect_transaction_block(
         &self,
         sender_address: SuiAddress,
@@ -7,10 +7,10 @@ use jsonrpsee::proc_macros::rpc;
 
 use sui_json_rpc_types::{
     DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseOptions, SuiTransactionSubmissionStatus,
 };
 use sui_open_rpc_macros::open_rpc;
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::sui_serde::BigInt;
 
@@ -40,6 +40,17 @@ pub trait WriteApi {
         request_type: Option<ExecuteTransactionRequestType>,
     ) -> RpcResult<SuiTransactionBlockResponse>;
 
+    /// Return where a transaction submitted to this node is: pending or failed in the quorum driver,
+    /// certified, executed by this node, or included in a checkpoint executed by this node.
+    /// The status of a transaction this node has not executed is only kept for a limited time
+    /// after it was submitted.
+    #[method(name = "getTransactionSubmissionStatus")]
+    async fn get_transaction_submission_status(
+        &self,
+        /// the digest of the submitted transaction
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<SuiTransactionSubmissionStatus>>;
+
     /// Runs the transaction in dev-inspect mode. Which allows for nearly any
     /// transaction (or Move call) with any arguments. Detailed results are
     /// provided, including both the transaction effects and any return values.
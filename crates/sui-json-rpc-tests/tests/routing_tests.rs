@@ -10,6 +10,7 @@ use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::rpc_params;
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
+use serde_json::{json, Value};
 use std::env;
 use sui_config::local_ip_utils;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerType, SuiRpcModule};
@@ -136,6 +137,69 @@ async fn test_disable_routing() {
     assert!(response.is_err());
 }
 
+#[tokio::test]
+async fn test_batch_request() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new(), None, None);
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_max_batch_size(3);
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder
+        .start(address, None, ServerType::Http, None)
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+    let client = reqwest::Client::new();
+    let foo = |id: u64| json!({"jsonrpc": "2.0", "id": id, "method": "test_foo", "params": [true]});
+
+    // Responses are returned in the order of the requests, and a malformed or failing request
+    // does not fail the whole batch.
+    let responses: Value = client
+        .post(&url)
+        .json(&json!([
+            foo(1),
+            "Bad json input",
+            {"jsonrpc": "2.0", "id": 3, "method": "test_foo_1_5", "params": ["string"]},
+        ]))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let responses = responses.as_array().unwrap();
+    assert_eq!(3, responses.len());
+    assert_eq!(json!(1), responses[0]["id"]);
+    assert_eq!(json!("Some string"), responses[0]["result"]);
+    assert!(responses[1]["error"].is_object());
+    assert_eq!(json!(3), responses[2]["id"]);
+    assert!(responses[2]["error"].is_object());
+
+    // Batches over the maximum size are rejected with a single error
+    let response: Value = client
+        .post(&url)
+        .json(&json!([foo(1), foo(2), foo(3), foo(4)]))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(response["error"].is_object());
+
+    // Empty batches are invalid requests
+    let response: Value = client
+        .post(&url)
+        .json(&json!([]))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(response["error"].is_object());
+}
+
 // TODO(chris): clean up this after March 27th, 2023
 // #[tokio::test]
 // async fn test_rpc_backward_compatibility_batched_request() {
@@ -28,7 +28,8 @@ use sui_types::base_types::{
 };
 use sui_types::crypto::SuiSignature;
 use sui_types::digests::{
-    CheckpointDigest, ConsensusCommitDigest, ObjectDigest, TransactionEventsDigest,
+    CheckpointDigest, ConsensusCommitDigest, ObjectDigest, TransactionEffectsDigest,
+    TransactionEventsDigest,
 };
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
 use sui_types::error::{ExecutionError, SuiError, SuiResult};
@@ -39,7 +40,7 @@ use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::messages_consensus::ConsensusDeterminedVersionAssignments;
 use sui_types::object::Owner;
 use sui_types::parse_sui_type_tag;
-use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::quorum_driver_types::{ExecuteTransactionRequestType, TransactionSubmissionStatus};
 use sui_types::signature::GenericSignature;
 use sui_types::storage::{DeleteKind, WriteKind};
 use sui_types::sui_serde::Readable;
@@ -1333,6 +1334,62 @@ impl From<ExecutionStatus> for SuiExecutionStatus {
     }
 }
 
+/// Where a transaction submitted to a full node is, as seen by that node.
+#[serde_as]
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(
+    rename = "TransactionSubmissionStatus",
+    rename_all = "camelCase",
+    tag = "status"
+)]
+pub enum SuiTransactionSubmissionStatus {
+    /// Submitted, and not yet certified by a quorum of validators.
+    Pending,
+    /// Certified by a quorum of validators, and not yet executed by the node.
+    #[serde(rename_all = "camelCase")]
+    Certified {
+        effects_digest: TransactionEffectsDigest,
+    },
+    /// Executed by the node, and not yet included in a checkpoint executed by the node.
+    #[serde(rename_all = "camelCase")]
+    ExecutedLocally {
+        effects_digest: TransactionEffectsDigest,
+    },
+    /// Included in a checkpoint executed by the node.
+    #[serde(rename_all = "camelCase")]
+    Checkpointed {
+        effects_digest: TransactionEffectsDigest,
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "BigInt<u64>")]
+        checkpoint: CheckpointSequenceNumber,
+    },
+    /// The transaction could not be finalized.
+    Failed { error: String },
+}
+
+impl From<TransactionSubmissionStatus> for SuiTransactionSubmissionStatus {
+    fn from(status: TransactionSubmissionStatus) -> Self {
+        match status {
+            TransactionSubmissionStatus::Pending => Self::Pending,
+            TransactionSubmissionStatus::Certified(effects_digest) => {
+                Self::Certified { effects_digest }
+            }
+            TransactionSubmissionStatus::ExecutedLocally(effects_digest) => {
+                Self::ExecutedLocally { effects_digest }
+            }
+            TransactionSubmissionStatus::Checkpointed(effects_digest, _epoch, checkpoint) => {
+                Self::Checkpointed {
+                    effects_digest,
+                    checkpoint,
+                }
+            }
+            TransactionSubmissionStatus::Failed(error) => Self::Failed {
+                error: error.to_string(),
+            },
+        }
+    }
+}
+
 fn to_sui_object_ref(refs: Vec<ObjectRef>) -> Vec<SuiObjectRef> {
     refs.into_iter().map(SuiObjectRef::from).collect()
 }
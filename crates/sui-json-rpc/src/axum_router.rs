@@ -28,11 +28,13 @@ use sui_types::traffic_control::ClientIdSource;
 use sui_types::traffic_control::{PolicyConfig, Weight};
 use tracing::error;
 
+use crate::metrics::BatchMetrics;
 use crate::routing_layer::RpcRouter;
 use sui_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
 
 pub const MAX_RESPONSE_SIZE: u32 = 2 << 30;
 const TOO_MANY_REQUESTS_MSG: &str = "Too many requests";
+const EMPTY_BATCH_MSG: &str = "Empty batch request";
 
 #[derive(Clone, Debug)]
 pub struct JsonRpcService<L> {
@@ -45,6 +47,9 @@ pub struct JsonRpcService<L> {
     rpc_router: RpcRouter,
     traffic_controller: Option<Arc<TrafficController>>,
     client_id_source: Option<ClientIdSource>,
+    /// Maximum number of requests in a batch request.
+    max_batch_size: usize,
+    batch_metrics: BatchMetrics,
 }
 
 impl<L> JsonRpcService<L> {
@@ -55,11 +60,15 @@ impl<L> JsonRpcService<L> {
         remote_fw_config: Option<RemoteFirewallConfig>,
         policy_config: Option<PolicyConfig>,
         traffic_controller_metrics: TrafficControllerMetrics,
+        max_batch_size: usize,
+        batch_metrics: BatchMetrics,
     ) -> Self {
         Self {
             methods,
             rpc_router,
             logger,
+            max_batch_size,
+            batch_metrics,
             id_provider: Arc::new(RandomIntegerIdProvider),
             traffic_controller: policy_config.clone().map(|policy| {
                 Arc::new(TrafficController::spawn(
@@ -211,30 +220,95 @@ async fn process_raw_request<L: Logger>(
         None => None,
     };
     if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-        // check if either IP is blocked, in which case return early
-        if let Some(traffic_controller) = &service.traffic_controller {
-            if let Err(blocked_response) =
-                handle_traffic_req(traffic_controller.clone(), &client).await
-            {
-                return blocked_response;
-            }
-        }
+        process_traffic_controlled_request(service, request, api_version, client).await
+    } else if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+        process_batch_request(service, batch, api_version, client).await
+    } else {
+        let (id, code) = prepare_error(raw_request);
+        MethodResponse::error(id, ErrorObject::from(code))
+    }
+}
 
-        // handle response tallying
-        let response = process_request(request, api_version, service.call_data()).await;
-        if let Some(traffic_controller) = &service.traffic_controller {
-            handle_traffic_resp(traffic_controller.clone(), client, &response);
+async fn process_traffic_controlled_request<L: Logger>(
+    service: &JsonRpcService<L>,
+    request: Request<'_>,
+    api_version: Option<&str>,
+    client: Option<IpAddr>,
+) -> MethodResponse {
+    // check if either IP is blocked, in which case return early
+    if let Some(traffic_controller) = &service.traffic_controller {
+        if let Err(blocked_response) = handle_traffic_req(traffic_controller.clone(), &client).await
+        {
+            return blocked_response;
         }
+    }
 
-        response
-    } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-        MethodResponse::error(
+    // handle response tallying
+    let response = process_request(request, api_version, service.call_data()).await;
+    if let Some(traffic_controller) = &service.traffic_controller {
+        handle_traffic_resp(traffic_controller.clone(), client, &response);
+    }
+
+    response
+}
+
+/// Processes the requests of a batch concurrently, and combines their responses into an array in
+/// the order of the requests. Every request of the batch is accounted for by the traffic
+/// controller as if it had been sent on its own.
+async fn process_batch_request<L: Logger>(
+    service: &JsonRpcService<L>,
+    batch: Vec<&RawValue>,
+    api_version: Option<&str>,
+    client: Option<IpAddr>,
+) -> MethodResponse {
+    if batch.is_empty() {
+        service.batch_metrics.on_rejected_batch();
+        return MethodResponse::error(
             Id::Null,
-            ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
-        )
-    } else {
-        let (id, code) = prepare_error(raw_request);
-        MethodResponse::error(id, ErrorObject::from(code))
+            ErrorObject::borrowed(ErrorCode::InvalidRequest.code(), &EMPTY_BATCH_MSG, None),
+        );
+    }
+    if batch.len() > service.max_batch_size {
+        service.batch_metrics.on_rejected_batch();
+        return MethodResponse::error(
+            Id::Null,
+            ErrorObject::owned(
+                ErrorCode::InvalidRequest.code(),
+                format!(
+                    "Batch request of {} requests exceeds the maximum of {} requests",
+                    batch.len(),
+                    service.max_batch_size
+                ),
+                None::<()>,
+            ),
+        );
+    }
+    service.batch_metrics.on_batch(batch.len());
+
+    let responses = futures::future::join_all(batch.into_iter().map(|raw_request| async move {
+        match serde_json::from_str::<Request>(raw_request.get()) {
+            Ok(request) => {
+                service.batch_metrics.on_batched_call(&request.method);
+                process_traffic_controlled_request(service, request, api_version, client).await
+            }
+            Err(_) => {
+                let (id, code) = prepare_error(raw_request.get());
+                MethodResponse::error(id, ErrorObject::from(code))
+            }
+        }
+    }))
+    .await;
+
+    let success = responses.iter().all(|response| response.success);
+    let result = responses
+        .into_iter()
+        .map(|response| response.result)
+        .collect::<Vec<_>>()
+        .join(",");
+    MethodResponse {
+        result: format!("[{result}]"),
+        success,
+        error_code: None,
     }
 }
 
@@ -30,7 +30,7 @@ use sui_json_rpc_api::{
 use sui_open_rpc::{Module, Project};
 
 use crate::error::Error;
-use crate::metrics::MetricsLogger;
+use crate::metrics::{BatchMetrics, MetricsLogger};
 use crate::routing_layer::RpcRouter;
 
 pub mod authority_state;
@@ -55,12 +55,16 @@ pub const APP_NAME_HEADER: &str = "app-name";
 
 pub const MAX_REQUEST_SIZE: u32 = 2 << 30;
 
+/// Default maximum number of requests in a batch request.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 50;
+
 pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
     policy_config: Option<PolicyConfig>,
     firewall_config: Option<RemoteFirewallConfig>,
+    max_batch_size: usize,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -89,9 +93,15 @@ impl JsonRpcServerBuilder {
             registry: prometheus_registry.clone(),
             policy_config,
             firewall_config,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
         }
     }
 
+    /// Sets the maximum number of requests accepted in a batch request.
+    pub fn set_max_batch_size(&mut self, max_batch_size: usize) {
+        self.max_batch_size = max_batch_size;
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
@@ -174,6 +184,7 @@ impl JsonRpcServerBuilder {
         let methods_names = module.method_names().collect::<Vec<_>>();
 
         let metrics_logger = MetricsLogger::new(&self.registry, &methods_names);
+        let batch_metrics = BatchMetrics::new(&self.registry, &methods_names);
         let traffic_controller_metrics = TrafficControllerMetrics::new(&self.registry);
 
         let middleware = tower::ServiceBuilder::new()
@@ -187,6 +198,8 @@ impl JsonRpcServerBuilder {
             self.firewall_config.clone(),
             self.policy_config.clone(),
             traffic_controller_metrics,
+            self.max_batch_size,
+            batch_metrics,
         );
 
         let mut router = axum::Router::new();
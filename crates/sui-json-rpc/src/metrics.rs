@@ -8,8 +8,10 @@ use http_body::Body;
 use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
 use jsonrpsee::types::Params;
 use prometheus::{
-    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, HistogramVec, IntCounterVec, IntGaugeVec,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec,
 };
 use sui_json_rpc_api::TRANSIENT_ERROR_CODE;
 use sui_json_rpc_api::{CLIENT_SDK_TYPE_HEADER, CLIENT_TARGET_API_VERSION_HEADER};
@@ -19,6 +21,7 @@ const SPAM_LABEL: &str = "SPAM";
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 10., 20., 30., 60., 90.,
 ];
+const BATCH_SIZE_BUCKETS: &[f64] = &[1., 2., 5., 10., 20., 50., 100., 200., 500., 1000.];
 
 #[derive(Debug, Clone)]
 pub struct Metrics {
@@ -269,3 +272,63 @@ impl Logger for MetricsLogger {
             .dec();
     }
 }
+
+/// Metrics of batch requests. Every request of a batch is also recorded by [`MetricsLogger`] like
+/// a request sent on its own.
+#[derive(Clone)]
+pub struct BatchMetrics {
+    /// Number of requests per batch request
+    batch_size: Histogram,
+    /// Counter of requests sent as part of a batch, route is a label
+    batched_requests_by_route: IntCounterVec,
+    /// Batch requests rejected because they are empty or too large
+    rejected_batches: IntCounter,
+    method_whitelist: HashSet<String>,
+}
+
+impl BatchMetrics {
+    pub fn new(registry: &prometheus::Registry, method_whitelist: &[&str]) -> Self {
+        Self {
+            batch_size: register_histogram_with_registry!(
+                "rpc_batch_size",
+                "Number of requests per batch request",
+                BATCH_SIZE_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            batched_requests_by_route: register_int_counter_vec_with_registry!(
+                "rpc_batched_requests_by_route",
+                "Number of requests sent as part of a batch request by route",
+                &["route"],
+                registry,
+            )
+            .unwrap(),
+            rejected_batches: register_int_counter_with_registry!(
+                "rpc_rejected_batches",
+                "Number of batch requests rejected because they are empty or too large",
+                registry,
+            )
+            .unwrap(),
+            method_whitelist: method_whitelist.iter().map(|s| (*s).into()).collect(),
+        }
+    }
+
+    pub fn on_batch(&self, size: usize) {
+        self.batch_size.observe(size as f64);
+    }
+
+    pub fn on_batched_call(&self, method_name: &str) {
+        let method_name = if self.method_whitelist.contains(method_name) {
+            method_name
+        } else {
+            SPAM_LABEL
+        };
+        self.batched_requests_by_route
+            .with_label_values(&[method_name])
+            .inc();
+    }
+
+    pub fn on_rejected_batch(&self) {
+        self.rejected_batches.inc();
+    }
+}
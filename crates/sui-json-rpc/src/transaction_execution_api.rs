@@ -19,6 +19,7 @@ use sui_json_rpc_api::{JsonRpcMetrics, WriteApiOpenRpc, WriteApiServer};
 use sui_json_rpc_types::{
     DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlock,
     SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    SuiTransactionSubmissionStatus,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::SuiAddress;
@@ -323,6 +324,19 @@ impl WriteApiServer for TransactionExecutionApi {
         })
     }
 
+    #[instrument(skip(self))]
+    async fn get_transaction_submission_status(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<SuiTransactionSubmissionStatus>> {
+        with_tracing!(async move {
+            Ok(self
+                .transaction_orchestrator
+                .transaction_submission_status(&digest)?
+                .map(SuiTransactionSubmissionStatus::from))
+        })
+    }
+
     #[instrument(skip(self))]
     async fn dev_inspect_transaction_block(
         &self,
@@ -0,0 +1,72 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local cache of end-of-epoch checkpoint summaries in the checkpoint summary directory.
+
+use anyhow::anyhow;
+use std::{
+    fs,
+    io::{Read, Write},
+    path::PathBuf,
+};
+use sui_types::messages_checkpoint::CertifiedCheckpointSummary;
+
+use crate::config::{CheckpointsList, Config};
+
+fn checkpoint_path(config: &Config, seq: u64) -> PathBuf {
+    let mut checkpoint_path = config.checkpoint_summary_dir.clone();
+    checkpoint_path.push(format!("{}.yaml", seq));
+    checkpoint_path
+}
+
+pub(crate) fn read_checkpoint_list(config: &Config) -> anyhow::Result<CheckpointsList> {
+    let mut checkpoints_path = config.checkpoint_summary_dir.clone();
+    checkpoints_path.push("checkpoints.yaml");
+    // Read the resulting file and parse the yaml checkpoint list
+    let reader = fs::File::open(checkpoints_path.clone())?;
+    Ok(serde_yaml::from_reader(reader)?)
+}
+
+pub(crate) fn write_checkpoint_list(
+    config: &Config,
+    checkpoints_list: &CheckpointsList,
+) -> anyhow::Result<()> {
+    // Write the checkpoint list to a file
+    let mut checkpoints_path = config.checkpoint_summary_dir.clone();
+    checkpoints_path.push("checkpoints.yaml");
+    let mut writer = fs::File::create(checkpoints_path.clone())?;
+    let bytes = serde_yaml::to_vec(&checkpoints_list)?;
+    writer
+        .write_all(&bytes)
+        .map_err(|_| anyhow!("Unable to serialize checkpoint list"))
+}
+
+/// Reads a cached checkpoint summary, `None` if it is not cached.
+pub(crate) fn read_checkpoint(
+    config: &Config,
+    seq: u64,
+) -> anyhow::Result<Option<CertifiedCheckpointSummary>> {
+    let checkpoint_path = checkpoint_path(config, seq);
+    if !checkpoint_path.exists() {
+        return Ok(None);
+    }
+    let mut reader = fs::File::open(checkpoint_path.clone())?;
+    let metadata = fs::metadata(&checkpoint_path)?;
+    let mut buffer = vec![0; metadata.len() as usize];
+    reader.read_exact(&mut buffer)?;
+    bcs::from_bytes(&buffer)
+        .map(Some)
+        .map_err(|_| anyhow!("Unable to parse checkpoint file"))
+}
+
+pub(crate) fn write_checkpoint(
+    config: &Config,
+    summary: &CertifiedCheckpointSummary,
+) -> anyhow::Result<()> {
+    // Write the checkpoint summary to a file
+    let mut writer = fs::File::create(checkpoint_path(config, summary.sequence_number))?;
+    let bytes =
+        bcs::to_bytes(&summary).map_err(|_| anyhow!("Unable to serialize checkpoint summary"))?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fs, path::Path, path::PathBuf};
+
+// The config file for the light client including the root of trust genesis digest
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Config {
+    /// Full node url
+    pub full_node_url: String,
+
+    /// Checkpoint summary directory
+    pub checkpoint_summary_dir: PathBuf,
+
+    //  Genesis file name
+    pub genesis_filename: PathBuf,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let reader = fs::File::open(path)?;
+        Ok(serde_yaml::from_reader(reader)?)
+    }
+
+    pub fn rest_url(&self) -> String {
+        format!("{}/rest", self.full_node_url)
+    }
+
+    pub fn genesis_path(&self) -> PathBuf {
+        let mut genesis_path = self.checkpoint_summary_dir.clone();
+        genesis_path.push(&self.genesis_filename);
+        genesis_path
+    }
+}
+
+// The list of checkpoints at the end of each epoch
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct CheckpointsList {
+    // List of end of epoch checkpoints
+    pub checkpoints: Vec<u64>,
+}
@@ -0,0 +1,21 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client for the Sui blockchain.
+//!
+//! [`LightClient`] syncs and verifies the chain of end-of-epoch checkpoints from
+//! genesis, and uses the resulting committees to verify transactions, effects,
+//! events and objects served by a full node against certified checkpoints.
+//! [`VerifyingProxy`] exposes the same checks as a local JSON-RPC endpoint.
+
+mod checkpoints;
+pub mod config;
+pub mod light_client;
+pub mod package_store;
+pub mod proxy;
+pub mod verifier;
+
+pub use config::{CheckpointsList, Config};
+pub use light_client::LightClient;
+pub use package_store::RemotePackageStore;
+pub use proxy::VerifyingProxy;
@@ -0,0 +1,282 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+use std::sync::RwLock;
+use sui_config::genesis::Genesis;
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_rest_api::{CheckpointData, CheckpointTransaction, Client};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_types::{
+    base_types::ObjectID,
+    committee::{Committee, EpochId},
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEvents},
+    event::Event,
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber},
+    object::Object,
+};
+use tracing::debug;
+
+use crate::checkpoints::{
+    read_checkpoint, read_checkpoint_list, write_checkpoint, write_checkpoint_list,
+};
+use crate::config::Config;
+use crate::verifier::{
+    extract_verified_transaction, verify_checkpoint, verify_object_in_effects,
+    verify_object_not_stale, CommitteeChain,
+};
+
+/// A light client for the Sui blockchain. It keeps a chain of committees verified
+/// from genesis through end-of-epoch checkpoints, and uses it to verify the data
+/// returned by an untrusted full node against certified checkpoints.
+pub struct LightClient {
+    config: Config,
+    rest_client: Client,
+    sui_client: SuiClient,
+    chain: RwLock<CommitteeChain>,
+}
+
+impl LightClient {
+    /// Creates a light client from `config`. The committee chain is built from the
+    /// genesis committee and the end-of-epoch checkpoints already cached in the
+    /// checkpoint summary directory, each verified again; call `sync` to extend it.
+    pub async fn new(config: Config) -> anyhow::Result<Self> {
+        let genesis_committee = Genesis::load(config.genesis_path())?.committee()?;
+        let mut chain = CommitteeChain::new(genesis_committee);
+        for ckp_id in read_checkpoint_list(&config)?.checkpoints {
+            let Some(summary) = read_checkpoint(&config, ckp_id)? else {
+                break;
+            };
+            chain.append(summary)?;
+        }
+
+        let rest_client = Client::new(config.rest_url());
+        let sui_client = SuiClientBuilder::default()
+            .build(config.full_node_url.as_str())
+            .await?;
+        Ok(Self {
+            config,
+            rest_client,
+            sui_client,
+            chain: RwLock::new(chain),
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The latest epoch whose committee is known.
+    pub fn latest_epoch(&self) -> EpochId {
+        self.chain.read().unwrap().latest_committee().epoch
+    }
+
+    /// The verified committee of `epoch`.
+    pub fn committee(&self, epoch: EpochId) -> anyhow::Result<Committee> {
+        self.chain
+            .read()
+            .unwrap()
+            .committee(epoch)
+            .cloned()
+            .ok_or(anyhow!(
+                "No verified committee for epoch {}. Need to Sync.",
+                epoch
+            ))
+    }
+
+    /// The verified end-of-epoch checkpoints, in epoch order.
+    pub fn end_of_epoch_checkpoints(&self) -> Vec<CertifiedCheckpointSummary> {
+        self.chain.read().unwrap().checkpoints().to_vec()
+    }
+
+    /// Downloads and verifies all end-of-epoch checkpoints up to the latest
+    /// epoch, caching them in the checkpoint summary directory.
+    pub async fn sync(&self) -> anyhow::Result<()> {
+        self.sync_checkpoint_list_to_latest().await?;
+
+        let checkpoints_list = read_checkpoint_list(&self.config)?;
+        let verified = self.chain.read().unwrap().checkpoints().len();
+        for ckp_id in checkpoints_list.checkpoints.into_iter().skip(verified) {
+            // If file exists read the file otherwise download it from the server
+            let (summary, cached) = match read_checkpoint(&self.config, ckp_id)? {
+                Some(summary) => (summary, true),
+                None => (
+                    self.rest_client.get_checkpoint_summary(ckp_id).await?,
+                    false,
+                ),
+            };
+            self.chain.write().unwrap().append(summary.clone())?;
+            // Only persist checkpoints that have been verified by the previous committee
+            if !cached {
+                write_checkpoint(&self.config, &summary)?;
+            }
+            debug!(
+                "Epoch: {} Checkpoint ID: {}",
+                summary.epoch(),
+                summary.digest()
+            );
+        }
+        Ok(())
+    }
+
+    /// Run binary search to for each end of epoch checkpoint that is missing
+    /// between the latest on the list and the latest checkpoint.
+    async fn sync_checkpoint_list_to_latest(&self) -> anyhow::Result<()> {
+        // Get the local checkpoint list
+        let mut checkpoints_list = read_checkpoint_list(&self.config)?;
+        let latest_in_list = checkpoints_list
+            .checkpoints
+            .last()
+            .ok_or(anyhow!("Empty checkpoint list"))?;
+
+        // Download the latest in list checkpoint
+        let summary = self
+            .rest_client
+            .get_checkpoint_summary(*latest_in_list)
+            .await?;
+        let mut last_epoch = summary.epoch();
+        let mut last_checkpoint_seq = summary.sequence_number;
+
+        // Download the very latest checkpoint
+        let latest = self.rest_client.get_latest_checkpoint().await?;
+
+        // Binary search to find missing checkpoints
+        while last_epoch + 1 < latest.epoch() {
+            let mut start = last_checkpoint_seq;
+            let mut end = latest.sequence_number;
+
+            let target_epoch = last_epoch + 1;
+            debug!("Target Epoch: {}", target_epoch);
+            let mut found_summary = None;
+
+            while start < end {
+                let mid = (start + end) / 2;
+                let summary = self.rest_client.get_checkpoint_summary(mid).await?;
+
+                debug!(
+                    "Epoch: {} Seq: {}: {}",
+                    summary.epoch(),
+                    summary.sequence_number,
+                    summary.end_of_epoch_data.is_some()
+                );
+
+                if summary.epoch() == target_epoch && summary.end_of_epoch_data.is_some() {
+                    found_summary = Some(summary);
+                    break;
+                }
+
+                if summary.epoch() <= target_epoch {
+                    start = mid + 1;
+                } else {
+                    end = mid;
+                }
+            }
+
+            if let Some(summary) = found_summary {
+                // Note: Do not write summary to file, since we must only persist
+                //       checkpoints that have been verified by the previous committee
+
+                // Add to the list
+                checkpoints_list.checkpoints.push(summary.sequence_number);
+                write_checkpoint_list(&self.config, &checkpoints_list)?;
+
+                // Update
+                last_epoch = summary.epoch();
+                last_checkpoint_seq = summary.sequence_number;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the full checkpoint `seq` and verifies its summary and contents.
+    pub async fn get_verified_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> anyhow::Result<CheckpointData> {
+        let checkpoint = self.rest_client.get_full_checkpoint(seq).await?;
+        let committee = self.committee(checkpoint.checkpoint_summary.epoch())?;
+        verify_checkpoint(&checkpoint, &committee)?;
+        Ok(checkpoint)
+    }
+
+    /// Downloads the latest checkpoint known to the full node and verifies it.
+    pub async fn get_latest_verified_checkpoint(&self) -> anyhow::Result<CheckpointData> {
+        let latest = self.rest_client.get_latest_checkpoint().await?;
+        self.get_verified_checkpoint(latest.sequence_number).await
+    }
+
+    /// Returns the transaction `tid` with its effects, events and objects, as
+    /// included in a verified checkpoint.
+    pub async fn get_verified_transaction(
+        &self,
+        tid: TransactionDigest,
+    ) -> anyhow::Result<CheckpointTransaction> {
+        let (transaction, _) = self.get_verified_transaction_with_summary(tid).await?;
+        Ok(transaction)
+    }
+
+    /// Same as `get_verified_transaction`, also returning the summary of the
+    /// checkpoint that includes the transaction.
+    pub async fn get_verified_transaction_with_summary(
+        &self,
+        tid: TransactionDigest,
+    ) -> anyhow::Result<(CheckpointTransaction, CertifiedCheckpointSummary)> {
+        // Lookup the transaction id and get the checkpoint sequence number
+        let options = SuiTransactionBlockResponseOptions::new();
+        let seq = self
+            .sui_client
+            .read_api()
+            .get_transaction_with_options(tid, options)
+            .await?
+            .checkpoint
+            .ok_or(anyhow!("Transaction not found"))?;
+
+        // Download the full checkpoint for this sequence number
+        let checkpoint = self.rest_client.get_full_checkpoint(seq).await?;
+        let committee = self.committee(checkpoint.checkpoint_summary.epoch())?;
+        let transaction = extract_verified_transaction(&checkpoint, &committee, tid)?.clone();
+        Ok((transaction, checkpoint.checkpoint_summary))
+    }
+
+    pub async fn get_verified_effects_and_events(
+        &self,
+        tid: TransactionDigest,
+    ) -> anyhow::Result<(TransactionEffects, Option<TransactionEvents>)> {
+        let transaction = self.get_verified_transaction(tid).await?;
+        Ok((transaction.effects, transaction.events))
+    }
+
+    /// Returns the events emitted by transaction `tid`.
+    pub async fn get_verified_events(&self, tid: TransactionDigest) -> anyhow::Result<Vec<Event>> {
+        let (_, events) = self.get_verified_effects_and_events(tid).await?;
+        Ok(events.map(|events| events.data).unwrap_or_default())
+    }
+
+    /// Returns the latest version of object `id`, verified against the effects
+    /// of the transaction that last wrote it. The object must not be older than
+    /// its version in the latest checkpoint, though it may have changed since.
+    pub async fn get_verified_object(&self, id: ObjectID) -> anyhow::Result<Object> {
+        let object = self.get_verified_written_object(id).await?;
+        let latest = self.get_latest_verified_checkpoint().await?;
+        verify_object_not_stale(&object, &latest)?;
+        Ok(object)
+    }
+
+    /// Returns the version of object `id` served by the full node, verified
+    /// against the effects of the transaction that wrote it. Nothing prevents
+    /// the full node from serving an older version, which is only safe for
+    /// immutable objects, or once checked with `verify_object_not_stale`.
+    pub async fn get_verified_written_object(&self, id: ObjectID) -> anyhow::Result<Object> {
+        let object = self.rest_client.get_object(id).await?;
+
+        // Need to authenticate this object
+        let (effects, _) = self
+            .get_verified_effects_and_events(object.previous_transaction)
+            .await?;
+        verify_object_in_effects(&object, &effects)?;
+
+        Ok(object)
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use move_core_types::account_address::AccountAddress;
+use std::sync::Arc;
+use sui_package_resolver::{error::Error as ResolverError, Result as ResolverResult};
+use sui_package_resolver::{Package, PackageStore};
+
+use crate::light_client::LightClient;
+
+/// A `PackageStore` that only serves packages verified by the light client, so
+/// that types used to display events and objects are authentic too.
+pub struct RemotePackageStore {
+    client: Arc<LightClient>,
+}
+
+impl RemotePackageStore {
+    pub fn new(client: Arc<LightClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl PackageStore for RemotePackageStore {
+    /// Read package contents. Fails if `id` is not an object, not a package, or is malformed in
+    /// some way.
+    async fn fetch(&self, id: AccountAddress) -> ResolverResult<Arc<Package>> {
+        // Packages are immutable, so any verified version is the latest.
+        let object = self
+            .client
+            .get_verified_written_object(id.into())
+            .await
+            .map_err(|e| ResolverError::Store {
+                store: "RemotePackageStore",
+                source: Arc::from(Box::<dyn std::error::Error + Send + Sync>::from(e)),
+            })?;
+        let package = Package::read_from_object(&object)?;
+        Ok(Arc::new(package))
+    }
+}
@@ -0,0 +1,571 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A local JSON-RPC proxy that forwards reads to an untrusted full node and only
+//! returns results that verify against certified checkpoints.
+//!
+//! For every supported method the proxy rebuilds the expected JSON-RPC result from
+//! data verified by the [`LightClient`] and compares it with the full node's
+//! response. Responses that do not match, and requests for methods whose results
+//! cannot be verified, are answered with an [`UNVERIFIED_RESPONSE_CODE`] error.
+//!
+//! Objects are checked against the transaction that wrote them, and must be at least
+//! as new as the latest checkpoint the full node reports. A full node can still serve
+//! an object that was changed since then without being detected.
+
+use anyhow::{anyhow, bail};
+use axum::{extract::State, routing::post, Json, Router};
+use move_core_types::annotated_value::{MoveDatatypeLayout, MoveStructLayout, MoveTypeLayout};
+use serde_json::{json, Value};
+use std::{net::SocketAddr, sync::Arc};
+use sui_json_rpc_types::{
+    SuiEvent, SuiObjectData, SuiObjectDataOptions, SuiObjectResponse, SuiTransactionBlock,
+    SuiTransactionBlockEffects, SuiTransactionBlockResponse,
+};
+use sui_package_resolver::Resolver;
+use sui_rest_api::{CheckpointData, CheckpointTransaction};
+use sui_types::{
+    base_types::ObjectID,
+    digests::TransactionDigest,
+    effects::TransactionEvents,
+    messages_checkpoint::CertifiedCheckpointSummary,
+    object::{Data, Object},
+    TypeTag,
+};
+use tracing::{info, warn};
+
+use crate::light_client::LightClient;
+use crate::package_store::RemotePackageStore;
+use crate::verifier::verify_object_not_stale;
+
+/// JSON-RPC error code returned when a response could not be verified.
+pub const UNVERIFIED_RESPONSE_CODE: i64 = -32050;
+
+const GET_TRANSACTION_BLOCK: &str = "sui_getTransactionBlock";
+const MULTI_GET_TRANSACTION_BLOCKS: &str = "sui_multiGetTransactionBlocks";
+const GET_EVENTS: &str = "sui_getEvents";
+const GET_OBJECT: &str = "sui_getObject";
+const MULTI_GET_OBJECTS: &str = "sui_multiGetObjects";
+
+/// The methods whose results the proxy can verify.
+pub const VERIFIED_METHODS: &[&str] = &[
+    GET_TRANSACTION_BLOCK,
+    MULTI_GET_TRANSACTION_BLOCKS,
+    GET_EVENTS,
+    GET_OBJECT,
+    MULTI_GET_OBJECTS,
+];
+
+pub struct VerifyingProxy {
+    light_client: Arc<LightClient>,
+    resolver: Arc<Resolver<RemotePackageStore>>,
+    http_client: reqwest::Client,
+}
+
+impl VerifyingProxy {
+    pub fn new(light_client: Arc<LightClient>) -> Self {
+        let resolver = Arc::new(Resolver::new(RemotePackageStore::new(light_client.clone())));
+        Self {
+            light_client,
+            resolver,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn run(self, listen_address: SocketAddr) -> anyhow::Result<()> {
+        let router = Router::new()
+            .route("/", post(handle_json_rpc))
+            .with_state(Arc::new(self));
+        let listener = tokio::net::TcpListener::bind(listen_address).await?;
+        info!("Verifying proxy listening on {}", listen_address);
+        axum::serve(listener, router).await?;
+        Ok(())
+    }
+
+    async fn handle_request(&self, request: Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        if !VERIFIED_METHODS.contains(&method.as_str()) {
+            return unverified_response(
+                id,
+                format!("Method {method} cannot be verified by the light client"),
+            );
+        }
+        let params = request.get("params").cloned().unwrap_or(json!([]));
+
+        let upstream = match self.forward(&request).await {
+            Ok(upstream) => upstream,
+            Err(e) => return unverified_response(id, format!("Full node request failed: {e}")),
+        };
+        // Errors carry no data to verify, pass them through as is.
+        let Some(result) = upstream.get("result").cloned() else {
+            return upstream;
+        };
+        match self.verify_result(&method, &params, result).await {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => {
+                warn!("Unverified {} response: {:?}", method, e);
+                unverified_response(id, format!("Response could not be verified: {e}"))
+            }
+        }
+    }
+
+    async fn forward(&self, request: &Value) -> anyhow::Result<Value> {
+        Ok(self
+            .http_client
+            .post(&self.light_client.config().full_node_url)
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Verifies the `result` of a `method` call, returning the result to serve.
+    async fn verify_result(
+        &self,
+        method: &str,
+        params: &Value,
+        result: Value,
+    ) -> anyhow::Result<Value> {
+        Ok(match method {
+            GET_TRANSACTION_BLOCK => {
+                let tid: TransactionDigest = serde_json::from_value(param(params, 0)?)?;
+                let response: SuiTransactionBlockResponse = serde_json::from_value(result)?;
+                serde_json::to_value(self.verify_transaction_block(tid, response).await?)?
+            }
+            MULTI_GET_TRANSACTION_BLOCKS => {
+                let tids: Vec<TransactionDigest> = serde_json::from_value(param(params, 0)?)?;
+                let responses: Vec<SuiTransactionBlockResponse> = serde_json::from_value(result)?;
+                check_result_count(tids.len(), responses.len())?;
+                let mut verified = vec![];
+                for (tid, response) in tids.into_iter().zip(responses) {
+                    verified.push(self.verify_transaction_block(tid, response).await?);
+                }
+                serde_json::to_value(verified)?
+            }
+            GET_EVENTS => {
+                let tid: TransactionDigest = serde_json::from_value(param(params, 0)?)?;
+                let events: Vec<SuiEvent> = serde_json::from_value(result.clone())?;
+                let (transaction, summary) = self
+                    .light_client
+                    .get_verified_transaction_with_summary(tid)
+                    .await?;
+                self.verify_events(&events, transaction.events, tid, summary.timestamp_ms)
+                    .await?;
+                result
+            }
+            GET_OBJECT => {
+                let object_id: ObjectID = serde_json::from_value(param(params, 0)?)?;
+                let options = object_options(params, 1)?;
+                let response: SuiObjectResponse = serde_json::from_value(result)?;
+                let latest = self.light_client.get_latest_verified_checkpoint().await?;
+                serde_json::to_value(
+                    self.verify_object(object_id, response, &options, &latest)
+                        .await?,
+                )?
+            }
+            MULTI_GET_OBJECTS => {
+                let object_ids: Vec<ObjectID> = serde_json::from_value(param(params, 0)?)?;
+                let options = object_options(params, 1)?;
+                let responses: Vec<SuiObjectResponse> = serde_json::from_value(result)?;
+                check_result_count(object_ids.len(), responses.len())?;
+                let latest = self.light_client.get_latest_verified_checkpoint().await?;
+                let mut verified = vec![];
+                for (object_id, response) in object_ids.into_iter().zip(responses) {
+                    verified.push(
+                        self.verify_object(object_id, response, &options, &latest)
+                            .await?,
+                    );
+                }
+                serde_json::to_value(verified)?
+            }
+            _ => bail!("Method {method} cannot be verified by the light client"),
+        })
+    }
+
+    /// Verifies a response to a request for transaction `tid`. The verified data is
+    /// looked up by the requested digest, so that a full node can't answer with another
+    /// transaction.
+    async fn verify_transaction_block(
+        &self,
+        tid: TransactionDigest,
+        mut response: SuiTransactionBlockResponse,
+    ) -> anyhow::Result<SuiTransactionBlockResponse> {
+        let (transaction, summary) = self
+            .light_client
+            .get_verified_transaction_with_summary(tid)
+            .await?;
+
+        check_transaction_block(&response, &transaction, &summary)?;
+        if let Some(sui_transaction) = &response.transaction {
+            let expected = SuiTransactionBlock::try_from_with_package_resolver(
+                transaction.transaction.data().clone(),
+                self.resolver.clone(),
+            )
+            .await?;
+            anyhow::ensure!(*sui_transaction == expected, "Transaction does not match");
+        }
+        if let Some(events) = &response.events {
+            self.verify_events(&events.data, transaction.events, tid, summary.timestamp_ms)
+                .await?;
+        }
+        // Object and balance changes are computed by the full node from objects the
+        // checkpoint does not commit to, so they are dropped rather than trusted.
+        response.object_changes = None;
+        response.balance_changes = None;
+        Ok(response)
+    }
+
+    async fn verify_events(
+        &self,
+        events: &[SuiEvent],
+        verified_events: Option<TransactionEvents>,
+        tid: TransactionDigest,
+        timestamp_ms: u64,
+    ) -> anyhow::Result<()> {
+        let verified_events = verified_events.map(|e| e.data).unwrap_or_default();
+        anyhow::ensure!(
+            events.len() == verified_events.len(),
+            "Number of events does not match"
+        );
+        for (seq, (event, verified_event)) in events.iter().zip(verified_events).enumerate() {
+            let layout = match self
+                .resolver
+                .type_layout(TypeTag::Struct(Box::new(verified_event.type_.clone())))
+                .await?
+            {
+                MoveTypeLayout::Struct(s) => MoveDatatypeLayout::Struct(s),
+                MoveTypeLayout::Enum(e) => MoveDatatypeLayout::Enum(e),
+                _ => bail!("Unexpected layout for event type {}", verified_event.type_),
+            };
+            let expected = SuiEvent::try_from(
+                verified_event,
+                tid,
+                seq as u64,
+                event.timestamp_ms.map(|_| timestamp_ms),
+                layout,
+            )?;
+            anyhow::ensure!(*event == expected, "Event {} does not match", seq);
+        }
+        Ok(())
+    }
+
+    /// Verifies a response to a request for object `object_id`, rejecting objects older
+    /// than their versions in the `latest` checkpoint.
+    async fn verify_object(
+        &self,
+        object_id: ObjectID,
+        response: SuiObjectResponse,
+        options: &SuiObjectDataOptions,
+        latest: &CheckpointData,
+    ) -> anyhow::Result<SuiObjectResponse> {
+        // The absence of an object cannot be proven from checkpoint data.
+        let data = response
+            .data
+            .ok_or(anyhow!("Only existing objects can be verified"))?;
+        // Checked against `latest` below, shared by all the objects of a request.
+        let object = self
+            .light_client
+            .get_verified_written_object(object_id)
+            .await?;
+
+        let layout = match &object.data {
+            Data::Move(move_object) if options.show_bcs || options.show_content => {
+                match self
+                    .resolver
+                    .type_layout(move_object.type_().clone().into())
+                    .await?
+                {
+                    MoveTypeLayout::Struct(s) => Some(s),
+                    _ => bail!("Unexpected layout for object type {}", move_object.type_()),
+                }
+            }
+            _ => None,
+        };
+        let data = check_object(data, object, layout, options, latest)?;
+        Ok(SuiObjectResponse::new_with_data(data))
+    }
+}
+
+/// Checks the parts of a transaction block response that are compared as is with
+/// the verified `transaction` and the `summary` of the checkpoint that includes it.
+fn check_transaction_block(
+    response: &SuiTransactionBlockResponse,
+    transaction: &CheckpointTransaction,
+    summary: &CertifiedCheckpointSummary,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        response.digest == *transaction.transaction.digest(),
+        "Transaction {} does not match the requested {}",
+        response.digest,
+        transaction.transaction.digest()
+    );
+    if let Some(checkpoint) = response.checkpoint {
+        anyhow::ensure!(
+            checkpoint == summary.sequence_number,
+            "Checkpoint does not match"
+        );
+    }
+    if let Some(timestamp_ms) = response.timestamp_ms {
+        anyhow::ensure!(
+            timestamp_ms == summary.timestamp_ms,
+            "Timestamp does not match"
+        );
+    }
+    if !response.raw_transaction.is_empty() {
+        anyhow::ensure!(
+            response.raw_transaction == bcs::to_bytes(transaction.transaction.data())?,
+            "Raw transaction does not match"
+        );
+    }
+    if let Some(effects) = &response.effects {
+        let expected = SuiTransactionBlockEffects::try_from(transaction.effects.clone())?;
+        anyhow::ensure!(*effects == expected, "Effects do not match");
+    }
+    if !response.raw_effects.is_empty() {
+        anyhow::ensure!(
+            response.raw_effects == bcs::to_bytes(&transaction.effects)?,
+            "Raw effects do not match"
+        );
+    }
+    Ok(())
+}
+
+/// Checks object `data` from a response against the verified `object`, which must not
+/// be older than its versions in the `latest` checkpoint.
+fn check_object(
+    mut data: SuiObjectData,
+    object: Object,
+    layout: Option<MoveStructLayout>,
+    options: &SuiObjectDataOptions,
+    latest: &CheckpointData,
+) -> anyhow::Result<SuiObjectData> {
+    anyhow::ensure!(
+        data.object_id == object.id(),
+        "Object {} does not match the requested {}",
+        data.object_id,
+        object.id()
+    );
+    verify_object_not_stale(&object, latest)?;
+    let mut expected = SuiObjectData::try_from((
+        object.compute_object_reference(),
+        object,
+        layout,
+        options.clone(),
+    ))?;
+    // Display is rendered by the full node from templates outside of the
+    // object, so it is dropped rather than trusted.
+    data.display = None;
+    expected.display = None;
+    anyhow::ensure!(data == expected, "Object {} does not match", data.object_id);
+    Ok(data)
+}
+
+async fn handle_json_rpc(
+    State(proxy): State<Arc<VerifyingProxy>>,
+    Json(request): Json<Value>,
+) -> Json<Value> {
+    let response = match request {
+        Value::Array(requests) => {
+            let mut responses = vec![];
+            for request in requests {
+                responses.push(proxy.handle_request(request).await);
+            }
+            Value::Array(responses)
+        }
+        request => proxy.handle_request(request).await,
+    };
+    Json(response)
+}
+
+fn unverified_response(id: Value, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": UNVERIFIED_RESPONSE_CODE, "message": message },
+    })
+}
+
+fn param(params: &Value, index: usize) -> anyhow::Result<Value> {
+    params
+        .get(index)
+        .cloned()
+        .ok_or(anyhow!("Missing positional parameter {index}"))
+}
+
+/// Multi-get results are checked against the requested items in order, so there must
+/// be exactly one result per item.
+fn check_result_count(requested: usize, returned: usize) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        requested == returned,
+        "Requested {requested} items, but {returned} were returned"
+    );
+    Ok(())
+}
+
+fn object_options(params: &Value, index: usize) -> anyhow::Result<SuiObjectDataOptions> {
+    match params.get(index) {
+        Some(Value::Null) | None => Ok(SuiObjectDataOptions::default()),
+        Some(options) => Ok(serde_json::from_value(options.clone())?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::object::Owner;
+
+    use super::*;
+
+    fn read_checkpoint() -> CheckpointData {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("example_config/20958462.bcs");
+        bcs::from_bytes(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    fn find_transaction(
+        checkpoint: &CheckpointData,
+    ) -> (&CheckpointTransaction, &CheckpointTransaction) {
+        let tid =
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zi6cMVA9t4WhWk").unwrap();
+        let transaction = checkpoint
+            .transactions
+            .iter()
+            .find(|t| *t.transaction.digest() == tid)
+            .unwrap();
+        let other = checkpoint
+            .transactions
+            .iter()
+            .find(|t| *t.transaction.digest() != tid)
+            .unwrap();
+        (transaction, other)
+    }
+
+    fn transaction_block_response(
+        transaction: &CheckpointTransaction,
+        summary: &CertifiedCheckpointSummary,
+    ) -> SuiTransactionBlockResponse {
+        let mut response = SuiTransactionBlockResponse::new(*transaction.transaction.digest());
+        response.checkpoint = Some(summary.sequence_number);
+        response.timestamp_ms = Some(summary.timestamp_ms);
+        response.raw_transaction = bcs::to_bytes(transaction.transaction.data()).unwrap();
+        response.effects =
+            Some(SuiTransactionBlockEffects::try_from(transaction.effects.clone()).unwrap());
+        response.raw_effects = bcs::to_bytes(&transaction.effects).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_tampered_transaction_block_responses() {
+        let checkpoint = read_checkpoint();
+        let summary = &checkpoint.checkpoint_summary;
+        let (transaction, other) = find_transaction(&checkpoint);
+        let response = transaction_block_response(transaction, summary);
+        let other_response = transaction_block_response(other, summary);
+        check_transaction_block(&response, transaction, summary).unwrap();
+
+        // Another checkpoint
+        let mut tampered = response.clone();
+        tampered.checkpoint = Some(summary.sequence_number - 1);
+        assert!(check_transaction_block(&tampered, transaction, summary).is_err());
+
+        let mut tampered = response.clone();
+        tampered.timestamp_ms = Some(summary.timestamp_ms + 1);
+        assert!(check_transaction_block(&tampered, transaction, summary).is_err());
+
+        // The effects of another transaction
+        let mut tampered = response.clone();
+        tampered.effects = other_response.effects.clone();
+        assert!(check_transaction_block(&tampered, transaction, summary).is_err());
+
+        let mut tampered = response.clone();
+        tampered.raw_effects = other_response.raw_effects.clone();
+        assert!(check_transaction_block(&tampered, transaction, summary).is_err());
+
+        let mut tampered = response.clone();
+        tampered.raw_transaction = other_response.raw_transaction.clone();
+        assert!(check_transaction_block(&tampered, transaction, summary).is_err());
+    }
+
+    #[test]
+    fn test_substituted_responses() {
+        let checkpoint = read_checkpoint();
+        let summary = &checkpoint.checkpoint_summary;
+        let (transaction, other) = find_transaction(&checkpoint);
+
+        // Another transaction of the same checkpoint, that verifies on its own
+        let mut substituted = SuiTransactionBlockResponse::new(*other.transaction.digest());
+        substituted.checkpoint = Some(summary.sequence_number);
+        substituted.timestamp_ms = Some(summary.timestamp_ms);
+        check_transaction_block(&substituted, other, summary).unwrap();
+        assert!(check_transaction_block(&substituted, transaction, summary).is_err());
+
+        // Another object of the same checkpoint
+        let options = SuiObjectDataOptions::new();
+        let object = transaction.output_objects[0].clone();
+        let other_object = checkpoint
+            .transactions
+            .iter()
+            .flat_map(|t| &t.output_objects)
+            .find(|o| o.id() != object.id())
+            .unwrap()
+            .clone();
+        let substituted = SuiObjectData::try_from((
+            other_object.compute_object_reference(),
+            other_object,
+            None,
+            options.clone(),
+        ))
+        .unwrap();
+        assert!(check_object(substituted, object, None, &options, &checkpoint).is_err());
+
+        // Multi-gets must return one result per requested item
+        check_result_count(2, 2).unwrap();
+        assert!(check_result_count(2, 1).is_err());
+        assert!(check_result_count(1, 2).is_err());
+    }
+
+    #[test]
+    fn test_tampered_object_responses() {
+        let checkpoint = read_checkpoint();
+        let (transaction, _) = find_transaction(&checkpoint);
+        let options = SuiObjectDataOptions::new()
+            .with_type()
+            .with_owner()
+            .with_previous_transaction();
+        let object_data = |object: &Object| {
+            SuiObjectData::try_from((
+                object.compute_object_reference(),
+                object.clone(),
+                None,
+                options.clone(),
+            ))
+            .unwrap()
+        };
+
+        let object = transaction.output_objects[0].clone();
+        let data = object_data(&object);
+        check_object(data.clone(), object.clone(), None, &options, &checkpoint).unwrap();
+
+        let mut tampered = data.clone();
+        tampered.owner = Some(Owner::AddressOwner(SuiAddress::ZERO));
+        assert!(check_object(tampered, object.clone(), None, &options, &checkpoint).is_err());
+
+        let mut tampered = data.clone();
+        tampered.version = tampered.version.next();
+        assert!(check_object(tampered, object.clone(), None, &options, &checkpoint).is_err());
+
+        // A version replaced in the latest checkpoint, even if it matches the response
+        let stale = transaction.input_objects[0].clone();
+        let data = object_data(&stale);
+        assert!(check_object(data, stale, None, &options, &checkpoint).is_err());
+    }
+}
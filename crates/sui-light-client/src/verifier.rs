@@ -0,0 +1,443 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that do not need network access: verifying end-of-epoch checkpoints into a
+//! committee chain, and verifying checkpoint contents against a committee.
+
+use anyhow::anyhow;
+use std::collections::BTreeMap;
+use sui_rest_api::{CheckpointData, CheckpointTransaction};
+use sui_types::{
+    committee::{Committee, EpochId},
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSummary, EndOfEpochData},
+    object::Object,
+};
+
+/// Returns the committee of the epoch after the one `summary` ends.
+pub fn next_epoch_committee(summary: &CheckpointSummary) -> anyhow::Result<Committee> {
+    let EndOfEpochData {
+        next_epoch_committee,
+        ..
+    } = summary.end_of_epoch_data.as_ref().ok_or(anyhow!(
+        "Expected all checkpoints to be end-of-epoch checkpoints"
+    ))?;
+    Ok(Committee::new(
+        summary.epoch().checked_add(1).unwrap(),
+        next_epoch_committee.iter().cloned().collect(),
+    ))
+}
+
+/// The committees of consecutive epochs, starting from a trusted (genesis) committee.
+/// Each following committee is only added once the end-of-epoch checkpoint that
+/// announces it has been verified by the committee before it.
+#[derive(Debug, Clone)]
+pub struct CommitteeChain {
+    committees: BTreeMap<EpochId, Committee>,
+    checkpoints: Vec<CertifiedCheckpointSummary>,
+}
+
+impl CommitteeChain {
+    pub fn new(genesis_committee: Committee) -> Self {
+        Self {
+            committees: BTreeMap::from([(genesis_committee.epoch, genesis_committee)]),
+            checkpoints: vec![],
+        }
+    }
+
+    pub fn latest_committee(&self) -> &Committee {
+        // Unwrap safe: the chain always contains the genesis committee
+        self.committees.values().next_back().unwrap()
+    }
+
+    pub fn committee(&self, epoch: EpochId) -> Option<&Committee> {
+        self.committees.get(&epoch)
+    }
+
+    /// The verified end-of-epoch checkpoints, in epoch order.
+    pub fn checkpoints(&self) -> &[CertifiedCheckpointSummary] {
+        &self.checkpoints
+    }
+
+    /// Verifies `summary` as the end-of-epoch checkpoint of the latest epoch in the
+    /// chain, and extends the chain with the committee of the next epoch.
+    pub fn append(&mut self, summary: CertifiedCheckpointSummary) -> anyhow::Result<()> {
+        let committee = self.latest_committee();
+        anyhow::ensure!(
+            summary.epoch() == committee.epoch,
+            "Expected end-of-epoch checkpoint for epoch {}, got epoch {}",
+            committee.epoch,
+            summary.epoch()
+        );
+        summary.clone().try_into_verified(committee)?;
+        let next_committee = next_epoch_committee(&summary)?;
+        self.committees.insert(next_committee.epoch, next_committee);
+        self.checkpoints.push(summary);
+        Ok(())
+    }
+}
+
+/// Verifies the checkpoint summary and contents of `checkpoint` using `committee`.
+pub fn verify_checkpoint(checkpoint: &CheckpointData, committee: &Committee) -> anyhow::Result<()> {
+    checkpoint
+        .checkpoint_summary
+        .verify_with_contents(committee, Some(&checkpoint.checkpoint_contents))?;
+    Ok(())
+}
+
+/// Finds transaction `tid` in `checkpoint`, checking that the transaction, its effects,
+/// its events and its input and output objects are the ones authenticated by the checkpoint.
+pub fn extract_verified_transaction<'a>(
+    checkpoint: &'a CheckpointData,
+    committee: &Committee,
+    tid: TransactionDigest,
+) -> anyhow::Result<&'a CheckpointTransaction> {
+    // Verify the checkpoint summary using the committee
+    verify_checkpoint(checkpoint, committee)?;
+
+    // Check the validity of the transaction
+    let contents = &checkpoint.checkpoint_contents;
+    let (matching_tx, _) = checkpoint
+        .transactions
+        .iter()
+        .zip(contents.iter())
+        // Note that we get the digest of the effects to ensure this is
+        // indeed the correct effects that are authenticated in the contents.
+        .find(|(tx, digest)| {
+            tx.effects.execution_digests() == **digest && digest.transaction == tid
+        })
+        .ok_or(anyhow!("Transaction not found in checkpoint contents"))?;
+
+    // Check the transaction itself is the one the effects were produced by.
+    anyhow::ensure!(
+        *matching_tx.transaction.digest() == tid,
+        "Transaction digest does not match"
+    );
+
+    // Check the events are all correct.
+    let events_digest = matching_tx.events.as_ref().map(|events| events.digest());
+    anyhow::ensure!(
+        events_digest.as_ref() == matching_tx.effects.events_digest(),
+        "Events digest does not match"
+    );
+
+    verify_transaction_objects(matching_tx)?;
+
+    Ok(matching_tx)
+}
+
+/// Checks that the input and output objects of `transaction` are the objects its
+/// effects modified and produced.
+pub fn verify_transaction_objects(transaction: &CheckpointTransaction) -> anyhow::Result<()> {
+    let effects = &transaction.effects;
+
+    let mut outputs: Vec<_> = transaction
+        .output_objects
+        .iter()
+        .map(|object| object.compute_object_reference())
+        .collect();
+    let mut changed: Vec<_> = effects
+        .all_changed_objects()
+        .into_iter()
+        .map(|(object_ref, _, _)| object_ref)
+        .collect();
+    outputs.sort();
+    changed.sort();
+    anyhow::ensure!(
+        outputs == changed,
+        "Output objects do not match the effects"
+    );
+
+    let mut inputs: Vec<_> = transaction
+        .input_objects
+        .iter()
+        .map(|object| object.compute_object_reference())
+        .collect();
+    inputs.sort();
+    let inputs_match = match effects {
+        // Effects v1 do not include the digests of the objects they modify.
+        TransactionEffects::V1(_) => {
+            let mut modified = effects.modified_at_versions();
+            modified.sort();
+            inputs
+                .iter()
+                .map(|(id, version, _)| (*id, *version))
+                .eq(modified)
+        }
+        TransactionEffects::V2(_) => {
+            let mut modified: Vec<_> = effects
+                .old_object_metadata()
+                .into_iter()
+                .map(|(object_ref, _)| object_ref)
+                .collect();
+            modified.sort();
+            inputs == modified
+        }
+    };
+    anyhow::ensure!(inputs_match, "Input objects do not match the effects");
+    Ok(())
+}
+
+pub fn extract_verified_effects_and_events(
+    checkpoint: &CheckpointData,
+    committee: &Committee,
+    tid: TransactionDigest,
+) -> anyhow::Result<(TransactionEffects, Option<TransactionEvents>)> {
+    let matching_tx = extract_verified_transaction(checkpoint, committee, tid)?;
+    // Since we do not check objects we do not return them
+    Ok((matching_tx.effects.clone(), matching_tx.events.clone()))
+}
+
+/// Checks that `object` at its current version was written by the verified `effects`.
+pub fn verify_object_in_effects(
+    object: &Object,
+    effects: &TransactionEffects,
+) -> anyhow::Result<()> {
+    // check that this object ID, version and hash is in the effects
+    effects
+        .all_changed_objects()
+        .iter()
+        .find(|object_ref| object_ref.0 == object.compute_object_reference())
+        .ok_or(anyhow!("Object not found"))?;
+    Ok(())
+}
+
+/// Checks that `object` is not older than a version of it written, deleted or wrapped
+/// by a transaction in the verified `checkpoint`. Changes to the object in later
+/// checkpoints cannot be detected this way.
+pub fn verify_object_not_stale(object: &Object, checkpoint: &CheckpointData) -> anyhow::Result<()> {
+    let id = object.id();
+    for transaction in &checkpoint.transactions {
+        let effects = &transaction.effects;
+        let changed = effects
+            .all_changed_objects()
+            .into_iter()
+            .map(|(object_ref, _, _)| object_ref);
+        let removed = effects
+            .all_removed_objects()
+            .into_iter()
+            .map(|(object_ref, _)| object_ref);
+        for (_, version, _) in changed.chain(removed).filter(|(oid, _, _)| *oid == id) {
+            anyhow::ensure!(
+                object.version() >= version,
+                "Object {} at version {} is older than version {} in checkpoint {}",
+                id,
+                object.version(),
+                version,
+                checkpoint.checkpoint_summary.sequence_number
+            );
+        }
+    }
+    Ok(())
+}
+
+// Make a test namespace
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use sui_types::messages_checkpoint::FullCheckpointContents;
+
+    use super::*;
+
+    async fn read_full_checkpoint(checkpoint_path: &PathBuf) -> anyhow::Result<CheckpointData> {
+        let mut reader = fs::File::open(checkpoint_path.clone())?;
+        let metadata = fs::metadata(checkpoint_path)?;
+        let mut buffer = vec![0; metadata.len() as usize];
+        reader.read_exact(&mut buffer)?;
+        bcs::from_bytes(&buffer).map_err(|_| anyhow!("Unable to parse checkpoint file"))
+    }
+
+    // clippy ignore dead-code
+    #[allow(dead_code)]
+    async fn write_full_checkpoint(
+        checkpoint_path: &Path,
+        checkpoint: &CheckpointData,
+    ) -> anyhow::Result<()> {
+        let mut writer = fs::File::create(checkpoint_path)?;
+        let bytes = bcs::to_bytes(&checkpoint)
+            .map_err(|_| anyhow!("Unable to serialize checkpoint summary"))?;
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    fn read_end_of_epoch_checkpoint() -> CertifiedCheckpointSummary {
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("example_config/20873329.yaml");
+
+        let mut reader = fs::File::open(d.clone()).unwrap();
+        let metadata = fs::metadata(&d).unwrap();
+        let mut buffer = vec![0; metadata.len() as usize];
+        reader.read_exact(&mut buffer).unwrap();
+        bcs::from_bytes(&buffer)
+            .map_err(|_| anyhow!("Unable to parse checkpoint file"))
+            .unwrap()
+    }
+
+    async fn read_data() -> (Committee, CheckpointData) {
+        let checkpoint = read_end_of_epoch_checkpoint();
+
+        // Make a committee object using this
+        let committee = next_epoch_committee(&checkpoint).unwrap();
+
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("example_config/20958462.bcs");
+
+        let full_checkpoint = read_full_checkpoint(&d).await.unwrap();
+
+        (committee, full_checkpoint)
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_all_good() {
+        let (committee, full_checkpoint) = read_data().await;
+
+        extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zi6cMVA9t4WhWk").unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_bad_committee() {
+        let (mut committee, full_checkpoint) = read_data().await;
+
+        // Change committee
+        committee.epoch += 10;
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zi6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_no_transaction() {
+        let (committee, full_checkpoint) = read_data().await;
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zj6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_bad_contents() {
+        let (committee, mut full_checkpoint) = read_data().await;
+
+        // Change contents
+        let random_contents = FullCheckpointContents::random_for_testing();
+        full_checkpoint.checkpoint_contents = random_contents.checkpoint_contents();
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zj6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_bad_events() {
+        let (committee, mut full_checkpoint) = read_data().await;
+
+        let event = full_checkpoint.transactions[4]
+            .events
+            .as_ref()
+            .unwrap()
+            .data[0]
+            .clone();
+
+        for t in &mut full_checkpoint.transactions {
+            if let Some(events) = &mut t.events {
+                events.data.push(event.clone());
+            }
+        }
+
+        assert!(extract_verified_effects_and_events(
+            &full_checkpoint,
+            &committee,
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zj6cMVA9t4WhWk").unwrap(),
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_bad_objects() {
+        let (committee, full_checkpoint) = read_data().await;
+        let tid =
+            TransactionDigest::from_str("8RiKBwuAbtu8zNCtz8SrcfHyEUzto6zi6cMVA9t4WhWk").unwrap();
+        let index = full_checkpoint
+            .transactions
+            .iter()
+            .position(|t| *t.transaction.digest() == tid)
+            .unwrap();
+
+        // A missing output object
+        let mut checkpoint = full_checkpoint.clone();
+        checkpoint.transactions[index].output_objects.pop().unwrap();
+        assert!(extract_verified_transaction(&checkpoint, &committee, tid).is_err());
+
+        // An output object passed off as an input object
+        let mut checkpoint = full_checkpoint.clone();
+        let transaction = &mut checkpoint.transactions[index];
+        transaction.input_objects[0] = transaction.output_objects[0].clone();
+        assert!(extract_verified_transaction(&checkpoint, &committee, tid).is_err());
+
+        // An input object from another transaction
+        let mut checkpoint = full_checkpoint.clone();
+        let other = checkpoint
+            .transactions
+            .iter()
+            .position(|t| *t.transaction.digest() != tid && !t.input_objects.is_empty())
+            .unwrap();
+        let object = checkpoint.transactions[other].input_objects[0].clone();
+        checkpoint.transactions[index].input_objects.push(object);
+        assert!(extract_verified_transaction(&checkpoint, &committee, tid).is_err());
+
+        assert!(extract_verified_transaction(&full_checkpoint, &committee, tid).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_object_not_stale() {
+        let (_, full_checkpoint) = read_data().await;
+        let transaction = full_checkpoint
+            .transactions
+            .iter()
+            .find(|t| !t.input_objects.is_empty())
+            .unwrap();
+
+        // The versions written by the checkpoint are fresh
+        for object in &transaction.output_objects {
+            assert!(verify_object_not_stale(object, &full_checkpoint).is_ok());
+        }
+        // The versions they replaced are stale
+        for object in &transaction.input_objects {
+            assert!(verify_object_not_stale(object, &full_checkpoint).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_committee_chain_rejects_wrong_epoch() {
+        let checkpoint = read_end_of_epoch_checkpoint();
+        let committee = next_epoch_committee(&checkpoint).unwrap();
+        let mut chain = CommitteeChain::new(committee.clone());
+        assert_eq!(chain.latest_committee(), &committee);
+
+        // The checkpoint ends the epoch before the latest committee's
+        assert!(chain.append(checkpoint.clone()).is_err());
+        assert!(chain.checkpoints().is_empty());
+        assert!(chain.committee(checkpoint.epoch()).is_none());
+        assert_eq!(chain.committee(committee.epoch), Some(&committee));
+    }
+}
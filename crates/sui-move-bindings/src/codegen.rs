@@ -0,0 +1,459 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use anyhow::{anyhow, bail};
+use move_binary_format::file_format::Visibility;
+use move_binary_format::normalized::{Field, Function, Module, Type};
+use move_core_types::account_address::AccountAddress;
+use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+use crate::BindingsGenerator;
+
+const TYPE_TAG: &str = "::move_core_types::language_storage::TypeTag";
+const STRUCT_TAG: &str = "::move_core_types::language_storage::StructTag";
+const IDENTIFIER: &str = "::move_core_types::identifier::Identifier";
+const ARGUMENT: &str = "::sui_types::transaction::Argument";
+const DERIVES: &str =
+    "#[derive(Debug, Clone, PartialEq, Eq, ::serde::Serialize, ::serde::Deserialize)]";
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Keywords that can't be used as raw identifiers.
+const RESERVED_IDENTIFIERS: &[&str] = &["crate", "self", "Self", "super"];
+
+pub(crate) struct Generator<'a> {
+    config: &'a BindingsGenerator,
+    address: AccountAddress,
+    /// Indices of the non-phantom type parameters of each datatype in the package, which are the
+    /// type parameters of its Rust version.
+    type_params: BTreeMap<(String, String), Vec<usize>>,
+    out: String,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(config: &'a BindingsGenerator) -> Self {
+        let mut type_params = BTreeMap::new();
+        for module in &config.modules {
+            let structs = module
+                .structs
+                .iter()
+                .map(|(name, s)| (name, &s.type_parameters));
+            let enums = module
+                .enums
+                .iter()
+                .map(|(name, e)| (name, &e.type_parameters));
+            for (name, params) in structs.chain(enums) {
+                let kept = params
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, param)| !param.is_phantom)
+                    .map(|(i, _)| i)
+                    .collect();
+                type_params.insert((module.name.to_string(), name.to_string()), kept);
+            }
+        }
+        Self {
+            config,
+            address: config.modules[0].address,
+            type_params,
+            out: String::new(),
+        }
+    }
+
+    pub fn generate(mut self) -> anyhow::Result<String> {
+        self.line(
+            0,
+            format!(
+                "// Generated by sui-move-bindings for package {}. Do not edit.",
+                self.address.to_hex_literal()
+            ),
+        );
+        self.line(0, "");
+        self.line(0, "/// The package that functions are called on.");
+        self.line(
+            0,
+            "pub const PACKAGE_ID: ::sui_types::base_types::ObjectID = \
+             ::sui_types::base_types::ObjectID::new([",
+        );
+        for chunk in self.config.package_id.into_bytes().chunks(8) {
+            let bytes: Vec<_> = chunk.iter().map(|b| format!("0x{b:02x},")).collect();
+            self.line(1, bytes.join(" "));
+        }
+        self.line(0, "]);");
+
+        let config = self.config;
+        for module in &config.modules {
+            self.module(module)?;
+        }
+        Ok(self.out)
+    }
+
+    fn module(&mut self, module: &Module) -> anyhow::Result<()> {
+        let module_name = module.name.to_string();
+        self.line(0, "");
+        self.line(
+            0,
+            format!(
+                "/// Bindings for `{}::{module_name}`.",
+                self.address.to_hex_literal()
+            ),
+        );
+        self.line(0, "#[allow(clippy::all, dead_code, non_camel_case_types)]");
+        self.line(0, format!("pub mod {} {{", ident(&module_name)));
+        self.line(
+            1,
+            format!("pub const MODULE_NAME: &str = \"{module_name}\";"),
+        );
+
+        let mut function_names = BTreeSet::new();
+        for (name, s) in &module.structs {
+            let params = self.datatype_params(&module_name, name.as_str());
+            self.line(0, "");
+            self.line(1, DERIVES);
+            self.line(1, format!("pub struct {}{params} {{", ident(name.as_str())));
+            self.fields(2, &s.fields, true)?;
+            self.line(1, "}");
+            self.type_function(&module_name, name.as_str(), s.type_parameters.len());
+            function_names.insert(type_function_name(name.as_str()));
+        }
+
+        for (name, e) in &module.enums {
+            let params = self.datatype_params(&module_name, name.as_str());
+            self.line(0, "");
+            self.line(1, DERIVES);
+            self.line(1, format!("pub enum {}{params} {{", ident(name.as_str())));
+            for variant in &e.variants {
+                let variant_name = ident(variant.name.as_str());
+                if variant.fields.is_empty() {
+                    self.line(2, format!("{variant_name},"));
+                } else {
+                    self.line(2, format!("{variant_name} {{"));
+                    self.fields(3, &variant.fields, false)?;
+                    self.line(2, "},");
+                }
+            }
+            self.line(1, "}");
+            self.type_function(&module_name, name.as_str(), e.type_parameters.len());
+            function_names.insert(type_function_name(name.as_str()));
+        }
+
+        for (name, function) in &module.functions {
+            if !function.is_entry && function.visibility != Visibility::Public {
+                continue;
+            }
+            if !function_names.insert(name.to_string()) {
+                bail!(
+                    "Function {module_name}::{name} clashes with the generated type function of \
+                     the same name"
+                );
+            }
+            self.call_builder(&module_name, name.as_str(), function)?;
+        }
+
+        self.line(0, "}");
+        Ok(())
+    }
+
+    fn fields(&mut self, indent: usize, fields: &[Field], public: bool) -> anyhow::Result<()> {
+        let visibility = if public { "pub " } else { "" };
+        for field in fields {
+            let type_ = self.rust_type(&field.type_)?;
+            self.line(
+                indent,
+                format!("{visibility}{}: {type_},", ident(field.name.as_str())),
+            );
+        }
+        Ok(())
+    }
+
+    /// Generic parameters of the Rust version of a datatype, e.g. `<T0, T2>`.
+    fn datatype_params(&self, module: &str, name: &str) -> String {
+        let kept = &self.type_params[&(module.to_string(), name.to_string())];
+        if kept.is_empty() {
+            return String::new();
+        }
+        let params: Vec<_> = kept.iter().map(|i| format!("T{i}")).collect();
+        format!("<{}>", params.join(", "))
+    }
+
+    /// A function returning the `StructTag` of a datatype with the given type parameters.
+    fn type_function(&mut self, module: &str, name: &str, arity: usize) {
+        let address = self
+            .config
+            .type_origins
+            .get(&(module.to_string(), name.to_string()))
+            .map_or(self.address, |origin| AccountAddress::from(*origin));
+        let (param, type_params) = if arity == 0 {
+            (String::new(), "vec![]")
+        } else {
+            (
+                format!("type_params: [{TYPE_TAG}; {arity}]"),
+                "type_params.into()",
+            )
+        };
+        self.line(0, "");
+        self.line(1, format!("/// The `StructTag` of `{name}`."));
+        self.line(
+            1,
+            format!(
+                "pub fn {}({param}) -> {STRUCT_TAG} {{",
+                type_function_name(name)
+            ),
+        );
+        self.line(2, format!("{STRUCT_TAG} {{"));
+        self.line(
+            3,
+            format!(
+                "address: ::move_core_types::account_address::AccountAddress::from_hex_literal(\
+                 \"{}\").unwrap(),",
+                address.to_hex_literal()
+            ),
+        );
+        self.line(
+            3,
+            format!("module: {IDENTIFIER}::new(MODULE_NAME).unwrap(),"),
+        );
+        self.line(3, format!("name: {IDENTIFIER}::new(\"{name}\").unwrap(),"));
+        self.line(3, format!("type_params: {type_params},"));
+        self.line(2, "}");
+        self.line(1, "}");
+    }
+
+    /// A function adding a call to `function` to a programmable transaction.
+    fn call_builder(
+        &mut self,
+        module: &str,
+        name: &str,
+        function: &Function,
+    ) -> anyhow::Result<()> {
+        let mut parameters = function.parameters.as_slice();
+        if let Some((last, rest)) = parameters.split_last() {
+            if is_tx_context(last) {
+                parameters = rest;
+            }
+        }
+
+        self.line(0, "");
+        self.line(
+            1,
+            format!("/// Adds a call to `{module}::{name}` to `builder`."),
+        );
+        self.line(1, format!("pub fn {}(", ident(name)));
+        self.line(
+            2,
+            "builder: &mut ::sui_types::programmable_transaction_builder::\
+             ProgrammableTransactionBuilder,",
+        );
+        let arity = function.type_parameters.len();
+        if arity > 0 {
+            self.line(2, format!("type_args: [{TYPE_TAG}; {arity}],"));
+        }
+        let mut arguments = vec![];
+        for (i, parameter) in parameters.iter().enumerate() {
+            // Values are passed as pure inputs, everything else as the result of other
+            // commands or object inputs.
+            match pure_type(parameter) {
+                Some(type_) => {
+                    let type_ = self.rust_type(type_)?;
+                    self.line(2, format!("arg{i}: {type_},"));
+                    arguments.push(format!("builder.pure(arg{i})?,"));
+                }
+                None => {
+                    self.line(2, format!("arg{i}: {ARGUMENT},"));
+                    arguments.push(format!("arg{i},"));
+                }
+            }
+        }
+        self.line(1, format!(") -> ::anyhow::Result<{ARGUMENT}> {{"));
+        self.line(2, "let arguments = vec![");
+        for argument in arguments {
+            self.line(3, argument);
+        }
+        self.line(2, "];");
+        self.line(2, "Ok(builder.programmable_move_call(");
+        self.line(3, "super::PACKAGE_ID,");
+        self.line(3, format!("{IDENTIFIER}::new(MODULE_NAME)?,"));
+        self.line(3, format!("{IDENTIFIER}::new(\"{name}\")?,"));
+        self.line(
+            3,
+            if arity > 0 {
+                "type_args.into(),"
+            } else {
+                "vec![],"
+            },
+        );
+        self.line(3, "arguments,");
+        self.line(2, "))");
+        self.line(1, "}");
+        Ok(())
+    }
+
+    /// The Rust type of a Move type appearing in a datatype definition or pure argument.
+    fn rust_type(&self, type_: &Type) -> anyhow::Result<String> {
+        Ok(match type_ {
+            Type::Bool => "bool".to_string(),
+            Type::U8 => "u8".to_string(),
+            Type::U16 => "u16".to_string(),
+            Type::U32 => "u32".to_string(),
+            Type::U64 => "u64".to_string(),
+            Type::U128 => "u128".to_string(),
+            Type::U256 => "::move_core_types::u256::U256".to_string(),
+            Type::Address => "::sui_types::base_types::SuiAddress".to_string(),
+            Type::Vector(type_) => format!("::std::vec::Vec<{}>", self.rust_type(type_)?),
+            Type::TypeParameter(i) => format!("T{i}"),
+            Type::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => {
+                let key = (module.to_string(), name.to_string());
+                if *address == self.address {
+                    if let Some(kept) = self.type_params.get(&key) {
+                        let arguments = kept
+                            .iter()
+                            .map(|i| self.rust_type(&type_arguments[*i]))
+                            .collect::<anyhow::Result<Vec<_>>>()?;
+                        let arguments = if arguments.is_empty() {
+                            String::new()
+                        } else {
+                            format!("<{}>", arguments.join(", "))
+                        };
+                        return Ok(format!(
+                            "super::{}::{}{arguments}",
+                            ident(module.as_str()),
+                            ident(name.as_str())
+                        ));
+                    }
+                }
+                let rust_type = self
+                    .config
+                    .extern_types
+                    .get(&(*address, key.0, key.1))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "No Rust type for {}::{module}::{name}, map it to one with \
+                             `BindingsGenerator::extern_type`",
+                            address.to_hex_literal()
+                        )
+                    })?;
+                // Substitute from the last argument, so that `$1` does not match `$10`.
+                let mut rust_type = rust_type.clone();
+                for (i, argument) in type_arguments.iter().enumerate().rev() {
+                    let placeholder = format!("${i}");
+                    if rust_type.contains(&placeholder) {
+                        rust_type = rust_type.replace(&placeholder, &self.rust_type(argument)?);
+                    }
+                }
+                rust_type
+            }
+            Type::Signer | Type::Reference(_) | Type::MutableReference(_) => {
+                bail!("Type {type_:?} has no Rust equivalent")
+            }
+        })
+    }
+
+    fn line(&mut self, indent: usize, line: impl AsRef<str>) {
+        let line = line.as_ref();
+        if !line.is_empty() {
+            self.out.push_str(&"    ".repeat(indent));
+        }
+        writeln!(self.out, "{line}").unwrap();
+    }
+}
+
+/// The type of a parameter that can be passed as a pure input, if it can be.
+fn pure_type(type_: &Type) -> Option<&Type> {
+    let inner = match type_ {
+        Type::Reference(inner) | Type::MutableReference(inner) => inner,
+        type_ => type_,
+    };
+    is_pure(inner).then_some(inner)
+}
+
+fn is_pure(type_: &Type) -> bool {
+    match type_ {
+        Type::Bool
+        | Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::U128
+        | Type::U256
+        | Type::Address => true,
+        Type::Vector(inner) => is_pure(inner),
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => match (
+            address.to_hex_literal().as_str(),
+            module.as_str(),
+            name.as_str(),
+        ) {
+            ("0x1", "string", "String") | ("0x1", "ascii", "String") | ("0x2", "object", "ID") => {
+                true
+            }
+            ("0x1", "option", "Option") => type_arguments.iter().all(is_pure),
+            _ => false,
+        },
+        Type::Signer | Type::TypeParameter(_) | Type::Reference(_) | Type::MutableReference(_) => {
+            false
+        }
+    }
+}
+
+fn is_tx_context(type_: &Type) -> bool {
+    let (Type::Reference(inner) | Type::MutableReference(inner)) = type_ else {
+        return false;
+    };
+    matches!(
+        inner.as_ref(),
+        Type::Struct { address, module, name, .. }
+            if *address == SUI_FRAMEWORK_ADDRESS
+                && module.as_str() == "tx_context"
+                && name.as_str() == "TxContext"
+    )
+}
+
+/// Escape Move identifiers that are Rust keywords.
+fn ident(name: &str) -> String {
+    if RESERVED_IDENTIFIERS.contains(&name) {
+        format!("{name}_")
+    } else if RUST_KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Name of the function returning the `StructTag` of a datatype, e.g. `treasury_cap_type` for
+/// `TreasuryCap`.
+fn type_function_name(name: &str) -> String {
+    let chars: Vec<_> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    format!("{snake}_type")
+}
@@ -0,0 +1,250 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates typed Rust bindings for Move packages.
+//!
+//! For every module of a package, the generated code contains:
+//! - a Rust struct or enum for each Move struct or enum, deriving `serde::Serialize` and
+//!   `serde::Deserialize` so that values can be decoded from (and encoded to) BCS. Phantom type
+//!   parameters are omitted.
+//! - a `<name>_type` function for each struct or enum, returning its `StructTag`,
+//! - a call builder for each public or entry function, which adds a call to the function to a
+//!   `ProgrammableTransactionBuilder`. Arguments of primitive types (integers, `bool`,
+//!   `address`, strings, `ID`s, and vectors and options of these) are taken as Rust values and
+//!   added as pure inputs, all other arguments are taken as `Argument`s.
+//!
+//! The generated code depends on the `anyhow`, `serde`, `move-core-types` and `sui-types` crates.
+//!
+//! Move types from other packages are mapped to existing Rust types: common types from the Move
+//! standard library and the Sui framework are mapped by default, and more can be added with
+//! [BindingsGenerator::extern_type].
+//!
+//! # Build script
+//!
+//! ```rust,no_run
+//! // build.rs
+//! use std::path::{Path, PathBuf};
+//! use sui_move_bindings::BindingsGenerator;
+//!
+//! fn main() -> anyhow::Result<()> {
+//!     let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+//!     BindingsGenerator::from_package(Path::new("move/my_package"))?
+//!         .write_to_file(&out_dir.join("my_package.rs"))?;
+//!     println!("cargo:rerun-if-changed=move/my_package");
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The bindings can then be included with
+//! `mod my_package { include!(concat!(env!("OUT_DIR"), "/my_package.rs")); }`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use move_binary_format::normalized;
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_package::{BuildConfig as MoveBuildConfig, LintFlag};
+use sui_move_build::{BuildConfig, SuiPackageHooks};
+use sui_sdk::rpc_types::{SuiObjectDataOptions, SuiRawData};
+use sui_sdk::SuiClient;
+use sui_types::base_types::ObjectID;
+
+use crate::codegen::Generator;
+
+mod codegen;
+
+#[cfg(test)]
+#[path = "unit_tests/bindings_tests.rs"]
+mod bindings_tests;
+
+/// Rust types that Move types from the standard library and the Sui framework are mapped to.
+/// `$<n>` is replaced by the Rust type of the `n`-th type argument.
+const DEFAULT_EXTERN_TYPES: &[(&str, &str)] = &[
+    ("0x1::ascii::String", "::std::string::String"),
+    ("0x1::option::Option", "::std::option::Option<$0>"),
+    ("0x1::string::String", "::std::string::String"),
+    ("0x1::type_name::TypeName", "::std::string::String"),
+    ("0x2::bag::Bag", "::sui_types::collection_types::Bag"),
+    ("0x2::balance::Balance", "::sui_types::balance::Balance"),
+    ("0x2::balance::Supply", "::sui_types::balance::Supply"),
+    ("0x2::coin::Coin", "::sui_types::coin::Coin"),
+    ("0x2::coin::CoinMetadata", "::sui_types::coin::CoinMetadata"),
+    ("0x2::coin::TreasuryCap", "::sui_types::coin::TreasuryCap"),
+    (
+        "0x2::linked_table::LinkedTable",
+        "::sui_types::collection_types::LinkedTable<$0>",
+    ),
+    ("0x2::object::ID", "::sui_types::id::ID"),
+    ("0x2::object::UID", "::sui_types::id::UID"),
+    (
+        "0x2::object_bag::ObjectBag",
+        "::sui_types::collection_types::Bag",
+    ),
+    (
+        "0x2::object_table::ObjectTable",
+        "::sui_types::collection_types::Table",
+    ),
+    ("0x2::table::Table", "::sui_types::collection_types::Table"),
+    (
+        "0x2::table_vec::TableVec",
+        "::sui_types::collection_types::TableVec",
+    ),
+    ("0x2::url::Url", "::std::string::String"),
+    (
+        "0x2::vec_map::VecMap",
+        "::sui_types::collection_types::VecMap<$0, $1>",
+    ),
+    (
+        "0x2::vec_set::VecSet",
+        "::sui_types::collection_types::VecSet<$0>",
+    ),
+];
+
+/// Generates Rust bindings for the modules of a Move package. See the
+/// [crate documentation](crate) for the shape of the generated code.
+pub struct BindingsGenerator {
+    modules: Vec<normalized::Module>,
+    /// Package that functions are called on.
+    package_id: ObjectID,
+    /// Package that defined each type, keyed by module and type name, for upgraded packages.
+    type_origins: BTreeMap<(String, String), ObjectID>,
+    /// Rust types for Move types outside of the package, keyed by fully qualified Move type.
+    extern_types: BTreeMap<(AccountAddress, String, String), String>,
+}
+
+impl BindingsGenerator {
+    /// Generate bindings for the given modules, which must all belong to the same package.
+    pub fn from_modules(modules: &[CompiledModule]) -> anyhow::Result<Self> {
+        let modules: Vec<_> = modules.iter().map(normalized::Module::new).collect();
+        let address = modules
+            .first()
+            .ok_or_else(|| anyhow!("No modules to generate bindings for"))?
+            .address;
+        if let Some(module) = modules.iter().find(|m| m.address != address) {
+            bail!(
+                "Module {}::{} is not part of package {}",
+                module.address.to_hex_literal(),
+                module.name,
+                address.to_hex_literal()
+            );
+        }
+        let mut generator = Self {
+            modules,
+            package_id: ObjectID::from(address),
+            type_origins: BTreeMap::new(),
+            extern_types: BTreeMap::new(),
+        };
+        for (move_type, rust_type) in DEFAULT_EXTERN_TYPES {
+            generator = generator.extern_type(move_type, rust_type)?;
+        }
+        Ok(generator)
+    }
+
+    /// Generate bindings for the compiled modules (`.mv` files) in `dir`, such as the
+    /// `build/<package>/bytecode_modules` directory of a built package.
+    pub fn from_bytecode_dir(dir: &Path) -> anyhow::Result<Self> {
+        let mut modules = vec![];
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "mv") {
+                continue;
+            }
+            let bytes = std::fs::read(&path)?;
+            modules.push(
+                CompiledModule::deserialize_with_defaults(&bytes)
+                    .with_context(|| format!("Failed to deserialize {}", path.display()))?,
+            );
+        }
+        Self::from_modules(&modules)
+    }
+
+    /// Build the Move package at `path` and generate bindings for its modules. If the package
+    /// has been published, functions are called on its `published-at` address.
+    pub fn from_package(path: &Path) -> anyhow::Result<Self> {
+        move_package::package_hooks::register_package_hooks(Box::new(SuiPackageHooks));
+        let mut config = MoveBuildConfig::default();
+        config.lint_flag = LintFlag::LEVEL_NONE;
+        config.silence_warnings = true;
+        let build_config = BuildConfig {
+            config,
+            run_bytecode_verifier: false,
+            print_diags_to_stderr: true,
+            chain_id: None,
+        };
+        Self::from_package_with_config(path, build_config)
+    }
+
+    /// Like [BindingsGenerator::from_package], building the package with `build_config`.
+    pub fn from_package_with_config(
+        path: &Path,
+        build_config: BuildConfig,
+    ) -> anyhow::Result<Self> {
+        let package = build_config.build(path)?;
+        let published_at = package.published_at.clone().ok();
+        let generator = Self::from_modules(&package.into_modules())?;
+        Ok(match published_at {
+            Some(id) => generator.package_id(id),
+            None => generator,
+        })
+    }
+
+    /// Fetch the package `package_id` from the network and generate bindings for its modules.
+    pub async fn from_chain(client: &SuiClient, package_id: ObjectID) -> anyhow::Result<Self> {
+        let response = client
+            .read_api()
+            .get_object_with_options(package_id, SuiObjectDataOptions::new().with_bcs())
+            .await?;
+        let Some(SuiRawData::Package(package)) = response.data.and_then(|data| data.bcs) else {
+            bail!("Object {package_id} is not a package");
+        };
+        let modules = package
+            .module_map
+            .values()
+            .map(|bytes| CompiledModule::deserialize_with_defaults(bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut generator = Self::from_modules(&modules)?.package_id(package_id);
+        generator.type_origins = package
+            .type_origin_table
+            .into_iter()
+            .map(|origin| ((origin.module_name, origin.datatype_name), origin.package))
+            .collect();
+        Ok(generator)
+    }
+
+    /// Set the package that generated call builders call functions on. Defaults to the address of
+    /// the modules, and must be set to the latest version of upgraded packages.
+    pub fn package_id(mut self, package_id: ObjectID) -> Self {
+        self.package_id = package_id;
+        self
+    }
+
+    /// Map the Move type `move_type`, given as `<address>::<module>::<name>`, to the Rust type
+    /// `rust_type`, in which `$<n>` stands for the Rust type of the `n`-th type argument, e.g.
+    /// `extern_type("0x2::vec_map::VecMap", "::sui_types::collection_types::VecMap<$0, $1>")`.
+    pub fn extern_type(mut self, move_type: &str, rust_type: &str) -> anyhow::Result<Self> {
+        let parts: Vec<_> = move_type.split("::").collect();
+        let [address, module, name] = parts[..] else {
+            bail!("Expected a type of the form <address>::<module>::<name>, got {move_type}");
+        };
+        let address = AccountAddress::from_hex_literal(address)
+            .with_context(|| format!("Invalid address in {move_type}"))?;
+        self.extern_types.insert(
+            (address, module.to_string(), name.to_string()),
+            rust_type.to_string(),
+        );
+        Ok(self)
+    }
+
+    /// Generate the Rust bindings.
+    pub fn generate(&self) -> anyhow::Result<String> {
+        Generator::new(self).generate()
+    }
+
+    /// Generate the Rust bindings and write them to `path`.
+    pub fn write_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, self.generate()?)
+            .with_context(|| format!("Failed to write bindings to {}", path.display()))
+    }
+}
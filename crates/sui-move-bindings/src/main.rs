@@ -0,0 +1,73 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use clap::{ArgGroup, Parser};
+use sui_move_bindings::BindingsGenerator;
+use sui_sdk::SuiClientBuilder;
+use sui_types::base_types::ObjectID;
+
+/// Generate typed Rust bindings for a Move package.
+#[derive(Parser)]
+#[clap(
+    name = "sui-move-bindings",
+    group(ArgGroup::new("source").required(true).args(["package_path", "bytecode_dir", "package_id"]))
+)]
+struct Args {
+    /// Path to a Move package to build.
+    #[clap(long)]
+    package_path: Option<PathBuf>,
+    /// Directory of compiled modules (`.mv` files) of a package.
+    #[clap(long)]
+    bytecode_dir: Option<PathBuf>,
+    /// ID of a package published on chain.
+    #[clap(long, requires = "rpc_url")]
+    package_id: Option<ObjectID>,
+    /// URL of the fullnode to fetch the package from.
+    #[clap(long)]
+    rpc_url: Option<String>,
+    /// Package that functions are called on, if not the package the bindings are generated for.
+    #[clap(long)]
+    call_package_id: Option<ObjectID>,
+    /// Map a Move type from another package to a Rust type, as `<move type>=<rust type>`, e.g.
+    /// `0x2::vec_map::VecMap=::sui_types::collection_types::VecMap<$0, $1>`.
+    #[clap(long = "extern-type")]
+    extern_types: Vec<String>,
+    /// File to write the bindings to. Defaults to stdout.
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let mut generator = if let Some(path) = &args.package_path {
+        BindingsGenerator::from_package(path)?
+    } else if let Some(dir) = &args.bytecode_dir {
+        BindingsGenerator::from_bytecode_dir(dir)?
+    } else {
+        let package_id = args.package_id.unwrap();
+        let client = SuiClientBuilder::default()
+            .build(args.rpc_url.unwrap())
+            .await?;
+        BindingsGenerator::from_chain(&client, package_id).await?
+    };
+    if let Some(package_id) = args.call_package_id {
+        generator = generator.package_id(package_id);
+    }
+    for mapping in &args.extern_types {
+        let (move_type, rust_type) = mapping
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected <move type>=<rust type>, got {mapping}"))?;
+        generator = generator.extern_type(move_type, rust_type)?;
+    }
+
+    match &args.output {
+        Some(path) => generator.write_to_file(path)?,
+        None => print!("{}", generator.generate()?),
+    }
+    Ok(())
+}
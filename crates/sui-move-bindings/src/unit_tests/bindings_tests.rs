@@ -0,0 +1,154 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::{Path, PathBuf};
+
+use move_bytecode_utils::layout::TypeLayoutBuilder;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::annotated_value::MoveValue;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use sui_move_build::BuildConfig;
+use sui_types::base_types::SuiAddress;
+
+use crate::BindingsGenerator;
+
+mod generated {
+    include!("data/entry.rs");
+}
+
+fn example_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("unit_tests")
+        .join("data")
+        .join("example")
+}
+
+fn example_package() -> BindingsGenerator {
+    BindingsGenerator::from_package_with_config(&example_path(), BuildConfig::new_for_testing())
+        .unwrap()
+}
+
+#[test]
+fn generate_bindings() {
+    let bindings = example_package()
+        .extern_type("0x2::versioned::Versioned", "::my_crate::Versioned")
+        .unwrap()
+        .generate()
+        .unwrap();
+
+    for expected in [
+        "pub mod example {",
+        // Phantom type parameters are dropped.
+        "pub struct Pool<T1> {",
+        "pub id: ::sui_types::id::UID,",
+        "pub balance: ::sui_types::balance::Balance,",
+        "pub name: ::std::string::String,",
+        "pub meta: T1,",
+        "pub entries: ::std::vec::Vec<super::example::Entry>,",
+        "pub status: super::example::Status,",
+        "pub owner: ::sui_types::base_types::SuiAddress,",
+        "pub memo: ::std::option::Option<::std::vec::Vec<u8>>,",
+        "pub inner: ::my_crate::Versioned,",
+        "pub enum Status {",
+        "Active,",
+        "Closed {",
+        "at: u64,",
+        "pub fn pool_type(type_params: [::move_core_types::language_storage::TypeTag; 2])",
+        "pub fn entry_type() -> ::move_core_types::language_storage::StructTag {",
+        // Call builders for public and entry functions, without the `TxContext`.
+        "pub fn new(",
+        "arg1: ::std::string::String,",
+        "pub fn add(",
+        "arg1: ::sui_types::base_types::SuiAddress,",
+        "builder.pure(arg2)?,",
+        "pub fn close(",
+    ] {
+        assert!(
+            bindings.contains(expected),
+            "Missing `{expected}` in:\n{bindings}"
+        );
+    }
+    assert!(!bindings.contains("arg2: ::sui_types::transaction::Argument"));
+    assert!(!bindings.contains("pub fn entry_count("));
+    syn::parse_file(&bindings).unwrap_or_else(|e| panic!("Invalid Rust ({e}) in:\n{bindings}"));
+}
+
+#[test]
+fn generated_struct_matches_onchain_layout() {
+    let bindings = example_package()
+        .extern_type("0x2::versioned::Versioned", "::my_crate::Versioned")
+        .unwrap()
+        .generate()
+        .unwrap();
+    let file = syn::parse_file(&bindings).unwrap();
+    let (_, items) = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Mod(module) if module.ident == "example" => module.content.as_ref(),
+            _ => None,
+        })
+        .unwrap();
+    let entry = items
+        .iter()
+        .find(|item| matches!(item, syn::Item::Struct(s) if s.ident == "Entry"))
+        .unwrap();
+    let expected: syn::Item = syn::parse_str(include_str!("data/entry.rs")).unwrap();
+    assert_eq!(entry, &expected);
+
+    // Values of the generated struct are the BCS values of the Move struct
+    let package = BuildConfig::new_for_testing()
+        .build(&example_path())
+        .unwrap();
+    let address = *package
+        .get_modules()
+        .find(|module| module.name().as_str() == "example")
+        .unwrap()
+        .address();
+    let type_ = TypeTag::Struct(Box::new(StructTag {
+        address,
+        module: Identifier::new("example").unwrap(),
+        name: Identifier::new("Entry").unwrap(),
+        type_params: vec![],
+    }));
+    let layout = TypeLayoutBuilder::build_with_types(&type_, &package).unwrap();
+
+    let entry = generated::Entry {
+        owner: SuiAddress::from(AccountAddress::from_hex_literal("0x42").unwrap()),
+        amount: 7,
+        memo: Some(vec![1, 2, 3]),
+    };
+    let bytes = bcs::to_bytes(&entry).unwrap();
+    let value = MoveValue::simple_deserialize(&bytes, &layout).unwrap();
+    let MoveValue::Struct(move_struct) = &value else {
+        panic!("Expected a struct value");
+    };
+    assert_eq!(
+        move_struct.fields[0],
+        (
+            Identifier::new("owner").unwrap(),
+            MoveValue::Address(AccountAddress::from_hex_literal("0x42").unwrap())
+        )
+    );
+    assert_eq!(
+        move_struct.fields[1],
+        (Identifier::new("amount").unwrap(), MoveValue::U64(7))
+    );
+    let onchain_bytes = value.undecorate().simple_serialize().unwrap();
+    assert_eq!(bytes, onchain_bytes);
+    assert_eq!(
+        entry,
+        bcs::from_bytes::<generated::Entry>(&onchain_bytes).unwrap()
+    );
+}
+
+#[test]
+fn unmapped_extern_type() {
+    let err = example_package().generate().unwrap_err();
+    assert!(
+        err.to_string().contains("0x2::versioned::Versioned"),
+        "{err}"
+    );
+}
@@ -0,0 +1,11 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// The bindings generated for `example::Entry`, checked against the generator by
+// `generated_struct_matches_onchain_layout`.
+#[derive(Debug, Clone, PartialEq, Eq, ::serde::Serialize, ::serde::Deserialize)]
+pub struct Entry {
+    pub owner: ::sui_types::base_types::SuiAddress,
+    pub amount: u64,
+    pub memo: ::std::option::Option<::std::vec::Vec<u8>>,
+}
@@ -0,0 +1,55 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module example::example {
+    use std::string::String;
+    use sui::balance::{Self, Balance};
+    use sui::versioned::Versioned;
+
+    public struct Pool<phantom T, M: store> has key, store {
+        id: UID,
+        balance: Balance<T>,
+        name: String,
+        meta: M,
+        entries: vector<Entry>,
+        status: Status,
+    }
+
+    public struct Entry has copy, drop, store {
+        owner: address,
+        amount: u64,
+        memo: Option<vector<u8>>,
+    }
+
+    public enum Status has copy, drop, store {
+        Active,
+        Closed { at: u64 },
+    }
+
+    public struct Upgradeable has store {
+        inner: Versioned,
+    }
+
+    public fun new<T, M: store>(meta: M, name: String, ctx: &mut TxContext): Pool<T, M> {
+        Pool {
+            id: object::new(ctx),
+            balance: balance::zero(),
+            name,
+            meta,
+            entries: vector[],
+            status: Status::Active,
+        }
+    }
+
+    public fun add<T, M: store>(pool: &mut Pool<T, M>, owner: address, amount: u64) {
+        pool.entries.push_back(Entry { owner, amount, memo: option::none() });
+    }
+
+    entry fun close<T, M: store>(pool: &mut Pool<T, M>, at: u64) {
+        pool.status = Status::Closed { at };
+    }
+
+    fun entry_count<T, M: store>(pool: &Pool<T, M>): u64 {
+        pool.entries.length()
+    }
+}
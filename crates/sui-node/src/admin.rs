@@ -16,6 +16,7 @@ use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
 };
+use sui_config::{transaction_deny_config::TransactionDenyConfig, Config};
 use sui_types::{
     base_types::AuthorityName,
     crypto::{RandomnessPartialSignature, RandomnessRound, RandomnessSignature},
@@ -48,6 +49,20 @@ use tracing::info;
 //
 //   $ curl 'http://127.0.0.1:1337/node-config'
 //
+// View the transaction deny config currently in effect:
+//
+//   $ curl 'http://127.0.0.1:1337/transaction-deny-config'
+//
+// Reload the transaction deny config from a YAML file, or from the request body:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config?path=/opt/sui/config/deny.yaml'
+//   $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config' --data-binary @deny.yaml
+//
+// View the memory budget, shares and hit rates of the execution caches, when they are sized by
+// memory:
+//
+//   $ curl 'http://127.0.0.1:1337/execution-cache-sizing'
+//
 // Set a time-limited tracing config. After the duration expires, tracing will be disabled
 // automatically.
 //
@@ -77,6 +92,8 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const TRANSACTION_DENY_CONFIG: &str = "/transaction-deny-config";
+const EXECUTION_CACHE_SIZING: &str = "/execution-cache-sizing";
 const RANDOMNESS_PARTIAL_SIGS_ROUTE: &str = "/randomness-partial-sigs";
 const RANDOMNESS_INJECT_PARTIAL_SIGS_ROUTE: &str = "/randomness-inject-partial-sigs";
 const RANDOMNESS_INJECT_FULL_SIG_ROUTE: &str = "/randomness-inject-full-sig";
@@ -98,6 +115,12 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(TRANSACTION_DENY_CONFIG, get(transaction_deny_config))
+        .route(
+            TRANSACTION_DENY_CONFIG,
+            post(reload_transaction_deny_config),
+        )
+        .route(EXECUTION_CACHE_SIZING, get(execution_cache_sizing))
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -260,6 +283,60 @@ async fn node_config(State(state): State<Arc<AppState>>) -> (StatusCode, String)
     (StatusCode::OK, format!("{:#?}\n", node_config))
 }
 
+async fn transaction_deny_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let config = state.node.state().transaction_deny_config();
+    match serde_yaml::to_string(config.as_ref()) {
+        Ok(config) => (StatusCode::OK, config),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn execution_cache_sizing(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let Some(summary) = state.node.state().get_reconfig_api().cache_sizing() else {
+        return (
+            StatusCode::OK,
+            "execution caches are not sized by a memory budget\n".to_string(),
+        );
+    };
+    match serde_yaml::to_string(&summary) {
+        Ok(summary) => (StatusCode::OK, summary),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct ReloadTransactionDenyConfig {
+    // Read the new config from this file instead of the request body.
+    path: Option<String>,
+}
+
+async fn reload_transaction_deny_config(
+    State(state): State<Arc<AppState>>,
+    query: Query<ReloadTransactionDenyConfig>,
+    body: String,
+) -> (StatusCode, String) {
+    let Query(ReloadTransactionDenyConfig { path }) = query;
+
+    let config = match path {
+        Some(path) => TransactionDenyConfig::load(&path).map_err(|err| format!("{err:?}")),
+        None => serde_yaml::from_str(&body).map_err(|err| err.to_string()),
+    };
+
+    match config {
+        Ok(config) => {
+            state.node.reload_transaction_deny_config(config);
+            (
+                StatusCode::OK,
+                "transaction deny config reloaded\n".to_string(),
+            )
+        }
+        Err(err) => (
+            StatusCode::BAD_REQUEST,
+            format!("can't reload transaction deny config: {err}\n"),
+        ),
+    }
+}
+
 #[derive(Deserialize)]
 struct Epoch {
     epoch: u64,
@@ -62,6 +62,7 @@ use sui_archival::writer::ArchiveWriter;
 use sui_config::node::{DBCheckpointConfig, RunWithRange};
 use sui_config::node_config_metrics::NodeConfigMetrics;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_config::{ConsensusConfig, NodeConfig};
 use sui_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
@@ -226,6 +227,7 @@ pub struct SuiNode {
     transaction_orchestrator: Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
     registry_service: RegistryService,
     metrics: Arc<SuiNodeMetrics>,
+    node_config_metrics: Arc<NodeConfigMetrics>,
 
     _discovery: discovery::Handle,
     state_sync_handle: state_sync::Handle,
@@ -418,7 +420,8 @@ impl SuiNode {
         custom_rpc_runtime: Option<Handle>,
         software_version: &'static str,
     ) -> Result<Arc<SuiNode>> {
-        NodeConfigMetrics::new(&registry_service.default_registry()).record_metrics(&config);
+        let node_config_metrics = NodeConfigMetrics::new(&registry_service.default_registry());
+        node_config_metrics.record_metrics(&config);
         let mut config = config.clone();
         if config.supported_protocol_versions.is_none() {
             info!(
@@ -478,8 +481,12 @@ impl SuiNode {
         let cache_metrics = Arc::new(ResolverMetrics::new(&prometheus_registry));
         let signature_verifier_metrics = SignatureVerifierMetrics::new(&prometheus_registry);
 
-        let cache_traits =
-            build_execution_cache(&epoch_start_configuration, &prometheus_registry, &store);
+        let cache_traits = build_execution_cache(
+            &config.execution_cache,
+            &epoch_start_configuration,
+            &prometheus_registry,
+            &store,
+        );
 
         let auth_agg = {
             let safe_client_metrics_base = SafeClientMetricsBase::new(&prometheus_registry);
@@ -818,6 +825,7 @@ impl SuiNode {
             transaction_orchestrator,
             registry_service,
             metrics: sui_node_metrics,
+            node_config_metrics,
 
             _discovery: discovery_handle,
             state_sync_handle,
@@ -941,6 +949,9 @@ impl SuiNode {
                 &config.snapshot_path(),
                 remote_store_config.clone(),
                 60,
+                config
+                    .state_snapshot_write_config
+                    .full_snapshot_interval_epochs,
                 prometheus_registry,
                 checkpoint_store,
             )?;
@@ -1459,6 +1470,13 @@ impl SuiNode {
         self.state.clone()
     }
 
+    /// Replace the transaction deny config of the running node, and the metrics that describe it.
+    pub fn reload_transaction_deny_config(&self, config: TransactionDenyConfig) {
+        self.node_config_metrics
+            .record_transaction_deny_config(&config);
+        self.state.reload_transaction_deny_config(config);
+    }
+
     // Only used for testing because of how epoch store is loaded.
     pub fn reference_gas_price_for_testing(&self) -> Result<u64, anyhow::Error> {
         self.state.reference_gas_price_for_testing()
@@ -1966,6 +1984,9 @@ pub async fn build_http_server(
             config.policy_config.clone(),
             config.firewall_config.clone(),
         );
+        if let Some(max_batch_size) = config.json_rpc_max_batch_size {
+            server.set_max_batch_size(max_batch_size);
+        }
 
         let kv_store = build_kv_store(&state, config, prometheus_registry)?;
 
@@ -8,7 +8,9 @@ use crate::middleware::{
     expect_content_length, expect_mysten_proxy_header, expect_valid_public_key,
 };
 use crate::peers::{SuiNodeProvider, SuiPeer};
+use crate::relabel::Relabeler;
 use crate::var;
+use crate::write_buffer::WriteBuffer;
 use anyhow::Error;
 use anyhow::Result;
 use axum::{extract::DefaultBodyLimit, middleware, routing::post, Extension, Router};
@@ -65,11 +67,22 @@ pub async fn shutdown_signal(h: axum_server::Handle) {
 
 /// Reqwest client holds the global client for remote_push api calls
 /// it also holds the username and password.  The client has an underlying
-/// connection pool.  See reqwest documentation for details
+/// connection pool.  See reqwest documentation for details.  If a write buffer is set,
+/// requests remote_write fails to accept are spilled to it to be replayed later.
 #[derive(Clone)]
 pub struct ReqwestClient {
     pub client: reqwest::Client,
     pub settings: RemoteWriteConfig,
+    pub buffer: Option<WriteBuffer>,
+}
+
+impl ReqwestClient {
+    pub fn with_write_buffer(self, buffer: WriteBuffer) -> Self {
+        Self {
+            buffer: Some(buffer),
+            ..self
+        }
+    }
 }
 
 pub fn make_reqwest_client(settings: RemoteWriteConfig, user_agent: &str) -> ReqwestClient {
@@ -81,6 +94,7 @@ pub fn make_reqwest_client(settings: RemoteWriteConfig, user_agent: &str) -> Req
             .build()
             .expect("cannot create reqwest client"),
         settings,
+        buffer: None,
     }
 }
 
@@ -96,6 +110,7 @@ pub fn app(
     labels: Labels,
     client: ReqwestClient,
     relay: HistogramRelay,
+    relabeler: Relabeler,
     allower: Option<SuiNodeProvider>,
 ) -> Router {
     // build our application with a route and our sender mpsc
@@ -114,6 +129,7 @@ pub fn app(
     }
     router
         .layer(Extension(relay))
+        .layer(Extension(relabeler))
         .layer(Extension(labels))
         .layer(Extension(client))
         .layer(
@@ -5,6 +5,7 @@ use core::time::Duration;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::{serde_as, DurationSeconds};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use tracing::debug;
 
 #[serde_as]
@@ -18,6 +19,11 @@ pub struct ProxyConfig {
     pub static_peers: Option<StaticPeerValidationConfig>,
     pub metrics_address: String,
     pub histogram_address: String,
+    #[serde(default)]
+    pub relabel: RelabelConfig,
+    /// if set, remote_write requests that fail are buffered on disk and replayed once
+    /// remote_write recovers, instead of being dropped
+    pub write_buffer: Option<WriteBufferConfig>,
 }
 
 #[serde_as]
@@ -85,11 +91,104 @@ pub struct StaticPubKey {
     pub peer_id: String,
 }
 
+/// RelabelConfig controls which series received from nodes we relay upstream, how they are
+/// labeled, and how many distinct series a single node may send us.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RelabelConfig {
+    /// rules applied in order to every series, after our own labels are populated
+    #[serde(default)]
+    pub rules: Vec<RelabelRule>,
+    /// the most distinct series a single node may send within series-limit-interval
+    pub max_series_per_node: Option<usize>,
+    /// the most distinct series of a single metric a node may send within series-limit-interval
+    pub max_series_per_metric: Option<usize>,
+    /// how often we forget the series seen from each node
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "series_limit_interval_default")]
+    pub series_limit_interval: Duration,
+}
+
+impl Default for RelabelConfig {
+    fn default() -> Self {
+        Self {
+            rules: vec![],
+            max_series_per_node: None,
+            max_series_per_metric: None,
+            series_limit_interval: series_limit_interval_default(),
+        }
+    }
+}
+
+/// RelabelRule is a single relabeling step, modeled after prometheus relabel_config actions.
+/// source-labels values are joined with `;` before matching; the metric name can be matched
+/// with the `__name__` label.  Regexes must match the whole joined value.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum RelabelRule {
+    /// drop series whose source labels match regex
+    #[serde(rename_all = "kebab-case")]
+    Drop {
+        source_labels: Vec<String>,
+        regex: String,
+    },
+    /// only keep series whose source labels match regex
+    #[serde(rename_all = "kebab-case")]
+    Keep {
+        source_labels: Vec<String>,
+        regex: String,
+    },
+    /// rename the label from to to, keeping its value
+    #[serde(rename_all = "kebab-case")]
+    Rename { from: String, to: String },
+    /// set target-label to a hash of the source labels, modulo modulus if provided
+    #[serde(rename_all = "kebab-case")]
+    Hash {
+        source_labels: Vec<String>,
+        target_label: String,
+        modulus: Option<u64>,
+    },
+}
+
+/// WriteBufferConfig controls the on-disk queue we spill remote_write requests to while the
+/// remote_write endpoint is unavailable.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WriteBufferConfig {
+    /// directory buffered requests are kept in
+    /// please use an absolute path
+    pub path: PathBuf,
+    /// the most bytes of compressed requests we buffer; the oldest are dropped beyond this
+    #[serde(default = "write_buffer_max_bytes_default")]
+    pub max_bytes: u64,
+    /// how long we wait before retrying remote_write after a failed replay
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "write_buffer_retry_interval_default")]
+    pub retry_interval: Duration,
+}
+
 /// the default idle worker per host (reqwest to remote write url call)
 fn pool_max_idle_per_host_default() -> usize {
     8
 }
 
+/// the default interval after which we forget the series seen from each node
+fn series_limit_interval_default() -> Duration {
+    Duration::from_secs(3600)
+}
+
+/// the default size of the write buffer, 1 GiB
+fn write_buffer_max_bytes_default() -> u64 {
+    1024 * 1024 * 1024
+}
+
+/// the default interval between replays of the write buffer while remote_write is unavailable
+fn write_buffer_retry_interval_default() -> Duration {
+    Duration::from_secs(10)
+}
+
 /// the default hostname we will use if not provided
 fn hostname_default() -> Option<String> {
     Some("localhost".to_string())
@@ -4,6 +4,7 @@
 use crate::admin::ReqwestClient;
 use crate::prom_to_mimir::Mimir;
 use crate::remote_write::WriteRequest;
+use crate::write_buffer::WriteBuffer;
 use anyhow::Result;
 use axum::body::Bytes;
 use axum::http::StatusCode;
@@ -17,7 +18,7 @@ use prometheus::{Counter, CounterVec, HistogramVec};
 use prost::Message;
 use protobuf::CodedInputStream;
 use std::io::Read;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 static CONSUMER_OPS_SUBMITTED: Lazy<Counter> = Lazy::new(|| {
     register_counter!(
@@ -239,6 +240,51 @@ async fn check_response(
     }
 }
 
+/// post a compressed request to remote_write
+pub(crate) async fn post_remote_write(
+    rc: &ReqwestClient,
+    compressed: Bytes,
+) -> reqwest::Result<reqwest::Response> {
+    rc.client
+        .post(rc.settings.url.to_owned())
+        .header(reqwest::header::CONTENT_ENCODING, "snappy")
+        .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .basic_auth(
+            rc.settings.username.to_owned(),
+            Some(rc.settings.password.to_owned()),
+        )
+        .body(compressed)
+        .send()
+        .await
+}
+
+/// is_retryable is true for remote_write responses that mean remote_write is unavailable rather
+/// than that it rejected the request, so sending it again later may succeed
+pub(crate) fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// spill a request remote_write could not accept to the write buffer, to be replayed later
+async fn buffer_request(buffer: &WriteBuffer, request: &WriteRequest, compressed: Bytes) {
+    let samples = request
+        .timeseries
+        .iter()
+        .map(|ts| ts.samples.len() as u64)
+        .sum();
+    match buffer.push(compressed, samples).await {
+        Ok(()) => CONSUMER_OPS
+            .with_label_values(&["buffer_request", "success"])
+            .inc(),
+        Err(error) => {
+            CONSUMER_OPS
+                .with_label_values(&["buffer_request", "failed"])
+                .inc();
+            error!("DROPPING METRICS, unable to buffer them; {error}");
+        }
+    }
+}
+
 async fn convert(
     mfs: Vec<MetricFamily>,
 ) -> Result<impl Iterator<Item = WriteRequest>, (StatusCode, &'static str)> {
@@ -270,6 +316,10 @@ async fn convert(
 /// future optimizations would be to use multiple tcp connections to mimir, within reason. Nevertheless
 /// we await on each post of each metric family so it shouldn't block any other async work in a
 /// significant way.
+///
+/// If the client has a write buffer, requests that can't be delivered because remote_write is
+/// unavailable are buffered instead of dropped, as are all requests while older ones are still
+/// waiting to be replayed, so remote_write receives them in order.
 pub async fn convert_to_remote_write(
     rc: ReqwestClient,
     node_metric: NodeMetric,
@@ -288,28 +338,31 @@ pub async fn convert_to_remote_write(
 
     // a counter so we don't iterate the node data 2x
     let mut mf_cnt = 0;
+    let mut buffered = false;
     for request in remote_write_protos {
         mf_cnt += 1;
-        let compressed = match encode_compress(&request) {
-            Ok(compressed) => compressed,
+        let compressed: Bytes = match encode_compress(&request) {
+            Ok(compressed) => compressed.into(),
             Err(error) => return error,
         };
 
-        let response = match rc
-            .client
-            .post(rc.settings.url.to_owned())
-            .header(reqwest::header::CONTENT_ENCODING, "snappy")
-            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .basic_auth(
-                rc.settings.username.to_owned(),
-                Some(rc.settings.password.to_owned()),
-            )
-            .body(compressed)
-            .send()
-            .await
-        {
+        if let Some(buffer) = rc.buffer.as_ref().filter(|buffer| !buffer.is_empty()) {
+            buffer_request(buffer, &request, compressed).await;
+            buffered = true;
+            continue;
+        }
+
+        let response = match post_remote_write(&rc, compressed.clone()).await {
             Ok(response) => response,
+            Err(error) if rc.buffer.is_some() => {
+                CONSUMER_OPS
+                    .with_label_values(&["check_response", "BUFFERED"])
+                    .inc();
+                warn!("buffering metrics due to post error: {error}");
+                buffer_request(rc.buffer.as_ref().unwrap(), &request, compressed).await;
+                buffered = true;
+                continue;
+            }
             Err(error) => {
                 CONSUMER_OPS
                     .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
@@ -323,6 +376,20 @@ pub async fn convert_to_remote_write(
             }
         };
 
+        if let Some(buffer) = rc
+            .buffer
+            .as_ref()
+            .filter(|_| is_retryable(response.status()))
+        {
+            CONSUMER_OPS
+                .with_label_values(&["check_response", "BUFFERED"])
+                .inc();
+            warn!("({}) buffering metrics for retry", response.status());
+            buffer_request(buffer, &request, compressed).await;
+            buffered = true;
+            continue;
+        }
+
         match check_response(request, response).await {
             Ok(_) => (),
             Err(err) => {
@@ -333,19 +400,35 @@ pub async fn convert_to_remote_write(
     }
     CONSUMER_OPS_SUBMITTED.inc_by(mf_cnt as f64);
     timer.observe_duration();
+    if buffered {
+        return (StatusCode::ACCEPTED, "buffered metrics for retry");
+    }
     (StatusCode::CREATED, "created")
 }
 
 #[cfg(test)]
 mod tests {
+    use axum::extract::State;
+    use axum::routing::post;
+    use axum::Router;
+    use multiaddr::Multiaddr;
     use prometheus::proto;
+    use prost::Message;
     use protobuf;
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use crate::{
-        consumer::populate_labels,
+        admin::{generate_self_cert, ReqwestClient},
+        config::{RemoteWriteConfig, WriteBufferConfig},
+        consumer::{convert_to_remote_write, populate_labels, NodeMetric},
         prom_to_mimir::tests::{
-            create_histogram, create_labels, create_metric_family, create_metric_histogram,
+            create_counter, create_histogram, create_labels, create_metric_counter,
+            create_metric_family, create_metric_histogram,
         },
+        remote_write::WriteRequest,
+        write_buffer::WriteBuffer,
     };
 
     #[test]
@@ -375,4 +458,135 @@ mod tests {
             ])
         );
     }
+
+    /// a remote_write that answers with `status`, recording the metric names it accepted in order
+    #[derive(Clone, Default)]
+    struct MockRemoteWrite {
+        status: Arc<AtomicU16>,
+        received: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockRemoteWrite {
+        fn set_status(&self, status: reqwest::StatusCode) {
+            self.status.store(status.as_u16(), Ordering::SeqCst);
+        }
+
+        fn received(&self) -> Vec<String> {
+            self.received.lock().unwrap().clone()
+        }
+
+        async fn serve(self, listener: std::net::TcpListener) {
+            async fn handler(
+                State(mock): State<MockRemoteWrite>,
+                body: axum::body::Bytes,
+            ) -> axum::http::StatusCode {
+                let status =
+                    axum::http::StatusCode::from_u16(mock.status.load(Ordering::SeqCst)).unwrap();
+                if status != axum::http::StatusCode::OK {
+                    return status;
+                }
+                let decompressed = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+                let request = WriteRequest::decode(decompressed.as_slice()).unwrap();
+                let mut received = mock.received.lock().unwrap();
+                for ts in request.timeseries {
+                    let name = ts
+                        .labels
+                        .into_iter()
+                        .find(|l| l.name == "__name__")
+                        .unwrap();
+                    received.push(name.value);
+                }
+                status
+            }
+
+            let app = Router::new()
+                .route("/v1/push", post(handler))
+                .with_state(self);
+            listener.set_nonblocking(true).unwrap();
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            axum::serve(listener, app).await.unwrap();
+        }
+    }
+
+    fn node_metric(name: &str) -> NodeMetric {
+        NodeMetric {
+            peer_addr: Multiaddr::empty(),
+            public_key: generate_self_cert("sui".into()).1,
+            data: vec![create_metric_family(
+                name,
+                "some help this is",
+                None,
+                protobuf::RepeatedField::from_vec(vec![create_metric_counter(
+                    protobuf::RepeatedField::from_vec(create_labels(vec![("some", "label")])),
+                    create_counter(2046.0),
+                )]),
+            )],
+        }
+    }
+
+    async fn wait_for_replay(buffer: &WriteBuffer, mock: &MockRemoteWrite, expected: &[&str]) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !buffer.is_empty() || mock.received() != expected {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("expected {expected:?}, received {:?}", mock.received()));
+    }
+
+    #[tokio::test]
+    async fn requests_are_buffered_and_replayed_in_order_across_an_outage() {
+        // reserve a port, nothing listens on it until remote_write comes back up
+        let listener = std::net::TcpListener::bind("localhost:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = WriteBuffer::open(WriteBufferConfig {
+            path: dir.path().to_path_buf(),
+            max_bytes: 1024 * 1024,
+            retry_interval: Duration::from_millis(10),
+        })
+        .unwrap();
+        let rc = ReqwestClient {
+            client: reqwest::Client::new(),
+            settings: RemoteWriteConfig {
+                url: format!("http://localhost:{port}/v1/push"),
+                username: "bar".into(),
+                password: "foo".into(),
+                ..Default::default()
+            },
+            buffer: Some(buffer.clone()),
+        };
+
+        // connection errors are buffered
+        let (status, _) = convert_to_remote_write(rc.clone(), node_metric("a")).await;
+        assert_eq!(status, axum::http::StatusCode::ACCEPTED);
+        assert!(!buffer.is_empty());
+
+        // remote_write is back but failing, new requests queue behind the buffered ones
+        let mock = MockRemoteWrite::default();
+        mock.set_status(reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let listener = std::net::TcpListener::bind(("localhost", port)).unwrap();
+        tokio::spawn(mock.clone().serve(listener));
+        tokio::spawn(buffer.clone().replay(rc.clone()));
+        let (status, _) = convert_to_remote_write(rc.clone(), node_metric("b")).await;
+        assert_eq!(status, axum::http::StatusCode::ACCEPTED);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(mock.received().is_empty());
+
+        // once it recovers, everything is replayed in the order it was received
+        mock.set_status(reqwest::StatusCode::OK);
+        wait_for_replay(&buffer, &mock, &["a", "b"]).await;
+        let (status, _) = convert_to_remote_write(rc.clone(), node_metric("c")).await;
+        assert_eq!(status, axum::http::StatusCode::CREATED);
+        assert_eq!(mock.received(), ["a", "b", "c"]);
+
+        // 5xx responses are buffered and replayed too
+        mock.set_status(reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let (status, _) = convert_to_remote_write(rc.clone(), node_metric("d")).await;
+        assert_eq!(status, axum::http::StatusCode::ACCEPTED);
+        mock.set_status(reqwest::StatusCode::OK);
+        wait_for_replay(&buffer, &mock, &["a", "b", "c", "d"]).await;
+    }
 }
@@ -5,6 +5,7 @@ use crate::consumer::{convert_to_remote_write, populate_labels, NodeMetric};
 use crate::histogram_relay::HistogramRelay;
 use crate::middleware::LenDelimProtobuf;
 use crate::peers::SuiPeer;
+use crate::relabel::Relabeler;
 use axum::{
     extract::{ConnectInfo, Extension},
     http::StatusCode,
@@ -49,6 +50,7 @@ pub async fn publish_metrics(
         name, public_key, ..
     }): Extension<SuiPeer>,
     Extension(relay): Extension<HistogramRelay>,
+    Extension(relabeler): Extension<Relabeler>,
     LenDelimProtobuf(data): LenDelimProtobuf,
 ) -> (StatusCode, &'static str) {
     HANDLER_HITS
@@ -57,7 +59,15 @@ pub async fn publish_metrics(
     let timer = HTTP_HANDLER_DURATION
         .with_label_values(&["publish_metrics", &name])
         .start_timer();
-    let data = populate_labels(name, labels.network, labels.inventory_hostname, data);
+    let data = relabeler.apply(
+        &name,
+        populate_labels(
+            name.clone(),
+            labels.network,
+            labels.inventory_hostname,
+            data,
+        ),
+    );
     relay.submit(data.clone());
     let response = convert_to_remote_write(
         client.clone(),
@@ -10,7 +10,9 @@ pub mod metrics;
 pub mod middleware;
 pub mod peers;
 pub mod prom_to_mimir;
+pub mod relabel;
 pub mod remote_write;
+pub mod write_buffer;
 
 /// var extracts environment variables at runtime with a default fallback value
 /// if a default is not provided, the value is simply an empty string if not found
@@ -38,6 +40,7 @@ mod tests {
     use crate::admin::Labels;
     use crate::histogram_relay::HistogramRelay;
     use crate::prom_to_mimir::tests::*;
+    use crate::relabel::Relabeler;
 
     use crate::{admin::CertKeyPair, config::RemoteWriteConfig, peers::SuiNodeProvider};
     use axum::http::StatusCode;
@@ -116,6 +119,7 @@ mod tests {
             },
             client,
             HistogramRelay::new(),
+            Relabeler::default(),
             Some(allower.clone()),
         );
 
@@ -11,6 +11,8 @@ use sui_proxy::{
     },
     config::load,
     histogram_relay, metrics,
+    relabel::Relabeler,
+    write_buffer::WriteBuffer,
 };
 use sui_tls::TlsAcceptor;
 use telemetry_subscribers::TelemetryConfig;
@@ -70,10 +72,16 @@ async fn main() -> Result<()> {
             create_server_cert_enforce_peer(config.dynamic_peers, config.static_peers)
                 .expect("unable to create tls server config")
         };
+    let relabeler = Relabeler::new(config.relabel).expect("invalid relabel config");
     let histogram_listener = std::net::TcpListener::bind(config.histogram_address).unwrap();
     let metrics_listener = std::net::TcpListener::bind(config.metrics_address).unwrap();
     let acceptor = TlsAcceptor::new(tls_config);
-    let client = make_reqwest_client(config.remote_write, APP_USER_AGENT);
+    let mut client = make_reqwest_client(config.remote_write, APP_USER_AGENT);
+    if let Some(write_buffer) = config.write_buffer {
+        let buffer = WriteBuffer::open(write_buffer).expect("unable to open write buffer");
+        tokio::spawn(buffer.clone().replay(client.clone()));
+        client = client.with_write_buffer(buffer);
+    }
     let histogram_relay = histogram_relay::start_prometheus_server(histogram_listener);
     let registry_service = metrics::start_prometheus_server(metrics_listener);
     let prometheus_registry = registry_service.default_registry();
@@ -92,6 +100,7 @@ async fn main() -> Result<()> {
         },
         client,
         histogram_relay,
+        relabeler,
         allower,
     );
 
@@ -0,0 +1,468 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::config::{RelabelConfig, RelabelRule};
+use anyhow::{Context, Result};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use once_cell::sync::Lazy;
+use prometheus::proto::{self, MetricFamily};
+use prometheus::{register_counter_vec, register_histogram_vec};
+use prometheus::{CounterVec, HistogramVec};
+use protobuf::RepeatedField;
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// the label prometheus uses for the metric name, we use it to match on metric family names
+const METRIC_NAME_LABEL: &str = "__name__";
+/// the separator we join source label values with before matching or hashing them
+const SOURCE_LABELS_SEPARATOR: &str = ";";
+
+static RELABEL_DROPPED_SERIES: Lazy<CounterVec> = Lazy::new(|| {
+    register_counter_vec!(
+        "relabel_dropped_series",
+        "Number of series dropped before remote write, by relabel rules or series limits.",
+        &["reason", "host"]
+    )
+    .unwrap()
+});
+static RELABEL_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "relabel_duration_seconds",
+        "The time it takes to relabel and limit the metrics of a single push in seconds.",
+        &["operation"],
+        vec![
+            1e-06, 2e-06, 4e-06, 8e-06, 1.6e-05, 3.2e-05, 6.4e-05, 0.000128, 0.000256, 0.000512,
+            0.001024, 0.002048, 0.004096, 0.008192, 0.016384
+        ],
+    )
+    .unwrap()
+});
+
+/// a relabel rule with its regex compiled
+enum Rule {
+    Drop {
+        source_labels: Vec<String>,
+        regex: Regex,
+    },
+    Keep {
+        source_labels: Vec<String>,
+        regex: Regex,
+    },
+    Rename {
+        from: String,
+        to: String,
+    },
+    Hash {
+        source_labels: Vec<String>,
+        target_label: String,
+        modulus: Option<u64>,
+    },
+}
+
+/// the distinct series we have accepted from a node since `since`
+struct NodeSeries {
+    since: Instant,
+    series: HashSet<u64>,
+    series_per_metric: HashMap<String, usize>,
+}
+
+impl NodeSeries {
+    fn new() -> Self {
+        Self {
+            since: Instant::now(),
+            series: HashSet::new(),
+            series_per_metric: HashMap::new(),
+        }
+    }
+}
+
+/// Relabeler applies our relabel rules and per node series limits to the metrics pushed by nodes,
+/// before they are relayed to remote write.  Series already seen from a node within the limit
+/// interval are always accepted, so a node over its limit keeps reporting its existing series.
+#[derive(Clone)]
+pub struct Relabeler {
+    rules: Arc<Vec<Rule>>,
+    max_series_per_node: Option<usize>,
+    max_series_per_metric: Option<usize>,
+    series_limit_interval: Duration,
+    nodes: Arc<Mutex<HashMap<String, NodeSeries>>>,
+}
+
+impl Default for Relabeler {
+    fn default() -> Self {
+        Self::new(RelabelConfig::default()).expect("the default relabel config is valid")
+    }
+}
+
+impl Relabeler {
+    pub fn new(config: RelabelConfig) -> Result<Self> {
+        let rules = config
+            .rules
+            .into_iter()
+            .map(|rule| {
+                Ok(match rule {
+                    RelabelRule::Drop {
+                        source_labels,
+                        regex,
+                    } => Rule::Drop {
+                        source_labels,
+                        regex: anchored_regex(&regex)?,
+                    },
+                    RelabelRule::Keep {
+                        source_labels,
+                        regex,
+                    } => Rule::Keep {
+                        source_labels,
+                        regex: anchored_regex(&regex)?,
+                    },
+                    RelabelRule::Rename { from, to } => Rule::Rename { from, to },
+                    RelabelRule::Hash {
+                        source_labels,
+                        target_label,
+                        modulus,
+                    } => {
+                        anyhow::ensure!(modulus != Some(0), "hash modulus cannot be 0");
+                        Rule::Hash {
+                            source_labels,
+                            target_label,
+                            modulus,
+                        }
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            rules: Arc::new(rules),
+            max_series_per_node: config.max_series_per_node,
+            max_series_per_metric: config.max_series_per_metric,
+            series_limit_interval: config.series_limit_interval,
+            nodes: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// apply our relabel rules and the series limits of `host` to the metric families it pushed
+    pub fn apply(&self, host: &str, data: Vec<MetricFamily>) -> Vec<MetricFamily> {
+        let timer = RELABEL_DURATION.with_label_values(&["apply"]).start_timer();
+        let mut nodes = self.nodes.lock().unwrap();
+        let node = nodes.entry(host.to_owned()).or_insert_with(NodeSeries::new);
+        if node.since.elapsed() >= self.series_limit_interval {
+            *node = NodeSeries::new();
+        }
+
+        let mut relabeled = 0;
+        let mut limited = 0;
+        let data = data
+            .into_iter()
+            .filter_map(|mut mf| {
+                let name = mf.get_name().to_owned();
+                let metrics: Vec<proto::Metric> = mf
+                    .take_metric()
+                    .into_iter()
+                    .filter_map(|mut m| {
+                        if !self.relabel(&name, &mut m) {
+                            relabeled += 1;
+                            return None;
+                        }
+                        if !self.admit(node, &name, &m) {
+                            limited += 1;
+                            return None;
+                        }
+                        Some(m)
+                    })
+                    .collect();
+                if metrics.is_empty() {
+                    return None;
+                }
+                mf.set_metric(RepeatedField::from_vec(metrics));
+                Some(mf)
+            })
+            .collect();
+
+        RELABEL_DROPPED_SERIES
+            .with_label_values(&["relabel", host])
+            .inc_by(relabeled as f64);
+        RELABEL_DROPPED_SERIES
+            .with_label_values(&["series_limit", host])
+            .inc_by(limited as f64);
+        timer.observe_duration();
+        data
+    }
+
+    /// apply our rules to a single series, returns false if the series should be dropped
+    fn relabel(&self, name: &str, m: &mut proto::Metric) -> bool {
+        for rule in self.rules.iter() {
+            match rule {
+                Rule::Drop {
+                    source_labels,
+                    regex,
+                } => {
+                    if regex.is_match(&source_value(name, m, source_labels)) {
+                        return false;
+                    }
+                }
+                Rule::Keep {
+                    source_labels,
+                    regex,
+                } => {
+                    if !regex.is_match(&source_value(name, m, source_labels)) {
+                        return false;
+                    }
+                }
+                Rule::Rename { from, to } => {
+                    let labels = m.mut_label();
+                    if let Some(position) = labels.iter().position(|l| l.get_name() == from) {
+                        // the renamed label replaces any label that already has its new name
+                        let mut label = labels.remove(position);
+                        labels.retain(|l| l.get_name() != to);
+                        label.set_name(to.to_owned());
+                        labels.push(label);
+                    }
+                }
+                Rule::Hash {
+                    source_labels,
+                    target_label,
+                    modulus,
+                } => {
+                    let digest = Blake2b256::digest(source_value(name, m, source_labels));
+                    let value = match modulus {
+                        Some(modulus) => {
+                            let mut bytes = [0u8; 8];
+                            bytes.copy_from_slice(&digest.as_ref()[..8]);
+                            (u64::from_be_bytes(bytes) % modulus).to_string()
+                        }
+                        None => hex::encode(digest),
+                    };
+                    set_label(m, target_label, value);
+                }
+            }
+        }
+        true
+    }
+
+    /// count a series against the limits of its node, returns false if it is over them
+    fn admit(&self, node: &mut NodeSeries, name: &str, m: &proto::Metric) -> bool {
+        if self.max_series_per_node.is_none() && self.max_series_per_metric.is_none() {
+            return true;
+        }
+        let key = series_key(name, m);
+        if node.series.contains(&key) {
+            return true;
+        }
+        if matches!(self.max_series_per_node, Some(max) if node.series.len() >= max) {
+            return false;
+        }
+        let series_for_metric = node.series_per_metric.entry(name.to_owned()).or_default();
+        if matches!(self.max_series_per_metric, Some(max) if *series_for_metric >= max) {
+            return false;
+        }
+        *series_for_metric += 1;
+        node.series.insert(key);
+        true
+    }
+}
+
+/// compile a regex that, like prometheus, must match the whole value
+fn anchored_regex(regex: &str) -> Result<Regex> {
+    Regex::new(&format!("^(?:{regex})$")).context(format!("invalid relabel regex {regex:?}"))
+}
+
+/// the values of `source_labels` on a series joined together, missing labels are empty
+fn source_value(name: &str, m: &proto::Metric, source_labels: &[String]) -> String {
+    source_labels
+        .iter()
+        .map(|source| {
+            if source == METRIC_NAME_LABEL {
+                return name;
+            }
+            m.get_label()
+                .iter()
+                .find(|l| l.get_name() == source)
+                .map(|l| l.get_value())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(SOURCE_LABELS_SEPARATOR)
+}
+
+fn set_label(m: &mut proto::Metric, name: &str, value: String) {
+    let labels = m.mut_label();
+    match labels.iter_mut().find(|l| l.get_name() == name) {
+        Some(label) => label.set_value(value),
+        None => {
+            let mut label = proto::LabelPair::default();
+            label.set_name(name.to_owned());
+            label.set_value(value);
+            labels.push(label);
+        }
+    }
+}
+
+/// identifies a series by its metric name and label set, regardless of label order
+fn series_key(name: &str, m: &proto::Metric) -> u64 {
+    let mut labels: Vec<_> = m
+        .get_label()
+        .iter()
+        .map(|l| (l.get_name(), l.get_value()))
+        .collect();
+    labels.sort();
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    labels.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prom_to_mimir::tests::{
+        create_counter, create_labels, create_metric_counter, create_metric_family,
+    };
+
+    fn counter_family(name: &str, series: Vec<Vec<(&str, &str)>>) -> MetricFamily {
+        create_metric_family(
+            name,
+            "some help this is",
+            Some(proto::MetricType::COUNTER),
+            RepeatedField::from_vec(
+                series
+                    .into_iter()
+                    .map(|labels| {
+                        create_metric_counter(
+                            RepeatedField::from_vec(create_labels(labels)),
+                            create_counter(1.0),
+                        )
+                    })
+                    .collect(),
+            ),
+        )
+    }
+
+    fn relabeler(rules: &str) -> Relabeler {
+        Relabeler::new(RelabelConfig {
+            rules: serde_yaml::from_str(rules).unwrap(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn drop_and_keep() {
+        let relabeler = relabeler(
+            r#"
+- action: drop
+  source-labels: [__name__]
+  regex: tokio_.*
+- action: keep
+  source-labels: [__name__, kind]
+  regex: .*;(read|write)
+"#,
+        );
+        let data = relabeler.apply(
+            "validator-0",
+            vec![
+                counter_family("tokio_tasks", vec![vec![("kind", "read")]]),
+                counter_family(
+                    "requests",
+                    vec![vec![("kind", "read")], vec![("kind", "delete")], vec![]],
+                ),
+            ],
+        );
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].get_name(), "requests");
+        assert_eq!(data[0].get_metric().len(), 1);
+        assert_eq!(
+            data[0].get_metric()[0].get_label(),
+            &create_labels(vec![("kind", "read")])
+        );
+    }
+
+    #[test]
+    fn rename_and_hash() {
+        let relabeler = relabeler(
+            r#"
+- action: rename
+  from: peer
+  to: peer_id
+- action: hash
+  source-labels: [peer_id]
+  target-label: peer_bucket
+  modulus: 8
+"#,
+        );
+        let data = relabeler.apply(
+            "validator-0",
+            vec![counter_family(
+                "connections",
+                vec![vec![("peer", "abcd"), ("peer_id", "stale")]],
+            )],
+        );
+        let labels = data[0].get_metric()[0].get_label();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].get_name(), "peer_id");
+        assert_eq!(labels[0].get_value(), "abcd");
+        assert_eq!(labels[1].get_name(), "peer_bucket");
+        let bucket: u64 = labels[1].get_value().parse().unwrap();
+        assert!(bucket < 8);
+
+        // the same source values always hash to the same bucket
+        let again = relabeler.apply(
+            "validator-1",
+            vec![counter_family("connections", vec![vec![("peer", "abcd")]])],
+        );
+        assert_eq!(again[0].get_metric()[0].get_label()[1], labels[1]);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let config = |rules: &str| RelabelConfig {
+            rules: serde_yaml::from_str(rules).unwrap(),
+            ..Default::default()
+        };
+        assert!(Relabeler::new(config(
+            "[{action: drop, source-labels: [__name__], regex: '('}]"
+        ))
+        .is_err());
+        assert!(Relabeler::new(config(
+            "[{action: hash, source-labels: [a], target-label: b, modulus: 0}]"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn series_limits() {
+        let relabeler = Relabeler::new(RelabelConfig {
+            max_series_per_node: Some(3),
+            max_series_per_metric: Some(2),
+            ..Default::default()
+        })
+        .unwrap();
+        let push = || {
+            vec![
+                counter_family(
+                    "a",
+                    vec![vec![("i", "0")], vec![("i", "1")], vec![("i", "2")]],
+                ),
+                counter_family("b", vec![vec![("i", "0")], vec![("i", "1")]]),
+            ]
+        };
+
+        // a is limited to 2 series, and the node to 3 overall
+        let data = relabeler.apply("validator-0", push());
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].get_metric().len(), 2);
+        assert_eq!(data[1].get_metric().len(), 1);
+
+        // series we have already accepted keep being accepted
+        let data = relabeler.apply("validator-0", push());
+        assert_eq!(data[0].get_metric().len(), 2);
+        assert_eq!(data[1].get_metric().len(), 1);
+
+        // other nodes have their own limits
+        let data = relabeler.apply("validator-1", push());
+        assert_eq!(data[0].get_metric().len(), 2);
+        assert_eq!(data[1].get_metric().len(), 1);
+    }
+}
@@ -0,0 +1,402 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::admin::ReqwestClient;
+use crate::config::WriteBufferConfig;
+use crate::consumer::{is_retryable, post_remote_write};
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use once_cell::sync::Lazy;
+use prometheus::{register_counter, register_counter_vec, register_int_gauge};
+use prometheus::{Counter, CounterVec, IntGauge};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+
+static WRITE_BUFFER_DEPTH: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "write_buffer_depth",
+        "Number of remote_write requests buffered on disk waiting to be replayed.",
+    )
+    .unwrap()
+});
+static WRITE_BUFFER_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "write_buffer_bytes",
+        "Size in bytes of the compressed remote_write requests buffered on disk.",
+    )
+    .unwrap()
+});
+static WRITE_BUFFER_DROPPED_SAMPLES: Lazy<Counter> = Lazy::new(|| {
+    register_counter!(
+        "write_buffer_dropped_samples",
+        "Number of buffered samples dropped, because the buffer was full or remote_write rejected them.",
+    )
+    .unwrap()
+});
+static WRITE_BUFFER_OPS: Lazy<CounterVec> = Lazy::new(|| {
+    register_counter_vec!(
+        "write_buffer_operations",
+        "Operations counters and status from operations performed on the write buffer.",
+        &["operation", "status"]
+    )
+    .unwrap()
+});
+
+/// a buffered request, stored in a file named `{seq}-{samples}` so we can account for its
+/// samples without decoding it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    seq: u64,
+    samples: u64,
+    bytes: u64,
+}
+
+impl Entry {
+    fn file_name(&self) -> String {
+        format!("{:020}-{}", self.seq, self.samples)
+    }
+
+    fn parse(path: &Path, bytes: u64) -> Option<Self> {
+        let (seq, samples) = path.file_name()?.to_str()?.split_once('-')?;
+        Some(Self {
+            seq: seq.parse().ok()?,
+            samples: samples.parse().ok()?,
+            bytes,
+        })
+    }
+}
+
+struct State {
+    entries: VecDeque<Entry>,
+    total_bytes: u64,
+    next_seq: u64,
+    /// pushes that have not buffered their request yet, which new requests must still queue behind
+    pending: usize,
+}
+
+/// WriteBuffer is a bounded on-disk queue of compressed remote_write requests that could not be
+/// delivered.  Requests are replayed in the order they were buffered; once the buffer is full the
+/// oldest requests are dropped, as they are the most likely to be rejected as too old anyway.
+#[derive(Clone)]
+pub struct WriteBuffer {
+    path: PathBuf,
+    max_bytes: u64,
+    retry_interval: Duration,
+    /// only held to update the in-memory state, never across file IO
+    state: Arc<Mutex<State>>,
+    /// serializes pushes across their file IO, so requests are buffered in order
+    writer: Arc<Mutex<()>>,
+    notify: Arc<Notify>,
+}
+
+impl WriteBuffer {
+    /// open the buffer at the configured path, picking up requests buffered before a restart
+    pub fn open(config: WriteBufferConfig) -> Result<Self> {
+        fs::create_dir_all(&config.path)
+            .context(format!("cannot create write buffer dir {:?}", config.path))?;
+        let mut entries = vec![];
+        for dir_entry in fs::read_dir(&config.path)? {
+            let dir_entry = dir_entry?;
+            let path = dir_entry.path();
+            if path.extension().is_some_and(|ext| ext == "tmp") {
+                // a request we crashed while writing, it was never acknowledged as buffered
+                fs::remove_file(&path)?;
+                continue;
+            }
+            match Entry::parse(&path, dir_entry.metadata()?.len()) {
+                Some(entry) => entries.push(entry),
+                None => warn!("ignoring unexpected file in write buffer: {:?}", path),
+            }
+        }
+        entries.sort_by_key(|entry| entry.seq);
+
+        let state = State {
+            total_bytes: entries.iter().map(|entry| entry.bytes).sum(),
+            next_seq: entries.last().map_or(0, |entry| entry.seq + 1),
+            entries: entries.into(),
+            pending: 0,
+        };
+        info!(
+            "opened write buffer at {:?} with {} buffered requests",
+            config.path,
+            state.entries.len()
+        );
+        set_gauges(&state);
+        Ok(Self {
+            path: config.path,
+            max_bytes: config.max_bytes,
+            retry_interval: config.retry_interval,
+            state: Arc::new(Mutex::new(state)),
+            writer: Arc::new(Mutex::new(())),
+            notify: Arc::new(Notify::new()),
+        })
+    }
+
+    /// true if nothing is waiting to be replayed. new requests must be buffered behind any that
+    /// are, including those still being written, so remote_write receives samples in order
+    pub fn is_empty(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.entries.is_empty() && state.pending == 0
+    }
+
+    /// buffer a compressed request holding `samples` samples, dropping the oldest requests if
+    /// needed to stay within max_bytes.  The file IO runs on a blocking thread, outside of the
+    /// state lock, so replaying and checking for buffered requests never wait on the disk.
+    pub async fn push(&self, compressed: Bytes, samples: u64) -> Result<()> {
+        let buffer = self.clone();
+        tokio::task::spawn_blocking(move || buffer.push_blocking(&compressed, samples)).await?
+    }
+
+    fn push_blocking(&self, compressed: &[u8], samples: u64) -> Result<()> {
+        let bytes = compressed.len() as u64;
+        if bytes > self.max_bytes {
+            WRITE_BUFFER_DROPPED_SAMPLES.inc_by(samples as f64);
+            WRITE_BUFFER_OPS
+                .with_label_values(&["push", "failed"])
+                .inc();
+            anyhow::bail!("request of {} bytes is larger than the write buffer", bytes);
+        }
+        self.state.lock().unwrap().pending += 1;
+        let _writer = self.writer.lock().unwrap();
+
+        let (entry, evicted) = {
+            let mut state = self.state.lock().unwrap();
+            let mut evicted = vec![];
+            while state.total_bytes + bytes > self.max_bytes {
+                let Some(oldest) = state.entries.pop_front() else {
+                    break;
+                };
+                state.total_bytes -= oldest.bytes;
+                evicted.push(oldest);
+            }
+            let entry = Entry {
+                seq: state.next_seq,
+                samples,
+                bytes,
+            };
+            state.next_seq += 1;
+            (entry, evicted)
+        };
+        for oldest in evicted {
+            self.remove_file(oldest);
+            WRITE_BUFFER_DROPPED_SAMPLES.inc_by(oldest.samples as f64);
+            WRITE_BUFFER_OPS
+                .with_label_values(&["push", "evicted"])
+                .inc();
+        }
+
+        // write then rename, so a crash never leaves a partial request behind
+        let path = self.path.join(entry.file_name());
+        let tmp_path = path.with_extension("tmp");
+        let written = fs::write(&tmp_path, compressed).and_then(|_| fs::rename(&tmp_path, &path));
+
+        let mut state = self.state.lock().unwrap();
+        state.pending -= 1;
+        if let Err(error) = written {
+            set_gauges(&state);
+            WRITE_BUFFER_DROPPED_SAMPLES.inc_by(samples as f64);
+            WRITE_BUFFER_OPS
+                .with_label_values(&["push", "failed"])
+                .inc();
+            return Err(error.into());
+        }
+        state.total_bytes += entry.bytes;
+        state.entries.push_back(entry);
+        set_gauges(&state);
+        drop(state);
+        WRITE_BUFFER_OPS
+            .with_label_values(&["push", "success"])
+            .inc();
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    /// the oldest buffered request and its contents
+    async fn front(&self) -> Result<Option<(Entry, Bytes)>> {
+        let buffer = self.clone();
+        tokio::task::spawn_blocking(move || buffer.front_blocking()).await?
+    }
+
+    fn front_blocking(&self) -> Result<Option<(Entry, Bytes)>> {
+        loop {
+            let Some(entry) = self.state.lock().unwrap().entries.front().copied() else {
+                return Ok(None);
+            };
+            match fs::read(self.path.join(entry.file_name())) {
+                Ok(compressed) => return Ok(Some((entry, compressed.into()))),
+                // evicted since we looked at the front, move on to the next oldest request
+                Err(error)
+                    if error.kind() == std::io::ErrorKind::NotFound
+                        && self.state.lock().unwrap().entries.front() != Some(&entry) =>
+                {
+                    continue
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// remove `entry` once it has been replayed, unless it was evicted in the meantime
+    async fn pop(&self, entry: Entry) -> Result<()> {
+        let buffer = self.clone();
+        tokio::task::spawn_blocking(move || buffer.pop_blocking(entry)).await?
+    }
+
+    fn pop_blocking(&self, entry: Entry) -> Result<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.entries.front() != Some(&entry) {
+                return Ok(());
+            }
+            state.entries.pop_front();
+            state.total_bytes -= entry.bytes;
+            set_gauges(&state);
+        }
+        fs::remove_file(self.path.join(entry.file_name()))?;
+        Ok(())
+    }
+
+    /// delete the file of an evicted request, which is no longer part of the buffer either way
+    fn remove_file(&self, entry: Entry) {
+        if let Err(error) = fs::remove_file(self.path.join(entry.file_name())) {
+            error!("unable to remove evicted request from write buffer; {error}");
+        }
+    }
+
+    /// replay buffered requests to remote_write in order, waiting retry_interval after each
+    /// failure.  This runs for the life of the proxy.
+    pub async fn replay(self, rc: ReqwestClient) {
+        loop {
+            let (entry, compressed) = match self.front().await {
+                Ok(Some(front)) => front,
+                Ok(None) => {
+                    self.notify.notified().await;
+                    continue;
+                }
+                Err(error) => {
+                    error!("unable to read from write buffer; {error}");
+                    tokio::time::sleep(self.retry_interval).await;
+                    continue;
+                }
+            };
+
+            let delivered = match post_remote_write(&rc, compressed).await {
+                Ok(response) if response.status().is_success() => true,
+                Ok(response) if is_retryable(response.status()) => {
+                    warn!(
+                        "remote_write still unavailable ({}), retrying buffered requests",
+                        response.status()
+                    );
+                    false
+                }
+                Ok(response) => {
+                    // retrying won't help a request remote_write rejects, eg out of order samples
+                    error!(
+                        "({}) DROPPING buffered metrics rejected by remote_write",
+                        response.status()
+                    );
+                    WRITE_BUFFER_DROPPED_SAMPLES.inc_by(entry.samples as f64);
+                    true
+                }
+                Err(error) => {
+                    warn!("remote_write still unavailable ({error}), retrying buffered requests");
+                    false
+                }
+            };
+
+            if !delivered {
+                WRITE_BUFFER_OPS
+                    .with_label_values(&["replay", "failed"])
+                    .inc();
+                tokio::time::sleep(self.retry_interval).await;
+                continue;
+            }
+            WRITE_BUFFER_OPS
+                .with_label_values(&["replay", "success"])
+                .inc();
+            if let Err(error) = self.pop(entry).await {
+                error!("unable to remove replayed request from write buffer; {error}");
+                tokio::time::sleep(self.retry_interval).await;
+            }
+        }
+    }
+}
+
+fn set_gauges(state: &State) {
+    WRITE_BUFFER_DEPTH.set(state.entries.len() as i64);
+    WRITE_BUFFER_BYTES.set(state.total_bytes as i64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(path: &Path, max_bytes: u64) -> WriteBuffer {
+        WriteBuffer::open(WriteBufferConfig {
+            path: path.to_path_buf(),
+            max_bytes,
+            retry_interval: Duration::from_millis(10),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn requests_are_kept_in_order_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = open(dir.path(), 1024);
+        assert!(buffer.is_empty());
+        buffer.push_blocking(b"first", 1).unwrap();
+        buffer.push_blocking(b"second", 2).unwrap();
+
+        let buffer = open(dir.path(), 1024);
+        let (entry, compressed) = buffer.front_blocking().unwrap().unwrap();
+        assert_eq!(&compressed[..], b"first");
+        assert_eq!(entry.samples, 1);
+        buffer.pop_blocking(entry).unwrap();
+
+        buffer.push_blocking(b"third", 3).unwrap();
+        let (entry, compressed) = buffer.front_blocking().unwrap().unwrap();
+        assert_eq!(&compressed[..], b"second");
+        buffer.pop_blocking(entry).unwrap();
+        let (entry, compressed) = buffer.front_blocking().unwrap().unwrap();
+        assert_eq!(&compressed[..], b"third");
+        buffer.pop_blocking(entry).unwrap();
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn oldest_requests_are_dropped_when_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = open(dir.path(), 10);
+        buffer.push_blocking(b"aaaa", 1).unwrap();
+        buffer.push_blocking(b"bbbb", 1).unwrap();
+        buffer.push_blocking(b"cccc", 1).unwrap();
+
+        let (entry, compressed) = buffer.front_blocking().unwrap().unwrap();
+        assert_eq!(&compressed[..], b"bbbb");
+        assert_eq!(buffer.state.lock().unwrap().total_bytes, 8);
+
+        // a request larger than the whole buffer is refused outright
+        assert!(buffer.push_blocking(b"this is too large", 1).is_err());
+        assert_eq!(buffer.front_blocking().unwrap().unwrap().0, entry);
+    }
+
+    #[test]
+    fn buffered_requests_are_readable_while_a_push_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = open(dir.path(), 1024);
+        buffer.push_blocking(b"first", 1).unwrap();
+
+        // a push writing its file holds the writer lock, and nothing else
+        let _writing = buffer.writer.lock().unwrap();
+        assert!(!buffer.is_empty());
+        let (entry, compressed) = buffer.front_blocking().unwrap().unwrap();
+        assert_eq!(&compressed[..], b"first");
+        buffer.pop_blocking(entry).unwrap();
+        assert!(buffer.is_empty());
+    }
+}
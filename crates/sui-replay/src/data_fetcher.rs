@@ -1,17 +1,28 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::replay::{ExecutionSandboxState, ProtocolVersionSummary};
 use crate::types::ReplayEngineError;
 use crate::types::EPOCH_CHANGE_STRUCT_TAG;
 use async_trait::async_trait;
 use futures::future::join_all;
+use futures::future::AbortHandle;
+use indicatif::MultiProgress;
 use lru::LruCache;
 use move_core_types::parser::parse_struct_tag;
 use parking_lot::RwLock;
 use rand::Rng;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
+use sui_config::node::ArchiveReaderConfig;
+use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::NodeStateDump;
 use sui_json_rpc_api::QUERY_MAX_RESULT_LIMIT;
 use sui_json_rpc_types::EventFilter;
@@ -21,15 +32,25 @@ use sui_json_rpc_types::SuiObjectData;
 use sui_json_rpc_types::SuiObjectDataOptions;
 use sui_json_rpc_types::SuiObjectResponse;
 use sui_json_rpc_types::SuiPastObjectResponse;
+use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::SuiClient;
-use sui_types::base_types::{ObjectID, SequenceNumber, VersionNumber};
-use sui_types::digests::TransactionDigest;
+use sui_snapshot::reader::StateSnapshotReaderV1;
+use sui_types::base_types::{EpochId, ObjectID, SequenceNumber, VersionNumber};
+use sui_types::digests::{ChainIdentifier, TransactionDigest};
+use sui_types::error::SuiError;
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpoint,
+};
 use sui_types::object::Object;
+use sui_types::storage::{ObjectStore, ReadStore, SharedInMemoryStore};
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
 use sui_types::transaction::SenderSignedData;
 use sui_types::transaction::TransactionDataAPI;
 use sui_types::transaction::{EndOfEpochTransactionKind, TransactionKind};
+use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
+use tracing::info;
 
 /// This trait defines the interfaces for fetching data from some local or remote store
 #[async_trait]
@@ -96,13 +117,14 @@ pub(crate) trait DataFetcher {
 pub enum Fetchers {
     Remote(RemoteFetcher),
     NodeStateDump(NodeStateDumpFetcher),
+    Archive(ArchiveFetcher),
 }
 
 impl Fetchers {
     pub fn as_remote(&self) -> &RemoteFetcher {
         match self {
             Fetchers::Remote(q) => q,
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::Archive(_) => panic!("not a remote fetcher"),
         }
     }
 
@@ -114,13 +136,13 @@ impl Fetchers {
                 q.clear_cache_for_new_task();
                 q
             }
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            Fetchers::NodeStateDump(_) | Fetchers::Archive(_) => panic!("not a remote fetcher"),
         }
     }
 
     pub fn as_node_state_dump(&self) -> &NodeStateDumpFetcher {
         match self {
-            Fetchers::Remote(_) => panic!("not a node state dump fetcher"),
+            Fetchers::Remote(_) | Fetchers::Archive(_) => panic!("not a node state dump fetcher"),
             Fetchers::NodeStateDump(q) => q,
         }
     }
@@ -136,6 +158,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_versioned(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_versioned(objects).await,
+            Fetchers::Archive(q) => q.multi_get_versioned(objects).await,
         }
     }
 
@@ -146,6 +169,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_latest(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_latest(objects).await,
+            Fetchers::Archive(q) => q.multi_get_latest(objects).await,
         }
     }
 
@@ -156,6 +180,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_checkpoint_txs(id).await,
             Fetchers::NodeStateDump(q) => q.get_checkpoint_txs(id).await,
+            Fetchers::Archive(q) => q.get_checkpoint_txs(id).await,
         }
     }
 
@@ -166,6 +191,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_transaction(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_transaction(tx_digest).await,
+            Fetchers::Archive(q) => q.get_transaction(tx_digest).await,
         }
     }
 
@@ -176,6 +202,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_loaded_child_objects(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_loaded_child_objects(tx_digest).await,
+            Fetchers::Archive(q) => q.get_loaded_child_objects(tx_digest).await,
         }
     }
 
@@ -183,6 +210,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_latest_checkpoint_sequence_number().await,
             Fetchers::NodeStateDump(q) => q.get_latest_checkpoint_sequence_number().await,
+            Fetchers::Archive(q) => q.get_latest_checkpoint_sequence_number().await,
         }
     }
 
@@ -200,6 +228,10 @@ impl DataFetcher for Fetchers {
                 q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
                     .await
             }
+            Fetchers::Archive(q) => {
+                q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
+                    .await
+            }
         }
     }
 
@@ -210,6 +242,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
+            Fetchers::Archive(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
         }
     }
 
@@ -220,12 +253,14 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_change_events(reverse).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_change_events(reverse).await,
+            Fetchers::Archive(q) => q.get_epoch_change_events(reverse).await,
         }
     }
     async fn get_chain_id(&self) -> Result<String, ReplayEngineError> {
         match self {
             Fetchers::Remote(q) => q.get_chain_id().await,
             Fetchers::NodeStateDump(q) => q.get_chain_id().await,
+            Fetchers::Archive(q) => q.get_chain_id().await,
         }
     }
     async fn get_child_object(
@@ -236,6 +271,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_child_object(object_id, version_upper_bound).await,
             Fetchers::NodeStateDump(q) => q.get_child_object(object_id, version_upper_bound).await,
+            Fetchers::Archive(q) => q.get_child_object(object_id, version_upper_bound).await,
         }
     }
 }
@@ -824,3 +860,478 @@ impl DataFetcher for NodeStateDumpFetcher {
         unimplemented!("get child object is not implemented for state dump");
     }
 }
+
+/// Fetches the data needed for replay from a local state archive and a formal snapshot, so
+/// checkpoints can be replayed without a fullnode.
+///
+/// Objects come from the live object set of the snapshot and from the objects written by the
+/// transactions replayed since, so only the epoch following the snapshot can be replayed, starting
+/// from its first checkpoint, and its transactions must be replayed in checkpoint order.
+#[derive(Clone)]
+pub struct ArchiveFetcher {
+    chain_id: String,
+    /// Checkpoints loaded from the archive
+    checkpoints: SharedInMemoryStore,
+    checkpoint_range: RangeInclusive<CheckpointSequenceNumber>,
+    /// Checkpoint each loaded transaction was included in
+    tx_checkpoints: Arc<HashMap<TransactionDigest, CheckpointSequenceNumber>>,
+    /// Live objects at the end of the snapshot epoch
+    snapshot: Arc<AuthorityPerpetualTables>,
+    /// Objects written by the transactions replayed since the snapshot
+    written_objects: Arc<RwLock<BTreeMap<(ObjectID, SequenceNumber), Object>>>,
+    /// The system state at the start of the replayed epoch
+    epoch: EpochId,
+    epoch_start_timestamp_ms: u64,
+    reference_gas_price: u64,
+    protocol_version: u64,
+    epoch_change_tx: TransactionDigest,
+}
+
+impl ArchiveFetcher {
+    /// Loads `checkpoint_range` from the archive at `archive_path`, and restores the snapshot of
+    /// the epoch before it from `snapshot_path` into `snapshot_db_path`, unless a previous run
+    /// already did.
+    pub async fn new(
+        archive_path: &Path,
+        snapshot_path: &Path,
+        snapshot_db_path: &Path,
+        checkpoint_range: RangeInclusive<CheckpointSequenceNumber>,
+    ) -> Result<Self, ReplayEngineError> {
+        let reader = open_archive(archive_path).await?;
+
+        // The genesis checkpoint identifies the chain
+        let checkpoints = SharedInMemoryStore::default();
+        reader
+            .read_summaries_for_list_no_verify(
+                checkpoints.clone(),
+                vec![0],
+                Arc::new(AtomicU64::new(0)),
+            )
+            .await
+            .map_err(archive_error)?;
+        reader
+            .read(
+                checkpoints.clone(),
+                *checkpoint_range.start()..*checkpoint_range.end() + 1,
+                Arc::new(AtomicU64::new(0)),
+                Arc::new(AtomicU64::new(0)),
+                false,
+            )
+            .await
+            .map_err(archive_error)?;
+
+        let chain_id = checkpoints
+            .get_checkpoint_by_sequence_number(0)
+            .map_err(SuiError::from)?
+            .map(|genesis| ChainIdentifier::from(*genesis.digest()).to_string())
+            .ok_or_else(|| ReplayEngineError::UnableToGetChainId {
+                err: "Genesis checkpoint missing from archive".to_string(),
+            })?;
+
+        let mut tx_checkpoints = HashMap::new();
+        let mut epoch = None;
+        for seq in checkpoint_range.clone() {
+            let (Some(summary), Some(contents)) = (
+                checkpoints
+                    .get_checkpoint_by_sequence_number(seq)
+                    .map_err(SuiError::from)?,
+                checkpoints
+                    .get_full_checkpoint_contents_by_sequence_number(seq)
+                    .map_err(SuiError::from)?,
+            ) else {
+                return Err(ReplayEngineError::GeneralError {
+                    err: format!("Checkpoint {seq} is not available in the archive"),
+                });
+            };
+            if *epoch.get_or_insert(summary.epoch()) != summary.epoch() {
+                return Err(ReplayEngineError::GeneralError {
+                    err: format!(
+                        "Checkpoints {}-{} span more than one epoch, only the epoch following the snapshot can be replayed",
+                        checkpoint_range.start(),
+                        checkpoint_range.end()
+                    ),
+                });
+            }
+            for tx in contents.iter() {
+                tx_checkpoints.insert(*tx.transaction.digest(), seq);
+            }
+        }
+        let epoch = epoch.expect("Checkpoint range must not be empty");
+        if epoch == 0 {
+            return Err(ReplayEngineError::GeneralError {
+                err: "Transactions from epoch 0 cannot be replayed from a snapshot".to_string(),
+            });
+        }
+        // The snapshot doesn't have the objects written by the transactions of the epoch before
+        // the start of the range, so they must be replayed too
+        let epoch_start = reader
+            .get_manifest()
+            .await
+            .map_err(archive_error)?
+            .next_checkpoint_after_epoch(epoch - 1);
+        if *checkpoint_range.start() != epoch_start {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "Replays from an archive must start at checkpoint {epoch_start}, the first of epoch {epoch}"
+                ),
+            });
+        }
+
+        let snapshot = restore_snapshot(snapshot_path, snapshot_db_path, epoch - 1).await?;
+        let system_state = get_sui_system_state(&snapshot)?;
+        if system_state.epoch() != epoch {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "Snapshot is at the start of epoch {}, but checkpoints are from epoch {}",
+                    system_state.epoch(),
+                    epoch
+                ),
+            });
+        }
+        // The system state was last written by the transaction that started this epoch
+        let epoch_change_tx = snapshot
+            .get_object(&SUI_SYSTEM_STATE_OBJECT_ID)
+            .map_err(SuiError::from)?
+            .ok_or(ReplayEngineError::ObjectNotExist {
+                id: SUI_SYSTEM_STATE_OBJECT_ID,
+            })?
+            .previous_transaction;
+
+        Ok(Self {
+            chain_id,
+            checkpoints,
+            checkpoint_range,
+            tx_checkpoints: Arc::new(tx_checkpoints),
+            snapshot: Arc::new(snapshot),
+            written_objects: Arc::new(RwLock::new(BTreeMap::new())),
+            epoch,
+            epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms(),
+            reference_gas_price: system_state.reference_gas_price(),
+            protocol_version: system_state.protocol_version(),
+            epoch_change_tx,
+        })
+    }
+
+    /// Returns the first and last checkpoints of `epoch` in the archive at `archive_path`, without
+    /// loading them.
+    pub async fn checkpoints_for_epoch(
+        archive_path: &Path,
+        epoch: EpochId,
+    ) -> Result<(CheckpointSequenceNumber, CheckpointSequenceNumber), ReplayEngineError> {
+        let manifest = open_archive(archive_path)
+            .await?
+            .get_manifest()
+            .await
+            .map_err(archive_error)?;
+        let start = if epoch == 0 {
+            0
+        } else {
+            manifest.next_checkpoint_after_epoch(epoch - 1)
+        };
+        let end = manifest
+            .next_checkpoint_after_epoch(epoch)
+            .min(manifest.next_checkpoint_seq_num());
+        if start >= end {
+            return Err(ReplayEngineError::UnableToDetermineCheckpoint { epoch });
+        }
+        Ok((start, end - 1))
+    }
+
+    /// The summary of the only protocol version the loaded checkpoints can use.
+    pub fn protocol_version_summary(&self) -> ProtocolVersionSummary {
+        ProtocolVersionSummary {
+            protocol_version: self.protocol_version,
+            epoch_start: self.epoch,
+            epoch_end: self.epoch,
+            checkpoint_start: Some(*self.checkpoint_range.start()),
+            checkpoint_end: Some(*self.checkpoint_range.end()),
+            epoch_change_tx: self.epoch_change_tx,
+        }
+    }
+
+    /// Records the objects written by a replayed transaction, for the transactions after it to
+    /// read.
+    pub fn insert_written_objects(&self, sandbox_state: &ExecutionSandboxState) {
+        let Some(store) = &sandbox_state.local_exec_temporary_store else {
+            return;
+        };
+        let mut written_objects = self.written_objects.write();
+        for object in store.written.values() {
+            written_objects.insert((object.id(), object.version()), object.clone());
+        }
+    }
+
+    fn get_versioned(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Object, ReplayEngineError> {
+        if let Some(object) = self.written_objects.read().get(&(*id, version)) {
+            return Ok(object.clone());
+        }
+        self.snapshot
+            .get_object_by_key(id, version)
+            .map_err(SuiError::from)?
+            .ok_or(ReplayEngineError::ObjectVersionNotFound { id: *id, version })
+    }
+
+    /// The latest version of `id` written by a replayed transaction, no later than
+    /// `version_upper_bound`.
+    fn get_written_lt_or_eq(
+        &self,
+        id: &ObjectID,
+        version_upper_bound: SequenceNumber,
+    ) -> Option<Object> {
+        self.written_objects
+            .read()
+            .range((*id, SequenceNumber::MIN)..=(*id, version_upper_bound))
+            .next_back()
+            .map(|(_, object)| object.clone())
+    }
+
+    fn get_full_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<(VerifiedCheckpoint, FullCheckpointContents), ReplayEngineError> {
+        self.checkpoints
+            .get_checkpoint_by_sequence_number(seq)
+            .map_err(SuiError::from)?
+            .zip(
+                self.checkpoints
+                    .get_full_checkpoint_contents_by_sequence_number(seq)
+                    .map_err(SuiError::from)?,
+            )
+            .ok_or(ReplayEngineError::GeneralError {
+                err: format!("Checkpoint {seq} was not loaded from the archive"),
+            })
+    }
+}
+
+#[async_trait]
+impl DataFetcher for ArchiveFetcher {
+    async fn multi_get_versioned(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|(id, version)| self.get_versioned(id, *version))
+            .collect()
+    }
+
+    async fn multi_get_latest(
+        &self,
+        objects: &[ObjectID],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|id| {
+                if let Some(object) = self.get_written_lt_or_eq(id, SequenceNumber::MAX) {
+                    return Ok(object);
+                }
+                self.snapshot
+                    .get_object(id)
+                    .map_err(SuiError::from)?
+                    .ok_or(ReplayEngineError::ObjectNotExist { id: *id })
+            })
+            .collect()
+    }
+
+    async fn get_checkpoint_txs(
+        &self,
+        id: u64,
+    ) -> Result<Vec<TransactionDigest>, ReplayEngineError> {
+        let (_, contents) = self.get_full_checkpoint(id)?;
+        Ok(contents.iter().map(|tx| *tx.transaction.digest()).collect())
+    }
+
+    async fn get_transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<SuiTransactionBlockResponse, ReplayEngineError> {
+        let seq = self
+            .tx_checkpoints
+            .get(tx_digest)
+            .ok_or(ReplayEngineError::GeneralError {
+                err: format!("Transaction {tx_digest} is not in the loaded checkpoints"),
+            })?;
+        let (summary, contents) = self.get_full_checkpoint(*seq)?;
+        let tx = contents
+            .iter()
+            .find(|tx| tx.transaction.digest() == tx_digest)
+            .expect("Transaction must be in its checkpoint");
+
+        let mut response = SuiTransactionBlockResponse::new(*tx_digest);
+        response.raw_transaction = bcs::to_bytes(tx.transaction.data())
+            .map_err(|e| ReplayEngineError::GeneralError { err: e.to_string() })?;
+        response.effects = Some(SuiTransactionBlockEffects::try_from(tx.effects.clone())?);
+        response.checkpoint = Some(*seq);
+        response.timestamp_ms = Some(summary.timestamp_ms);
+        Ok(response)
+    }
+
+    async fn get_loaded_child_objects(
+        &self,
+        _: &TransactionDigest,
+    ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
+        Ok(vec![])
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, ReplayEngineError> {
+        Ok(*self.checkpoint_range.end())
+    }
+
+    async fn fetch_random_transaction(
+        &self,
+        checkpoint_id_start: Option<u64>,
+        checkpoint_id_end: Option<u64>,
+    ) -> Result<TransactionDigest, ReplayEngineError> {
+        let checkpoint_id_start = checkpoint_id_start.unwrap_or(*self.checkpoint_range.start());
+        let checkpoint_id_end = checkpoint_id_end.unwrap_or(*self.checkpoint_range.end());
+        let checkpoint_id = rand::thread_rng().gen_range(checkpoint_id_start..=checkpoint_id_end);
+
+        let txs = self.get_checkpoint_txs(checkpoint_id).await?;
+        let tx_idx = rand::thread_rng().gen_range(0..txs.len());
+
+        Ok(txs[tx_idx])
+    }
+
+    async fn get_epoch_start_timestamp_and_rgp(
+        &self,
+        epoch_id: u64,
+    ) -> Result<(u64, u64), ReplayEngineError> {
+        if epoch_id != self.epoch {
+            return Err(ReplayEngineError::EventNotFound { epoch: epoch_id });
+        }
+        Ok((self.epoch_start_timestamp_ms, self.reference_gas_price))
+    }
+
+    async fn get_epoch_change_events(
+        &self,
+        _reverse: bool,
+    ) -> Result<Vec<SuiEvent>, ReplayEngineError> {
+        Err(ReplayEngineError::GeneralError {
+            err: "Events are not available when replaying from an archive".to_string(),
+        })
+    }
+
+    async fn get_chain_id(&self) -> Result<String, ReplayEngineError> {
+        Ok(self.chain_id.clone())
+    }
+
+    async fn get_child_object(
+        &self,
+        object_id: &ObjectID,
+        version_upper_bound: VersionNumber,
+    ) -> Result<Object, ReplayEngineError> {
+        if let Some(object) = self.get_written_lt_or_eq(object_id, version_upper_bound) {
+            return Ok(object);
+        }
+        self.snapshot
+            .find_object_lt_or_eq_version(*object_id, version_upper_bound)?
+            .ok_or(ReplayEngineError::ObjectNotExist { id: *object_id })
+    }
+}
+
+const ARCHIVE_DOWNLOAD_CONCURRENCY: usize = 16;
+
+/// Name of the file marking a snapshot db as fully restored
+const RESTORED_MARKER: &str = "RESTORED";
+
+fn local_store_config(path: &Path) -> ObjectStoreConfig {
+    ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(path.to_path_buf()),
+        ..Default::default()
+    }
+}
+
+async fn open_archive(path: &Path) -> Result<ArchiveReader, ReplayEngineError> {
+    let reader = ArchiveReader::new(
+        ArchiveReaderConfig {
+            remote_store_config: local_store_config(path),
+            download_concurrency: NonZeroUsize::new(ARCHIVE_DOWNLOAD_CONCURRENCY).unwrap(),
+            use_for_pruning_watermark: false,
+        },
+        &ArchiveReaderMetrics::new(&prometheus::Registry::new()),
+    )
+    .map_err(archive_error)?;
+    reader.sync_manifest_once().await.map_err(archive_error)?;
+    Ok(reader)
+}
+
+fn archive_error(err: anyhow::Error) -> ReplayEngineError {
+    ReplayEngineError::GeneralError {
+        err: format!("Unable to read archive: {err}"),
+    }
+}
+
+/// Restores the live object set of the formal snapshot of `epoch` into a db under `db_path`,
+/// including the full snapshot a delta snapshot is based on.
+async fn restore_snapshot(
+    snapshot_path: &Path,
+    db_path: &Path,
+    epoch: EpochId,
+) -> Result<AuthorityPerpetualTables, ReplayEngineError> {
+    let snapshot_error = |err: anyhow::Error| ReplayEngineError::GeneralError {
+        err: format!("Unable to restore snapshot of epoch {epoch}: {err}"),
+    };
+    let store_path = db_path.join("store");
+    let staging_path = db_path.join("staging");
+    let marker = db_path.join(RESTORED_MARKER);
+    if marker.exists() {
+        info!(
+            "Using snapshot of epoch {} restored in {:?}",
+            epoch, db_path
+        );
+        return Ok(AuthorityPerpetualTables::open(&store_path, None));
+    }
+    if db_path.exists() {
+        // A previous restore was interrupted
+        std::fs::remove_dir_all(db_path).map_err(|e| snapshot_error(e.into()))?;
+    }
+
+    info!("Restoring snapshot of epoch {} into {:?}", epoch, db_path);
+    let perpetual_db = AuthorityPerpetualTables::open(&store_path, None);
+    let remote_store_config = local_store_config(snapshot_path);
+    let staging_store_config = local_store_config(&staging_path);
+    let concurrency = NonZeroUsize::new(ARCHIVE_DOWNLOAD_CONCURRENCY).unwrap();
+    let mut reader = StateSnapshotReaderV1::new(
+        epoch,
+        &remote_store_config,
+        &staging_store_config,
+        usize::MAX,
+        concurrency,
+        MultiProgress::new(),
+    )
+    .await
+    .map_err(snapshot_error)?;
+    if let Some(base_epoch) = reader.base_epoch() {
+        let mut base_reader = StateSnapshotReaderV1::new(
+            base_epoch,
+            &remote_store_config,
+            &staging_store_config,
+            usize::MAX,
+            concurrency,
+            MultiProgress::new(),
+        )
+        .await
+        .map_err(snapshot_error)?;
+        let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+        base_reader
+            .read(&perpetual_db, abort_registration, None)
+            .await
+            .map_err(snapshot_error)?;
+    }
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    reader
+        .read(&perpetual_db, abort_registration, None)
+        .await
+        .map_err(snapshot_error)?;
+
+    if staging_path.exists() {
+        std::fs::remove_dir_all(&staging_path).map_err(|e| snapshot_error(e.into()))?;
+    }
+    std::fs::write(marker, []).map_err(|e| snapshot_error(e.into()))?;
+    Ok(perpetual_db)
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_recursion::async_recursion;
-use clap::Parser;
+use clap::{Args, Parser};
 use config::ReplayableNetworkConfigSet;
 use fuzz::ReplayFuzzer;
 use fuzz::ReplayFuzzerConfig;
@@ -17,6 +17,7 @@ use tracing::warn;
 use transaction_provider::{FuzzStartPoint, TransactionSource};
 
 use crate::config::get_rpc_url;
+use crate::data_fetcher::ArchiveFetcher;
 use crate::replay::ExecutionSandboxState;
 use crate::replay::LocalExec;
 use crate::replay::ProtocolVersionSummary;
@@ -169,6 +170,8 @@ pub enum ReplayToolCommand {
         terminate_early: bool,
         #[arg(long, short, default_value = "16")]
         max_tasks: u64,
+        #[command(flatten)]
+        archive: ArchiveReplayArgs,
     },
 
     /// Replay all transactions in an epoch
@@ -180,6 +183,8 @@ pub enum ReplayToolCommand {
         terminate_early: bool,
         #[arg(long, short, default_value = "16")]
         max_tasks: u64,
+        #[command(flatten)]
+        archive: ArchiveReplayArgs,
     },
 
     /// Run the replay based fuzzer
@@ -197,6 +202,25 @@ pub enum ReplayToolCommand {
     Report,
 }
 
+/// Replay checkpoints offline, from local copies of the state archive and formal snapshots
+/// instead of a fullnode. Only the epoch following a snapshot can be replayed, starting from its
+/// first checkpoint, and replay stops at the first transaction that fails, as the transactions
+/// after it read the objects it writes.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ArchiveReplayArgs {
+    /// Path to a local state archive to read checkpoints from
+    #[arg(long, requires = "snapshot_path")]
+    pub archive_path: Option<PathBuf>,
+    /// Path to local formal snapshots. The snapshot of the epoch before the replayed checkpoints
+    /// is restored to read objects from
+    #[arg(long, requires = "archive_path")]
+    pub snapshot_path: Option<PathBuf>,
+    /// Where to restore the snapshot, so later replays of the same epoch can reuse it. Defaults
+    /// to a temporary directory
+    #[arg(long, requires = "archive_path")]
+    pub snapshot_db_path: Option<PathBuf>,
+}
+
 #[async_recursion]
 pub async fn execute_replay_command(
     rpc_url: Option<String>,
@@ -457,9 +481,53 @@ pub async fn execute_replay_command(
             end,
             terminate_early,
             max_tasks,
+            archive,
         } => {
             assert!(start <= end, "Start checkpoint must be <= end checkpoint");
             assert!(max_tasks > 0, "Max tasks must be > 0");
+            if let (Some(archive_path), Some(snapshot_path)) =
+                (&archive.archive_path, &archive.snapshot_path)
+            {
+                // Each transaction reads the objects written by the ones before it, so checkpoints
+                // are executed in order on a single task
+                info!(
+                    "Executing checkpoints {} to {} from archive {:?}",
+                    start, end, archive_path
+                );
+                let tempdir = tempfile::tempdir()?;
+                let snapshot_db_path = archive
+                    .snapshot_db_path
+                    .unwrap_or_else(|| tempdir.path().join("snapshot"));
+                let fetcher = ArchiveFetcher::new(
+                    archive_path,
+                    snapshot_path,
+                    &snapshot_db_path,
+                    start..=end,
+                )
+                .await?;
+                let time = std::time::Instant::now();
+                let checkpoints: Vec<_> = (start..=end).collect();
+                let (succeeded, total) = LocalExec::new_for_archive(fetcher)
+                    .init_for_execution()
+                    .await?
+                    .execute_all_in_checkpoints(
+                        &checkpoints,
+                        &safety,
+                        terminate_early,
+                        use_authority,
+                    )
+                    .await?;
+                let time_ms = time.elapsed().as_millis() as u64;
+                info!(
+                    "Executed {} checkpoints @ {}/{} total TXs succeeded in {} ms ({}) avg TX/s",
+                    end - start + 1,
+                    succeeded,
+                    total,
+                    time_ms,
+                    (total as f64) / (time_ms as f64 / 1000.0)
+                );
+                return Ok(Some((succeeded, total)));
+            }
             let checkpoints_per_task = ((end - start + max_tasks) / max_tasks) as usize;
             let mut handles = vec![];
             info!(
@@ -523,11 +591,19 @@ pub async fn execute_replay_command(
             epoch,
             terminate_early,
             max_tasks,
+            archive,
         } => {
-            let lx =
-                LocalExec::new_from_fn_url(&rpc_url.clone().expect("Url must be provided")).await?;
-
-            let (start, end) = lx.checkpoints_for_epoch(epoch).await?;
+            let (start, end) = match &archive.archive_path {
+                Some(archive_path) => {
+                    ArchiveFetcher::checkpoints_for_epoch(archive_path, epoch).await?
+                }
+                None => {
+                    LocalExec::new_from_fn_url(&rpc_url.clone().expect("Url must be provided"))
+                        .await?
+                        .checkpoints_for_epoch(epoch)
+                        .await?
+                }
+            };
 
             info!(
                 "Executing epoch {} (checkpoint range {}-{}) with at most {} tasks",
@@ -544,6 +620,7 @@ pub async fn execute_replay_command(
                     end,
                     terminate_early,
                     max_tasks,
+                    archive,
                 },
             )
             .await;
@@ -4,7 +4,8 @@
 use crate::chain_from_chain_id;
 use crate::{
     data_fetcher::{
-        extract_epoch_and_version, DataFetcher, Fetchers, NodeStateDumpFetcher, RemoteFetcher,
+        extract_epoch_and_version, ArchiveFetcher, DataFetcher, Fetchers, NodeStateDumpFetcher,
+        RemoteFetcher,
     },
     displays::{
         transaction_displays::{transform_command_results_to_annotated, FullPTB},
@@ -437,6 +438,32 @@ impl LocalExec {
         })
     }
 
+    /// Replays checkpoints offline, from a local state archive and the formal snapshot of the
+    /// epoch before them. See `ArchiveFetcher`.
+    pub fn new_for_archive(fetcher: ArchiveFetcher) -> Self {
+        // Use a throwaway metrics registry for local execution.
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(LimitsMetrics::new(&registry));
+
+        Self {
+            client: None,
+            protocol_version_epoch_table: BTreeMap::new(),
+            protocol_version_system_package_table: BTreeMap::new(),
+            current_protocol_version: fetcher.protocol_version_summary().protocol_version,
+            exec_store_events: Arc::new(Mutex::new(Vec::new())),
+            metrics,
+            storage: Storage::default(),
+            fetcher: Fetchers::Archive(fetcher),
+            // TODO: make these configurable
+            num_retries_for_timeout: RPC_TIMEOUT_ERR_NUM_RETRIES,
+            sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+            executor_version: None,
+            protocol_version: None,
+            enable_profiler: None,
+            config_and_versions: None,
+        }
+    }
+
     pub async fn multi_download_and_store(
         &mut self,
         objs: &[(ObjectID, SequenceNumber)],
@@ -651,6 +678,14 @@ impl LocalExec {
             .map_err(|e| ReplayEngineError::SuiRpcError { err: e.to_string() })
     }
 
+    /// Replays the transactions of `checkpoint_ids` in order, returning how many succeeded out of
+    /// how many there were.
+    ///
+    /// When replaying from an archive, later transactions read the objects written by earlier
+    /// ones, which are only recorded once their effects match, so one failure would cascade into
+    /// every transaction touching the same objects. Archive replays therefore stop at the first
+    /// failure. Unsupported transactions are still skipped, as the only one within an archive
+    /// replay is the end of epoch transaction, which is always the last of the epoch.
     pub async fn execute_all_in_checkpoints(
         &mut self,
         checkpoint_ids: &[u64],
@@ -677,16 +712,24 @@ impl LocalExec {
                     None,
                 )
                 .await
-                .map(|q| q.check_effects())
+                .and_then(|q| q.check_effects().map(|_| q))
             {
-                Err(e) | Ok(Err(e)) => {
-                    if terminate_early {
+                Err(e) => {
+                    let unsupported =
+                        matches!(e, ReplayEngineError::TransactionNotSupported { .. });
+                    if terminate_early || (self.is_archive_replay() && !unsupported) {
                         return Err(e);
                     }
                     error!("Error executing tx: {},  {:#?}", tx, e);
                     continue;
                 }
-                _ => (),
+                Ok(sandbox_state) => {
+                    // Later transactions read the objects written by this one from the archive
+                    // fetcher, so only record them once the effects are known to be correct
+                    if let Fetchers::Archive(fetcher) = &self.fetcher {
+                        fetcher.insert_written_objects(&sandbox_state);
+                    }
+                }
             }
 
             succeeded += 1;
@@ -701,8 +744,12 @@ impl LocalExec {
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
         let tx_digest = &tx_info.tx_digest;
+        let tx_data = tx_info.sender_signed_data.transaction_data();
+        let is_system_tx = tx_data.is_system_tx();
         // TODO: Support system transactions.
-        if tx_info.sender_signed_data.transaction_data().is_system_tx() {
+        // Archive replays execute the system transactions within an epoch, as the objects they
+        // write (eg the clock) cannot be fetched from anywhere else.
+        if is_system_tx && (!self.is_archive_replay() || tx_data.is_end_of_epoch_tx()) {
             warn!(
                 "System TX replay not supported: {}, skipping transaction",
                 tx_digest
@@ -753,12 +800,17 @@ impl LocalExec {
         let expensive_checks = true;
         let transaction_kind = override_transaction_kind.unwrap_or(tx_info.kind.clone());
         let certificate_deny_set = HashSet::new();
-        let (inner_store, gas_status, effects, result) = if let Ok(gas_status) = SuiGasStatus::new(
-            tx_info.gas_budget,
-            tx_info.gas_price,
-            tx_info.reference_gas_price,
-            protocol_config,
-        ) {
+        let gas_status = if is_system_tx {
+            Ok(SuiGasStatus::new_unmetered())
+        } else {
+            SuiGasStatus::new(
+                tx_info.gas_budget,
+                tx_info.gas_price,
+                tx_info.reference_gas_price,
+                protocol_config,
+            )
+        };
+        let (inner_store, gas_status, effects, result) = if let Ok(gas_status) = gas_status {
             executor.execute_transaction_to_effects(
                 &self,
                 protocol_config,
@@ -922,7 +974,7 @@ impl LocalExec {
         .unwrap();
         let (kind, signer, gas) = executable.transaction_data().execution_parts();
         let executor = sui_execution::executor(&protocol_config, true, None).unwrap();
-        let (_, _, effects, exec_res) = executor.execute_transaction_to_effects(
+        let (inner_store, _, effects, exec_res) = executor.execute_transaction_to_effects(
             &store,
             &protocol_config,
             Arc::new(LimitsMetrics::new(&Registry::new())),
@@ -944,7 +996,7 @@ impl LocalExec {
         Ok(ExecutionSandboxState {
             transaction_info: pre_run_sandbox.transaction_info.clone(),
             required_objects,
-            local_exec_temporary_store: None, // We dont capture it for cert exec run
+            local_exec_temporary_store: Some(inner_store),
             local_exec_effects: effects,
             local_exec_status: Some(exec_res),
         })
@@ -1085,7 +1137,11 @@ impl LocalExec {
     }
 
     pub fn is_remote_replay(&self) -> bool {
-        matches!(self.fetcher, Fetchers::Remote(_))
+        matches!(self.fetcher, Fetchers::Remote(_) | Fetchers::Archive(_))
+    }
+
+    pub fn is_archive_replay(&self) -> bool {
+        matches!(self.fetcher, Fetchers::Archive(_))
     }
 
     /// Must be called after `populate_protocol_version_tables`
@@ -1094,7 +1150,7 @@ impl LocalExec {
         protocol_version: u64,
     ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
         match &self.fetcher {
-            Fetchers::Remote(_) => Ok(self
+            Fetchers::Remote(_) | Fetchers::Archive(_) => Ok(self
                 .protocol_version_system_package_table
                 .get(&protocol_version)
                 .ok_or(ReplayEngineError::FrameworkObjectVersionTableNotPopulated {
@@ -1213,6 +1269,25 @@ impl LocalExec {
     }
 
     pub async fn populate_protocol_version_tables(&mut self) -> Result<(), ReplayEngineError> {
+        if let Fetchers::Archive(fetcher) = &self.fetcher {
+            // An archive replay covers a single epoch, so the system packages in the snapshot
+            // are the ones for its protocol version
+            let summary = fetcher.protocol_version_summary();
+            let system_packages = self
+                .multi_download_latest(&Self::system_package_ids(summary.protocol_version))
+                .await?;
+            self.protocol_version_system_package_table.insert(
+                summary.protocol_version,
+                system_packages
+                    .iter()
+                    .map(|o| (o.id(), o.version()))
+                    .collect(),
+            );
+            self.protocol_version_epoch_table =
+                BTreeMap::from([(summary.protocol_version, summary)]);
+            return Ok(());
+        }
+
         self.protocol_version_epoch_table = self.protocol_ver_to_epoch_map().await?;
 
         let system_package_revisions = self.system_package_versions().await?;
@@ -1464,15 +1539,13 @@ impl LocalExec {
         assert!(self.is_remote_replay());
         // Fetch full transaction content
         let tx_info = self.fetcher.get_transaction(tx_digest).await?;
-        let sender = match tx_info.clone().transaction.unwrap().data {
-            sui_json_rpc_types::SuiTransactionBlockData::V1(tx) => tx.sender,
-        };
         let SuiTransactionBlockEffects::V1(effects) = tx_info.clone().effects.unwrap();
 
         let config_objects = self.add_config_objects_if_needed(effects.status());
 
         let raw_tx_bytes = tx_info.clone().raw_transaction;
         let orig_tx: SenderSignedData = bcs::from_bytes(&raw_tx_bytes).unwrap();
+        let sender = orig_tx.transaction_data().sender();
         let input_objs = orig_tx
             .transaction_data()
             .input_objects()
@@ -1495,14 +1568,8 @@ impl LocalExec {
                 }
             })
             .collect();
-        let gas_data = match tx_info.clone().transaction.unwrap().data {
-            sui_json_rpc_types::SuiTransactionBlockData::V1(tx) => tx.gas_data,
-        };
-        let gas_object_refs: Vec<_> = gas_data
-            .payment
-            .iter()
-            .map(|obj_ref| obj_ref.to_object_ref())
-            .collect();
+        let gas_data = orig_tx.transaction_data().gas_data();
+        let gas_object_refs = gas_data.payment.clone();
         let receiving_objs = orig_tx
             .transaction_data()
             .receiving_objects()
@@ -1792,8 +1859,11 @@ impl LocalExec {
         self.multi_download_and_store(&shared_refs).await?;
 
         // Download gas (although this should already be in cache from modified at versions?)
-        let gas_refs: Vec<_> = tx_info.gas.iter().map(|w| (w.0, w.1)).collect();
-        self.multi_download_and_store(&gas_refs).await?;
+        // System transactions have a placeholder gas object, which does not exist
+        if !tx_info.sender_signed_data.transaction_data().is_system_tx() {
+            let gas_refs: Vec<_> = tx_info.gas.iter().map(|w| (w.0, w.1)).collect();
+            self.multi_download_and_store(&gas_refs).await?;
+        }
 
         // Fetch the input objects we know from the raw transaction
         let input_objs = self
@@ -0,0 +1,239 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use prometheus::Registry;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::time::Duration;
+use sui_archival::read_manifest;
+use sui_archival::writer::ArchiveWriter;
+use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_core::db_checkpoint_handler::SUCCESS_MARKER;
+use sui_replay::{execute_replay_command, ArchiveReplayArgs, ReplayToolCommand};
+use sui_snapshot::uploader::StateSnapshotUploader;
+use sui_storage::{FileCompression, StorageFormat};
+use sui_types::base_types::ExecutionData;
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpointContents,
+};
+use sui_types::storage::SharedInMemoryStore;
+use test_cluster::{TestCluster, TestClusterBuilder};
+
+fn local_store_config(path: &Path) -> ObjectStoreConfig {
+    ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(path.to_path_buf()),
+        ..Default::default()
+    }
+}
+
+async fn wait_for(what: &str, mut done: impl FnMut() -> bool) {
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while !done() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("timed out waiting for {what}"));
+}
+
+/// Writes formal snapshots of every epoch into `snapshot_path`, from the db checkpoints the first
+/// validator takes at the end of each epoch, the way a node configured to write snapshots does.
+/// The uploader stops once the returned sender is dropped.
+fn start_snapshot_uploader(
+    test_cluster: &TestCluster,
+    snapshot_path: &Path,
+    staging_path: &Path,
+) -> tokio::sync::broadcast::Sender<()> {
+    let (db_checkpoint_path, checkpoint_store) =
+        test_cluster.all_validator_handles()[0].with(|node| {
+            (
+                node.db_checkpoint_path(),
+                node.state().get_checkpoint_store().clone(),
+            )
+        });
+    let uploader = StateSnapshotUploader::new(
+        &db_checkpoint_path,
+        staging_path,
+        local_store_config(snapshot_path),
+        1,
+        None,
+        &Registry::new(),
+        checkpoint_store,
+    )
+    .unwrap();
+    uploader.start()
+}
+
+/// Archives the checkpoints executed by the fullnode up to the end of `epoch` into
+/// `archive_path`, returning the checkpoints of `epoch`.
+async fn write_archive(
+    test_cluster: &TestCluster,
+    epoch: u64,
+    archive_path: &Path,
+    staging_path: &Path,
+) -> RangeInclusive<CheckpointSequenceNumber> {
+    let state = test_cluster.fullnode_handle.sui_node.state();
+    let checkpoint_store = state.get_checkpoint_store();
+    let transactions = state.get_transaction_cache_reader();
+    let store = SharedInMemoryStore::default();
+    let (mut start, mut end) = (None, None);
+    // The archive writer only closes the last file of an epoch once it sees a checkpoint from the
+    // next one, so that one is archived too
+    for seq in 0.. {
+        wait_for("checkpoint execution", || {
+            checkpoint_store
+                .get_highest_executed_checkpoint_seq_number()
+                .unwrap()
+                >= Some(seq)
+        })
+        .await;
+        let checkpoint = checkpoint_store
+            .get_checkpoint_by_sequence_number(seq)
+            .unwrap()
+            .unwrap();
+        let contents = checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)
+            .unwrap()
+            .unwrap();
+        let executions: Vec<_> = contents
+            .iter()
+            .map(|digests| {
+                let transaction = transactions
+                    .get_transaction_block(&digests.transaction)
+                    .unwrap()
+                    .unwrap();
+                let effects = transactions.get_effects(&digests.effects).unwrap().unwrap();
+                ExecutionData::new((*transaction).clone().into_inner(), effects)
+            })
+            .collect();
+        let contents = FullCheckpointContents::from_contents_and_execution_data(
+            contents,
+            executions.into_iter(),
+        );
+        store.inner_mut().insert_checkpoint(&checkpoint);
+        store.inner_mut().insert_checkpoint_contents(
+            &checkpoint,
+            VerifiedCheckpointContents::new_unchecked(contents),
+        );
+
+        if checkpoint.epoch() == epoch {
+            start.get_or_insert(seq);
+            end = Some(seq);
+        } else if checkpoint.epoch() > epoch {
+            break;
+        }
+    }
+    let epoch_checkpoints = start.unwrap()..=end.unwrap();
+
+    let archive_writer = ArchiveWriter::new(
+        local_store_config(staging_path),
+        local_store_config(archive_path),
+        FileCompression::Zstd,
+        StorageFormat::Blob,
+        Duration::from_secs(600),
+        256 * 1024 * 1024,
+        &Registry::new(),
+    )
+    .await
+    .unwrap();
+    let _kill = archive_writer.start(store).await.unwrap();
+    let archive_store = local_store_config(archive_path).make().unwrap();
+    tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            if let Ok(manifest) = read_manifest(archive_store.clone()).await {
+                if manifest.next_checkpoint_seq_num() > *epoch_checkpoints.end() {
+                    break;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for the archive");
+    epoch_checkpoints
+}
+
+#[tokio::test]
+async fn replay_epoch_from_archive_and_snapshot() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(3_600_000)
+        .with_enable_db_checkpoints_validators()
+        .build()
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot_path = dir.path().join("snapshot");
+    let archive_path = dir.path().join("archive");
+    let _uploader = start_snapshot_uploader(
+        &test_cluster,
+        &snapshot_path,
+        &dir.path().join("snapshot_staging"),
+    );
+
+    test_cluster.trigger_reconfiguration().await;
+    let recipient = test_cluster.get_address_1();
+    for _ in 0..3 {
+        let tx = test_cluster
+            .test_transaction_builder()
+            .await
+            .transfer_sui(Some(1_000), recipient)
+            .build();
+        test_cluster.sign_and_execute_transaction(&tx).await;
+    }
+    test_cluster.trigger_reconfiguration().await;
+
+    let epoch_0_snapshot = snapshot_path.join("epoch_0").join(SUCCESS_MARKER);
+    wait_for("the snapshot of epoch 0", || epoch_0_snapshot.exists()).await;
+    let checkpoints = write_archive(
+        &test_cluster,
+        1,
+        &archive_path,
+        &dir.path().join("archive_staging"),
+    )
+    .await;
+
+    let archive = ArchiveReplayArgs {
+        archive_path: Some(archive_path),
+        snapshot_path: Some(snapshot_path),
+        snapshot_db_path: Some(dir.path().join("snapshot_db")),
+    };
+    let (succeeded, total) = execute_replay_command(
+        None,
+        false,
+        false,
+        None,
+        None,
+        ReplayToolCommand::ReplayEpoch {
+            epoch: 1,
+            terminate_early: false,
+            max_tasks: 1,
+            archive: archive.clone(),
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    // Everything but the end of epoch transaction is replayed, the transfers and the system
+    // transactions before them
+    assert!(total > 3);
+    assert_eq!(succeeded, total - 1);
+
+    // The objects written by the transactions before the start of the range are not in the
+    // snapshot, so replays must start from the first checkpoint of the epoch
+    execute_replay_command(
+        None,
+        false,
+        false,
+        None,
+        None,
+        ReplayToolCommand::ReplayCheckpoints {
+            start: checkpoints.start() + 1,
+            end: *checkpoints.end(),
+            terminate_early: false,
+            max_tasks: 1,
+            archive,
+        },
+    )
+    .await
+    .unwrap_err();
+}
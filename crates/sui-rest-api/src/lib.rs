@@ -82,6 +82,7 @@ const ENDPOINTS: &[&dyn ApiEndpoint<RestService>] = &[
     &system::GetProtocolConfig,
     &system::GetGasInfo,
     &transactions::ExecuteTransaction,
+    &transactions::GetTransactionSubmissionStatus,
     &coins::GetCoinInfo,
 ];
 
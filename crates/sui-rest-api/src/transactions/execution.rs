@@ -4,12 +4,14 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::extract::{Query, State};
+use axum::extract::{Path, Query, State};
+use axum::Json;
 use schemars::JsonSchema;
 use sui_sdk2::types::framework::Coin;
 use sui_sdk2::types::{
     Address, BalanceChange, CheckpointSequenceNumber, Object, Owner, SignedTransaction,
-    TransactionEffects, TransactionEvents, ValidatorAggregatedSignature,
+    TransactionDigest, TransactionEffects, TransactionEffectsDigest, TransactionEvents,
+    ValidatorAggregatedSignature,
 };
 use tap::Pipe;
 
@@ -17,6 +19,7 @@ use crate::openapi::{
     ApiEndpoint, OperationBuilder, RequestBodyBuilder, ResponseBuilder, RouteHandler,
 };
 use crate::response::Bcs;
+use crate::transactions::TransactionNotFoundError;
 use crate::{accept::AcceptFormat, response::ResponseContent};
 use crate::{RestService, Result};
 
@@ -33,6 +36,15 @@ pub trait TransactionExecutor: Send + Sync {
         sui_types::quorum_driver_types::QuorumDriverError,
     >;
 
+    /// Returns where a transaction submitted for execution is, if it is known.
+    fn transaction_submission_status(
+        &self,
+        digest: sui_types::digests::TransactionDigest,
+    ) -> Result<
+        Option<sui_types::quorum_driver_types::TransactionSubmissionStatus>,
+        sui_types::error::SuiError,
+    >;
+
     //TODO include Simulate functionality
 }
 
@@ -310,6 +322,110 @@ enum BinaryEffectsFinality {
     },
 }
 
+pub struct GetTransactionSubmissionStatus;
+
+impl ApiEndpoint<RestService> for GetTransactionSubmissionStatus {
+    fn method(&self) -> axum::http::Method {
+        axum::http::Method::GET
+    }
+
+    fn path(&self) -> &'static str {
+        "/transactions/{transaction}/submission-status"
+    }
+
+    fn operation(
+        &self,
+        generator: &mut schemars::gen::SchemaGenerator,
+    ) -> openapiv3::v3_1::Operation {
+        OperationBuilder::new()
+            .tag("Transactions")
+            .operation_id("GetTransactionSubmissionStatus")
+            .path_parameter::<TransactionDigest>("transaction", generator)
+            .response(
+                200,
+                ResponseBuilder::new()
+                    .json_content::<TransactionSubmissionStatus>(generator)
+                    .build(),
+            )
+            .response(404, ResponseBuilder::new().build())
+            .build()
+    }
+
+    fn handler(&self) -> RouteHandler<RestService> {
+        RouteHandler::new(self.method(), get_transaction_submission_status)
+    }
+}
+
+/// Transaction Submission Status REST endpoint.
+///
+/// Reports where a transaction is: pending or failed in the QuorumDriver, certified, executed by
+/// this node, or included in a checkpoint executed by this node. The status of a transaction
+/// that this node has not executed is only kept for a limited time after it was submitted.
+async fn get_transaction_submission_status(
+    Path(transaction_digest): Path<TransactionDigest>,
+    State(state): State<Option<Arc<dyn TransactionExecutor>>>,
+) -> Result<Json<TransactionSubmissionStatus>> {
+    let executor = state.ok_or_else(|| anyhow::anyhow!("No Transaction Executor"))?;
+    executor
+        .transaction_submission_status(transaction_digest.into())
+        .map_err(anyhow::Error::from)?
+        .ok_or(TransactionNotFoundError(transaction_digest))?
+        .pipe(TransactionSubmissionStatus::from)
+        .pipe(Json)
+        .pipe(Ok)
+}
+
+/// Response type for the transaction submission status endpoint
+#[serde_with::serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionSubmissionStatus {
+    /// Submitted, and not yet certified by a quorum of validators
+    Pending,
+    /// Certified by a quorum of validators, and not yet executed by this node
+    Certified {
+        effects_digest: TransactionEffectsDigest,
+    },
+    /// Executed by this node, and not yet included in a checkpoint executed by this node
+    ExecutedLocally {
+        effects_digest: TransactionEffectsDigest,
+    },
+    /// Included in a checkpoint executed by this node
+    Checkpointed {
+        effects_digest: TransactionEffectsDigest,
+        #[serde_as(as = "sui_types::sui_serde::BigInt<u64>")]
+        #[schemars(with = "crate::_schemars::U64")]
+        checkpoint: CheckpointSequenceNumber,
+    },
+    /// Could not be finalized
+    Failed { error: String },
+}
+
+impl From<sui_types::quorum_driver_types::TransactionSubmissionStatus>
+    for TransactionSubmissionStatus
+{
+    fn from(value: sui_types::quorum_driver_types::TransactionSubmissionStatus) -> Self {
+        use sui_types::quorum_driver_types::TransactionSubmissionStatus::*;
+
+        match value {
+            Pending => Self::Pending,
+            Certified(effects_digest) => Self::Certified {
+                effects_digest: effects_digest.into(),
+            },
+            ExecutedLocally(effects_digest) => Self::ExecutedLocally {
+                effects_digest: effects_digest.into(),
+            },
+            Checkpointed(effects_digest, _epoch, checkpoint) => Self::Checkpointed {
+                effects_digest: effects_digest.into(),
+                checkpoint,
+            },
+            Failed(error) => Self::Failed {
+                error: error.to_string(),
+            },
+        }
+    }
+}
+
 fn coins(objects: &[Object]) -> impl Iterator<Item = (&Address, Coin<'_>)> + '_ {
     objects.iter().filter_map(|object| {
         let address = match object.owner() {
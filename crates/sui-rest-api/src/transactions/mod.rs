@@ -5,8 +5,10 @@ mod execution;
 pub use execution::EffectsFinality;
 pub use execution::ExecuteTransaction;
 pub use execution::ExecuteTransactionQueryParameters;
+pub use execution::GetTransactionSubmissionStatus;
 pub use execution::TransactionExecutionResponse;
 pub use execution::TransactionExecutor;
+pub use execution::TransactionSubmissionStatus;
 
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
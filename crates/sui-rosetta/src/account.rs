@@ -7,16 +7,16 @@ use axum_extra::extract::WithRejection;
 use futures::StreamExt;
 
 use sui_sdk::rpc_types::StakeStatus;
-use sui_sdk::{SuiClient, SUI_COIN_TYPE};
+use sui_sdk::SuiClient;
 use sui_types::base_types::SuiAddress;
 use tracing::info;
 
 use crate::errors::Error;
 use crate::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountCoinsRequest, AccountCoinsResponse,
-    Amount, Coin, SubAccount, SubAccountType, SubBalance,
+    Amount, Coin, Currency, SubAccount, SubAccountType, SubBalance,
 };
-use crate::{OnlineServerContext, SuiEnv};
+use crate::{OnlineServerContext, SuiEnv, SUI};
 use std::time::Duration;
 
 /// Get an array of all AccountBalances for an AccountIdentifier and the BlockIdentifier
@@ -68,14 +68,14 @@ pub async fn balance(
         }
         Err(Error::RetryExhausted(String::from("retry")))
     } else {
+        let currencies = if request.currencies.is_empty() {
+            vec![SUI.clone()]
+        } else {
+            request.currencies
+        };
         // Get current live balance
         while retry_attempts > 0 {
-            let balances_first = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_first = get_balances(&ctx, address, &currencies).await?;
 
             // Get current latest checkpoint
             let checkpoint1 = ctx
@@ -101,12 +101,7 @@ pub async fn balance(
             }
 
             // Get live balance again
-            let balances_second = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_second = get_balances(&ctx, address, &currencies).await?;
 
             // if those two live balances are equal then that is the current balance for checkpoint2
             if balances_first.eq(&balances_second) {
@@ -116,7 +111,7 @@ pub async fn balance(
                 );
                 return Ok(AccountBalanceResponse {
                     block_identifier: ctx.blocks().create_block_identifier(checkpoint2).await?,
-                    balances: vec![Amount::new(balances_first)],
+                    balances: balances_first,
                 });
             } else {
                 // balances are different so we need to try again.
@@ -131,6 +126,28 @@ pub async fn balance(
     }
 }
 
+/// The live balances of `address` in `currencies`, with the currencies described by the
+/// coin metadata of their coin types.
+async fn get_balances(
+    ctx: &OnlineServerContext,
+    address: SuiAddress,
+    currencies: &[Currency],
+) -> Result<Vec<Amount>, Error> {
+    let mut balances = vec![];
+    for currency in currencies {
+        let coin_type = currency.coin_type()?;
+        let currency = ctx.coin_metadata_cache.get_currency(&coin_type).await?;
+        let balance = ctx
+            .client
+            .coin_read_api()
+            .get_balance(address, Some(coin_type.to_canonical_string(true)))
+            .await?
+            .total_balance as i128;
+        balances.push(Amount::new_with_currency(balance, currency));
+    }
+    Ok(balances)
+}
+
 async fn get_sub_account_balances(
     account_type: SubAccountType,
     client: &SuiClient,
@@ -201,16 +218,28 @@ pub async fn coins(
     WithRejection(Json(request), _): WithRejection<Json<AccountCoinsRequest>, Error>,
 ) -> Result<AccountCoinsResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
-    let coins = context
-        .client
-        .coin_read_api()
-        .get_coins_stream(
-            request.account_identifier.address,
-            Some(SUI_COIN_TYPE.to_string()),
-        )
-        .map(Coin::from)
-        .collect()
-        .await;
+    let currencies = if request.currencies.is_empty() {
+        vec![SUI.clone()]
+    } else {
+        request.currencies
+    };
+    let mut coins = vec![];
+    for currency in currencies {
+        let coin_type = currency.coin_type()?;
+        let currency = context.coin_metadata_cache.get_currency(&coin_type).await?;
+        coins.extend(
+            context
+                .client
+                .coin_read_api()
+                .get_coins_stream(
+                    request.account_identifier.address,
+                    Some(coin_type.to_canonical_string(true)),
+                )
+                .map(|coin| Coin::new(coin, currency.clone()))
+                .collect::<Vec<_>>()
+                .await,
+        );
+    }
 
     Ok(AccountCoinsResponse {
         block_identifier: context.blocks().current_block_identifier().await?,
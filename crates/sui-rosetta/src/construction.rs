@@ -253,6 +253,36 @@ pub async fn metadata(
             let amount = amounts.iter().sum::<u64>();
             (Some(amount), vec![])
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            let coin_type = currency.coin_type()?;
+            if context.coin_metadata_cache.get_currency(&coin_type).await? != *currency {
+                return Err(Error::InvalidInput(format!(
+                    "Currency does not match the coin metadata of {}",
+                    currency.metadata.coin_type
+                )));
+            }
+            let amount = amounts.iter().sum::<u64>();
+            // The coins to pay are objects of the transaction, gas is selected separately.
+            let coins = context
+                .client
+                .coin_read_api()
+                .select_coins(
+                    *sender,
+                    Some(coin_type.to_canonical_string(true)),
+                    amount.into(),
+                    vec![],
+                )
+                .await?
+                .into_iter()
+                .map(|c| c.object_ref())
+                .collect();
+            (Some(0), coins)
+        }
         InternalOperation::Stake { amount, .. } => (*amount, vec![]),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
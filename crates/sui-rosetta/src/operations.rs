@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Not;
 use std::str::FromStr;
 use std::vec;
@@ -26,14 +26,15 @@ use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::object::Owner;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
-use sui_types::transaction::TransactionData;
+use sui_types::transaction::{CallArg, TransactionData, TransactionDataAPI, TransactionKind};
 use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
 
+use crate::state::CoinMetadataCache;
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -101,6 +102,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -135,6 +137,47 @@ impl Operations {
         })
     }
 
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let mut recipients = vec![];
+        let mut amounts = vec![];
+        let mut sender = None;
+        let mut currency: Option<Currency> = None;
+        for op in self {
+            if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
+                if *currency.get_or_insert_with(|| amount.currency.clone()) != amount.currency {
+                    return Err(Error::MalformedOperationError(
+                        "PayCoin operations should all have the same currency.".into(),
+                    ));
+                }
+                if amount.value.is_negative() {
+                    sender = Some(account.address)
+                } else {
+                    recipients.push(account.address);
+                    let amount = amount.value.abs();
+                    if amount > u64::MAX as i128 {
+                        return Err(Error::InvalidInput(
+                            "Input amount exceed u64::MAX".to_string(),
+                        ));
+                    }
+                    amounts.push(amount as u64)
+                }
+            }
+        }
+        let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
+        let currency = currency.ok_or_else(|| Error::MissingInput("Currency".to_string()))?;
+        if currency.coin_type()? == GAS::type_tag() {
+            return Err(Error::InvalidInput(
+                "PayCoin cannot pay SUI, use PaySui instead.".to_string(),
+            ));
+        }
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
         let mut ops = self
             .0
@@ -213,14 +256,17 @@ impl Operations {
         Ok(InternalOperation::WithdrawStake { sender, stake_ids })
     }
 
+    /// `currency` is the currency of the coins paid from input objects, if known. Without it,
+    /// transactions paying coins that are not SUI are parsed as generic transactions.
     fn from_transaction(
         tx: SuiTransactionBlockKind,
         sender: SuiAddress,
         status: Option<OperationStatus>,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         Ok(match tx {
             SuiTransactionBlockKind::ProgrammableTransaction(pt) => {
-                Self::parse_programmable_transaction(sender, status, pt)?
+                Self::parse_programmable_transaction(sender, status, pt, currency)?
             }
             _ => vec![Operation::generic_op(status, sender, tx)],
         })
@@ -230,10 +276,13 @@ impl Operations {
         sender: SuiAddress,
         status: Option<OperationStatus>,
         pt: SuiProgrammableTransactionBlock,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         #[derive(Debug)]
         enum KnownValue {
             GasCoin(u64),
+            // Coin of the currency paid from input objects
+            Coin(u64),
         }
         fn resolve_result(
             known_results: &[Vec<KnownValue>],
@@ -249,16 +298,18 @@ impl Operations {
             known_results: &[Vec<KnownValue>],
             coin: SuiArgument,
             amounts: &[SuiArgument],
+            pays_coin: bool,
         ) -> Option<Vec<KnownValue>> {
-            match coin {
+            let is_gas_coin = match coin {
                 SuiArgument::Result(i) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, 0)?;
+                    matches!(resolve_result(known_results, i, 0)?, KnownValue::GasCoin(_))
                 }
                 SuiArgument::NestedResult(i, j) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)?;
+                    matches!(resolve_result(known_results, i, j)?, KnownValue::GasCoin(_))
                 }
-                SuiArgument::GasCoin => (),
-                // Might not be a SUI coin
+                SuiArgument::GasCoin => true,
+                // Might not be a SUI coin, only known when the currency being paid is known
+                SuiArgument::Input(_) if pays_coin => false,
                 SuiArgument::Input(_) => return None,
             };
             let amounts = amounts
@@ -273,13 +324,27 @@ impl Operations {
                         | SuiArgument::Result(_)
                         | SuiArgument::NestedResult(_, _) => return None,
                     };
-                    Some(KnownValue::GasCoin(value))
+                    Some(if is_gas_coin {
+                        KnownValue::GasCoin(value)
+                    } else {
+                        KnownValue::Coin(value)
+                    })
                 })
                 .collect::<Option<_>>()?;
             Some(amounts)
         }
+        fn merge_coins(
+            coin: SuiArgument,
+            sources: &[SuiArgument],
+            pays_coin: bool,
+        ) -> Option<Vec<KnownValue>> {
+            // Only merging input coins of the currency being paid is known
+            let is_input = |arg: &SuiArgument| matches!(arg, SuiArgument::Input(_));
+            (pays_coin && is_input(&coin) && sources.iter().all(is_input)).then(Vec::new)
+        }
         fn transfer_object(
             aggregated_recipients: &mut HashMap<SuiAddress, u64>,
+            aggregated_coin_recipients: &mut HashMap<SuiAddress, u64>,
             inputs: &[SuiCallArg],
             known_results: &[Vec<KnownValue>],
             objs: &[SuiArgument],
@@ -292,19 +357,16 @@ impl Operations {
                 }
             };
             for obj in objs {
-                let value = match *obj {
-                    SuiArgument::Result(i) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, 0)?;
-                        value
-                    }
-                    SuiArgument::NestedResult(i, j) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, j)?;
-                        value
-                    }
+                let known_value = match *obj {
+                    SuiArgument::Result(i) => resolve_result(known_results, i, 0)?,
+                    SuiArgument::NestedResult(i, j) => resolve_result(known_results, i, j)?,
                     SuiArgument::GasCoin | SuiArgument::Input(_) => return None,
                 };
-                let aggregate = aggregated_recipients.entry(addr).or_default();
-                *aggregate += value;
+                let (aggregated, value) = match known_value {
+                    KnownValue::GasCoin(value) => (&mut *aggregated_recipients, value),
+                    KnownValue::Coin(value) => (&mut *aggregated_coin_recipients, value),
+                };
+                *aggregated.entry(addr).or_default() += value;
             }
             Some(vec![])
         }
@@ -318,7 +380,9 @@ impl Operations {
                 [_, coin, validator] => {
                     let amount = match coin {
                         SuiArgument::Result(i) =>{
-                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))?;
+                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))? else {
+                                return Ok(None);
+                            };
                             value
                         },
                         _ => return Ok(None),
@@ -363,16 +427,20 @@ impl Operations {
         let SuiProgrammableTransactionBlock { inputs, commands } = &pt;
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut aggregated_coin_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let pays_coin = currency.is_some();
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
         for command in commands {
             let result = match command {
                 SuiCommand::SplitCoins(coin, amounts) => {
-                    split_coins(inputs, &known_results, *coin, amounts)
+                    split_coins(inputs, &known_results, *coin, amounts, pays_coin)
                 }
+                SuiCommand::MergeCoins(coin, sources) => merge_coins(*coin, sources, pays_coin),
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
+                    &mut aggregated_coin_recipients,
                     inputs,
                     &known_results,
                     objs,
@@ -408,16 +476,35 @@ impl Operations {
             }
         }
 
-        if !needs_generic && !aggregated_recipients.is_empty() {
-            let total_paid: u64 = aggregated_recipients.values().copied().sum();
-            operations.extend(
-                aggregated_recipients
-                    .into_iter()
-                    .map(|(recipient, amount)| {
-                        Operation::pay_sui(status, recipient, amount.into())
-                    }),
-            );
-            operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+        if !needs_generic
+            && !(aggregated_recipients.is_empty() && aggregated_coin_recipients.is_empty())
+        {
+            if !aggregated_recipients.is_empty() {
+                let total_paid: u64 = aggregated_recipients.values().copied().sum();
+                operations.extend(
+                    aggregated_recipients
+                        .into_iter()
+                        .map(|(recipient, amount)| {
+                            Operation::pay_sui(status, recipient, amount.into())
+                        }),
+                );
+                operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+            }
+            // Coin values are only known when the currency is known.
+            if let (false, Some(currency)) = (aggregated_coin_recipients.is_empty(), currency) {
+                let total_paid: u64 = aggregated_coin_recipients.values().copied().sum();
+                operations.extend(aggregated_coin_recipients.into_iter().map(
+                    |(recipient, amount)| {
+                        Operation::pay_coin(status, recipient, amount.into(), currency.clone())
+                    },
+                ));
+                operations.push(Operation::pay_coin(
+                    status,
+                    sender,
+                    -(total_paid as i128),
+                    currency,
+                ));
+            }
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -455,31 +542,45 @@ impl Operations {
             && tx.function == WITHDRAW_STAKE_FUN_NAME.as_str()
     }
 
+    /// Resolves the currency of each balance change, Rosetta only care about address owner.
+    /// Coin types without `CoinMetadata` are not Rosetta currencies, their changes are skipped.
+    async fn balance_changes_with_currency(
+        balance_changes: Vec<BalanceChange>,
+        cache: &CoinMetadataCache,
+    ) -> Result<Vec<(SuiAddress, Currency, i128)>, Error> {
+        let mut changes = vec![];
+        for balance_change in balance_changes {
+            if let Owner::AddressOwner(owner) = balance_change.owner {
+                if let Some(currency) = cache.find_currency(&balance_change.coin_type).await? {
+                    changes.push((owner, currency, balance_change.amount));
+                }
+            }
+        }
+        Ok(changes)
+    }
+
     fn process_balance_change(
         gas_owner: SuiAddress,
         gas_used: i128,
-        balance_changes: &[BalanceChange],
+        balance_changes: Vec<(SuiAddress, Currency, i128)>,
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
+        balances: HashMap<(SuiAddress, Currency), i128>,
     ) -> impl Iterator<Item = Operation> {
-        let mut balances = balance_changes
-            .iter()
-            .fold(balances, |mut balances, balance_change| {
-                // Rosetta only care about address owner
-                if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
-                    }
-                }
+        let mut balances = balance_changes.into_iter().fold(
+            balances,
+            |mut balances, (owner, currency, amount)| {
+                *balances.entry((owner, currency)).or_default() += amount;
                 balances
-            });
+            },
+        );
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, SUI.clone())).or_default() -= gas_used;
 
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        let balance_change = balances.into_iter().filter(|(_, amount)| *amount != 0).map(
+            move |((addr, currency), amount)| {
+                Operation::balance_change(status, addr, amount, currency)
+            },
+        );
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -494,18 +595,56 @@ impl Operations {
 impl TryFrom<SuiTransactionBlockData> for Operations {
     type Error = Error;
     fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
+        Self::from_data(data, None)
+    }
+}
+
+impl Operations {
+    fn from_data(data: SuiTransactionBlockData, currency: Option<Currency>) -> Result<Self, Error> {
         let sender = *data.sender();
         Ok(Self::new(Self::from_transaction(
             data.transaction().clone(),
             sender,
             None,
+            currency,
         )?))
     }
-}
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+    /// Parse operations from transaction data, `currency` is the currency paid by a PayCoin
+    /// transaction. Without it, the currency carried by the PayCoin transactions built by
+    /// `InternalOperation::try_into_data` is used.
+    pub fn try_from_data(data: TransactionData, currency: Option<Currency>) -> Result<Self, Error> {
+        let currency = currency.or_else(|| Self::pay_coin_currency(&data));
+        struct NoOpsModuleResolver;
+        impl ModuleResolver for NoOpsModuleResolver {
+            type Error = Error;
+            fn get_module(&self, _id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+                Ok(None)
+            }
+        }
+        // Rosetta don't need the call args to be parsed into readable format
+        Self::from_data(
+            SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?,
+            currency,
+        )
+    }
+
+    /// The currency carried by the last input of a PayCoin transaction, if any.
+    fn pay_coin_currency(data: &TransactionData) -> Option<Currency> {
+        let TransactionKind::ProgrammableTransaction(pt) = data.kind() else {
+            return None;
+        };
+        let Some(CallArg::Pure(bytes)) = pt.inputs.last() else {
+            return None;
+        };
+        let currency: String = bcs::from_bytes(bytes).ok()?;
+        serde_json::from_str(&currency).ok()
+    }
+
+    pub async fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -520,7 +659,27 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             - gas_summary.computation_cost as i128;
 
         let status = Some(effect.into_status().into());
-        let ops: Operations = tx.data.try_into()?;
+
+        let balance_changes = Self::balance_changes_with_currency(
+            response
+                .balance_changes
+                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            cache,
+        )
+        .await?;
+        // A transaction moving a single currency other than SUI might be paying that currency.
+        let coin_currencies = balance_changes
+            .iter()
+            .map(|(_, currency, _)| currency)
+            .filter(|currency| **currency != *SUI)
+            .collect::<HashSet<_>>();
+        let currency = if coin_currencies.len() == 1 {
+            coin_currencies.into_iter().next().cloned()
+        } else {
+            None
+        };
+
+        let ops = Self::from_data(tx.data, currency)?;
         let ops = ops.set_status(status).into_iter();
 
         // We will need to subtract the operation amounts from the actual balance
@@ -532,7 +691,9 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
                     if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
                         (&op.account, &op.amount, &op.status)
                     {
-                        *balances.entry(acc.address).or_default() -= amount.value;
+                        *balances
+                            .entry((acc.address, amount.currency.clone()))
+                            .or_default() -= amount.value;
                     }
                     balances
                 });
@@ -564,8 +725,8 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= principal_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -578,9 +739,7 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
         let coin_change_operations = Self::process_balance_change(
             gas_owner,
             gas_used,
-            &response
-                .balance_changes
-                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            balance_changes,
             status,
             accounted_balances,
         );
@@ -602,15 +761,7 @@ fn is_unstake_event(tag: &StructTag) -> bool {
 impl TryFrom<TransactionData> for Operations {
     type Error = Error;
     fn try_from(data: TransactionData) -> Result<Self, Self::Error> {
-        struct NoOpsModuleResolver;
-        impl ModuleResolver for NoOpsModuleResolver {
-            type Error = Error;
-            fn get_module(&self, _id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
-                Ok(None)
-            }
-        }
-        // Rosetta don't need the call args to be parsed into readable format
-        SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?.try_into()
+        Self::try_from_data(data, None)
     }
 }
 
@@ -698,13 +849,35 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(
+        status: Option<OperationStatus>,
+        addr: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
         Self {
             operation_identifier: Default::default(),
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(Amount::new_with_currency(amount, currency)),
             coin_change: None,
             metadata: None,
         }
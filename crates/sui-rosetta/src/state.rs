@@ -3,30 +3,43 @@
 
 use crate::operations::Operations;
 use crate::types::{
-    Block, BlockHash, BlockIdentifier, BlockResponse, Transaction, TransactionIdentifier,
+    Block, BlockHash, BlockIdentifier, BlockResponse, Currency, CurrencyMetadata, Transaction,
+    TransactionIdentifier,
 };
-use crate::Error;
+use crate::{Error, SUI};
 use async_trait::async_trait;
-use std::sync::Arc;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::rpc_types::Checkpoint;
 use sui_sdk::SuiClient;
+use sui_types::gas_coin::GAS;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::TypeTag;
 
 #[cfg(test)]
 #[path = "unit_tests/balance_changing_tx_tests.rs"]
 mod balance_changing_tx_tests;
 
+const COIN_METADATA_CACHE_SIZE: usize = 1000;
+
 #[derive(Clone)]
 pub struct OnlineServerContext {
     pub client: SuiClient,
+    pub coin_metadata_cache: CoinMetadataCache,
     block_provider: Arc<dyn BlockProvider + Send + Sync>,
 }
 
 impl OnlineServerContext {
-    pub fn new(client: SuiClient, block_provider: Arc<dyn BlockProvider + Send + Sync>) -> Self {
+    pub fn new(
+        client: SuiClient,
+        block_provider: Arc<dyn BlockProvider + Send + Sync>,
+        coin_metadata_cache: CoinMetadataCache,
+    ) -> Self {
         Self {
             client,
+            coin_metadata_cache,
             block_provider,
         }
     }
@@ -50,9 +63,68 @@ pub trait BlockProvider {
     ) -> Result<BlockIdentifier, Error>;
 }
 
+/// Rosetta currencies of coin types, built from the `CoinMetadata` of each coin type.
+#[derive(Clone)]
+pub struct CoinMetadataCache {
+    client: SuiClient,
+    currencies: Arc<Mutex<LruCache<TypeTag, Currency>>>,
+}
+
+impl CoinMetadataCache {
+    pub fn new(client: SuiClient) -> Self {
+        Self {
+            client,
+            currencies: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(COIN_METADATA_CACHE_SIZE).unwrap(),
+            ))),
+        }
+    }
+
+    pub async fn get_currency(&self, coin_type: &TypeTag) -> Result<Currency, Error> {
+        self.find_currency(coin_type).await?.ok_or_else(|| {
+            Error::DataError(format!(
+                "Coin metadata not found for coin type [{}]",
+                coin_type.to_canonical_string(true)
+            ))
+        })
+    }
+
+    /// Same as `get_currency`, returning `None` for coin types without `CoinMetadata`.
+    pub async fn find_currency(&self, coin_type: &TypeTag) -> Result<Option<Currency>, Error> {
+        if *coin_type == GAS::type_tag() {
+            return Ok(Some(SUI.clone()));
+        }
+        if let Some(currency) = self.currencies.lock().unwrap().get(coin_type) {
+            return Ok(Some(currency.clone()));
+        }
+        let coin_type_str = coin_type.to_canonical_string(true);
+        let Some(metadata) = self
+            .client
+            .coin_read_api()
+            .get_coin_metadata(coin_type_str.clone())
+            .await?
+        else {
+            return Ok(None);
+        };
+        let currency = Currency {
+            symbol: metadata.symbol,
+            decimals: metadata.decimals as u64,
+            metadata: CurrencyMetadata {
+                coin_type: coin_type_str,
+            },
+        };
+        self.currencies
+            .lock()
+            .unwrap()
+            .put(coin_type.clone(), currency.clone());
+        Ok(Some(currency))
+    }
+}
+
 #[derive(Clone)]
 pub struct CheckpointBlockProvider {
     client: SuiClient,
+    coin_metadata_cache: CoinMetadataCache,
 }
 
 #[async_trait]
@@ -103,8 +175,11 @@ impl BlockProvider for CheckpointBlockProvider {
 }
 
 impl CheckpointBlockProvider {
-    pub fn new(client: SuiClient) -> Self {
-        Self { client }
+    pub fn new(client: SuiClient, coin_metadata_cache: CoinMetadataCache) -> Self {
+        Self {
+            client,
+            coin_metadata_cache,
+        }
     }
 
     async fn create_block_response(&self, checkpoint: Checkpoint) -> Result<BlockResponse, Error> {
@@ -127,7 +202,8 @@ impl CheckpointBlockProvider {
             for tx in transaction_responses.into_iter() {
                 transactions.push(Transaction {
                     transaction_identifier: TransactionIdentifier { hash: tx.digest },
-                    operations: Operations::try_from(tx)?,
+                    operations: Operations::try_from_response(tx, &self.coin_metadata_cache)
+                        .await?,
                     related_transactions: vec![],
                     metadata: None,
                 })
@@ -23,7 +23,7 @@ use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{Argument, CallArg, Command, ObjectArg, TransactionData};
-use sui_types::SUI_SYSTEM_PACKAGE_ID;
+use sui_types::{parse_sui_type_tag, TypeTag, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::errors::{Error, ErrorType};
 use crate::operations::Operations;
@@ -95,11 +95,69 @@ impl From<SuiAddress> for AccountIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    pub metadata: CurrencyMetadata,
+}
+
+impl Currency {
+    pub fn coin_type(&self) -> Result<TypeTag, Error> {
+        if self.metadata.coin_type.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Missing coin type metadata for currency [{}] with {} decimals",
+                self.symbol, self.decimals
+            )));
+        }
+        parse_sui_type_tag(&self.metadata.coin_type).map_err(|e| {
+            Error::InvalidInput(format!(
+                "Invalid coin type [{}]: {e}",
+                self.metadata.coin_type
+            ))
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct CurrencyFields {
+            symbol: String,
+            decimals: u64,
+            metadata: Option<CurrencyMetadata>,
+        }
+        let CurrencyFields {
+            symbol,
+            decimals,
+            metadata,
+        } = CurrencyFields::deserialize(deserializer)?;
+        // Only SUI can be given without metadata, the coin type of any other currency is left
+        // empty for `Currency::coin_type` to reject it.
+        let metadata = metadata.unwrap_or_else(|| {
+            if symbol == SUI.symbol && decimals == SUI.decimals {
+                SUI.metadata.clone()
+            } else {
+                CurrencyMetadata::default()
+            }
+        });
+        Ok(Currency {
+            symbol,
+            decimals,
+            metadata,
+        })
+    }
 }
+
+/// The coin type `T` of the `Coin<T>` objects of a currency.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct CurrencyMetadata {
+    pub coin_type: String,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
     pub network_identifier: NetworkIdentifier,
@@ -159,6 +217,13 @@ impl Amount {
             metadata: None,
         }
     }
+    pub fn new_with_currency(value: i128, currency: Currency) -> Self {
+        Self {
+            value,
+            currency,
+            metadata: None,
+        }
+    }
     pub fn new_from_sub_balances(sub_balances: Vec<SubBalance>) -> Self {
         let value = sub_balances.iter().map(|b| b.value).sum();
 
@@ -197,6 +262,8 @@ pub struct AccountCoinsRequest {
     pub network_identifier: NetworkIdentifier,
     pub account_identifier: AccountIdentifier,
     pub include_mempool: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub currencies: Vec<Currency>,
 }
 #[derive(Serialize)]
 pub struct AccountCoinsResponse {
@@ -214,8 +281,8 @@ pub struct Coin {
     pub amount: Amount,
 }
 
-impl From<sui_sdk::rpc_types::Coin> for Coin {
-    fn from(coin: sui_sdk::rpc_types::Coin) -> Self {
+impl Coin {
+    pub fn new(coin: sui_sdk::rpc_types::Coin, currency: Currency) -> Self {
         Self {
             coin_identifier: CoinIdentifier {
                 identifier: CoinID {
@@ -223,11 +290,7 @@ impl From<sui_sdk::rpc_types::Coin> for Coin {
                     version: coin.version,
                 },
             },
-            amount: Amount {
-                value: coin.balance as i128,
-                currency: SUI.clone(),
-                metadata: None,
-            },
+            amount: Amount::new_with_currency(coin.balance as i128, currency),
         }
     }
 }
@@ -409,6 +472,7 @@ pub enum OperationType {
     StakePrinciple,
     // sui-rosetta supported operation type
     PaySui,
+    PayCoin,
     Stake,
     WithdrawStake,
     // All other Sui transaction types, readonly
@@ -867,6 +931,12 @@ pub enum InternalOperation {
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
     },
+    PayCoin {
+        sender: SuiAddress,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        currency: Currency,
+    },
     Stake {
         sender: SuiAddress,
         validator: SuiAddress,
@@ -883,6 +953,7 @@ impl InternalOperation {
     pub fn sender(&self) -> SuiAddress {
         match self {
             InternalOperation::PaySui { sender, .. }
+            | InternalOperation::PayCoin { sender, .. }
             | InternalOperation::Stake { sender, .. }
             | InternalOperation::WithdrawStake { sender, .. } => *sender,
         }
@@ -899,6 +970,23 @@ impl InternalOperation {
                 builder.pay_sui(recipients, amounts)?;
                 builder.finish()
             }
+            // The coins to pay from are in metadata.objects, gas is paid with metadata.coins.
+            Self::PayCoin {
+                recipients,
+                amounts,
+                currency,
+                ..
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.pay(metadata.objects, recipients, amounts)?;
+                // [WORKAROUND] - the currency paid cannot be found from the transaction data, it is
+                // carried by an unused pure input for /construction/parse to find it, see
+                // `Operations::try_from_data`.
+                let currency = serde_json::to_string(&currency)
+                    .map_err(|e| Error::InvalidInput(format!("Invalid currency: {e}")))?;
+                builder.pure(currency)?;
+                builder.finish()
+            }
             InternalOperation::Stake {
                 validator, amount, ..
             } => {
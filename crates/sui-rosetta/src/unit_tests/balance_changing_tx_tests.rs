@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::operations::Operations;
+use crate::state::CoinMetadataCache;
 use crate::types::{ConstructionMetadata, OperationStatus, OperationType};
+use crate::SUI;
 use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::StructTag;
@@ -749,7 +751,10 @@ async fn test_transaction(
         ));
     }
 
-    let ops = response.clone().try_into().unwrap();
+    let coin_cache = CoinMetadataCache::new(client.clone());
+    let ops = Operations::try_from_response(response.clone(), &coin_cache)
+        .await
+        .unwrap();
     let balances_from_ops = extract_balance_changes_from_ops(ops);
 
     // get actual balance changed after transaction
@@ -779,7 +784,10 @@ fn extract_balance_changes_from_ops(ops: Operations) -> HashMap<SuiAddress, i128
                     | OperationType::StakePrinciple
                     | OperationType::Stake => {
                         if let (Some(addr), Some(amount)) = (op.account, op.amount) {
-                            *changes.entry(addr.address).or_default() += amount.value
+                            // Only SUI balances are checked
+                            if amount.currency == *SUI {
+                                *changes.entry(addr.address).or_default() += amount.value
+                            }
                         }
                     }
                     _ => {}
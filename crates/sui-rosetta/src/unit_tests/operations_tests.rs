@@ -7,8 +7,10 @@ use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 
+use crate::errors::Error;
 use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::types::{ConstructionMetadata, Currency, CurrencyMetadata, OperationType};
+use crate::SUI;
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -50,6 +52,85 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+#[tokio::test]
+async fn test_pay_coin_operation_data_parsing() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let coins = (0..2)
+        .map(|_| {
+            (
+                ObjectID::random(),
+                SequenceNumber::new(),
+                ObjectDigest::random(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let currency = Currency {
+        symbol: "USDC".to_string(),
+        decimals: 6,
+        metadata: CurrencyMetadata {
+            coin_type: "0x2::usdc::USDC".to_string(),
+        },
+    };
+
+    let sender = SuiAddress::random_for_testing_only();
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .pay(
+                coins.clone(),
+                vec![SuiAddress::random_for_testing_only()],
+                vec![10000],
+            )
+            .unwrap();
+        builder.finish()
+    };
+    let gas_price = 10;
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![gas],
+        pt,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        gas_price,
+    );
+
+    // The currency is not part of the transaction data
+    let ops: Operations = data.clone().try_into()?;
+    assert_ne!(Some(OperationType::PayCoin), ops.type_());
+
+    let ops = Operations::try_from_data(data, Some(currency.clone()))?;
+    assert_eq!(Some(OperationType::PayCoin), ops.type_());
+
+    // The transactions built by the construction API carry it, for /construction/parse
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins,
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+    };
+    let data = ops.clone().into_internal()?.try_into_data(metadata)?;
+    let parsed_ops: Operations = data.try_into()?;
+    assert_eq!(ops, parsed_ops);
+
+    Ok(())
+}
+
+#[test]
+fn test_currency_without_metadata() -> Result<(), anyhow::Error> {
+    let currency: Currency = serde_json::from_str(r#"{"symbol": "SUI", "decimals": 9}"#)?;
+    assert_eq!(*SUI, currency);
+
+    let currency: Currency = serde_json::from_str(r#"{"symbol": "USDC", "decimals": 6}"#)?;
+    assert!(matches!(currency.coin_type(), Err(Error::InvalidInput(_))));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
@@ -13,6 +13,7 @@ use sui_rosetta::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, NetworkIdentifier,
     SubAccount, SubAccountType, SuiEnv,
 };
+use sui_rosetta::CoinMetadataCache;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
@@ -174,7 +175,9 @@ async fn test_stake() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -234,7 +237,9 @@ async fn test_stake_all() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -350,7 +355,9 @@ async fn test_withdraw_stake() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -419,7 +426,9 @@ async fn test_pay_sui() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -478,7 +487,9 @@ async fn test_pay_sui_multiple_times() {
             tx.effects.as_ref().unwrap().status()
         );
 
-        let ops2 = Operations::try_from(tx).unwrap();
+        let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+            .await
+            .unwrap();
         assert!(
             ops2.contains(&ops),
             "Operation mismatch. expecting:{}, got:{}",
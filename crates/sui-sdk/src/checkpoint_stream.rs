@@ -0,0 +1,214 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Follows checkpoints in order, with their transactions, effects, events and objects, using
+//! the fullnode REST API.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use futures::StreamExt;
+//! use sui_sdk::checkpoint_stream::CheckpointStreamBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), anyhow::Error> {
+//!     let mut checkpoints = CheckpointStreamBuilder::new("http://127.0.0.1:9000/v2")
+//!         .start_from(0)
+//!         .build();
+//!     while let Some(checkpoint) = checkpoints.next().await {
+//!         let checkpoint = checkpoint?;
+//!         println!(
+//!             "Checkpoint {} has {} transactions",
+//!             checkpoint.checkpoint_summary.sequence_number,
+//!             checkpoint.transactions.len()
+//!         );
+//!     }
+//!     // The stream ends after an error, and can be resumed from `checkpoints.cursor()`.
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::stream::{self, BoxStream};
+use futures::{ready, Stream, StreamExt};
+use sui_rest_api::Client;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::debug;
+
+use crate::error::{Error, SuiRpcResult};
+
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// A builder for a [CheckpointStream].
+///
+/// By default the stream starts from checkpoint 0, fetches up to 8 checkpoints ahead of the
+/// consumer, polls for new checkpoints every second once it has caught up, and retries failed
+/// requests 5 times before returning an error.
+pub struct CheckpointStreamBuilder {
+    rest_url: String,
+    start: CheckpointSequenceNumber,
+    max_in_flight: usize,
+    poll_interval: Duration,
+    max_retries: usize,
+}
+
+impl CheckpointStreamBuilder {
+    /// Create a builder for a stream of the checkpoints of the fullnode REST API at `rest_url`.
+    pub fn new(rest_url: impl AsRef<str>) -> Self {
+        Self {
+            rest_url: rest_url.as_ref().to_string(),
+            start: 0,
+            max_in_flight: 8,
+            poll_interval: Duration::from_secs(1),
+            max_retries: 5,
+        }
+    }
+
+    /// Set the first checkpoint of the stream, e.g. the [CheckpointStream::cursor] of a previous
+    /// stream to resume from.
+    pub fn start_from(mut self, cursor: CheckpointSequenceNumber) -> Self {
+        self.start = cursor;
+        self
+    }
+
+    /// Set how many checkpoints are fetched ahead of the consumer
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Set how often to check for new checkpoints once the stream has caught up
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Set how many times a failed request is retried before the stream returns an error
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn build(self) -> CheckpointStream {
+        let client = Client::new(&self.rest_url);
+        let Self {
+            start,
+            max_in_flight,
+            poll_interval,
+            max_retries,
+            ..
+        } = self;
+
+        // Sequence numbers of the checkpoints to fetch, waiting for each to be certified.
+        let sequence_numbers = stream::unfold(
+            (client.clone(), start, None),
+            move |(client, next, latest)| async move {
+                match wait_for_checkpoint(&client, next, latest, poll_interval, max_retries).await {
+                    Ok(latest) => Some((Ok(next), (client, next + 1, Some(latest)))),
+                    Err(e) => Some((Err(e), (client, next, latest))),
+                }
+            },
+        );
+
+        // Fetch checkpoints concurrently, in order. The consumer not polling the stream stops
+        // further requests once `max_in_flight` checkpoints are buffered.
+        let checkpoints = sequence_numbers
+            .map(move |sequence_number| {
+                let client = client.clone();
+                async move {
+                    let sequence_number = sequence_number?;
+                    with_retries(max_retries, || client.get_full_checkpoint(sequence_number)).await
+                }
+            })
+            .buffered(max_in_flight)
+            .map(|checkpoint| checkpoint.map_err(|e| Error::RestApiError(format!("{e:#}"))));
+
+        CheckpointStream {
+            inner: checkpoints.boxed(),
+            cursor: start,
+            done: false,
+        }
+    }
+}
+
+/// A stream of consecutive checkpoints, which waits for new checkpoints once it has caught up
+/// with the network. Build it with a [CheckpointStreamBuilder].
+///
+/// The stream ends after returning an error. [CheckpointStream::cursor] is the checkpoint to
+/// resume from in a new stream.
+pub struct CheckpointStream {
+    inner: BoxStream<'static, SuiRpcResult<CheckpointData>>,
+    cursor: CheckpointSequenceNumber,
+    done: bool,
+}
+
+impl CheckpointStream {
+    /// The sequence number of the next checkpoint the stream returns.
+    pub fn cursor(&self) -> CheckpointSequenceNumber {
+        self.cursor
+    }
+}
+
+impl Stream for CheckpointStream {
+    type Item = SuiRpcResult<CheckpointData>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let item = ready!(self.inner.poll_next_unpin(cx));
+        match &item {
+            Some(Ok(checkpoint)) => {
+                self.cursor = *checkpoint.checkpoint_summary.sequence_number() + 1;
+            }
+            Some(Err(_)) | None => self.done = true,
+        }
+        Poll::Ready(item)
+    }
+}
+
+/// Wait until checkpoint `next` has been certified, returning the latest certified checkpoint.
+/// `latest` is the latest certified checkpoint already known.
+async fn wait_for_checkpoint(
+    client: &Client,
+    next: CheckpointSequenceNumber,
+    latest: Option<CheckpointSequenceNumber>,
+    poll_interval: Duration,
+    max_retries: usize,
+) -> anyhow::Result<CheckpointSequenceNumber> {
+    if let Some(latest) = latest.filter(|latest| next <= *latest) {
+        return Ok(latest);
+    }
+    loop {
+        let checkpoint = with_retries(max_retries, || client.get_latest_checkpoint()).await?;
+        if *checkpoint.sequence_number() >= next {
+            return Ok(*checkpoint.sequence_number());
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+async fn with_retries<T, F, Fut>(max_retries: usize, mut request: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut delay = Duration::from_millis(100);
+    let mut retries = 0;
+    loop {
+        match request().await {
+            Err(e) if retries < max_retries => {
+                debug!("Retrying failed checkpoint request in {delay:?}: {e:#}");
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
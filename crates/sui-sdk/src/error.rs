@@ -24,6 +24,8 @@ pub enum Error {
     FailToConfirmTransactionStatus(TransactionDigest, u64),
     #[error("Data error: {0}")]
     DataError(String),
+    #[error("Rest API error: {0}")]
+    RestApiError(String),
     #[error("Client/Server api version mismatch, client api version : {client_version}, server api version : {server_version}")]
     ServerVersionMismatch {
         client_version: String,
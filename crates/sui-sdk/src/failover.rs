@@ -0,0 +1,493 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A JSON-RPC client that spreads requests over several HTTP endpoints of the same network.
+//!
+//! Requests are sent to a single active endpoint. When it fails with a transport error the
+//! client fails over to the next healthy endpoint, and a background task periodically checks the
+//! health of every endpoint, moving back to the preferred (earliest configured) endpoint once it
+//! has recovered.
+//!
+//! Optionally, reads can be hedged: if the active endpoint has not answered within a delay, the
+//! same request is also sent to another endpoint and whichever response arrives first is used.
+//!
+//! With consistent reads enabled, every request is batched with a query for the latest checkpoint
+//! of the endpoint serving it, or followed by that query on endpoints that reject batches. The
+//! client remembers the highest checkpoint returned along with a response, and only sends requests
+//! to endpoints that are known to have reached that checkpoint, so that reads never go backwards
+//! in time.
+//!
+//! Batches and notifications fail over the same way as requests, but are never hedged and do not
+//! move the checkpoint reads have to be served from.
+
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future::{join_all, select, Either};
+use jsonrpsee::core::client::{BatchResponse, ClientT};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::Error;
+use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::types::error::CallError;
+use jsonrpsee::types::ErrorObject;
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use sui_json_rpc_api::ReadApiClient;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+/// Requests that are never hedged. Submitting the same transaction to several fullnodes is safe,
+/// but only adds load to the network.
+const UNHEDGED_METHODS: &[&str] = &["sui_executeTransactionBlock"];
+
+/// Batched with every request when reads are consistent, to learn the state it was served from.
+const LATEST_CHECKPOINT_METHOD: &str = "sui_getLatestCheckpointSequenceNumber";
+
+pub(crate) struct FailoverConfig {
+    /// How often the health of every endpoint is checked.
+    pub health_check_interval: Duration,
+    /// Send reads to a second endpoint if the active one has not answered within this delay.
+    pub hedge_after: Option<Duration>,
+    /// Never read from an endpoint that may be behind a checkpoint that has already been read.
+    pub consistent_reads: bool,
+}
+
+pub(crate) struct FailoverClient {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// Endpoints in order of preference.
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+    hedge_after: Option<Duration>,
+    consistent_reads: bool,
+    /// Highest checkpoint returned along with a response served to this client.
+    watermark: AtomicU64,
+}
+
+struct Endpoint {
+    url: String,
+    http: HttpClient,
+    healthy: AtomicBool,
+    /// Latest checkpoint reported by the endpoint, either by a health check or along with a
+    /// response, a lower bound of the state it serves.
+    checkpoint: AtomicU64,
+    /// Cleared once the endpoint has rejected a batch, after which its latest checkpoint is
+    /// queried separately.
+    batches: AtomicBool,
+}
+
+/// Parameters that have already been serialized, so that they can be sent to several endpoints.
+#[derive(Clone)]
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+impl FailoverClient {
+    /// Create a client over `endpoints`, listed in order of preference, with requests initially
+    /// sent to the endpoint at index `active`. Health checks only run when there is more than one
+    /// endpoint, and must be started from within a tokio runtime.
+    pub fn new(
+        endpoints: Vec<(String, HttpClient)>,
+        active: usize,
+        config: FailoverConfig,
+    ) -> Self {
+        assert!(active < endpoints.len(), "active endpoint out of range");
+        let inner = Arc::new(Inner {
+            endpoints: endpoints
+                .into_iter()
+                .map(|(url, http)| Endpoint {
+                    url,
+                    http,
+                    healthy: AtomicBool::new(true),
+                    checkpoint: AtomicU64::new(0),
+                    batches: AtomicBool::new(true),
+                })
+                .collect(),
+            active: AtomicUsize::new(active),
+            hedge_after: config.hedge_after,
+            consistent_reads: config.consistent_reads,
+            watermark: AtomicU64::new(0),
+        });
+        if inner.endpoints.len() > 1 {
+            tokio::spawn(health_check_loop(
+                Arc::downgrade(&inner),
+                config.health_check_interval,
+            ));
+        }
+        Self { inner }
+    }
+
+    /// The http client of the endpoint currently serving requests.
+    pub fn active_http(&self) -> &HttpClient {
+        &self.inner.endpoints[self.inner.active()].http
+    }
+}
+
+impl Inner {
+    fn active(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    fn caught_up(&self, index: usize) -> bool {
+        !self.consistent_reads
+            || self.endpoints[index].checkpoint.load(Ordering::Relaxed)
+                >= self.watermark.load(Ordering::Relaxed)
+    }
+
+    fn can_serve(&self, index: usize) -> bool {
+        self.endpoints[index].healthy.load(Ordering::Relaxed) && self.caught_up(index)
+    }
+
+    /// Ask the endpoint for its latest checkpoint, recording whether it is healthy.
+    async fn refresh(&self, index: usize) -> bool {
+        let endpoint = &self.endpoints[index];
+        match endpoint.http.get_latest_checkpoint_sequence_number().await {
+            Ok(checkpoint) => {
+                endpoint
+                    .checkpoint
+                    .fetch_max(*checkpoint, Ordering::Relaxed);
+                endpoint.healthy.store(true, Ordering::Relaxed);
+                true
+            }
+            Err(e) => {
+                if endpoint.healthy.swap(false, Ordering::Relaxed) {
+                    warn!("RPC endpoint {} failed health check: {e}", endpoint.url);
+                }
+                false
+            }
+        }
+    }
+
+    /// Make sure the endpoint at `index` can serve a consistent read, failing over if it can't.
+    async fn ensure_caught_up(&self, index: usize) -> Result<usize, Error> {
+        if self.caught_up(index) || (self.refresh(index).await && self.caught_up(index)) {
+            return Ok(index);
+        }
+        self.failover(index).await.ok_or_else(|| {
+            Error::Custom(format!(
+                "No RPC endpoint has reached checkpoint {}",
+                self.watermark.load(Ordering::Relaxed)
+            ))
+        })
+    }
+
+    /// Switch away from the endpoint at `failed`, returning the endpoint to use instead.
+    async fn failover(&self, failed: usize) -> Option<usize> {
+        self.endpoints[failed]
+            .healthy
+            .store(false, Ordering::Relaxed);
+        let n = self.endpoints.len();
+        for candidate in (1..n).map(|offset| (failed + offset) % n) {
+            // With consistent reads, confirm how far along the candidate is instead of relying on
+            // the last health check.
+            let usable = if self.consistent_reads {
+                self.refresh(candidate).await && self.caught_up(candidate)
+            } else {
+                self.endpoints[candidate].healthy.load(Ordering::Relaxed)
+            };
+            if !usable {
+                continue;
+            }
+            return Some(
+                match self.active.compare_exchange(
+                    failed,
+                    candidate,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        warn!(
+                            "Failing over from RPC endpoint {} to {}",
+                            self.endpoints[failed].url, self.endpoints[candidate].url
+                        );
+                        candidate
+                    }
+                    // Another request already failed over.
+                    Err(active) => active,
+                },
+            );
+        }
+        None
+    }
+
+    /// Move requests to the most preferred endpoint that can serve them.
+    fn select_preferred(&self) {
+        let Some(preferred) = (0..self.endpoints.len()).find(|i| self.can_serve(*i)) else {
+            return;
+        };
+        let previous = self.active.swap(preferred, Ordering::Relaxed);
+        if previous != preferred {
+            info!(
+                "Switching RPC endpoint from {} to {}",
+                self.endpoints[previous].url, self.endpoints[preferred].url
+            );
+        }
+    }
+
+    /// Record that the endpoint at `index` served a request after reaching `checkpoint`.
+    fn served(&self, index: usize, checkpoint: u64) {
+        self.endpoints[index]
+            .checkpoint
+            .fetch_max(checkpoint, Ordering::Relaxed);
+        self.watermark.fetch_max(checkpoint, Ordering::Relaxed);
+    }
+
+    /// An endpoint other than `active` that a hedged request can be sent to.
+    fn hedge_candidate(&self, active: usize) -> Option<usize> {
+        let n = self.endpoints.len();
+        (1..n)
+            .map(|offset| (active + offset) % n)
+            .find(|i| self.can_serve(*i))
+    }
+
+    async fn request_once<R: DeserializeOwned>(
+        &self,
+        index: usize,
+        method: &str,
+        params: RawParams,
+    ) -> Result<R, Error> {
+        let endpoint = &self.endpoints[index];
+        if !self.consistent_reads {
+            return endpoint.http.request(method, params).await;
+        }
+        if !endpoint.batches.load(Ordering::Relaxed) {
+            return self.request_then_checkpoint(index, method, params).await;
+        }
+
+        // Ask for the latest checkpoint in the same batch, so that the watermark follows the
+        // state the response was actually served from, rather than the last health check.
+        let mut batch = BatchRequestBuilder::new();
+        batch.insert(method, params.clone())?;
+        batch.insert(LATEST_CHECKPOINT_METHOD, RawParams(None))?;
+        let rejected = match endpoint
+            .http
+            .batch_request::<serde_json::Value>(batch)
+            .await
+        {
+            Ok(responses) => {
+                let mut responses = responses.into_iter();
+                match (responses.next(), responses.next()) {
+                    (Some(response), Some(checkpoint)) => {
+                        return self.batch_served(index, method, response, checkpoint)
+                    }
+                    _ => "incomplete batch response".to_string(),
+                }
+            }
+            Err(e) if is_transport_error(&e) => return Err(e),
+            Err(e) => e.to_string(),
+        };
+        warn!(
+            "RPC endpoint {} does not support batches ({rejected}), querying its latest checkpoint separately",
+            endpoint.url
+        );
+        endpoint.batches.store(false, Ordering::Relaxed);
+        self.request_then_checkpoint(index, method, params).await
+    }
+
+    /// Unpack the response to a request batched with a query for the latest checkpoint.
+    fn batch_served<R: DeserializeOwned>(
+        &self,
+        index: usize,
+        method: &str,
+        response: Result<serde_json::Value, ErrorObject<'_>>,
+        checkpoint: Result<serde_json::Value, ErrorObject<'_>>,
+    ) -> Result<R, Error> {
+        let response = response.map_err(|e| Error::Call(CallError::Custom(e.into_owned())))?;
+        let response = serde_json::from_value(response).map_err(Error::ParseError)?;
+        match checkpoint
+            .ok()
+            .and_then(|checkpoint| checkpoint.as_str()?.parse().ok())
+        {
+            Some(checkpoint) => self.served(index, checkpoint),
+            None => debug!(
+                "RPC endpoint {} did not return its latest checkpoint with {method}",
+                self.endpoints[index].url
+            ),
+        }
+        Ok(response)
+    }
+
+    /// Send a request to an endpoint that rejects batches, then ask for its latest checkpoint,
+    /// which the state the response was served from can't be ahead of. If that fails, the
+    /// checkpoint of the last health check is used instead.
+    async fn request_then_checkpoint<R: DeserializeOwned>(
+        &self,
+        index: usize,
+        method: &str,
+        params: RawParams,
+    ) -> Result<R, Error> {
+        let endpoint = &self.endpoints[index];
+        let response = endpoint.http.request(method, params).await?;
+        if !self.refresh(index).await {
+            debug!(
+                "RPC endpoint {} did not return its latest checkpoint after {method}",
+                endpoint.url
+            );
+        }
+        self.served(index, endpoint.checkpoint.load(Ordering::Relaxed));
+        Ok(response)
+    }
+
+    /// Send a request with `send` to the active endpoint, once it can serve consistent reads,
+    /// failing over to the next endpoint whenever it fails with a transport error.
+    async fn with_failover<T, F, Fut>(&self, method: &str, mut send: F) -> Result<T, Error>
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut active = self.ensure_caught_up(self.active()).await?;
+        for _ in 0..self.endpoints.len() {
+            let error = match send(active).await {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+            if !is_transport_error(&error) {
+                return Err(error);
+            }
+            warn!(
+                "Request {method} to RPC endpoint {} failed: {error}",
+                self.endpoints[active].url
+            );
+            match self.failover(active).await {
+                Some(next) => active = next,
+                None => return Err(error),
+            }
+        }
+        Err(Error::Custom(format!(
+            "Request {method} failed on every RPC endpoint"
+        )))
+    }
+
+    async fn hedged_request<R: DeserializeOwned>(
+        &self,
+        active: usize,
+        method: &str,
+        params: RawParams,
+        delay: Duration,
+    ) -> Result<R, Error> {
+        let Some(backup_index) = self.hedge_candidate(active) else {
+            return self.request_once(active, method, params).await;
+        };
+        let primary = self.request_once(active, method, params.clone());
+        let backup = async move {
+            tokio::time::sleep(delay).await;
+            debug!(
+                "Hedging {method} to RPC endpoint {}",
+                self.endpoints[backup_index].url
+            );
+            self.request_once(backup_index, method, params).await
+        };
+        futures::pin_mut!(primary, backup);
+        let remaining = match select(primary, backup).await {
+            Either::Left((Ok(response), _)) | Either::Right((Ok(response), _)) => {
+                return Ok(response)
+            }
+            Either::Left((Err(e), _)) if !is_transport_error(&e) => return Err(e),
+            Either::Left((Err(_), backup)) => Either::Left(backup),
+            Either::Right((Err(_), primary)) => Either::Right(primary),
+        };
+        match remaining {
+            Either::Left(backup) => backup.await,
+            Either::Right(primary) => primary.await,
+        }
+    }
+}
+
+impl Debug for FailoverClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let urls: Vec<_> = self.inner.endpoints.iter().map(|e| &e.url).collect();
+        f.debug_struct("FailoverClient")
+            .field("endpoints", &urls)
+            .field("active", &self.inner.active())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ClientT for FailoverClient {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let inner = &self.inner;
+        let params = RawParams(params.to_rpc_params().map_err(Error::ParseError)?);
+        inner
+            .with_failover(method, |active| {
+                inner.endpoints[active]
+                    .http
+                    .notification(method, params.clone())
+            })
+            .await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let inner = &self.inner;
+        let params = RawParams(params.to_rpc_params().map_err(Error::ParseError)?);
+        let hedge_after = inner
+            .hedge_after
+            .filter(|_| !UNHEDGED_METHODS.contains(&method));
+
+        inner
+            .with_failover(method, |active| {
+                let params = params.clone();
+                async move {
+                    match hedge_after {
+                        Some(delay) => inner.hedged_request(active, method, params, delay).await,
+                        None => inner.request_once(active, method, params).await,
+                    }
+                }
+            })
+            .await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, Error>
+    where
+        R: DeserializeOwned + Debug + 'a,
+    {
+        let inner = &self.inner;
+        inner
+            .with_failover("batch", |active| {
+                inner.endpoints[active].http.batch_request(batch.clone())
+            })
+            .await
+    }
+}
+
+fn is_transport_error(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Transport(_) | Error::RequestTimeout | Error::RestartNeeded(_)
+    )
+}
+
+async fn health_check_loop(inner: Weak<Inner>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        // Stop once the client has been dropped.
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        join_all((0..inner.endpoints.len()).map(|i| inner.refresh(i))).await;
+        inner.select_preferred();
+    }
+}
@@ -0,0 +1,516 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A pool of gas coins for an address that submits many transactions concurrently.
+//!
+//! Transactions that run in parallel from the same address must not use the same gas coin,
+//! otherwise they contend for the coin's lock and at most one of them can be certified. The
+//! [GasPool] splits the address's SUI into a set of coins, leases each coin to one transaction at
+//! a time, and tracks the coin's latest version from the transaction's effects so that the next
+//! lease can use it right away.
+//!
+//! A background task periodically reloads the coins owned by the address, merges coins whose
+//! balance has dropped below [GasPoolConfig::min_coin_balance] and splits the largest coin when
+//! the pool has fewer than [GasPoolConfig::target_coin_count] coins.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use sui_sdk::gas_pool::{GasPool, GasPoolConfig};
+//! use sui_sdk::SuiClientBuilder;
+//! use sui_types::crypto::{get_key_pair, AccountKeyPair, SuiKeyPair};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), anyhow::Error> {
+//!     let sui = SuiClientBuilder::default().build_localnet().await?;
+//!     let (_, keypair): (_, AccountKeyPair) = get_key_pair();
+//!     let pool = GasPool::new(sui, SuiKeyPair::Ed25519(keypair), GasPoolConfig::default()).await?;
+//!
+//!     let lease = pool.lease(10_000_000).await?;
+//!     println!("Paying for gas with {:?}", lease.object_ref());
+//!     // Once the transaction has been executed, return the coin with its effects using
+//!     // `lease.release(&effects)`, or return it unchanged if it was never submitted.
+//!     lease.release_unused();
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail};
+use futures::StreamExt;
+use sui_json_rpc_types::{
+    SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions,
+};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::SuiKeyPair;
+use sui_types::object::Owner;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::transaction::{Transaction, TransactionData};
+use tokio::sync::{mpsc, Notify};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use crate::{SuiClient, SUI_COIN_TYPE};
+
+#[derive(Clone, Debug)]
+pub struct GasPoolConfig {
+    /// Number of gas coins the pool tries to maintain.
+    pub target_coin_count: usize,
+    /// Coins with a lower balance are merged together with the largest coin.
+    pub min_coin_balance: u64,
+    /// Gas budget of the transactions the pool submits to split and merge coins.
+    pub maintenance_gas_budget: u64,
+    /// How often the pool reloads its coins and rebalances them.
+    pub rebalance_interval: Duration,
+    /// How long a coin whose lease was dropped without being released is kept out of the pool,
+    /// unless a newer version of it is found earlier. The transaction that used it may still be
+    /// in flight.
+    pub dropped_lease_timeout: Duration,
+}
+
+impl Default for GasPoolConfig {
+    fn default() -> Self {
+        Self {
+            target_coin_count: 16,
+            min_coin_balance: 100_000_000,
+            maintenance_gas_budget: 50_000_000,
+            rebalance_interval: Duration::from_secs(30),
+            dropped_lease_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Leases gas coins of a single address to concurrent transactions. See the
+/// [module documentation](self) for details.
+pub struct GasPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client: SuiClient,
+    keypair: SuiKeyPair,
+    owner: SuiAddress,
+    config: GasPoolConfig,
+    state: Mutex<PoolState>,
+    /// Notified whenever a coin is returned to the pool.
+    returned: Notify,
+    /// Ids of coins whose lease was dropped without being released.
+    dropped_leases: mpsc::UnboundedSender<ObjectID>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    available: BTreeMap<ObjectID, PooledCoin>,
+    leased: BTreeMap<ObjectID, PooledCoin>,
+    /// Coins whose latest version is unknown, with the version they were leased at and when
+    /// their lease was dropped.
+    dropped: BTreeMap<ObjectID, (PooledCoin, Instant)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PooledCoin {
+    object_ref: ObjectRef,
+    balance: u64,
+}
+
+impl PoolState {
+    fn coin_count(&self) -> usize {
+        self.available.len() + self.leased.len() + self.dropped.len()
+    }
+
+    fn take(&mut self, id: &ObjectID) -> Option<PooledCoin> {
+        let coin = self.available.remove(id)?;
+        self.leased.insert(*id, coin);
+        Some(coin)
+    }
+}
+
+impl GasPool {
+    /// Create a pool over the SUI coins owned by the address of `keypair`, splitting them up to
+    /// `config.target_coin_count` coins before returning.
+    pub async fn new(
+        client: SuiClient,
+        keypair: SuiKeyPair,
+        config: GasPoolConfig,
+    ) -> anyhow::Result<Self> {
+        let (dropped_leases, dropped_lease_rx) = mpsc::unbounded_channel();
+        let inner = Arc::new(Inner {
+            client,
+            owner: SuiAddress::from(&keypair.public()),
+            keypair,
+            config,
+            state: Mutex::new(PoolState::default()),
+            returned: Notify::new(),
+            dropped_leases,
+        });
+        inner.reload().await?;
+        inner.rebalance().await?;
+        tokio::spawn(maintenance_loop(Arc::downgrade(&inner), dropped_lease_rx));
+        Ok(Self { inner })
+    }
+
+    /// The address whose coins are pooled.
+    pub fn owner(&self) -> SuiAddress {
+        self.inner.owner
+    }
+
+    /// Number of coins currently available for lease.
+    pub fn available_coins(&self) -> usize {
+        self.inner.state.lock().unwrap().available.len()
+    }
+
+    /// Lease a coin with a balance of at least `budget`, waiting for one to be returned if all
+    /// such coins are leased. Fails if no coin in the pool has a large enough balance.
+    pub async fn lease(&self, budget: u64) -> anyhow::Result<GasCoinLease> {
+        loop {
+            // Register for notifications before checking, so that a coin returned in between
+            // is not missed.
+            let returned = self.inner.returned.notified();
+            {
+                let mut state = self.inner.state.lock().unwrap();
+                // Use the smallest sufficient coin, to keep large coins for large budgets.
+                let id = state
+                    .available
+                    .iter()
+                    .filter(|(_, coin)| coin.balance >= budget)
+                    .min_by_key(|(_, coin)| coin.balance)
+                    .map(|(id, _)| *id);
+                if let Some(coin) = id.and_then(|id| state.take(&id)) {
+                    return Ok(GasCoinLease {
+                        inner: self.inner.clone(),
+                        coin: Some(coin),
+                    });
+                }
+                let could_fit = state
+                    .leased
+                    .values()
+                    .chain(state.dropped.values().map(|(coin, _)| coin))
+                    .any(|coin| coin.balance >= budget);
+                if !could_fit {
+                    bail!(
+                        "No gas coin of {} has a balance of at least {budget}",
+                        self.inner.owner
+                    );
+                }
+            }
+            returned.await;
+        }
+    }
+}
+
+/// A gas coin leased from a [GasPool]. Return it with [GasCoinLease::release] once the
+/// transaction using it has been executed. If the lease is dropped instead, the coin only
+/// returns to the pool once its latest version has been reloaded.
+pub struct GasCoinLease {
+    inner: Arc<Inner>,
+    coin: Option<PooledCoin>,
+}
+
+impl GasCoinLease {
+    pub fn object_ref(&self) -> ObjectRef {
+        self.coin().object_ref
+    }
+
+    pub fn balance(&self) -> u64 {
+        self.coin().balance
+    }
+
+    fn coin(&self) -> &PooledCoin {
+        self.coin.as_ref().expect("lease has not been released")
+    }
+
+    /// Return the coin to the pool, updated with the effects of the transaction that used it
+    /// for gas.
+    ///
+    /// The new balance is computed from the transaction's gas cost, so it is only exact if the
+    /// transaction did not otherwise spend from the gas coin. It is corrected the next time the
+    /// pool reloads its coins.
+    pub fn release(mut self, effects: &SuiTransactionBlockEffects) {
+        let coin = self.coin.take().unwrap();
+        let id = coin.object_ref.0;
+        let gas = effects.gas_object();
+        let mut state = self.inner.state.lock().unwrap();
+        state.leased.remove(&id);
+        if gas.reference.object_id != id {
+            warn!(
+                "Effects of {} did not use gas coin {id}",
+                effects.transaction_digest()
+            );
+            state.dropped.insert(id, (coin, Instant::now()));
+            return;
+        }
+        if gas.owner != Owner::AddressOwner(self.inner.owner) {
+            debug!("Gas coin {id} is no longer owned by {}", self.inner.owner);
+            return;
+        }
+        let net_gas_usage = effects.gas_cost_summary().net_gas_usage();
+        state.available.insert(
+            id,
+            PooledCoin {
+                object_ref: gas.reference.to_object_ref(),
+                balance: coin.balance.saturating_add_signed(-net_gas_usage),
+            },
+        );
+        drop(state);
+        self.inner.returned.notify_waiters();
+    }
+
+    /// Return the coin to the pool unchanged, because no transaction using it was submitted.
+    pub fn release_unused(mut self) {
+        let coin = self.coin.take().unwrap();
+        let mut state = self.inner.state.lock().unwrap();
+        state.leased.remove(&coin.object_ref.0);
+        state.available.insert(coin.object_ref.0, coin);
+        drop(state);
+        self.inner.returned.notify_waiters();
+    }
+}
+
+impl Drop for GasCoinLease {
+    fn drop(&mut self) {
+        let Some(coin) = self.coin.take() else {
+            return;
+        };
+        let id = coin.object_ref.0;
+        let mut state = self.inner.state.lock().unwrap();
+        state.leased.remove(&id);
+        state.dropped.insert(id, (coin, Instant::now()));
+        drop(state);
+        // The maintenance task stops once the pool and all leases are gone.
+        let _ = self.inner.dropped_leases.send(id);
+    }
+}
+
+impl Inner {
+    /// Reload the coins owned by the pool's address, returning dropped leases to the pool once
+    /// they have been used or have timed out.
+    async fn reload(&self) -> anyhow::Result<()> {
+        let coins: BTreeMap<_, _> = self
+            .client
+            .coin_read_api()
+            .get_coins_stream(self.owner, Some(SUI_COIN_TYPE.to_string()))
+            .map(|coin| {
+                (
+                    coin.coin_object_id,
+                    PooledCoin {
+                        object_ref: coin.object_ref(),
+                        balance: coin.balance,
+                    },
+                )
+            })
+            .collect()
+            .await;
+
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let timeout = self.config.dropped_lease_timeout;
+        let dropped = std::mem::take(&mut state.dropped);
+        for (id, (leased, dropped_at)) in dropped {
+            let timed_out = now.duration_since(dropped_at) >= timeout;
+            match coins.get(&id) {
+                Some(coin) if timed_out || coin.object_ref.1 > leased.object_ref.1 => {
+                    state.available.insert(id, *coin);
+                }
+                // Deleted, or transferred away.
+                None if timed_out => {}
+                // The transaction that leased it may not have been executed yet.
+                _ => {
+                    state.dropped.insert(id, (leased, dropped_at));
+                }
+            }
+        }
+        state.available.retain(|id, _| coins.contains_key(id));
+        for (id, coin) in coins {
+            if state.leased.contains_key(&id) || state.dropped.contains_key(&id) {
+                continue;
+            }
+            // The fullnode may not have indexed the latest version released from effects yet.
+            let entry = state.available.entry(id).or_insert(coin);
+            if coin.object_ref.1 > entry.object_ref.1 {
+                *entry = coin;
+            }
+        }
+        drop(state);
+        self.returned.notify_waiters();
+        Ok(())
+    }
+
+    /// Merge coins below the minimum balance into the largest coin, and split the largest coin
+    /// if the pool has fewer coins than its target.
+    async fn rebalance(&self) -> anyhow::Result<()> {
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+        let budget = self.config.maintenance_gas_budget;
+
+        let merge = {
+            let mut state = self.state.lock().unwrap();
+            let largest = state
+                .available
+                .iter()
+                .max_by_key(|(_, coin)| coin.balance)
+                .map(|(id, _)| *id);
+            let small: Vec<_> = state
+                .available
+                .iter()
+                .filter(|(id, coin)| {
+                    Some(**id) != largest && coin.balance < self.config.min_coin_balance
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            match largest {
+                Some(largest) if !small.is_empty() => Some((
+                    state.take(&largest).unwrap(),
+                    small
+                        .iter()
+                        .map(|id| state.take(id).unwrap())
+                        .collect::<Vec<_>>(),
+                )),
+                _ => None,
+            }
+        };
+        if let Some((gas, small)) = merge {
+            info!("Merging {} small gas coins of {}", small.len(), self.owner);
+            let data = TransactionData::new_pay_all_sui(
+                self.owner,
+                small.iter().map(|coin| coin.object_ref).collect(),
+                self.owner,
+                gas.object_ref,
+                budget,
+                gas_price,
+            );
+            self.execute_maintenance(data, std::iter::once(&gas).chain(&small))
+                .await?;
+        }
+
+        let split = {
+            let mut state = self.state.lock().unwrap();
+            let missing = self
+                .config
+                .target_coin_count
+                .saturating_sub(state.coin_count());
+            let largest = state
+                .available
+                .values()
+                .max_by_key(|coin| coin.balance)
+                .copied();
+            match largest {
+                Some(largest) if missing > 0 => {
+                    // Share the largest coin's balance evenly between itself and the `missing`
+                    // new coins, without creating coins below the minimum balance.
+                    let spendable = largest.balance.saturating_sub(budget);
+                    let pieces =
+                        (missing as u64 + 1).min(spendable / self.config.min_coin_balance.max(1));
+                    (pieces > 1).then(|| {
+                        state.take(&largest.object_ref.0);
+                        (largest, pieces - 1, spendable / pieces)
+                    })
+                }
+                _ => None,
+            }
+        };
+        if let Some((gas, count, amount)) = split {
+            info!("Splitting {count} gas coins of {amount} for {}", self.owner);
+            let data = TransactionData::new_pay_sui(
+                self.owner,
+                vec![],
+                vec![self.owner; count as usize],
+                vec![amount; count as usize],
+                gas.object_ref,
+                budget,
+                gas_price,
+            )?;
+            self.execute_maintenance(data, std::iter::once(&gas))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Execute a transaction that splits or merges `coins`, which have been taken out of the
+    /// pool, and reload the pool to pick up the resulting coins.
+    async fn execute_maintenance(
+        &self,
+        data: TransactionData,
+        coins: impl Iterator<Item = &PooledCoin>,
+    ) -> anyhow::Result<()> {
+        let ids: Vec<_> = coins.map(|coin| coin.object_ref.0).collect();
+        let result = self.execute(data).await;
+        {
+            let mut state = self.state.lock().unwrap();
+            for id in &ids {
+                if let Some(coin) = state.leased.remove(id) {
+                    if result.is_err() {
+                        // The transaction may still have been executed.
+                        state.dropped.insert(*id, (coin, Instant::now()));
+                    }
+                }
+            }
+        }
+        result?;
+        self.reload().await
+    }
+
+    async fn execute(&self, data: TransactionData) -> anyhow::Result<()> {
+        let tx = Transaction::from_data_and_signer(data, vec![&self.keypair]);
+        let response = self
+            .client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+        let effects = response
+            .effects
+            .ok_or_else(|| anyhow!("Missing effects of transaction {}", response.digest))?;
+        if !effects.status().is_ok() {
+            bail!(
+                "Gas pool transaction {} failed: {:?}",
+                response.digest,
+                effects.status()
+            );
+        }
+        Ok(())
+    }
+}
+
+async fn maintenance_loop(
+    inner: Weak<Inner>,
+    mut dropped_leases: mpsc::UnboundedReceiver<ObjectID>,
+) {
+    let Some(interval) = inner.upgrade().map(|inner| inner.config.rebalance_interval) else {
+        return;
+    };
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval.tick().await;
+    loop {
+        let rebalance = tokio::select! {
+            _ = interval.tick() => true,
+            dropped = dropped_leases.recv() => match dropped {
+                Some(id) => {
+                    debug!("Lease of gas coin {id} was dropped");
+                    false
+                }
+                None => return,
+            },
+        };
+        // Stop once the pool has been dropped.
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        let result = if rebalance {
+            match inner.reload().await {
+                Ok(()) => inner.rebalance().await,
+                Err(e) => Err(e),
+            }
+        } else {
+            inner.reload().await
+        };
+        if let Err(e) = result {
+            warn!("Failed to maintain gas pool of {}: {e}", inner.owner);
+        }
+    }
+}
@@ -99,9 +99,13 @@ use sui_types::base_types::{ObjectID, ObjectInfo, SuiAddress};
 
 use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriverApi, ReadApi};
 use crate::error::{Error, SuiRpcResult};
+use crate::failover::{FailoverClient, FailoverConfig};
 
 pub mod apis;
+pub mod checkpoint_stream;
 pub mod error;
+mod failover;
+pub mod gas_pool;
 pub mod json_rpc_error;
 pub mod sui_client_config;
 pub mod wallet_context;
@@ -122,6 +126,13 @@ pub const SUI_TESTNET_URL: &str = "https://fullnode.testnet.sui.io:443";
 /// value of your choice to prevent the inactive WS subscription being
 /// disconnected due to proxy timeout.
 ///
+/// Additional HTTP endpoints of the same network can be configured with
+/// `fallback_urls`. Requests fail over to them when the endpoint in use returns a
+/// transport error, and move back to the earliest configured endpoint once periodic
+/// health checks see it recover. Reads can optionally be hedged across endpoints with
+/// `hedge_reads_after`, and `consistent_reads` prevents reads from going back to an
+/// earlier checkpoint when switching endpoints.
+///
 /// # Examples
 ///
 /// ```rust,no_run
@@ -142,6 +153,10 @@ pub struct SuiClientBuilder {
     ws_url: Option<String>,
     ws_ping_interval: Option<Duration>,
     basic_auth: Option<(String, String)>,
+    fallback_urls: Vec<String>,
+    health_check_interval: Duration,
+    hedge_after: Option<Duration>,
+    consistent_reads: bool,
 }
 
 impl Default for SuiClientBuilder {
@@ -152,6 +167,10 @@ impl Default for SuiClientBuilder {
             ws_url: None,
             ws_ping_interval: None,
             basic_auth: None,
+            fallback_urls: vec![],
+            health_check_interval: Duration::from_secs(5),
+            hedge_after: None,
+            consistent_reads: false,
         }
     }
 }
@@ -187,6 +206,36 @@ impl SuiClientBuilder {
         self
     }
 
+    /// Set the HTTP URLs to fail over to, in order of preference, when the URL passed to
+    /// `build` is unavailable
+    pub fn fallback_urls(mut self, urls: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.fallback_urls = urls
+            .into_iter()
+            .map(|url| url.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Set how often the health of the HTTP endpoints is checked when fallback URLs are set
+    pub fn health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// Also send a read to a second healthy endpoint if the first has not answered within
+    /// `delay`, using whichever response arrives first
+    pub fn hedge_reads_after(mut self, delay: Duration) -> Self {
+        self.hedge_after = Some(delay);
+        self
+    }
+
+    /// Only read from endpoints known to have reached the latest checkpoint already read from,
+    /// so that reads never go backwards when switching endpoints
+    pub fn consistent_reads(mut self, consistent_reads: bool) -> Self {
+        self.consistent_reads = consistent_reads;
+        self
+    }
+
     /// Returns a [SuiClient] object connected to the Sui network running at the URI provided.
     ///
     /// # Examples
@@ -244,15 +293,38 @@ impl SuiClientBuilder {
             None
         };
 
-        let http = HttpClientBuilder::default()
-            .max_request_body_size(2 << 30)
-            .max_concurrent_requests(self.max_concurrent_requests)
-            .set_headers(headers.clone())
-            .request_timeout(self.request_timeout)
-            .build(http)?;
-
-        let info = Self::get_server_info(&http, &ws).await?;
-
+        let endpoints = std::iter::once(http.as_ref().to_string())
+            .chain(self.fallback_urls)
+            .map(|url| {
+                let http = HttpClientBuilder::default()
+                    .max_request_body_size(2 << 30)
+                    .max_concurrent_requests(self.max_concurrent_requests)
+                    .set_headers(headers.clone())
+                    .request_timeout(self.request_timeout)
+                    .build(&url)?;
+                Ok((url, http))
+            })
+            .collect::<SuiRpcResult<Vec<_>>>()?;
+
+        // Start with the first endpoint that responds.
+        let mut server_info = Err(Error::DataError("No HTTP endpoint configured".into()));
+        for (i, (_, http)) in endpoints.iter().enumerate() {
+            server_info = Self::get_server_info(http, &ws).await.map(|info| (i, info));
+            if server_info.is_ok() {
+                break;
+            }
+        }
+        let (active, info) = server_info?;
+
+        let http = FailoverClient::new(
+            endpoints,
+            active,
+            FailoverConfig {
+                health_check_interval: self.health_check_interval,
+                hedge_after: self.hedge_after,
+                consistent_reads: self.consistent_reads,
+            },
+        );
         let rpc = RpcClient { http, ws, info };
         let api = Arc::new(rpc);
         let read_api = Arc::new(ReadApi::new(api.clone()));
@@ -439,7 +511,7 @@ pub struct SuiClient {
 }
 
 pub(crate) struct RpcClient {
-    http: HttpClient,
+    http: FailoverClient,
     ws: Option<WsClient>,
     info: ServerInfo,
 }
@@ -523,9 +595,9 @@ impl SuiClient {
         &self.transaction_builder
     }
 
-    /// Returns a reference to the underlying http client.
+    /// Returns a reference to the underlying http client of the endpoint currently in use.
     pub fn http(&self) -> &HttpClient {
-        &self.api.http
+        self.api.http.active_http()
     }
 
     /// Returns a reference to the underlying WebSocket client, if any.
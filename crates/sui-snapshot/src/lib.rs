@@ -114,6 +114,16 @@ use tokio::time::Instant;
 ///├──────────────────────────────┤
 ///│      sha3 <32 bytes>         │
 ///└──────────────────────────────┘
+///
+/// Delta Snapshots
+/// A delta snapshot of epoch E only contains the objects that changed since a full snapshot of an
+/// earlier base epoch B, so that live_objects(E) = live_objects(B) - removed + added. Objects that
+/// are added or modified since B are stored in *.obj and *.ref files as above. References to
+/// objects of B that are deleted, wrapped or modified by E are stored in REMOVED files
+/// (<bucket_number>_<partition_number>.rm), in the REFERENCE file format. Delta snapshots use
+/// version 2 of the MANIFEST, which records the base epoch. A delta is always based on a full
+/// snapshot, so restoring a delta needs exactly one full snapshot and one delta, and the result
+/// is verified against the root state hash of epoch E.
 const OBJECT_FILE_MAGIC: u32 = 0x00B7EC75;
 const REFERENCE_FILE_MAGIC: u32 = 0xDEADBEEF;
 const MANIFEST_FILE_MAGIC: u32 = 0x00C0FFEE;
@@ -142,6 +152,7 @@ const FILE_METADATA_BYTES: usize =
 pub enum FileType {
     Object = 0,
     Reference,
+    RemovedReference,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -162,6 +173,9 @@ impl FileMetadata {
             FileType::Reference => {
                 dir_path.child(&*format!("{}_{}.ref", self.bucket_num, self.part_num))
             }
+            FileType::RemovedReference => {
+                dir_path.child(&*format!("{}_{}.rm", self.bucket_num, self.part_num))
+            }
         }
     }
     pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
@@ -177,30 +191,53 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+/// Manifest of a delta snapshot, see the format description above.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ManifestV2 {
+    pub snapshot_version: u8,
+    pub address_length: u64,
+    pub file_metadata: Vec<FileMetadata>,
+    pub epoch: u64,
+    /// Epoch of the full snapshot this snapshot is a delta of.
+    pub base_epoch: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    V2(ManifestV2),
 }
 
 impl Manifest {
     pub fn snapshot_version(&self) -> u8 {
         match self {
             Self::V1(manifest) => manifest.snapshot_version,
+            Self::V2(manifest) => manifest.snapshot_version,
         }
     }
     pub fn address_length(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.address_length,
+            Self::V2(manifest) => manifest.address_length,
         }
     }
     pub fn file_metadata(&self) -> &Vec<FileMetadata> {
         match self {
             Self::V1(manifest) => &manifest.file_metadata,
+            Self::V2(manifest) => &manifest.file_metadata,
         }
     }
     pub fn epoch(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.epoch,
+            Self::V2(manifest) => manifest.epoch,
+        }
+    }
+    /// The epoch of the full snapshot this snapshot is a delta of, if it is a delta snapshot.
+    pub fn base_epoch(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(manifest) => Some(manifest.base_epoch),
         }
     }
 }
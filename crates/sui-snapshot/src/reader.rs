@@ -26,6 +26,7 @@ use std::sync::Arc;
 use sui_config::object_storage_config::ObjectStoreConfig;
 use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
 use sui_core::authority::AuthorityStore;
+use sui_core::state_accumulator::StateAccumulator;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::http::HttpDownloaderBuilder;
 use sui_storage::object_store::util::{copy_file, copy_files, path_to_filesystem};
@@ -47,6 +48,8 @@ pub struct StateSnapshotReaderV1 {
     local_object_store: Arc<dyn ObjectStorePutExt>,
     ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
     object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    removed_ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    base_epoch: Option<u64>,
     indirect_objects_threshold: usize,
     m: MultiProgress,
     concurrency: usize,
@@ -93,7 +96,7 @@ impl StateSnapshotReaderV1 {
             &manifest_file_path,
         )?)?;
         let snapshot_version = manifest.snapshot_version();
-        if snapshot_version != 1u8 {
+        if snapshot_version != 1u8 && snapshot_version != 2u8 {
             return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
         }
         if manifest.address_length() as usize > ObjectID::LENGTH {
@@ -107,6 +110,7 @@ impl StateSnapshotReaderV1 {
         }
         let mut object_files = BTreeMap::new();
         let mut ref_files = BTreeMap::new();
+        let mut removed_ref_files = BTreeMap::new();
         for file_metadata in manifest.file_metadata() {
             match file_metadata.file_type {
                 FileType::Object => {
@@ -121,11 +125,18 @@ impl StateSnapshotReaderV1 {
                         .or_insert_with(BTreeMap::new);
                     entry.insert(file_metadata.part_num, file_metadata.clone());
                 }
+                FileType::RemovedReference => {
+                    let entry = removed_ref_files
+                        .entry(file_metadata.bucket_num)
+                        .or_insert_with(BTreeMap::new);
+                    entry.insert(file_metadata.part_num, file_metadata.clone());
+                }
             }
         }
         let epoch_dir_path = Path::from(epoch_dir);
         let files: Vec<Path> = ref_files
             .values()
+            .chain(removed_ref_files.values())
             .flat_map(|entry| {
                 let files: Vec<_> = entry
                     .values()
@@ -160,12 +171,23 @@ impl StateSnapshotReaderV1 {
             local_object_store,
             ref_files,
             object_files,
+            removed_ref_files,
+            base_epoch: manifest.base_epoch(),
             indirect_objects_threshold,
             m,
             concurrency: download_concurrency.get(),
         })
     }
 
+    /// The epoch of the full snapshot this snapshot is a delta of, if it is a delta snapshot.
+    pub fn base_epoch(&self) -> Option<u64> {
+        self.base_epoch
+    }
+
+    /// Restores the snapshot into `perpetual_db`. A delta snapshot must be read after the full
+    /// snapshot of its base epoch has been read into the same db, as it removes objects of the
+    /// base that are no longer live. Accumulators sent to `sender` only cover the objects added
+    /// by a delta; the removed objects are available from `removed_ref_iter`.
     pub async fn read(
         &mut self,
         perpetual_db: &AuthorityPerpetualTables,
@@ -234,6 +256,11 @@ impl StateSnapshotReaderV1 {
         if let Some(handle) = accum_handle {
             handle.await?;
         }
+
+        if self.base_epoch.is_some() {
+            info!("Removing objects of the base snapshot that are no longer live");
+            AuthorityStore::bulk_remove_live_objects(perpetual_db, self.removed_ref_iter()?)?;
+        }
         Ok(())
     }
 
@@ -286,7 +313,7 @@ impl StateSnapshotReaderV1 {
                         // TODO depending on concurrency limit here, we may be
                         // materializing too many refs into memory at once.
                         // This is only done because ObjectRefIter is not Send
-                        let obj_digests = {
+                        let obj_refs = {
                             let file_metadata = ref_files
                                 .get(bucket)
                                 .expect("No ref files found for bucket: {bucket_num}")
@@ -301,13 +328,17 @@ impl StateSnapshotReaderV1 {
                             )
                             .expect("Failed to create object ref iter")
                         }
-                        .map(|obj_ref| obj_ref.2)
-                        .collect::<Vec<ObjectDigest>>();
+                        .collect::<Vec<ObjectRef>>();
                         let sender_clone = sender.clone();
                         tokio::spawn(async move {
                             let mut partial_acc = Accumulator::default();
-                            let num_objects = obj_digests.len();
-                            partial_acc.insert_all(obj_digests);
+                            let num_objects = obj_refs.len();
+                            for obj_ref in &obj_refs {
+                                StateAccumulator::accumulate_live_object_ref(
+                                    &mut partial_acc,
+                                    obj_ref,
+                                );
+                            }
                             sender_clone
                                 .send((partial_acc, num_objects as u64))
                                 .await
@@ -467,6 +498,24 @@ impl StateSnapshotReaderV1 {
         )
     }
 
+    /// An iterator over the references of all objects removed since the base snapshot, if this is
+    /// a delta snapshot.
+    pub fn removed_ref_iter(&self) -> Result<impl Iterator<Item = ObjectRef>> {
+        let iters = self
+            .removed_ref_files
+            .values()
+            .flat_map(|part_files| part_files.values())
+            .map(|file_metadata| {
+                ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    self.epoch_dir(),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(iters.into_iter().flatten())
+    }
+
     fn buckets(&self) -> Result<Vec<u32>> {
         Ok(self.ref_files.keys().copied().collect())
     }
@@ -475,7 +524,7 @@ impl StateSnapshotReaderV1 {
         Path::from(format!("epoch_{}", self.epoch))
     }
 
-    fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
+    pub(crate) fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
         let manifest_file = File::open(path)?;
         let manifest_file_size = manifest_file.metadata()?.len() as usize;
         let mut manifest_reader = BufReader::new(manifest_file);
@@ -15,9 +15,10 @@ use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_protocol_config::ProtocolConfig;
 use sui_types::accumulator::Accumulator;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
 use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
 use tempfile::tempdir;
 
 fn temp_dir() -> std::path::PathBuf {
@@ -98,7 +99,7 @@ async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
     let root_accumulator =
         ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
     snapshot_writer
-        .write_internal(0, true, perpetual_db.clone(), root_accumulator)
+        .write_internal(0, None, true, perpetual_db.clone(), root_accumulator)
         .await?;
     let local_store_restore_config = ObjectStoreConfig {
         object_store: Some(ObjectStoreType::File),
@@ -153,7 +154,7 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     let root_accumulator =
         ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
     snapshot_writer
-        .write_internal(0, true, perpetual_db.clone(), root_accumulator)
+        .write_internal(0, None, true, perpetual_db.clone(), root_accumulator)
         .await?;
     let local_store_restore_config = ObjectStoreConfig {
         object_store: Some(ObjectStoreType::File),
@@ -181,3 +182,262 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_snapshot_delta() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+
+    // Full snapshot of epoch 0
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .write_internal(0, None, true, perpetual_db.clone(), root_accumulator)
+    .await?;
+
+    // Modify some objects and create new ones, then write a delta snapshot of epoch 1
+    let owner = SuiAddress::random_for_testing_only();
+    let modified = ObjectID::in_range(ObjectID::from_single_byte(0x10), 100)?;
+    for id in modified {
+        let object =
+            Object::with_id_owner_version_for_testing(id, SequenceNumber::from_u64(5), owner);
+        perpetual_db.insert_object_test_only(object)?;
+    }
+    let created = ObjectID::in_range(ObjectID::ZERO, 1050)?;
+    for id in created.into_iter().skip(1000) {
+        let object =
+            Object::with_id_owner_version_for_testing(id, SequenceNumber::from_u64(5), owner);
+        perpetual_db.insert_object_test_only(object)?;
+    }
+    let epoch_1_accumulator = accumulate_live_object_set(&perpetual_db, true);
+    StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .write_internal(
+        1,
+        Some(0),
+        true,
+        perpetual_db.clone(),
+        ECMHLiveObjectSetDigest::from(epoch_1_accumulator.digest()),
+    )
+    .await?;
+
+    // Restore the base and then the delta
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let mut delta_reader = StateSnapshotReaderV1::new(
+        1,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    assert_eq!(delta_reader.base_epoch(), Some(0));
+    let mut base_reader = StateSnapshotReaderV1::new(
+        0,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    assert_eq!(base_reader.base_epoch(), None);
+
+    // The delta removes the previous versions of the modified objects
+    let mut restored_accumulator = Accumulator::default();
+    let removed: Vec<_> = delta_reader.removed_ref_iter()?.collect();
+    assert_eq!(removed.len(), 100);
+    for object_ref in &removed {
+        StateAccumulator::remove_live_object_ref(&mut restored_accumulator, object_ref);
+    }
+
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    base_reader
+        .read(
+            &restored_perpetual_db,
+            abort_registration,
+            Some(sender.clone()),
+        )
+        .await?;
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    delta_reader
+        .read(&restored_perpetual_db, abort_registration, Some(sender))
+        .await?;
+    let mut num_objects = 0;
+    while let Some((partial_acc, num_partial_objects)) = receiver.recv().await {
+        restored_accumulator.union(&partial_acc);
+        num_objects += num_partial_objects;
+    }
+    assert_eq!(num_objects - removed.len() as u64, 1050);
+    assert_eq!(restored_accumulator.digest(), epoch_1_accumulator.digest());
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_delta_with_tombstones() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+
+    // Full snapshot of epoch 0, including objects that are wrapped at that point
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    let ids = ObjectID::in_range(ObjectID::ZERO, 1030)?;
+    let (deleted, rest) = ids.split_at(10);
+    let (wrapped, rest) = rest.split_at(10);
+    let unwrapped = &rest[980..];
+    for id in unwrapped {
+        perpetual_db
+            .insert_tombstone_test_only(ObjectKey(*id, SequenceNumber::from_u64(3)), true)?;
+    }
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .write_internal(0, None, true, perpetual_db.clone(), root_accumulator)
+    .await?;
+
+    // Delete and wrap some objects, and unwrap the wrapped ones, then write a delta snapshot of
+    // epoch 1
+    let version = SequenceNumber::from_u64(5);
+    for id in deleted {
+        perpetual_db.insert_tombstone_test_only(ObjectKey(*id, version), false)?;
+    }
+    for id in wrapped {
+        perpetual_db.insert_tombstone_test_only(ObjectKey(*id, version), true)?;
+    }
+    let owner = SuiAddress::random_for_testing_only();
+    for id in unwrapped {
+        let object = Object::with_id_owner_version_for_testing(*id, version, owner);
+        perpetual_db.insert_object_test_only(object)?;
+    }
+    let epoch_1_accumulator = accumulate_live_object_set(&perpetual_db, true);
+    StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .write_internal(
+        1,
+        Some(0),
+        true,
+        perpetual_db.clone(),
+        ECMHLiveObjectSetDigest::from(epoch_1_accumulator.digest()),
+    )
+    .await?;
+
+    // Restore the base and then the delta
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let mut delta_reader = StateSnapshotReaderV1::new(
+        1,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    let mut base_reader = StateSnapshotReaderV1::new(
+        0,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+
+    // The delta removes the deleted and newly wrapped objects, and the tombstones of the unwrapped
+    // ones
+    let mut restored_accumulator = Accumulator::default();
+    let removed: Vec<_> = delta_reader.removed_ref_iter()?.collect();
+    assert_eq!(removed.len(), 30);
+    assert_eq!(
+        removed
+            .iter()
+            .filter(|object_ref| object_ref.2 == ObjectDigest::OBJECT_DIGEST_WRAPPED)
+            .count(),
+        unwrapped.len()
+    );
+    for object_ref in &removed {
+        StateAccumulator::remove_live_object_ref(&mut restored_accumulator, object_ref);
+    }
+
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    base_reader
+        .read(
+            &restored_perpetual_db,
+            abort_registration,
+            Some(sender.clone()),
+        )
+        .await?;
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    delta_reader
+        .read(&restored_perpetual_db, abort_registration, Some(sender))
+        .await?;
+    while let Some((partial_acc, _)) = receiver.recv().await {
+        restored_accumulator.union(&partial_acc);
+    }
+    assert_eq!(restored_accumulator.digest(), epoch_1_accumulator.digest());
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    Ok(())
+}
@@ -4,6 +4,7 @@
 use crate::writer::StateSnapshotWriterV1;
 use anyhow::Result;
 use bytes::Bytes;
+use object_store::path::Path;
 use object_store::DynObjectStore;
 use prometheus::{
     register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
@@ -18,7 +19,7 @@ use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::db_checkpoint_handler::{STATE_SNAPSHOT_COMPLETED_MARKER, SUCCESS_MARKER};
 use sui_storage::object_store::util::{
-    find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
+    exists, find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
     run_manifest_update_loop,
 };
 use sui_storage::FileCompression;
@@ -65,6 +66,9 @@ pub struct StateSnapshotUploader {
     snapshot_store: Arc<DynObjectStore>,
     /// Time interval to check for presence of new db checkpoint
     interval: Duration,
+    /// If set, only epochs that are a multiple of this interval get a full snapshot, and other
+    /// epochs get a delta snapshot based on the preceding full snapshot
+    full_snapshot_interval: Option<u64>,
     metrics: Arc<StateSnapshotUploaderMetrics>,
 }
 
@@ -74,6 +78,7 @@ impl StateSnapshotUploader {
         staging_path: &std::path::Path,
         snapshot_store_config: ObjectStoreConfig,
         interval_s: u64,
+        full_snapshot_interval: Option<u64>,
         registry: &Registry,
         checkpoint_store: Arc<CheckpointStore>,
     ) -> Result<Arc<Self>> {
@@ -95,6 +100,7 @@ impl StateSnapshotUploader {
             staging_store: staging_store_config.make()?,
             snapshot_store: snapshot_store_config.make()?,
             interval: Duration::from_secs(interval_s),
+            full_snapshot_interval: full_snapshot_interval.filter(|interval| *interval > 1),
             metrics: StateSnapshotUploaderMetrics::new(registry),
         }))
     }
@@ -117,7 +123,11 @@ impl StateSnapshotUploader {
         dirs.sort_by_key(|(epoch_num, _path)| *epoch_num);
         for (epoch, db_path) in dirs {
             if missing_epochs.contains(epoch) || *epoch >= last_missing_epoch {
-                info!("Starting state snapshot creation for epoch: {}", *epoch);
+                let base_epoch = self.delta_base_epoch(*epoch).await;
+                info!(
+                    "Starting state snapshot creation for epoch: {}, base epoch: {:?}",
+                    *epoch, base_epoch
+                );
                 let state_snapshot_writer = StateSnapshotWriterV1::new_from_store(
                     &self.staging_path,
                     &self.staging_store,
@@ -140,7 +150,7 @@ impl StateSnapshotUploader {
                     .expect("Expected at least one commitment")
                     .clone();
                 state_snapshot_writer
-                    .write(*epoch, db, state_hash_commitment)
+                    .write(*epoch, base_epoch, db, state_hash_commitment)
                     .await?;
                 info!("State snapshot creation successful for epoch: {}", *epoch);
                 // Drop marker in the output directory that upload completed successfully
@@ -202,6 +212,26 @@ impl StateSnapshotUploader {
         Ok(())
     }
 
+    /// The epoch of the full snapshot a snapshot of `epoch` should be a delta of, or None if a full
+    /// snapshot should be written.
+    async fn delta_base_epoch(&self, epoch: u64) -> Option<u64> {
+        let interval = self.full_snapshot_interval?;
+        let base_epoch = epoch - epoch % interval;
+        if base_epoch == epoch {
+            return None;
+        }
+        let success_marker = Path::from(format!("epoch_{}", base_epoch)).child(SUCCESS_MARKER);
+        if exists(&self.snapshot_store, &success_marker).await {
+            Some(base_epoch)
+        } else {
+            info!(
+                "Full snapshot for epoch {} is missing, writing a full snapshot for epoch {}",
+                base_epoch, epoch
+            );
+            None
+        }
+    }
+
     async fn get_missing_epochs(&self) -> Result<Vec<u64>> {
         let missing_epochs = find_missing_epochs_dirs(&self.snapshot_store, SUCCESS_MARKER).await?;
         Ok(missing_epochs.to_vec())
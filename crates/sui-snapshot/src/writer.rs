@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+use crate::reader::{ObjectRefIter, StateSnapshotReaderV1};
 use crate::{
     compute_sha3_checksum, create_file_metadata, FileCompression, FileMetadata, FileType, Manifest,
-    ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
+    ManifestV1, ManifestV2, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
     OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use byteorder::{BigEndian, ByteOrder};
 use fastcrypto::hash::MultisetHash;
 use futures::StreamExt;
@@ -15,7 +16,7 @@ use integer_encoding::VarInt;
 use object_store::path::Path;
 use object_store::DynObjectStore;
 use std::collections::hash_map::Entry::Vacant;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Seek, SeekFrom, Write};
@@ -28,7 +29,9 @@ use sui_core::authority::CHAIN_IDENTIFIER;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 use sui_storage::blob::{Blob, BlobEncoding, BLOB_ENCODING_BYTES};
-use sui_storage::object_store::util::{copy_file, delete_recursively, path_to_filesystem};
+use sui_storage::object_store::util::{
+    copy_file, copy_files, delete_recursively, path_to_filesystem,
+};
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::{ObjectID, ObjectRef};
 use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
@@ -38,7 +41,7 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::task::JoinHandle;
 use tokio_stream::wrappers::ReceiverStream;
-use tracing::debug;
+use tracing::{debug, info};
 
 /// LiveObjectSetWriterV1 writes live object set. It creates multiple *.obj files and *.ref file
 struct LiveObjectSetWriterV1 {
@@ -193,19 +196,147 @@ impl LiveObjectSetWriterV1 {
         Ok(())
     }
     fn write_object_ref(&mut self, object_ref: &ObjectRef) -> Result<()> {
-        let mut buf = [0u8; OBJECT_REF_BYTES];
-        buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
-        BigEndian::write_u64(
-            &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
-            object_ref.1.value(),
-        );
-        buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
-            .copy_from_slice(object_ref.2.as_ref());
-        self.ref_wbuf.write_all(&buf)?;
+        self.ref_wbuf.write_all(&encode_object_ref(object_ref))?;
         Ok(())
     }
 }
 
+fn encode_object_ref(object_ref: &ObjectRef) -> [u8; OBJECT_REF_BYTES] {
+    let mut buf = [0u8; OBJECT_REF_BYTES];
+    buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
+    BigEndian::write_u64(
+        &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
+        object_ref.1.value(),
+    );
+    buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
+        .copy_from_slice(object_ref.2.as_ref());
+    buf
+}
+
+/// RemovedRefWriterV1 writes the references of objects removed since the base snapshot of a delta
+/// snapshot. It creates multiple *.rm files in the REFERENCE file format.
+struct RemovedRefWriterV1 {
+    dir_path: PathBuf,
+    bucket_num: u32,
+    current_part_num: u32,
+    wbuf: BufWriter<File>,
+    n: usize,
+    files: Vec<FileMetadata>,
+    sender: Option<Sender<FileMetadata>>,
+    file_compression: FileCompression,
+}
+
+impl RemovedRefWriterV1 {
+    fn new(
+        dir_path: PathBuf,
+        bucket_num: u32,
+        file_compression: FileCompression,
+        sender: Sender<FileMetadata>,
+    ) -> Result<Self> {
+        let part_num = 1;
+        let (n, f) = Self::removed_ref_file(dir_path.clone(), bucket_num, part_num)?;
+        Ok(RemovedRefWriterV1 {
+            dir_path,
+            bucket_num,
+            current_part_num: part_num,
+            wbuf: BufWriter::new(f),
+            n,
+            files: vec![],
+            sender: Some(sender),
+            file_compression,
+        })
+    }
+    pub fn write(&mut self, object_ref: &ObjectRef) -> Result<()> {
+        if self.n + OBJECT_REF_BYTES > FILE_MAX_BYTES {
+            self.finalize()?;
+            self.current_part_num += 1;
+            let (n, f) = Self::removed_ref_file(
+                self.dir_path.clone(),
+                self.bucket_num,
+                self.current_part_num,
+            )?;
+            self.n = n;
+            self.wbuf = BufWriter::new(f);
+        }
+        self.wbuf.write_all(&encode_object_ref(object_ref))?;
+        self.n += OBJECT_REF_BYTES;
+        Ok(())
+    }
+    pub fn done(mut self) -> Result<Vec<FileMetadata>> {
+        self.finalize()?;
+        self.sender = None;
+        Ok(self.files.clone())
+    }
+    fn removed_ref_file(
+        dir_path: PathBuf,
+        bucket_num: u32,
+        part_num: u32,
+    ) -> Result<(usize, File)> {
+        let path = dir_path.join(format!("{bucket_num}_{part_num}.rm"));
+        let tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.rm.tmp"));
+        let mut f = File::create(tmp_path.clone())?;
+        f.rewind()?;
+        let mut metab = [0u8; MAGIC_BYTES];
+        BigEndian::write_u32(&mut metab, REFERENCE_FILE_MAGIC);
+        let n = f.write(&metab)?;
+        drop(f);
+        fs::rename(tmp_path, path.clone())?;
+        let mut f = OpenOptions::new().append(true).open(path)?;
+        f.seek(SeekFrom::Start(n as u64))?;
+        Ok((n, f))
+    }
+    fn finalize(&mut self) -> Result<()> {
+        self.wbuf.flush()?;
+        self.wbuf.get_ref().sync_data()?;
+        let off = self.wbuf.get_ref().stream_position()?;
+        self.wbuf.get_ref().set_len(off)?;
+        let file_path = self
+            .dir_path
+            .join(format!("{}_{}.rm", self.bucket_num, self.current_part_num));
+        let file_metadata = create_file_metadata(
+            &file_path,
+            self.file_compression,
+            FileType::RemovedReference,
+            self.bucket_num,
+            self.current_part_num,
+        )?;
+        self.files.push(file_metadata.clone());
+        if let Some(sender) = &self.sender {
+            sender.blocking_send(file_metadata)?;
+        }
+        Ok(())
+    }
+}
+
+/// The object references of the full snapshot a delta snapshot is based on, downloaded to the
+/// local staging dir.
+struct BaseSnapshot {
+    epoch: u64,
+    local_staging_dir: PathBuf,
+    /// Reference files ordered by bucket and partition number.
+    ref_files: Vec<FileMetadata>,
+}
+
+impl BaseSnapshot {
+    /// An iterator over the references of all live objects of the base snapshot, in the order
+    /// they were written in.
+    fn ref_iter(&self) -> Result<impl Iterator<Item = ObjectRef>> {
+        let dir_path = Path::from(format!("epoch_{}", self.epoch));
+        let iters = self
+            .ref_files
+            .iter()
+            .map(|file_metadata| {
+                ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir.clone(),
+                    dir_path.clone(),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(iters.into_iter().flatten())
+    }
+}
+
 /// StateSnapshotWriterV1 writes snapshot files to a local staging dir and simultaneously uploads them
 /// to a remote object store
 pub struct StateSnapshotWriterV1 {
@@ -255,9 +386,12 @@ impl StateSnapshotWriterV1 {
         })
     }
 
+    /// Writes a snapshot of the live object set at the end of `epoch`. If `base_epoch` is set, only
+    /// the objects that changed since the full snapshot of `base_epoch` are written.
     pub async fn write(
         self,
         epoch: u64,
+        base_epoch: Option<u64>,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         root_state_hash: ECMHLiveObjectSetDigest,
     ) -> Result<()> {
@@ -274,6 +408,7 @@ impl StateSnapshotWriterV1 {
         let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
         self.write_internal(
             epoch,
+            base_epoch,
             include_wrapped_tombstone,
             perpetual_db,
             root_state_hash,
@@ -284,10 +419,15 @@ impl StateSnapshotWriterV1 {
     pub(crate) async fn write_internal(
         mut self,
         epoch: u64,
+        base_epoch: Option<u64>,
         include_wrapped_tombstone: bool,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         root_state_hash: ECMHLiveObjectSetDigest,
     ) -> Result<()> {
+        let base = match base_epoch {
+            Some(base_epoch) => Some(self.download_base(epoch, base_epoch).await?),
+            None => None,
+        };
         self.setup_epoch_dir(epoch).await?;
 
         let manifest_file_path = self.epoch_dir(epoch).child("MANIFEST");
@@ -305,7 +445,15 @@ impl StateSnapshotWriterV1 {
                 Self::bucket_func,
                 include_wrapped_tombstone,
                 root_state_hash,
-            )
+                base.as_ref(),
+            )?;
+            if let Some(base) = base {
+                fs::remove_dir_all(path_to_filesystem(
+                    base.local_staging_dir,
+                    &Path::from(format!("epoch_{}", base.epoch)),
+                )?)?;
+            }
+            Ok::<(), anyhow::Error>(())
         });
         write_handler.await?.context(format!(
             "Failed to write state snapshot for epoch: {}",
@@ -327,6 +475,76 @@ impl StateSnapshotWriterV1 {
         Ok(())
     }
 
+    /// Downloads the manifest and reference files of the full snapshot of `base_epoch` to the local
+    /// staging dir.
+    async fn download_base(&self, epoch: u64, base_epoch: u64) -> Result<BaseSnapshot> {
+        if base_epoch >= epoch {
+            bail!("Base epoch {base_epoch} of delta snapshot must be before epoch {epoch}");
+        }
+        let base_dir = self.epoch_dir(base_epoch);
+        let local_base_dir_path = path_to_filesystem(self.local_staging_dir.clone(), &base_dir)?;
+        if local_base_dir_path.exists() {
+            fs::remove_dir_all(&local_base_dir_path)?;
+        }
+        fs::create_dir_all(&local_base_dir_path)?;
+
+        let manifest_file_path = base_dir.child("MANIFEST");
+        copy_file(
+            &manifest_file_path,
+            &manifest_file_path,
+            &self.remote_object_store,
+            &self.local_staging_store,
+        )
+        .await?;
+        let manifest = StateSnapshotReaderV1::read_manifest(path_to_filesystem(
+            self.local_staging_dir.clone(),
+            &manifest_file_path,
+        )?)?;
+        if manifest.epoch() != base_epoch {
+            bail!("Downloaded manifest is not for epoch: {base_epoch}");
+        }
+        if let Some(base_of_base) = manifest.base_epoch() {
+            bail!(
+                "Snapshot of epoch {base_epoch} is a delta of epoch {base_of_base}, \
+                 delta snapshots must be based on a full snapshot"
+            );
+        }
+
+        let mut ref_files: BTreeMap<(u32, u32), FileMetadata> = BTreeMap::new();
+        for file_metadata in manifest.file_metadata() {
+            if file_metadata.file_type == FileType::Reference {
+                ref_files.insert(
+                    (file_metadata.bucket_num, file_metadata.part_num),
+                    file_metadata.clone(),
+                );
+            }
+        }
+        let ref_files: Vec<_> = ref_files.into_values().collect();
+        let files: Vec<Path> = ref_files
+            .iter()
+            .map(|file_metadata| file_metadata.file_path(&base_dir))
+            .collect();
+        info!(
+            "Downloading {} reference files of base snapshot for epoch: {}",
+            files.len(),
+            base_epoch
+        );
+        copy_files(
+            &files,
+            &files,
+            &self.remote_object_store,
+            &self.local_staging_store,
+            NonZeroUsize::new(self.concurrency).unwrap(),
+            None,
+        )
+        .await?;
+        Ok(BaseSnapshot {
+            epoch: base_epoch,
+            local_staging_dir: self.local_staging_dir.clone(),
+            ref_files,
+        })
+    }
+
     fn start_upload(
         &self,
         epoch: u64,
@@ -374,16 +592,60 @@ impl StateSnapshotWriterV1 {
         bucket_func: F,
         include_wrapped_tombstone: bool,
         root_state_hash: ECMHLiveObjectSetDigest,
+        base: Option<&BaseSnapshot>,
     ) -> Result<()>
     where
         F: Fn(&LiveObject) -> u32,
     {
         let mut object_writers: HashMap<u32, LiveObjectSetWriterV1> = HashMap::new();
+        let mut removed_writer: Option<RemovedRefWriterV1> = None;
         let local_staging_dir_path =
             path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
         let mut acc = Accumulator::default();
+        // Both the live object set and the base snapshot's references are ordered by object ID, so
+        // the delta is computed by merging them.
+        let mut base_refs = match base {
+            Some(base) => Some(base.ref_iter()?.peekable()),
+            None => None,
+        };
+        let mut last_base_id: Option<ObjectID> = None;
+        // Returns whether `base_ref` is still live, and records it as removed otherwise.
+        let mut merge_base_ref =
+            |base_ref: ObjectRef, live_ref: Option<ObjectRef>| -> Result<bool> {
+                if last_base_id.is_some_and(|id| id >= base_ref.0) {
+                    bail!("References of the base snapshot are not ordered by object ID");
+                }
+                last_base_id = Some(base_ref.0);
+                if live_ref == Some(base_ref) {
+                    return Ok(true);
+                }
+                if removed_writer.is_none() {
+                    removed_writer = Some(RemovedRefWriterV1::new(
+                        local_staging_dir_path.clone(),
+                        1,
+                        self.file_compression,
+                        sender.clone(),
+                    )?);
+                }
+                removed_writer
+                    .as_mut()
+                    .context("Unexpected missing removed reference writer")?
+                    .write(&base_ref)?;
+                Ok(false)
+            };
         for object in perpetual_db.iter_live_object_set(include_wrapped_tombstone) {
             StateAccumulator::accumulate_live_object(&mut acc, &object);
+            if let Some(base_refs) = base_refs.as_mut() {
+                let object_ref = object.object_reference();
+                let mut unchanged = false;
+                while let Some(base_ref) = base_refs.next_if(|base_ref| base_ref.0 <= object_ref.0)
+                {
+                    unchanged = merge_base_ref(base_ref, Some(object_ref))?;
+                }
+                if unchanged {
+                    continue;
+                }
+            }
             let bucket_num = bucket_func(&object);
             if let Vacant(entry) = object_writers.entry(bucket_num) {
                 entry.insert(LiveObjectSetWriterV1::new(
@@ -403,23 +665,45 @@ impl StateSnapshotWriterV1 {
             root_state_hash,
             "Root state hash mismatch!"
         );
+        if let Some(base_refs) = base_refs {
+            for base_ref in base_refs {
+                merge_base_ref(base_ref, None)?;
+            }
+        }
         let mut files = vec![];
         for (_, writer) in object_writers.into_iter() {
             files.extend(writer.done()?);
         }
-        self.write_manifest(epoch, files)?;
+        if let Some(writer) = removed_writer {
+            files.extend(writer.done()?);
+        }
+        self.write_manifest(epoch, base.map(|base| base.epoch), files)?;
         Ok(())
     }
 
-    fn write_manifest(&mut self, epoch: u64, file_metadata: Vec<FileMetadata>) -> Result<()> {
+    fn write_manifest(
+        &mut self,
+        epoch: u64,
+        base_epoch: Option<u64>,
+        file_metadata: Vec<FileMetadata>,
+    ) -> Result<()> {
         let (f, manifest_file_path) = self.manifest_file(epoch)?;
         let mut wbuf = BufWriter::new(f);
-        let manifest: Manifest = Manifest::V1(ManifestV1 {
-            snapshot_version: 1,
-            address_length: ObjectID::LENGTH as u64,
-            file_metadata,
-            epoch,
-        });
+        let manifest: Manifest = match base_epoch {
+            None => Manifest::V1(ManifestV1 {
+                snapshot_version: 1,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata,
+                epoch,
+            }),
+            Some(base_epoch) => Manifest::V2(ManifestV2 {
+                snapshot_version: 2,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata,
+                epoch,
+                base_epoch,
+            }),
+        };
         let serialized_manifest = bcs::to_bytes(&manifest)?;
         wbuf.write_all(&serialized_manifest)?;
         wbuf.flush()?;
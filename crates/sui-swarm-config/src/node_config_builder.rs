@@ -236,6 +236,7 @@ impl ValidatorConfigBuilder {
             jsonrpc_server_type: None,
             policy_config: self.policy_config,
             firewall_config: self.firewall_config,
+            json_rpc_max_batch_size: None,
             execution_cache: ExecutionCacheConfig::default(),
             state_accumulator_v2: self.state_accumulator_v2,
             enable_soft_bundle: true,
@@ -518,6 +519,7 @@ impl FullnodeConfigBuilder {
             jsonrpc_server_type: None,
             policy_config: self.policy_config,
             firewall_config: self.fw_config,
+            json_rpc_max_batch_size: None,
             execution_cache: ExecutionCacheConfig::default(),
             state_accumulator_v2: true,
             enable_soft_bundle: true,
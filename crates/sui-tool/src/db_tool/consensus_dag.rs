@@ -0,0 +1,426 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs::File;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use bytes::Bytes;
+use clap::{Parser, ValueEnum};
+use consensus_config::Committee;
+use consensus_core::{
+    first_divergent_commit, ConsensusReplay, DagInspector, Round, ScoringEvaluator,
+};
+use sui_core::authority::epoch_start_configuration::EpochStartConfigTrait;
+use sui_protocol_config::ProtocolConfig;
+use sui_types::base_types::EpochId;
+use sui_types::digests::ChainIdentifier;
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+
+use super::export::{open_checkpoints, open_perpetual};
+
+/// Inspect the blocks and commits in the consensus store of a validator, with the db path set to
+/// an epoch directory under `consensus-db-path`. The store is opened as a secondary instance, so
+/// this can be run against the database of a live validator.
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct ConsensusDagOptions {
+    /// Where to keep the secondary instance's files. Defaults to a temporary directory.
+    #[arg(long = "secondary-path")]
+    secondary_path: Option<PathBuf>,
+    #[command(subcommand)]
+    cmd: ConsensusDagCommand,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub enum ConsensusDagCommand {
+    /// Print the blocks of a range of rounds
+    Blocks {
+        #[arg(long = "start-round")]
+        start_round: Round,
+        /// Defaults to the start round
+        #[arg(long = "end-round")]
+        end_round: Option<Round>,
+        /// Only print the blocks proposed by the authority with this index
+        #[arg(long = "authority")]
+        authority: Option<usize>,
+    },
+    /// Print commits with their leaders, linearized sub-dags and votes
+    Commits {
+        /// Defaults to the last commit
+        #[arg(long = "start-index")]
+        start_index: Option<u32>,
+        /// Defaults to the start index
+        #[arg(long = "end-index")]
+        end_index: Option<u32>,
+    },
+    /// Print the ancestors of blocks in a range of rounds that are missing from the store
+    MissingAncestors {
+        #[arg(long = "start-round")]
+        start_round: Round,
+        #[arg(long = "end-round")]
+        end_round: Round,
+    },
+    /// Export the blocks of a range of rounds, and the commits they lead, for visualization
+    Export {
+        #[arg(long = "start-round")]
+        start_round: Round,
+        #[arg(long = "end-round")]
+        end_round: Round,
+        #[arg(long = "format", short = 'f', value_enum, default_value_t = DagFormat::Dot)]
+        format: DagFormat,
+        /// The file to write to. Defaults to stdout.
+        #[arg(long = "output", short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Replay blocks through the commit rule offline, and compare the resulting commits with
+    /// the commits in the store. Only the current epoch of the node can be replayed.
+    Replay {
+        /// Path of the node's db, to read the committee and protocol config of the epoch from
+        #[arg(long = "node-db-path")]
+        node_db_path: PathBuf,
+        /// Index of the replayed authority in the committee
+        #[arg(long = "authority", default_value_t = 0)]
+        authority: usize,
+        /// Replay the blocks of a file written by `export --format blocks`, in the order of the
+        /// file, instead of the blocks in the store in round order
+        #[arg(long = "blocks-file")]
+        blocks_file: Option<PathBuf>,
+        /// Only replay blocks of the store up to this round, e.g. to bisect a divergence. Blocks
+        /// files are replayed in full, trim them with `export --end-round` instead.
+        #[arg(long = "end-round", conflicts_with = "blocks_file")]
+        end_round: Option<Round>,
+    },
+    /// Replay blocks once per leader scoring strategy, and compare the leader schedules,
+    /// skipped leaders and commit latency of the strategies. Only the current epoch of the node
+    /// can be evaluated.
+    EvaluateScoring {
+        /// Path of the node's db, to read the committee and protocol config of the epoch from
+        #[arg(long = "node-db-path")]
+        node_db_path: PathBuf,
+        /// Evaluate the blocks of a file written by `export --format blocks`, in the order of the
+        /// file, instead of the blocks in the store in round order
+        #[arg(long = "blocks-file")]
+        blocks_file: Option<PathBuf>,
+        /// Only evaluate blocks of the store up to this round. Blocks files are evaluated in full.
+        #[arg(long = "end-round", conflicts_with = "blocks_file")]
+        end_round: Option<Round>,
+        /// Number of commits the scores of each leader schedule are calculated from
+        #[arg(long = "commits-per-schedule")]
+        commits_per_schedule: Option<u64>,
+        /// Stake percentage of the authorities swapped out of the schedule. Defaults to the
+        /// protocol config.
+        #[arg(long = "bad-nodes-stake-threshold")]
+        bad_nodes_stake_threshold: Option<u64>,
+        /// Print the evaluations, including every schedule update, as JSON
+        #[arg(long = "json")]
+        json: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum DagFormat {
+    /// Graphviz DOT, e.g. to render with `dot -Tsvg`
+    Dot,
+    Json,
+    /// The serialized blocks, for `replay --blocks-file`
+    Blocks,
+}
+
+/// Rounds of blocks read from the store at once while replaying.
+const REPLAY_BATCH_ROUNDS: Round = 100;
+
+pub fn inspect_consensus_dag(db_path: &Path, opts: ConsensusDagOptions) -> anyhow::Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let secondary_path = opts
+        .secondary_path
+        .unwrap_or_else(|| temp_dir.path().to_path_buf());
+    let inspector = DagInspector::open(db_path, Some(&secondary_path))?;
+    match opts.cmd {
+        ConsensusDagCommand::Blocks {
+            start_round,
+            end_round,
+            authority,
+        } => {
+            let end_round = end_round.unwrap_or(start_round);
+            for block in inspector.blocks(start_round..=end_round, authority)? {
+                println!(
+                    "{} timestamp_ms={} transactions={} commit_votes={:?}",
+                    block.id, block.timestamp_ms, block.transactions, block.commit_votes
+                );
+                println!("  ancestors: {}", join(&block.ancestors));
+            }
+        }
+        ConsensusDagCommand::Commits {
+            start_index,
+            end_index,
+        } => {
+            let start_index = match start_index {
+                Some(index) => index,
+                None => inspector.last_commit_index()?,
+            };
+            if start_index == 0 {
+                bail!("Commit indices start at 1");
+            }
+            let end_index = end_index.unwrap_or(start_index);
+            for commit in inspector.commits(start_index..=end_index)? {
+                println!(
+                    "Commit {} ({}) timestamp_ms={} leader={}",
+                    commit.index, commit.digest, commit.timestamp_ms, commit.leader
+                );
+                println!("  sub-dag: {}", join(&commit.blocks));
+                println!("  votes: {}", join(&commit.votes));
+            }
+        }
+        ConsensusDagCommand::MissingAncestors {
+            start_round,
+            end_round,
+        } => {
+            let missing = inspector.missing_ancestors(start_round..=end_round)?;
+            for m in &missing {
+                println!("{} is missing ancestor {}", m.block, m.ancestor);
+            }
+            println!("Found {} missing ancestors", missing.len());
+        }
+        ConsensusDagCommand::Export {
+            start_round,
+            end_round,
+            format,
+            output,
+        } => {
+            let mut writer: Box<dyn Write> = match output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(stdout().lock()),
+            };
+            if let DagFormat::Blocks = format {
+                let blocks = inspector.serialized_blocks(start_round..=end_round)?;
+                let blocks: Vec<&[u8]> = blocks.iter().map(|block| block.as_ref()).collect();
+                writer.write_all(&bcs::to_bytes(&blocks)?)?;
+                writer.flush()?;
+                eprintln!("Exported {} blocks", blocks.len());
+                return Ok(());
+            }
+            let export = inspector.export(start_round..=end_round)?;
+            match format {
+                DagFormat::Dot => writer.write_all(export.to_dot().as_bytes())?,
+                DagFormat::Json => serde_json::to_writer_pretty(&mut writer, &export)?,
+                DagFormat::Blocks => unreachable!(),
+            }
+            writer.flush()?;
+            eprintln!(
+                "Exported {} blocks and {} commits",
+                export.blocks.len(),
+                export.commits.len()
+            );
+        }
+        ConsensusDagCommand::Replay {
+            node_db_path,
+            authority,
+            blocks_file,
+            end_round,
+        } => replay(
+            db_path,
+            &inspector,
+            &node_db_path,
+            authority,
+            blocks_file,
+            end_round.unwrap_or(Round::MAX),
+        )?,
+        ConsensusDagCommand::EvaluateScoring {
+            node_db_path,
+            blocks_file,
+            end_round,
+            commits_per_schedule,
+            bad_nodes_stake_threshold,
+            json,
+        } => {
+            let (committee, protocol_config) = load_epoch(db_path, &node_db_path)?;
+            let mut evaluator = ScoringEvaluator::new(committee, protocol_config);
+            if let Some(commits_per_schedule) = commits_per_schedule {
+                evaluator = evaluator.with_commits_per_schedule(commits_per_schedule);
+            }
+            if let Some(threshold) = bad_nodes_stake_threshold {
+                evaluator = evaluator.with_bad_nodes_stake_threshold(threshold)?;
+            }
+            let blocks = match blocks_file {
+                Some(path) => read_blocks_file(&path)?,
+                None => inspector.serialized_blocks(1..=end_round.unwrap_or(Round::MAX))?,
+            };
+            eprintln!("Evaluating scoring strategies over {} blocks", blocks.len());
+            let evaluations = evaluator.evaluate_all(blocks)?;
+            if json {
+                serde_json::to_writer_pretty(stdout().lock(), &evaluations)?;
+                println!();
+                return Ok(());
+            }
+            for evaluation in &evaluations {
+                println!(
+                    "{}: commits={} skipped_leader_rounds={} latency_rounds(mean={:.2}, max={}) latency_ms(mean={:.1}) schedule_updates={}",
+                    evaluation.strategy,
+                    evaluation.commits,
+                    evaluation.skipped_leader_rounds,
+                    evaluation.mean_commit_latency_rounds,
+                    evaluation.max_commit_latency_rounds,
+                    evaluation.mean_commit_latency_ms,
+                    evaluation.schedule_updates.len(),
+                );
+                println!(
+                    "  leaders per authority: {:?}",
+                    evaluation.leaders_per_authority
+                );
+                if let Some(update) = evaluation.schedule_updates.last() {
+                    println!(
+                        "  last schedule, from commits {}..={}: bad nodes {:?} swapped with good nodes {:?}",
+                        update.first_commit, update.last_commit, update.bad_nodes, update.good_nodes
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn replay(
+    db_path: &Path,
+    inspector: &DagInspector,
+    node_db_path: &Path,
+    authority: usize,
+    blocks_file: Option<PathBuf>,
+    end_round: Round,
+) -> anyhow::Result<()> {
+    let (committee, protocol_config) = load_epoch(db_path, node_db_path)?;
+    let mut replay = ConsensusReplay::new(committee, authority, protocol_config)?;
+    let mut commits = vec![];
+    let mut replayed_blocks = 0;
+    match blocks_file {
+        Some(path) => {
+            for block in read_blocks_file(&path)? {
+                replayed_blocks += 1;
+                commits.extend(replay.add_blocks(vec![block])?);
+            }
+        }
+        None => {
+            let mut start_round = 1;
+            while start_round <= end_round {
+                let batch_end = start_round
+                    .saturating_add(REPLAY_BATCH_ROUNDS - 1)
+                    .min(end_round);
+                let blocks = inspector.serialized_blocks(start_round..=batch_end)?;
+                if blocks.is_empty() {
+                    break;
+                }
+                replayed_blocks += blocks.len();
+                commits.extend(replay.add_blocks(blocks)?);
+                start_round = batch_end.saturating_add(1);
+            }
+        }
+    }
+
+    let recorded = match commits.last() {
+        Some(last) => inspector.commits(1..=last.index)?,
+        None => vec![],
+    };
+    let divergence = first_divergent_commit(&commits, &recorded);
+    for commit in &commits {
+        let status = match recorded.iter().find(|r| r.index == commit.index) {
+            Some(r) if r.digest == commit.digest => "matches",
+            Some(_) => "DIVERGES",
+            None => "not in store",
+        };
+        println!(
+            "Commit {} ({}) leader={} blocks={} {status}",
+            commit.index,
+            commit.digest,
+            commit.leader,
+            commit.blocks.len()
+        );
+    }
+    println!(
+        "Replayed {} blocks into {} commits, {} ancestors missing",
+        replayed_blocks,
+        commits.len(),
+        replay.missing_blocks().len()
+    );
+    match divergence {
+        Some(index) => println!("First divergent commit is {index}"),
+        None => println!("All replayed commits match the store"),
+    }
+    Ok(())
+}
+
+/// Reads the committee and protocol config of the current epoch of the node, checking that the
+/// consensus db is the one of that epoch.
+fn load_epoch(db_path: &Path, node_db_path: &Path) -> anyhow::Result<(Committee, ProtocolConfig)> {
+    let perpetual = open_perpetual(node_db_path, None)?;
+    let checkpoints = open_checkpoints(node_db_path, None)?;
+    let Some(epoch_start) = perpetual.epoch_start_configuration.get(&())? else {
+        bail!("The node db has no epoch start configuration");
+    };
+    let epoch_start = epoch_start.epoch_start_state();
+    let consensus_epoch = db_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.parse::<EpochId>().ok());
+    if consensus_epoch != Some(epoch_start.epoch()) {
+        bail!(
+            "The node is in epoch {}, but the consensus db path does not end with that epoch",
+            epoch_start.epoch()
+        );
+    }
+    let Some(genesis) = checkpoints.certified_checkpoints.get(&0)? else {
+        bail!("The node db has no genesis checkpoint to identify the chain with");
+    };
+    let chain = ChainIdentifier::from(*genesis.inner().digest()).chain();
+    let protocol_config = ProtocolConfig::get_for_version(epoch_start.protocol_version(), chain);
+
+    Ok((epoch_start.get_mysticeti_committee(), protocol_config))
+}
+
+/// Reads blocks written by `export --format blocks`.
+fn read_blocks_file(path: &Path) -> anyhow::Result<Vec<Bytes>> {
+    let blocks: Vec<Vec<u8>> = bcs::from_bytes(&std::fs::read(path)?)?;
+    Ok(blocks.into_iter().map(Bytes::from).collect())
+}
+
+fn join<T: std::fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn end_round_conflicts_with_blocks_file() {
+        for cmd in ["replay", "evaluate-scoring"] {
+            let args = [
+                "consensus-dag",
+                cmd,
+                "--node-db-path",
+                "db",
+                "--blocks-file",
+                "blocks",
+            ];
+            assert!(ConsensusDagOptions::try_parse_from(args).is_ok());
+            assert!(ConsensusDagOptions::try_parse_from(
+                args.into_iter().chain(["--end-round", "10"])
+            )
+            .is_err());
+            assert!(ConsensusDagOptions::try_parse_from([
+                "consensus-dag",
+                cmd,
+                "--node-db-path",
+                "db",
+                "--end-round",
+                "10"
+            ])
+            .is_ok());
+        }
+    }
+}
@@ -0,0 +1,557 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline consistency checks for the tables of a node database.
+
+use anyhow::anyhow;
+use fastcrypto::hash::MultisetHash;
+use std::fmt;
+use std::path::Path;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::state_accumulator::StateAccumulator;
+use sui_protocol_config::ProtocolConfig;
+use sui_types::accumulator::Accumulator;
+use sui_types::base_types::{EpochId, ObjectRef};
+use sui_types::digests::{ChainIdentifier, TransactionDigest, TransactionEffectsDigest};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::storage::ObjectStore;
+use tracing::info;
+
+pub struct VerifyConfig {
+    /// First checkpoint to verify. Defaults to the first checkpoint that has not been pruned.
+    pub start_checkpoint: Option<CheckpointSequenceNumber>,
+    /// Last checkpoint to verify (inclusive). Defaults to the highest executed checkpoint.
+    pub end_checkpoint: Option<CheckpointSequenceNumber>,
+    /// Skip recomputing the accumulator over the live object set, which reads every object.
+    pub skip_live_object_set: bool,
+    /// Rewind checkpoint execution to before the first checkpoint with missing transaction data.
+    pub repair: bool,
+}
+
+#[derive(Debug)]
+pub enum Inconsistency {
+    /// The highest executed checkpoint is ahead of the highest synced checkpoint.
+    ExecutedAheadOfSynced {
+        executed: CheckpointSequenceNumber,
+        synced: Option<CheckpointSequenceNumber>,
+    },
+    MissingCheckpoint(CheckpointSequenceNumber),
+    MissingCheckpointContents(CheckpointSequenceNumber),
+    MissingTransaction {
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+    },
+    MissingEffects {
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+    },
+    EffectsMismatch {
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+        expected: TransactionEffectsDigest,
+        actual: TransactionEffectsDigest,
+    },
+    MissingObject {
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+        object: ObjectRef,
+    },
+    ObjectDigestMismatch {
+        checkpoint: CheckpointSequenceNumber,
+        digest: TransactionDigest,
+        expected: ObjectRef,
+        actual: ObjectRef,
+    },
+    MissingRootStateHash(EpochId),
+    RootStateHashMismatch(EpochId),
+}
+
+impl Inconsistency {
+    /// The checkpoint whose execution needs to be redone to fix this inconsistency, if
+    /// re-executing it would fix it.
+    fn checkpoint_to_reexecute(&self) -> Option<CheckpointSequenceNumber> {
+        match self {
+            Inconsistency::MissingTransaction { checkpoint, .. }
+            | Inconsistency::MissingEffects { checkpoint, .. } => Some(*checkpoint),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::ExecutedAheadOfSynced { executed, synced } => write!(
+                f,
+                "highest executed checkpoint {executed} is ahead of highest synced checkpoint {synced:?}"
+            ),
+            Inconsistency::MissingCheckpoint(seq) => {
+                write!(f, "checkpoint {seq} is missing")
+            }
+            Inconsistency::MissingCheckpointContents(seq) => {
+                write!(f, "contents of checkpoint {seq} are missing")
+            }
+            Inconsistency::MissingTransaction { checkpoint, digest } => {
+                write!(f, "checkpoint {checkpoint}: transaction {digest} is missing")
+            }
+            Inconsistency::MissingEffects { checkpoint, digest } => write!(
+                f,
+                "checkpoint {checkpoint}: effects of transaction {digest} are missing"
+            ),
+            Inconsistency::EffectsMismatch {
+                checkpoint,
+                digest,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checkpoint {checkpoint}: transaction {digest} executed with effects {actual}, \
+                 but the checkpoint has effects {expected}"
+            ),
+            Inconsistency::MissingObject {
+                checkpoint,
+                digest,
+                object,
+            } => write!(
+                f,
+                "checkpoint {checkpoint}: object {:?} written by transaction {digest} is missing",
+                object
+            ),
+            Inconsistency::ObjectDigestMismatch {
+                checkpoint,
+                digest,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checkpoint {checkpoint}: object {:?} written by transaction {digest} is stored \
+                 as {:?}",
+                expected, actual
+            ),
+            Inconsistency::MissingRootStateHash(epoch) => {
+                write!(f, "root state hash of epoch {epoch} is missing")
+            }
+            Inconsistency::RootStateHashMismatch(epoch) => write!(
+                f,
+                "live object set does not match the root state hash of epoch {epoch}"
+            ),
+        }
+    }
+}
+
+/// Whether the live object set was checked against the root state hash of its epoch.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub enum LiveObjectSetCheck {
+    /// The check was disabled with `skip_live_object_set`.
+    #[default]
+    Disabled,
+    /// Checkpoint execution stopped in the middle of this epoch, so the live object set does not
+    /// correspond to any root state hash.
+    SkippedMidEpoch(EpochId),
+    Checked,
+}
+
+impl fmt::Display for LiveObjectSetCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveObjectSetCheck::Disabled => write!(f, "disabled"),
+            LiveObjectSetCheck::SkippedMidEpoch(epoch) => write!(
+                f,
+                "skipped, checkpoint execution stopped in the middle of epoch {epoch}"
+            ),
+            LiveObjectSetCheck::Checked => write!(f, "checked"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub checkpoints: u64,
+    pub transactions: u64,
+    pub objects: u64,
+    pub live_object_set: LiveObjectSetCheck,
+    pub inconsistencies: Vec<Inconsistency>,
+    /// Set if execution was rewound to repair inconsistencies.
+    pub rewound_to: Option<CheckpointSequenceNumber>,
+}
+
+/// Cross-check the checkpoint, transaction, effects and object tables of the database at
+/// `path`:
+///
+/// - every checkpoint up to the highest executed one has a summary and contents,
+/// - every transaction of an executed checkpoint has been executed with the checkpoint's effects,
+/// - every object version written by those effects exists, unless it has been pruned,
+/// - if the database is at the end of an epoch, the live object set matches the epoch's root
+///   state hash.
+pub fn verify_db(path: &Path, config: VerifyConfig) -> anyhow::Result<VerifyReport> {
+    let perpetual = AuthorityPerpetualTables::open(&path.join("store"), None);
+    let checkpoint_store = CheckpointStore::new(&path.join("checkpoints"));
+    let mut report = VerifyReport::default();
+
+    let Some(highest_executed) = checkpoint_store.get_highest_executed_checkpoint()? else {
+        info!("No checkpoints have been executed, nothing to verify");
+        return Ok(report);
+    };
+    let highest_executed_seq = *highest_executed.sequence_number();
+    let highest_synced = checkpoint_store
+        .get_highest_synced_checkpoint()?
+        .map(|checkpoint| *checkpoint.sequence_number());
+    if highest_synced.map_or(true, |synced| synced < highest_executed_seq) {
+        report
+            .inconsistencies
+            .push(Inconsistency::ExecutedAheadOfSynced {
+                executed: highest_executed_seq,
+                synced: highest_synced,
+            });
+    }
+
+    let checkpoints_pruned = checkpoint_store.get_highest_pruned_checkpoint_seq_number()?;
+    let objects_pruned = perpetual.get_highest_pruned_checkpoint()?;
+    let first_unpruned =
+        |pruned: CheckpointSequenceNumber| if pruned == 0 { 0 } else { pruned + 1 };
+    let start = config
+        .start_checkpoint
+        .unwrap_or(0)
+        .max(first_unpruned(checkpoints_pruned));
+    let end = config
+        .end_checkpoint
+        .unwrap_or(highest_executed_seq)
+        .min(highest_executed_seq);
+    info!("Verifying checkpoints {start}..={end}");
+
+    for seq in start..=end {
+        report.checkpoints += 1;
+        let Some(checkpoint) = checkpoint_store.get_checkpoint_by_sequence_number(seq)? else {
+            report
+                .inconsistencies
+                .push(Inconsistency::MissingCheckpoint(seq));
+            continue;
+        };
+        let Some(contents) =
+            checkpoint_store.get_checkpoint_contents(&checkpoint.content_digest)?
+        else {
+            report
+                .inconsistencies
+                .push(Inconsistency::MissingCheckpointContents(seq));
+            continue;
+        };
+
+        for digests in contents.iter() {
+            report.transactions += 1;
+            let digest = digests.transaction;
+            if perpetual.get_transaction(&digest)?.is_none() {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::MissingTransaction {
+                        checkpoint: seq,
+                        digest,
+                    });
+            }
+            let Some(effects) = perpetual.get_effects(&digest)? else {
+                report.inconsistencies.push(Inconsistency::MissingEffects {
+                    checkpoint: seq,
+                    digest,
+                });
+                continue;
+            };
+            if effects.digest() != digests.effects {
+                report.inconsistencies.push(Inconsistency::EffectsMismatch {
+                    checkpoint: seq,
+                    digest,
+                    expected: digests.effects,
+                    actual: effects.digest(),
+                });
+                continue;
+            }
+
+            if seq < first_unpruned(objects_pruned) {
+                continue;
+            }
+            let written = effects
+                .created()
+                .into_iter()
+                .chain(effects.mutated())
+                .chain(effects.unwrapped())
+                .map(|(object_ref, _)| object_ref);
+            for expected in written {
+                report.objects += 1;
+                match perpetual.get_object_by_key(&expected.0, expected.1)? {
+                    None => report.inconsistencies.push(Inconsistency::MissingObject {
+                        checkpoint: seq,
+                        digest,
+                        object: expected,
+                    }),
+                    Some(object) => {
+                        let actual = object.compute_object_reference();
+                        if actual != expected {
+                            report
+                                .inconsistencies
+                                .push(Inconsistency::ObjectDigestMismatch {
+                                    checkpoint: seq,
+                                    digest,
+                                    expected,
+                                    actual,
+                                });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // The live object set only corresponds to a root state hash when execution stopped at the
+    // end of an epoch.
+    let epoch = highest_executed.epoch();
+    if config.skip_live_object_set {
+        report.live_object_set = LiveObjectSetCheck::Disabled;
+    } else if let Some(end_of_epoch_data) = &highest_executed.end_of_epoch_data {
+        match perpetual.get_root_state_hash(epoch)? {
+            Some((last_checkpoint, expected)) if last_checkpoint == highest_executed_seq => {
+                // The root state hash of an epoch is re-accumulated without wrapped tombstones
+                // when the next epoch enables `simplified_unwrap_then_delete`, so the next
+                // epoch's protocol config decides whether they are part of it.
+                let version = end_of_epoch_data.next_epoch_protocol_version;
+                let chain = chain_identifier(&checkpoint_store)?.chain();
+                let protocol_config = ProtocolConfig::get_for_version_if_supported(version, chain)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Protocol version {} of epoch {} is not supported by this binary",
+                            version.as_u64(),
+                            epoch + 1
+                        )
+                    })?;
+                let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
+
+                info!("Accumulating the live object set of epoch {epoch}");
+                let mut accumulator = Accumulator::default();
+                for live_object in perpetual.iter_live_object_set(include_wrapped_tombstone) {
+                    StateAccumulator::accumulate_live_object(&mut accumulator, &live_object);
+                }
+                report.live_object_set = LiveObjectSetCheck::Checked;
+                if accumulator.digest() != expected.digest() {
+                    report
+                        .inconsistencies
+                        .push(Inconsistency::RootStateHashMismatch(epoch));
+                }
+            }
+            _ => report
+                .inconsistencies
+                .push(Inconsistency::MissingRootStateHash(epoch)),
+        }
+    } else {
+        report.live_object_set = LiveObjectSetCheck::SkippedMidEpoch(epoch);
+    }
+
+    if config.repair {
+        let first_broken = report
+            .inconsistencies
+            .iter()
+            .filter_map(Inconsistency::checkpoint_to_reexecute)
+            .min();
+        if let Some(first_broken) = first_broken {
+            let rewind_to = first_broken
+                .checked_sub(1)
+                .ok_or_else(|| anyhow!("Cannot rewind execution before genesis"))?;
+            let checkpoint = checkpoint_store
+                .get_checkpoint_by_sequence_number(rewind_to)?
+                .ok_or_else(|| anyhow!("Checkpoint {rewind_to} not found"))?;
+            checkpoint_store.set_highest_executed_checkpoint_subtle(&checkpoint)?;
+            report.rewound_to = Some(rewind_to);
+        }
+    }
+
+    Ok(report)
+}
+
+/// The chain the database belongs to, identified by its genesis checkpoint.
+fn chain_identifier(checkpoint_store: &CheckpointStore) -> anyhow::Result<ChainIdentifier> {
+    let genesis = checkpoint_store
+        .get_checkpoint_by_sequence_number(0)?
+        .ok_or_else(|| {
+            anyhow!(
+                "Cannot identify the chain without the genesis checkpoint, use \
+                 --skip-live-object-set to verify a pruned database"
+            )
+        })?;
+    Ok(ChainIdentifier::from(*genesis.digest()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::authority::authority_store_tables::LiveObject;
+    use sui_protocol_config::ProtocolVersion;
+    use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::messages_checkpoint::{
+        EndOfEpochData, VerifiedCheckpoint, VerifiedCheckpointContents,
+    };
+    use sui_types::object::Object;
+
+    fn config(skip_live_object_set: bool, repair: bool) -> VerifyConfig {
+        VerifyConfig {
+            start_checkpoint: None,
+            end_checkpoint: None,
+            skip_live_object_set,
+            repair,
+        }
+    }
+
+    /// Write checkpoints and their contents to the database at `path`, as executed up to the
+    /// last one, optionally followed by a checkpoint that ends the epoch.
+    fn write_checkpoints(
+        path: &Path,
+        committee: &CommitteeFixture,
+        mut checkpoints: Vec<VerifiedCheckpoint>,
+        contents: Vec<VerifiedCheckpointContents>,
+        next_epoch_protocol_version: Option<ProtocolVersion>,
+    ) -> Vec<VerifiedCheckpoint> {
+        if let Some(next_epoch_protocol_version) = next_epoch_protocol_version {
+            let (_, _, checkpoint) = committee.make_end_of_epoch_checkpoint(
+                checkpoints.last().unwrap().clone(),
+                Some(EndOfEpochData {
+                    next_epoch_committee: committee.committee().voting_rights.clone(),
+                    next_epoch_protocol_version,
+                    epoch_commitments: vec![],
+                }),
+            );
+            checkpoints.push(checkpoint);
+        }
+
+        let store = CheckpointStore::new(&path.join("checkpoints"));
+        for contents in contents.into_iter().chain(Some(empty_contents())) {
+            store
+                .insert_checkpoint_contents(contents.into_inner().into_checkpoint_contents())
+                .unwrap();
+        }
+        for checkpoint in &checkpoints {
+            store.insert_verified_checkpoint(checkpoint).unwrap();
+            store.update_highest_synced_checkpoint(checkpoint).unwrap();
+            store
+                .update_highest_executed_checkpoint(checkpoint)
+                .unwrap();
+        }
+        checkpoints
+    }
+
+    /// Write a few live objects to the database at `path`, and the root state hash of epoch 0,
+    /// over all of them or all but the last one.
+    fn write_live_objects(path: &Path, last_checkpoint: CheckpointSequenceNumber, complete: bool) {
+        let perpetual = AuthorityPerpetualTables::open(&path.join("store"), None);
+        let objects: Vec<_> = (0..3)
+            .map(|_| Object::with_id_owner_for_testing(ObjectID::random(), SuiAddress::ZERO))
+            .collect();
+        let mut accumulator = Accumulator::default();
+        for (i, object) in objects.into_iter().enumerate() {
+            if complete || i > 0 {
+                StateAccumulator::accumulate_live_object(
+                    &mut accumulator,
+                    &LiveObject::Normal(object.clone()),
+                );
+            }
+            perpetual.insert_object_test_only(object).unwrap();
+        }
+        perpetual
+            .insert_root_state_hash(0, last_checkpoint, accumulator)
+            .unwrap();
+    }
+
+    #[test]
+    fn skip_live_object_set_mid_epoch() {
+        let dir = tempfile::tempdir().unwrap();
+        let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+        let (checkpoints, contents, _, _) = committee.make_empty_checkpoints(5, None);
+        write_checkpoints(dir.path(), &committee, checkpoints, contents, None);
+
+        let report = verify_db(dir.path(), config(false, false)).unwrap();
+        assert!(report.inconsistencies.is_empty(), "{report:?}");
+        assert_eq!(report.checkpoints, 5);
+        assert_eq!(
+            report.live_object_set,
+            LiveObjectSetCheck::SkippedMidEpoch(0)
+        );
+
+        let report = verify_db(dir.path(), config(true, false)).unwrap();
+        assert_eq!(report.live_object_set, LiveObjectSetCheck::Disabled);
+    }
+
+    #[test]
+    fn check_live_object_set_at_end_of_epoch() {
+        for version in [ProtocolVersion::MIN, ProtocolVersion::MAX] {
+            let dir = tempfile::tempdir().unwrap();
+            let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+            let (checkpoints, contents, _, _) = committee.make_empty_checkpoints(3, None);
+            let checkpoints =
+                write_checkpoints(dir.path(), &committee, checkpoints, contents, Some(version));
+            write_live_objects(
+                dir.path(),
+                *checkpoints.last().unwrap().sequence_number(),
+                true,
+            );
+
+            let report = verify_db(dir.path(), config(false, false)).unwrap();
+            assert!(report.inconsistencies.is_empty(), "{report:?}");
+            assert_eq!(report.checkpoints, 4);
+            assert_eq!(report.live_object_set, LiveObjectSetCheck::Checked);
+        }
+    }
+
+    #[test]
+    fn detect_root_state_hash_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+        let (checkpoints, contents, _, _) = committee.make_empty_checkpoints(3, None);
+        let checkpoints = write_checkpoints(
+            dir.path(),
+            &committee,
+            checkpoints,
+            contents,
+            Some(ProtocolVersion::MAX),
+        );
+        write_live_objects(
+            dir.path(),
+            *checkpoints.last().unwrap().sequence_number(),
+            false,
+        );
+
+        let report = verify_db(dir.path(), config(false, false)).unwrap();
+        assert_eq!(report.live_object_set, LiveObjectSetCheck::Checked);
+        assert!(matches!(
+            report.inconsistencies[..],
+            [Inconsistency::RootStateHashMismatch(0)]
+        ));
+    }
+
+    #[test]
+    fn repair_missing_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+        // Every checkpoint but the first has transactions that were never executed.
+        let (checkpoints, contents, _, _) = committee.make_random_checkpoints(4, None);
+        write_checkpoints(dir.path(), &committee, checkpoints, contents, None);
+
+        let report = verify_db(dir.path(), config(true, true)).unwrap();
+        assert!(report.inconsistencies.iter().all(|i| matches!(
+            i,
+            Inconsistency::MissingTransaction { .. } | Inconsistency::MissingEffects { .. }
+        )));
+        assert_eq!(
+            report
+                .inconsistencies
+                .iter()
+                .filter_map(Inconsistency::checkpoint_to_reexecute)
+                .min(),
+            Some(1)
+        );
+        assert_eq!(report.rewound_to, Some(0));
+
+        let store = CheckpointStore::new(&dir.path().join("checkpoints"));
+        assert_eq!(
+            store.get_highest_executed_checkpoint_seq_number().unwrap(),
+            Some(0)
+        );
+    }
+}
@@ -0,0 +1,838 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export decoded rows from the tables of a node database, for offline analysis.
+//!
+//! Tables are opened as a RocksDB secondary instance, so this can be run against the database of
+//! a live node.
+
+use anyhow::{anyhow, bail, Context};
+use arrow_array::{ArrayRef, BooleanArray, RecordBatch, StringArray, UInt64Array};
+use clap::ValueEnum;
+use csv::WriterBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use sui_core::authority::authority_store_tables::{
+    AuthorityPerpetualTables, AuthorityPerpetualTablesReadOnly,
+};
+use sui_core::authority::authority_store_types::StoreObject;
+use sui_core::checkpoints::{CheckpointStore, CheckpointStoreReadOnly};
+use sui_types::base_types::{ExecutionDigests, ObjectID};
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::execution_status::ExecutionStatus;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, TrustedCheckpoint};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+use sui_types::transaction::{TransactionDataAPI, TrustedTransaction};
+use typed_store::rocks::MetricConf;
+use typed_store::traits::Map;
+
+/// Number of rows buffered before they are written out as a parquet row group.
+const PARQUET_ROW_GROUP_SIZE: usize = 16 * 1024;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportTable {
+    Objects,
+    Transactions,
+    Effects,
+    Checkpoints,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Jsonl,
+    Csv,
+    Parquet,
+}
+
+/// A half-open range `START..END`, where either bound may be omitted.
+#[derive(Clone, Debug, Default)]
+pub struct ExportRange {
+    start: Option<String>,
+    end: Option<String>,
+}
+
+impl FromStr for ExportRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once("..")
+            .ok_or_else(|| anyhow!("Invalid range '{s}', expected START..END"))?;
+        let bound = |b: &str| (!b.is_empty()).then(|| b.to_string());
+        Ok(Self {
+            start: bound(start),
+            end: bound(end),
+        })
+    }
+}
+
+impl ExportRange {
+    fn checkpoints(&self) -> anyhow::Result<(CheckpointSequenceNumber, CheckpointSequenceNumber)> {
+        let parse = |b: &Option<String>, default| {
+            b.as_deref()
+                .map(|b| {
+                    b.parse()
+                        .with_context(|| format!("Invalid checkpoint '{b}'"))
+                })
+                .unwrap_or(Ok(default))
+        };
+        Ok((parse(&self.start, 0)?, parse(&self.end, u64::MAX)?))
+    }
+
+    fn object_ids(&self) -> anyhow::Result<(ObjectID, Option<ObjectID>)> {
+        let parse =
+            |b: &str| ObjectID::from_str(b).with_context(|| format!("Invalid object ID '{b}'"));
+        let start = self.start.as_deref().map(parse).transpose()?;
+        let end = self.end.as_deref().map(parse).transpose()?;
+        Ok((start.unwrap_or(ObjectID::ZERO), end))
+    }
+}
+
+pub struct ExportConfig {
+    pub table: ExportTable,
+    pub format: ExportFormat,
+    pub range: Option<ExportRange>,
+    pub output: Option<PathBuf>,
+    pub secondary_path: Option<PathBuf>,
+}
+
+/// Export the rows of `config.table` in the database at `db_path`. Returns the number of rows
+/// exported.
+pub fn export_table(db_path: &Path, config: ExportConfig) -> anyhow::Result<usize> {
+    let ExportConfig {
+        table,
+        format,
+        range,
+        output,
+        secondary_path,
+    } = config;
+    let range = range.unwrap_or_default();
+
+    match table {
+        ExportTable::Objects => {
+            let perpetual = open_perpetual(db_path, secondary_path)?;
+            let mut writer = RowWriter::new(format, output)?;
+            export_objects(&perpetual, &range, &mut writer)?;
+            writer.finish()
+        }
+        ExportTable::Transactions => {
+            let perpetual = open_perpetual(db_path, secondary_path.clone())?;
+            let checkpoints = open_checkpoints(db_path, secondary_path)?;
+            let mut writer = RowWriter::new(format, output)?;
+            for digests in executed_digests(&perpetual, &checkpoints, &range)? {
+                let (digests, checkpoint) = digests?;
+                if let Some(tx) = perpetual.transactions.get(&digests.transaction)? {
+                    writer.write(&TransactionRow::new(&tx, checkpoint))?;
+                }
+            }
+            writer.finish()
+        }
+        ExportTable::Effects => {
+            let perpetual = open_perpetual(db_path, secondary_path.clone())?;
+            let checkpoints = open_checkpoints(db_path, secondary_path)?;
+            let mut writer = RowWriter::new(format, output)?;
+            for digests in executed_digests(&perpetual, &checkpoints, &range)? {
+                let (digests, checkpoint) = digests?;
+                if let Some(effects) = perpetual.effects.get(&digests.effects)? {
+                    writer.write(&EffectsRow::new(&effects, checkpoint))?;
+                }
+            }
+            writer.finish()
+        }
+        ExportTable::Checkpoints => {
+            let checkpoints = open_checkpoints(db_path, secondary_path)?;
+            let (start, end) = range.checkpoints()?;
+            let mut writer = RowWriter::new(format, output)?;
+            for entry in checkpoints
+                .certified_checkpoints
+                .safe_iter_with_bounds(Some(start), Some(end))
+            {
+                let (_, checkpoint) = entry?;
+                let num_transactions = checkpoints
+                    .checkpoint_content
+                    .get(&checkpoint.inner().data().content_digest)?
+                    .map(|contents| contents.size() as u64);
+                writer.write(&CheckpointRow::new(&checkpoint, num_transactions))?;
+            }
+            writer.finish()
+        }
+    }
+}
+
+pub(super) fn open_perpetual(
+    db_path: &Path,
+    secondary_path: Option<PathBuf>,
+) -> anyhow::Result<AuthorityPerpetualTablesReadOnly> {
+    let tables = AuthorityPerpetualTables::get_read_only_handle(
+        AuthorityPerpetualTables::path(&db_path.join("store")),
+        secondary_path.map(|p| p.join("perpetual")),
+        None,
+        MetricConf::new("db_tool_export"),
+    );
+    tables
+        .try_catch_up_with_primary_all()
+        .map_err(|e| anyhow!(e.to_string()))?;
+    Ok(tables)
+}
+
+pub(super) fn open_checkpoints(
+    db_path: &Path,
+    secondary_path: Option<PathBuf>,
+) -> anyhow::Result<CheckpointStoreReadOnly> {
+    let tables = CheckpointStore::get_read_only_handle(
+        db_path.join("checkpoints"),
+        secondary_path.map(|p| p.join("checkpoints")),
+        None,
+        MetricConf::new("db_tool_export"),
+    );
+    tables
+        .try_catch_up_with_primary_all()
+        .map_err(|e| anyhow!(e.to_string()))?;
+    Ok(tables)
+}
+
+type ExecutedDigests = anyhow::Result<(ExecutionDigests, Option<CheckpointSequenceNumber>)>;
+
+/// Digests of the transactions to export. With a range, these are the transactions of the
+/// checkpoints in the range. Without one, every executed transaction is exported, including ones
+/// that are not yet part of a checkpoint.
+fn executed_digests<'a>(
+    perpetual: &'a AuthorityPerpetualTablesReadOnly,
+    checkpoints: &'a CheckpointStoreReadOnly,
+    range: &ExportRange,
+) -> anyhow::Result<Box<dyn Iterator<Item = ExecutedDigests> + 'a>> {
+    if range.start.is_none() && range.end.is_none() {
+        return Ok(Box::new(perpetual.executed_effects.safe_iter().map(
+            move |entry| {
+                let (transaction, effects) = entry?;
+                let checkpoint = perpetual
+                    .executed_transactions_to_checkpoint
+                    .get(&transaction)?
+                    .map(|(_, checkpoint)| checkpoint);
+                Ok((
+                    ExecutionDigests {
+                        transaction,
+                        effects,
+                    },
+                    checkpoint,
+                ))
+            },
+        )));
+    }
+
+    let (start, end) = range.checkpoints()?;
+    Ok(Box::new(
+        checkpoints
+            .certified_checkpoints
+            .safe_iter_with_bounds(Some(start), Some(end))
+            .map(move |entry| -> anyhow::Result<Vec<ExecutedDigests>> {
+                let (sequence_number, checkpoint) = entry?;
+                let content_digest = checkpoint.inner().data().content_digest;
+                let contents = checkpoints
+                    .checkpoint_content
+                    .get(&content_digest)?
+                    .ok_or_else(|| {
+                        anyhow!("Contents of checkpoint {sequence_number} are missing")
+                    })?;
+                Ok(contents
+                    .iter()
+                    .map(|digests| Ok((*digests, Some(sequence_number))))
+                    .collect())
+            })
+            .flat_map(|digests| match digests {
+                Ok(digests) => digests,
+                Err(e) => vec![Err(e)],
+            }),
+    ))
+}
+
+fn export_objects(
+    perpetual: &AuthorityPerpetualTablesReadOnly,
+    range: &ExportRange,
+    writer: &mut RowWriter<ObjectRow>,
+) -> anyhow::Result<()> {
+    let (start, end) = range.object_ids()?;
+    let lower = ObjectKey::min_for_id(&start);
+    let upper = end.map(|end| ObjectKey::min_for_id(&end));
+    for entry in perpetual.objects.safe_iter_with_bounds(Some(lower), upper) {
+        let (key, store_object) = entry?;
+        let row = match store_object.inner() {
+            StoreObject::Deleted => ObjectRow::tombstone(&key, "deleted"),
+            StoreObject::Wrapped => ObjectRow::tombstone(&key, "wrapped"),
+            StoreObject::Value(_) => {
+                let object = perpetual
+                    .object(&key, store_object)?
+                    .ok_or_else(|| anyhow!("Cannot construct object {key:?}"))?;
+                ObjectRow::live(&key, &object)
+            }
+        };
+        writer.write(&row)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ObjectRow {
+    object_id: String,
+    version: u64,
+    /// Either `live`, or `deleted` / `wrapped` for tombstones.
+    status: &'static str,
+    digest: Option<String>,
+    type_: Option<String>,
+    owner: Option<String>,
+    previous_transaction: Option<String>,
+    storage_rebate: Option<u64>,
+    size: Option<u64>,
+}
+
+impl ExportRow for ObjectRow {
+    const COLUMNS: &'static [Column] = &[
+        Column::required("object_id", ColumnType::Str),
+        Column::required("version", ColumnType::U64),
+        Column::required("status", ColumnType::Str),
+        Column::optional("digest", ColumnType::Str),
+        Column::optional("type_", ColumnType::Str),
+        Column::optional("owner", ColumnType::Str),
+        Column::optional("previous_transaction", ColumnType::Str),
+        Column::optional("storage_rebate", ColumnType::U64),
+        Column::optional("size", ColumnType::U64),
+    ];
+}
+
+impl ObjectRow {
+    fn tombstone(key: &ObjectKey, status: &'static str) -> Self {
+        let ObjectKey(object_id, version) = key;
+        Self {
+            object_id: object_id.to_string(),
+            version: version.value(),
+            status,
+            digest: None,
+            type_: None,
+            owner: None,
+            previous_transaction: None,
+            storage_rebate: None,
+            size: None,
+        }
+    }
+
+    fn live(key: &ObjectKey, object: &Object) -> Self {
+        let ObjectKey(object_id, version) = key;
+        Self {
+            object_id: object_id.to_string(),
+            version: version.value(),
+            status: "live",
+            digest: Some(object.digest().to_string()),
+            type_: Some(match object.type_() {
+                Some(type_) => type_.to_string(),
+                None => "package".to_string(),
+            }),
+            owner: Some(object.owner.to_string()),
+            previous_transaction: Some(object.previous_transaction.to_string()),
+            storage_rebate: Some(object.storage_rebate),
+            size: Some(object.object_size_for_gas_metering() as u64),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TransactionRow {
+    digest: String,
+    checkpoint: Option<u64>,
+    kind: &'static str,
+    sender: String,
+    gas_owner: String,
+    gas_budget: u64,
+    gas_price: u64,
+    num_commands: u64,
+}
+
+impl ExportRow for TransactionRow {
+    const COLUMNS: &'static [Column] = &[
+        Column::required("digest", ColumnType::Str),
+        Column::optional("checkpoint", ColumnType::U64),
+        Column::required("kind", ColumnType::Str),
+        Column::required("sender", ColumnType::Str),
+        Column::required("gas_owner", ColumnType::Str),
+        Column::required("gas_budget", ColumnType::U64),
+        Column::required("gas_price", ColumnType::U64),
+        Column::required("num_commands", ColumnType::U64),
+    ];
+}
+
+impl TransactionRow {
+    fn new(tx: &TrustedTransaction, checkpoint: Option<CheckpointSequenceNumber>) -> Self {
+        let data = tx.inner().data().transaction_data();
+        Self {
+            digest: tx.inner().digest().to_string(),
+            checkpoint,
+            kind: data.kind().name(),
+            sender: data.sender().to_string(),
+            gas_owner: data.gas_owner().to_string(),
+            gas_budget: data.gas_budget(),
+            gas_price: data.gas_price(),
+            num_commands: data.kind().tx_count() as u64,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EffectsRow {
+    transaction_digest: String,
+    effects_digest: String,
+    checkpoint: Option<u64>,
+    executed_epoch: u64,
+    success: bool,
+    failure: Option<String>,
+    computation_cost: u64,
+    storage_cost: u64,
+    storage_rebate: u64,
+    non_refundable_storage_fee: u64,
+    created: u64,
+    mutated: u64,
+    deleted: u64,
+    wrapped: u64,
+    unwrapped: u64,
+    events_digest: Option<String>,
+}
+
+impl ExportRow for EffectsRow {
+    const COLUMNS: &'static [Column] = &[
+        Column::required("transaction_digest", ColumnType::Str),
+        Column::required("effects_digest", ColumnType::Str),
+        Column::optional("checkpoint", ColumnType::U64),
+        Column::required("executed_epoch", ColumnType::U64),
+        Column::required("success", ColumnType::Bool),
+        Column::optional("failure", ColumnType::Str),
+        Column::required("computation_cost", ColumnType::U64),
+        Column::required("storage_cost", ColumnType::U64),
+        Column::required("storage_rebate", ColumnType::U64),
+        Column::required("non_refundable_storage_fee", ColumnType::U64),
+        Column::required("created", ColumnType::U64),
+        Column::required("mutated", ColumnType::U64),
+        Column::required("deleted", ColumnType::U64),
+        Column::required("wrapped", ColumnType::U64),
+        Column::required("unwrapped", ColumnType::U64),
+        Column::optional("events_digest", ColumnType::Str),
+    ];
+}
+
+impl EffectsRow {
+    fn new(effects: &TransactionEffects, checkpoint: Option<CheckpointSequenceNumber>) -> Self {
+        let gas = effects.gas_cost_summary();
+        let failure = match effects.status() {
+            ExecutionStatus::Success => None,
+            ExecutionStatus::Failure { error, command } => Some(match command {
+                Some(command) => format!("{error:?} in command {command}"),
+                None => format!("{error:?}"),
+            }),
+        };
+        Self {
+            transaction_digest: effects.transaction_digest().to_string(),
+            effects_digest: effects.digest().to_string(),
+            checkpoint,
+            executed_epoch: effects.executed_epoch(),
+            success: failure.is_none(),
+            failure,
+            computation_cost: gas.computation_cost,
+            storage_cost: gas.storage_cost,
+            storage_rebate: gas.storage_rebate,
+            non_refundable_storage_fee: gas.non_refundable_storage_fee,
+            created: effects.created().len() as u64,
+            mutated: effects.mutated().len() as u64,
+            deleted: effects.deleted().len() as u64,
+            wrapped: effects.wrapped().len() as u64,
+            unwrapped: effects.unwrapped().len() as u64,
+            events_digest: effects.events_digest().map(|d| d.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CheckpointRow {
+    sequence_number: u64,
+    digest: String,
+    epoch: u64,
+    timestamp_ms: u64,
+    content_digest: String,
+    previous_digest: Option<String>,
+    network_total_transactions: u64,
+    num_transactions: Option<u64>,
+    end_of_epoch: bool,
+}
+
+impl ExportRow for CheckpointRow {
+    const COLUMNS: &'static [Column] = &[
+        Column::required("sequence_number", ColumnType::U64),
+        Column::required("digest", ColumnType::Str),
+        Column::required("epoch", ColumnType::U64),
+        Column::required("timestamp_ms", ColumnType::U64),
+        Column::required("content_digest", ColumnType::Str),
+        Column::optional("previous_digest", ColumnType::Str),
+        Column::required("network_total_transactions", ColumnType::U64),
+        Column::optional("num_transactions", ColumnType::U64),
+        Column::required("end_of_epoch", ColumnType::Bool),
+    ];
+}
+
+impl CheckpointRow {
+    fn new(checkpoint: &TrustedCheckpoint, num_transactions: Option<u64>) -> Self {
+        let summary = checkpoint.inner().data();
+        Self {
+            sequence_number: summary.sequence_number,
+            digest: checkpoint.inner().digest().to_string(),
+            epoch: summary.epoch,
+            timestamp_ms: summary.timestamp_ms,
+            content_digest: summary.content_digest.to_string(),
+            previous_digest: summary.previous_digest.map(|d| d.to_string()),
+            network_total_transactions: summary.network_total_transactions,
+            num_transactions,
+            end_of_epoch: summary.end_of_epoch_data.is_some(),
+        }
+    }
+}
+
+/// A row of an exported table. CSV and parquet files are written with the row type's fixed
+/// schema, so every file exported from a table has the same columns and column types, however
+/// few rows it has.
+trait ExportRow: Serialize {
+    /// The columns of the row, in order, named after the fields the row serializes.
+    const COLUMNS: &'static [Column];
+}
+
+struct Column {
+    name: &'static str,
+    type_: ColumnType,
+    nullable: bool,
+}
+
+impl Column {
+    const fn required(name: &'static str, type_: ColumnType) -> Self {
+        Self {
+            name,
+            type_,
+            nullable: false,
+        }
+    }
+
+    const fn optional(name: &'static str, type_: ColumnType) -> Self {
+        Self {
+            name,
+            type_,
+            nullable: true,
+        }
+    }
+
+    /// The value of this column in a row, or an error if it does not have the column's type.
+    fn value<'v, T>(
+        &self,
+        value: &'v serde_json::Value,
+        typed: impl Fn(&'v serde_json::Value) -> Option<T>,
+    ) -> anyhow::Result<Option<T>> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        typed(value)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Unexpected value {value} in column {}", self.name))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ColumnType {
+    U64,
+    Bool,
+    Str,
+}
+
+type JsonRow = serde_json::Map<String, serde_json::Value>;
+
+/// Writes rows as newline delimited JSON, CSV, or parquet.
+struct RowWriter<R> {
+    output: RowOutput,
+    rows: usize,
+    _row: PhantomData<R>,
+}
+
+enum RowOutput {
+    Jsonl(Box<dyn Write>),
+    Csv(csv::Writer<Box<dyn Write>>),
+    Parquet {
+        writer: ArrowWriter<File>,
+        buffer: Vec<JsonRow>,
+    },
+}
+
+impl<R: ExportRow> RowWriter<R> {
+    fn new(format: ExportFormat, output: Option<PathBuf>) -> anyhow::Result<Self> {
+        let file = |path: &Path| {
+            File::create(path).with_context(|| format!("Cannot create {}", path.display()))
+        };
+        let out = |output: Option<PathBuf>| -> anyhow::Result<Box<dyn Write>> {
+            Ok(match output {
+                Some(path) => Box::new(BufWriter::new(file(&path)?)),
+                None => Box::new(BufWriter::new(std::io::stdout())),
+            })
+        };
+        let output = match format {
+            ExportFormat::Jsonl => RowOutput::Jsonl(out(output)?),
+            ExportFormat::Csv => {
+                let mut writer = WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(out(output)?);
+                writer.write_record(R::COLUMNS.iter().map(|c| c.name))?;
+                RowOutput::Csv(writer)
+            }
+            ExportFormat::Parquet => {
+                let Some(path) = output else {
+                    bail!("--output is required when exporting to parquet");
+                };
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                let schema = record_batch::<R>(&[])?.schema();
+                RowOutput::Parquet {
+                    writer: ArrowWriter::try_new(file(&path)?, schema, Some(properties))?,
+                    buffer: vec![],
+                }
+            }
+        };
+        Ok(Self {
+            output,
+            rows: 0,
+            _row: PhantomData,
+        })
+    }
+
+    fn write(&mut self, row: &R) -> anyhow::Result<()> {
+        let json_row = || -> anyhow::Result<JsonRow> {
+            let serde_json::Value::Object(row) = serde_json::to_value(row)? else {
+                bail!("Rows must serialize to JSON objects");
+            };
+            Ok(row)
+        };
+        match &mut self.output {
+            RowOutput::Jsonl(out) => {
+                serde_json::to_writer(&mut *out, row)?;
+                out.write_all(b"\n")?;
+            }
+            RowOutput::Csv(writer) => {
+                let row = json_row()?;
+                writer.write_record(R::COLUMNS.iter().map(|c| {
+                    match row.get(c.name).unwrap_or(&serde_json::Value::Null) {
+                        serde_json::Value::Null => String::new(),
+                        serde_json::Value::String(s) => s.clone(),
+                        v => v.to_string(),
+                    }
+                }))?;
+            }
+            RowOutput::Parquet { writer, buffer } => {
+                buffer.push(json_row()?);
+                if buffer.len() >= PARQUET_ROW_GROUP_SIZE {
+                    writer.write(&record_batch::<R>(&std::mem::take(buffer))?)?;
+                }
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Flush any buffered rows, and return the number of rows written.
+    fn finish(self) -> anyhow::Result<usize> {
+        match self.output {
+            RowOutput::Jsonl(mut out) => out.flush()?,
+            RowOutput::Csv(mut writer) => writer.flush()?,
+            RowOutput::Parquet { mut writer, buffer } => {
+                if !buffer.is_empty() {
+                    writer.write(&record_batch::<R>(&buffer)?)?;
+                }
+                writer.close()?;
+            }
+        }
+        Ok(self.rows)
+    }
+}
+
+/// Convert rows to a record batch with the schema of `R`, failing if a row does not match it.
+fn record_batch<R: ExportRow>(rows: &[JsonRow]) -> anyhow::Result<RecordBatch> {
+    let mut arrays = vec![];
+    for column in R::COLUMNS {
+        let values = rows
+            .iter()
+            .map(|row| row.get(column.name).unwrap_or(&serde_json::Value::Null));
+        let array: ArrayRef = match column.type_ {
+            ColumnType::U64 => Arc::new(
+                values
+                    .map(|v| column.value(v, serde_json::Value::as_u64))
+                    .collect::<anyhow::Result<UInt64Array>>()?,
+            ),
+            ColumnType::Bool => Arc::new(
+                values
+                    .map(|v| column.value(v, serde_json::Value::as_bool))
+                    .collect::<anyhow::Result<BooleanArray>>()?,
+            ),
+            ColumnType::Str => Arc::new(
+                values
+                    .map(|v| column.value(v, serde_json::Value::as_str))
+                    .collect::<anyhow::Result<StringArray>>()?,
+            ),
+        };
+        arrays.push((column.name, array, column.nullable));
+    }
+    Ok(RecordBatch::try_from_iter_with_nullable(arrays)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use sui_types::base_types::SuiAddress;
+
+    /// Create a database with a few live objects, returning their IDs in key order.
+    fn populate_objects(db_path: &Path) -> (AuthorityPerpetualTables, Vec<ObjectID>) {
+        let perpetual = AuthorityPerpetualTables::open(&db_path.join("store"), None);
+        let mut ids: Vec<_> = (0..3).map(|_| ObjectID::random()).collect();
+        ids.sort();
+        for id in &ids {
+            perpetual
+                .insert_object_test_only(Object::with_id_owner_for_testing(*id, SuiAddress::ZERO))
+                .unwrap();
+        }
+        (perpetual, ids)
+    }
+
+    fn export_objects_to(db_path: &Path, format: ExportFormat, output: &Path) -> usize {
+        export_table(
+            db_path,
+            ExportConfig {
+                table: ExportTable::Objects,
+                format,
+                range: None,
+                output: Some(output.to_path_buf()),
+                secondary_path: Some(db_path.join("secondary")),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn export_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_perpetual, ids) = populate_objects(dir.path());
+        let output = dir.path().join("objects.csv");
+        assert_eq!(export_objects_to(dir.path(), ExportFormat::Csv, &output), 3);
+
+        let mut reader = csv::Reader::from_path(&output).unwrap();
+        let header: Vec<_> = reader.headers().unwrap().iter().map(String::from).collect();
+        let columns: Vec<_> = ObjectRow::COLUMNS.iter().map(|c| c.name).collect();
+        assert_eq!(header, columns);
+
+        let records: Vec<_> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), ids.len());
+        for (record, id) in records.iter().zip(&ids) {
+            assert_eq!(&record[0], id.to_string());
+            assert_eq!(&record[2], "live");
+            assert_eq!(record.len(), columns.len());
+        }
+    }
+
+    #[test]
+    fn export_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_perpetual, ids) = populate_objects(dir.path());
+        let output = dir.path().join("objects.parquet");
+        assert_eq!(
+            export_objects_to(dir.path(), ExportFormat::Parquet, &output),
+            3
+        );
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&output).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(Result::unwrap).collect();
+        let expected = record_batch::<ObjectRow>(&[]).unwrap().schema();
+        assert!(batches
+            .iter()
+            .all(|b| b.schema().fields() == expected.fields()));
+
+        let object_ids: Vec<_> = batches
+            .iter()
+            .flat_map(|b| {
+                let column = b.column_by_name("object_id").unwrap();
+                let column = column.as_any().downcast_ref::<StringArray>().unwrap();
+                column
+                    .iter()
+                    .map(|id| id.unwrap().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let ids: Vec<_> = ids.iter().map(|id| id.to_string()).collect();
+        assert_eq!(object_ids, ids);
+    }
+
+    #[test]
+    fn fixed_parquet_schema() {
+        let json = |row: &ObjectRow| match serde_json::to_value(row).unwrap() {
+            serde_json::Value::Object(row) => row,
+            _ => unreachable!(),
+        };
+        let object = Object::with_id_owner_for_testing(ObjectID::random(), SuiAddress::ZERO);
+        let key = ObjectKey::from(object.compute_object_reference());
+
+        // Rows of tombstones only have the same column types as rows of live objects.
+        let live = record_batch::<ObjectRow>(&[json(&ObjectRow::live(&key, &object))]).unwrap();
+        let tombstones = record_batch::<ObjectRow>(&[
+            json(&ObjectRow::tombstone(&key, "deleted")),
+            json(&ObjectRow::tombstone(&key, "wrapped")),
+        ])
+        .unwrap();
+        assert_eq!(live.schema(), tombstones.schema());
+        assert_eq!(tombstones.num_rows(), 2);
+
+        // Rows that do not match the schema are rejected.
+        let mut bad = json(&ObjectRow::live(&key, &object));
+        bad.insert("version".to_string(), "one".into());
+        assert!(record_batch::<ObjectRow>(&[bad]).is_err());
+
+        let mut missing = json(&ObjectRow::live(&key, &object));
+        missing.remove("object_id");
+        assert!(record_batch::<ObjectRow>(&[missing]).is_err());
+    }
+
+    #[test]
+    fn parse_range() {
+        let range: ExportRange = "10..20".parse().unwrap();
+        assert_eq!(range.checkpoints().unwrap(), (10, 20));
+
+        let range: ExportRange = "10..".parse().unwrap();
+        assert_eq!(range.checkpoints().unwrap(), (10, u64::MAX));
+
+        let range: ExportRange = "..0x5".parse().unwrap();
+        assert_eq!(
+            range.object_ids().unwrap(),
+            (
+                ObjectID::ZERO,
+                Some(ObjectID::from_hex_literal("0x5").unwrap())
+            )
+        );
+
+        assert!("10".parse::<ExportRange>().is_err());
+        assert!("a..b"
+            .parse::<ExportRange>()
+            .unwrap()
+            .checkpoints()
+            .is_err());
+    }
+}
@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use self::consensus_dag::{inspect_consensus_dag, ConsensusDagOptions};
 use self::db_dump::{dump_table, duplicate_objects_summary, list_tables, table_summary, StoreName};
+use self::db_verify::{verify_db, VerifyConfig};
+use self::export::{export_table, ExportConfig, ExportFormat, ExportRange, ExportTable};
 use self::index_search::{search_index, SearchRange};
 use crate::db_tool::db_dump::{compact, print_table_metadata, prune_checkpoints, prune_objects};
 use anyhow::{anyhow, bail};
@@ -17,7 +20,10 @@ use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::{CheckpointDigest, CheckpointSequenceNumber};
 use sui_types::storage::ObjectStore;
 use typed_store::rocks::MetricConf;
+mod consensus_dag;
 pub mod db_dump;
+mod db_verify;
+mod export;
 mod index_search;
 
 #[derive(Parser)]
@@ -25,6 +31,7 @@ mod index_search;
 pub enum DbToolCommand {
     ListTables,
     Dump(Options),
+    Export(ExportOptions),
     IndexSearchKeyRange(IndexSearchKeyRangeOptions),
     IndexSearchCount(IndexSearchCountOptions),
     TableSummary(Options),
@@ -42,6 +49,8 @@ pub enum DbToolCommand {
     PruneObjects,
     PruneCheckpoints,
     SetCheckpointWatermark(SetCheckpointWatermarkOptions),
+    Verify(VerifyOptions),
+    ConsensusDag(ConsensusDagOptions),
 }
 
 #[derive(Parser)]
@@ -90,6 +99,52 @@ pub struct Options {
     epoch: Option<EpochId>,
 }
 
+/// Export the decoded rows of a table, e.g. for incident analysis. The database is opened as a
+/// secondary instance, so this can be run against the database of a live node.
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct ExportOptions {
+    /// The table to export
+    #[arg(long = "table", short = 't', value_enum)]
+    table: ExportTable,
+    /// The output format
+    #[arg(long = "format", short = 'f', value_enum, default_value_t = ExportFormat::Jsonl)]
+    format: ExportFormat,
+    /// Only export rows in the range START..END, with END excluded and either bound optional.
+    /// Checkpoints, transactions and effects are selected by checkpoint sequence number, and
+    /// objects by object ID.
+    #[arg(long = "range", short = 'r')]
+    range: Option<ExportRange>,
+    /// The file to write to. Defaults to stdout for jsonl and csv, and is required for parquet.
+    #[arg(long = "output", short = 'o')]
+    output: Option<PathBuf>,
+    /// Where to keep the secondary instance's files. Defaults to a temporary directory.
+    #[arg(long = "secondary-path")]
+    secondary_path: Option<PathBuf>,
+}
+
+/// Check that the checkpoint, transaction, effects and object tables are consistent with each
+/// other, e.g. after a crash or a disk failure.
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct VerifyOptions {
+    /// The first checkpoint to verify. Defaults to the lowest checkpoint that has not been pruned.
+    #[arg(long = "start-checkpoint")]
+    start_checkpoint: Option<CheckpointSequenceNumber>,
+    /// The last checkpoint to verify. Defaults to the highest executed checkpoint.
+    #[arg(long = "end-checkpoint")]
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    /// Do not check the live object set against the root state hash of the epoch, which reads
+    /// every live object.
+    #[arg(long = "skip-live-object-set")]
+    skip_live_object_set: bool,
+    /// Rewind checkpoint execution to before the first checkpoint with a missing transaction or
+    /// effects, so that the node re-executes it on restart. Other inconsistencies are only
+    /// reported.
+    #[arg(long = "repair")]
+    repair: bool,
+}
+
 #[derive(Parser)]
 #[command(rename_all = "kebab-case")]
 pub struct PrintConsensusCommitOptions {
@@ -189,6 +244,20 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             d.page_size,
             d.page_number,
         ),
+        DbToolCommand::Export(e) => {
+            let rows = export_table(
+                &db_path,
+                ExportConfig {
+                    table: e.table,
+                    format: e.format,
+                    range: e.range,
+                    output: e.output,
+                    secondary_path: e.secondary_path,
+                },
+            )?;
+            eprintln!("Exported {} rows", rows);
+            Ok(())
+        }
         DbToolCommand::TableSummary(d) => {
             print_db_table_summary(d.store_name, d.epoch, db_path, &d.table_name)
         }
@@ -234,6 +303,33 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             Ok(())
         }
         DbToolCommand::SetCheckpointWatermark(d) => set_checkpoint_watermark(&db_path, d),
+        DbToolCommand::ConsensusDag(opts) => inspect_consensus_dag(&db_path, opts),
+        DbToolCommand::Verify(v) => {
+            let report = verify_db(
+                &db_path,
+                VerifyConfig {
+                    start_checkpoint: v.start_checkpoint,
+                    end_checkpoint: v.end_checkpoint,
+                    skip_live_object_set: v.skip_live_object_set,
+                    repair: v.repair,
+                },
+            )?;
+            for inconsistency in &report.inconsistencies {
+                println!("{}", inconsistency);
+            }
+            println!(
+                "Verified {} checkpoints, {} transactions and {} objects",
+                report.checkpoints, report.transactions, report.objects
+            );
+            println!("Live object set: {}", report.live_object_set);
+            if let Some(checkpoint) = report.rewound_to {
+                println!("Rewound checkpoint execution to checkpoint {}", checkpoint);
+            }
+            if !report.inconsistencies.is_empty() {
+                bail!("Found {} inconsistencies", report.inconsistencies.len());
+            }
+            Ok(())
+        }
     }
 }
 
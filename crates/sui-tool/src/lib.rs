@@ -53,6 +53,7 @@ use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::AuthorityStore;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::epoch::committee_store::CommitteeStore;
+use sui_core::state_accumulator::StateAccumulator;
 use sui_core::storage::RocksDbStore;
 use sui_snapshot::reader::StateSnapshotReaderV1;
 use sui_snapshot::setup_db_state;
@@ -946,47 +947,86 @@ pub async fn download_formal_snapshot(
         verify != SnapshotVerifyMode::None,
         all_checkpoints,
     );
-    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
     let perpetual_db_clone = perpetual_db.clone();
     let snapshot_dir = path.parent().unwrap().join("snapshot");
     if snapshot_dir.exists() {
         fs::remove_dir_all(snapshot_dir.clone())?;
     }
-    let snapshot_dir_clone = snapshot_dir.clone();
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(snapshot_dir.to_path_buf()),
+        ..Default::default()
+    };
+    let mut reader = StateSnapshotReaderV1::new(
+        epoch,
+        &snapshot_store_config,
+        &local_store_config,
+        usize::MAX,
+        NonZeroUsize::new(num_parallel_downloads).unwrap(),
+        m.clone(),
+    )
+    .await
+    .map_err(|err| anyhow!("Failed to create reader: {}", err))?;
+
+    // A delta snapshot is restored on top of the full snapshot it is based on. The objects it
+    // removes from the base are subtracted from the root accumulator up front, as the
+    // accumulator is order independent.
+    let mut root_accumulator = Accumulator::default();
+    let mut num_removed_objects = 0;
+    let base_reader = match reader.base_epoch() {
+        Some(base_epoch) => {
+            m.println(format!(
+                "Snapshot of epoch {} is a delta of the snapshot of epoch {}, restoring both",
+                epoch, base_epoch
+            ))?;
+            for object_ref in reader.removed_ref_iter()? {
+                StateAccumulator::remove_live_object_ref(&mut root_accumulator, &object_ref);
+                num_removed_objects += 1;
+            }
+            let base_reader = StateSnapshotReaderV1::new(
+                base_epoch,
+                &snapshot_store_config,
+                &local_store_config,
+                usize::MAX,
+                NonZeroUsize::new(num_parallel_downloads).unwrap(),
+                m.clone(),
+            )
+            .await
+            .map_err(|err| anyhow!("Failed to create reader for base snapshot: {}", err))?;
+            Some(base_reader)
+        }
+        None => None,
+    };
 
     // TODO if verify is false, we should skip generating these and
     // not pass in a channel to the reader
     let (sender, mut receiver) = mpsc::channel(num_parallel_downloads);
-    let m_clone = m.clone();
 
     let snapshot_handle = tokio::spawn(async move {
-        let local_store_config = ObjectStoreConfig {
-            object_store: Some(ObjectStoreType::File),
-            directory: Some(snapshot_dir_clone.to_path_buf()),
-            ..Default::default()
-        };
-        let mut reader = StateSnapshotReaderV1::new(
-            epoch,
-            &snapshot_store_config,
-            &local_store_config,
-            usize::MAX,
-            NonZeroUsize::new(num_parallel_downloads).unwrap(),
-            m_clone,
-        )
-        .await
-        .unwrap_or_else(|err| panic!("Failed to create reader: {}", err));
+        if let Some(mut base_reader) = base_reader {
+            let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+            base_reader
+                .read(
+                    &perpetual_db_clone,
+                    abort_registration,
+                    Some(sender.clone()),
+                )
+                .await
+                .unwrap_or_else(|err| panic!("Failed during read of base snapshot: {}", err));
+        }
+        let (_abort_handle, abort_registration) = AbortHandle::new_pair();
         reader
             .read(&perpetual_db_clone, abort_registration, Some(sender))
             .await
             .unwrap_or_else(|err| panic!("Failed during read: {}", err));
         Ok::<(), anyhow::Error>(())
     });
-    let mut root_accumulator = Accumulator::default();
     let mut num_live_objects = 0;
     while let Some((partial_acc, num_objects)) = receiver.recv().await {
         num_live_objects += num_objects;
         root_accumulator.union(&partial_acc);
     }
+    num_live_objects -= num_removed_objects;
     summaries_handle
         .await
         .expect("Task join failed")
@@ -1,14 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashSet;
+
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
-use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_config::transaction_deny_config::{MoveQualifiedName, TransactionDenyConfig};
 use sui_types::{
-    base_types::ObjectRef,
+    base_types::{ObjectID, ObjectRef},
     error::{SuiError, SuiResult, UserInputError},
     signature::GenericSignature,
     storage::BackingPackageStore,
     transaction::{Command, InputObjectKind, TransactionData, TransactionDataAPI},
+    TypeTag,
 };
 macro_rules! deny_if_true {
     ($cond:expr, $msg:expr) => {
@@ -40,6 +43,8 @@ pub fn check_transaction_for_signing(
 
     check_package_dependencies(filter_config, tx_data, package_store)?;
 
+    check_move_calls(filter_config, tx_data)?;
+
     check_receiving_objects(filter_config, receiving_objects)?;
 
     Ok(())
@@ -209,3 +214,82 @@ fn check_package_dependencies(
     }
     Ok(())
 }
+
+fn check_move_calls(filter_config: &TransactionDenyConfig, tx_data: &TransactionData) -> SuiResult {
+    let function_deny_set = filter_config.get_function_deny_set();
+    let type_deny_set = filter_config.get_type_deny_set();
+    if function_deny_set.is_empty() && type_deny_set.is_empty() {
+        return Ok(());
+    }
+    for command in tx_data.kind().iter_commands() {
+        let type_arguments = match command {
+            Command::MoveCall(call) => {
+                let function = MoveQualifiedName::new(
+                    call.package,
+                    call.module.as_str(),
+                    call.function.as_str(),
+                );
+                deny_if_true!(
+                    function_deny_set.contains(&function),
+                    format!("Access to function {} is temporarily disabled", function)
+                );
+                call.type_arguments.as_slice()
+            }
+            Command::MakeMoveVec(Some(type_argument), _) => std::slice::from_ref(type_argument),
+            Command::MakeMoveVec(None, _)
+            | Command::TransferObjects(..)
+            | Command::SplitCoins(..)
+            | Command::MergeCoins(..)
+            | Command::Publish(..)
+            | Command::Upgrade(..) => continue,
+        };
+        if type_deny_set.is_empty() {
+            continue;
+        }
+        let denied_type = type_arguments
+            .iter()
+            .find_map(|type_argument| find_denied_type(type_deny_set, type_argument));
+        if let Some(denied_type) = denied_type {
+            return Err(SuiError::UserInputError {
+                error: UserInputError::TransactionDenied {
+                    error: format!("Usage of type {} is temporarily disabled", denied_type),
+                },
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the first struct type mentioned in `type_tag` (including itself) that is in
+/// `deny_set`, if any.
+fn find_denied_type(
+    deny_set: &HashSet<MoveQualifiedName>,
+    type_tag: &TypeTag,
+) -> Option<MoveQualifiedName> {
+    match type_tag {
+        TypeTag::Vector(inner) => find_denied_type(deny_set, inner),
+        TypeTag::Struct(struct_tag) => {
+            let name = MoveQualifiedName::new(
+                ObjectID::from(struct_tag.address),
+                struct_tag.module.as_str(),
+                struct_tag.name.as_str(),
+            );
+            if deny_set.contains(&name) {
+                return Some(name);
+            }
+            struct_tag
+                .type_params
+                .iter()
+                .find_map(|param| find_denied_type(deny_set, param))
+        }
+        TypeTag::Bool
+        | TypeTag::U8
+        | TypeTag::U16
+        | TypeTag::U32
+        | TypeTag::U64
+        | TypeTag::U128
+        | TypeTag::U256
+        | TypeTag::Address
+        | TypeTag::Signer => None,
+    }
+}
@@ -444,10 +444,10 @@ pub struct MoveTypeBridgeRoute {
 /// Rust version of the Move limiter::TransferRecord type.
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct MoveTypeBridgeTransferRecord {
-    hour_head: u64,
-    hour_tail: u64,
-    per_hour_amounts: Vec<u64>,
-    total_amount: u64,
+    pub hour_head: u64,
+    pub hour_tail: u64,
+    pub per_hour_amounts: Vec<u64>,
+    pub total_amount: u64,
 }
 
 /// Rust version of the Move message::BridgeMessage type.
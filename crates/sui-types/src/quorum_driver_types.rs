@@ -7,6 +7,7 @@ use std::collections::BTreeMap;
 use crate::base_types::{AuthorityName, EpochId, ObjectRef, TransactionDigest};
 use crate::committee::StakeUnit;
 use crate::crypto::{AuthorityStrongQuorumSignInfo, ConciseAuthorityPublicKeyBytes};
+use crate::digests::TransactionEffectsDigest;
 use crate::effects::{
     CertifiedTransactionEffects, TransactionEffects, TransactionEvents,
     VerifiedCertifiedTransactionEffects,
@@ -87,6 +88,23 @@ pub enum EffectsFinalityInfo {
     Checkpointed(EpochId, CheckpointSequenceNumber),
 }
 
+/// Where a transaction submitted through TransactionOrchestrator is, as seen by the node it
+/// was submitted to.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TransactionSubmissionStatus {
+    /// Submitted to Quorum Driver, which has not yet formed an effects certificate.
+    Pending,
+    /// A quorum of validators certified the effects, and this node has not executed the
+    /// transaction yet.
+    Certified(TransactionEffectsDigest),
+    /// This node executed the transaction, and has not executed a checkpoint containing it yet.
+    ExecutedLocally(TransactionEffectsDigest),
+    /// This node executed the checkpoint containing the transaction.
+    Checkpointed(TransactionEffectsDigest, EpochId, CheckpointSequenceNumber),
+    /// Quorum Driver failed to finalize the transaction.
+    Failed(QuorumDriverError),
+}
+
 /// When requested to execute a transaction with WaitForLocalExecution,
 /// TransactionOrchestrator attempts to execute this transaction locally
 /// after it is finalized. This value represents whether the transaction
@@ -294,6 +294,18 @@ impl From<TransactionDigest> for crate::digests::TransactionDigest {
     }
 }
 
+impl From<crate::digests::TransactionEffectsDigest> for TransactionEffectsDigest {
+    fn from(value: crate::digests::TransactionEffectsDigest) -> Self {
+        Self::new(value.into_inner())
+    }
+}
+
+impl From<TransactionEffectsDigest> for crate::digests::TransactionEffectsDigest {
+    fn from(value: TransactionEffectsDigest) -> Self {
+        Self::new(value.into_inner())
+    }
+}
+
 impl From<crate::digests::ObjectDigest> for ObjectDigest {
     fn from(value: crate::digests::ObjectDigest) -> Self {
         Self::new(value.into_inner())
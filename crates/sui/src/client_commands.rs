@@ -742,6 +742,7 @@ impl SuiClientCommands {
                     end,
                     terminate_early,
                     max_tasks: 16,
+                    archive: Default::default(),
                 };
                 let rpc = context.config.get_active_env()?.rpc.clone();
                 let _command_result =
@@ -38,6 +38,10 @@ pub const DRY_RUN: &str = "dry-run";
 pub const SERIALIZE_UNSIGNED: &str = "serialize-unsigned-transaction";
 pub const SERIALIZE_SIGNED: &str = "serialize-signed-transaction";
 
+// Script directives, expanded before the commands are tokenized
+pub const FILE: &str = "file";
+pub const PARAM: &str = "param";
+
 // Types
 pub const U8: &str = "u8";
 pub const U16: &str = "u16";
@@ -6,11 +6,12 @@ use crate::{
     client_ptb::{
         ast::{ParsedProgram, Program},
         builder::PTBBuilder,
-        error::{build_error_reports, PTBError},
+        error::{build_error_reports, PTBError, Span},
+        script::ScriptExpander,
         token::{Lexeme, Token},
     },
     displays::Pretty,
-    sp,
+    err, sp,
 };
 
 use super::{ast::ProgramMetadata, lexer::Lexer, parser::ProgramParser};
@@ -18,6 +19,7 @@ use anyhow::{anyhow, ensure, Error};
 use clap::{arg, Args, ValueHint};
 use move_core_types::account_address::AccountAddress;
 use serde::Serialize;
+use std::path::Path;
 use sui_json_rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::{wallet_context::WalletContext, SuiClient};
@@ -53,11 +55,9 @@ impl PTB {
             ptb_description().print_help().unwrap();
             return Ok(());
         }
-        let source_string = to_source_string(self.args.clone());
-
         // Tokenize once to detect help flags
         let tokens = self.args.iter().map(|s| s.as_str());
-        for sp!(_, lexeme) in Lexer::new(tokens.clone()).into_iter().flatten() {
+        for sp!(_, lexeme) in Lexer::new(tokens).into_iter().flatten() {
             match lexeme {
                 Lexeme(Token::Command, "help") => return Ok(ptb_description().print_long_help()?),
                 Lexeme(Token::Flag, "h") => return Ok(ptb_description().print_help()?),
@@ -66,11 +66,11 @@ impl PTB {
             }
         }
 
-        // Tokenize and parse to get the program
-        let (program, program_metadata) = match ProgramParser::new(tokens)
-            .map_err(|e| vec![e])
-            .and_then(|parser| parser.parse())
-        {
+        // Expand script files and parameters, then tokenize and parse to get the program
+        let cwd = std::env::current_dir()
+            .map_err(|e| anyhow!("Cannot read the current working directory: {e}"))?;
+        let (source_string, parsed) = Self::parse_ptb_commands(self.args, &cwd);
+        let (program, program_metadata) = match parsed {
             Err(errors) => {
                 let suffix = if errors.len() > 1 { "s" } else { "" };
                 let rendered = build_error_reports(&source_string, errors);
@@ -234,11 +234,25 @@ impl PTB {
         builder.build(program).await
     }
 
-    /// Exposed for testing
-    pub fn parse_ptb_commands(args: Vec<String>) -> Result<ParsedProgram, Vec<PTBError>> {
-        ProgramParser::new(args.iter().map(|s| s.as_str()))
+    /// Expand script files and parameters in `args`, resolving file paths relative to `dir`, and
+    /// parse the resulting commands. Also returns the source string of the expanded commands, which
+    /// the spans of any errors refer to. Exposed for testing
+    pub fn parse_ptb_commands(
+        args: Vec<String>,
+        dir: &Path,
+    ) -> (String, Result<ParsedProgram, Vec<PTBError>>) {
+        let args = match ScriptExpander::new().expand(args.clone(), dir) {
+            Ok(args) => args,
+            Err(e) => {
+                let span = Span { start: 0, end: 0 };
+                return (to_source_string(args), Err(vec![err!(span, "{e:#}")]));
+            }
+        };
+
+        let parsed = ProgramParser::new(args.iter().map(|s| s.as_str()))
             .map_err(|e| vec![e])
-            .and_then(|parser| parser.parse())
+            .and_then(|parser| parser.parse());
+        (to_source_string(args), parsed)
     }
 }
 
@@ -295,6 +309,19 @@ pub fn ptb_description() -> clap::Command {
             --"dry-run"
             "Perform a dry run of the PTB instead of executing it."
         ))
+        .arg(arg!(
+            --"file" <PATH>
+            "Read PTB commands from a file. Can be used more than once, and from within files."
+        )
+        .long_help(
+            "Read PTB commands from a file. The file's contents are split into shell tokens, and \
+            lines starting with # are comments. Files can include other files, with relative \
+            paths resolved from the including file's directory.\
+            \n\nExamples:\
+            \n --file transfer.ptb\
+            \n --param AMOUNT 1000 --file split.ptb --gas-budget 50000000"
+        )
+        .value_hint(ValueHint::FilePath))
         .arg(arg!(
             --"gas-coin" <ID> ...
             "The object ID of the gas coin to use. If not specified, it will try to use the first \
@@ -384,6 +411,20 @@ pub fn ptb_description() -> clap::Command {
             --"upgrade" <MOVE_PACKAGE_PATH>
             "Upgrade the Move package. It takes as input the folder where the package exists."
         ).value_hint(ValueHint::DirPath))
+        .arg(arg!(
+            --"param" <PARAM>
+            "Bind a value to a parameter name, to be substituted for $NAME or ${NAME} in the PTB."
+        )
+        .long_help(
+            "Bind a value to a parameter name, to be substituted for $NAME or ${NAME} in the PTB \
+            and any files it includes. Parameters that are not bound with --param are read from \
+            the environment. Values bound on the command line take precedence over values bound \
+            in files, so files can provide defaults. Use $$ for a literal $.\
+            \n\nExamples:\
+            \n --param AMOUNT 1000 --split-coins gas [$AMOUNT]\
+            \n --param RECIPIENT @0x42 --file transfer.ptb"
+        )
+        .value_names(["NAME", "VALUE"]))
         .arg(arg!(
             --"preview"
             "Preview the list of PTB transactions instead of executing them."
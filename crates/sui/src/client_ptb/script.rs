@@ -0,0 +1,296 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Error};
+
+use super::ast::{FILE, PARAM};
+
+/// Expands PTB script directives in a list of shell tokens, before they are handed to the lexer:
+///
+/// - `--file <PATH>` is replaced by the shell tokens found in the file at `PATH`. Paths are
+///   resolved relative to the file that includes them (or the working directory for the command
+///   line). Files follow the shell's quoting rules, and lines starting with `#` are comments.
+/// - `--param <NAME> <VALUE>` binds `NAME` to `VALUE`. Bindings from the command line take
+///   precedence over bindings from files, so files can declare defaults.
+/// - `$NAME` and `${NAME}` are substituted with the value bound to `NAME`, falling back to the
+///   environment variable `NAME`. `$$` is a literal `$`. References inside string literals are
+///   left untouched.
+pub struct ScriptExpander {
+    params: BTreeMap<String, String>,
+    /// Files currently being included, used to detect cyclic includes.
+    include_stack: Vec<PathBuf>,
+}
+
+impl ScriptExpander {
+    pub fn new() -> Self {
+        Self {
+            params: BTreeMap::new(),
+            include_stack: vec![],
+        }
+    }
+
+    /// Expand the shell tokens passed to `sui client ptb`, resolving the paths of files included
+    /// from the command line relative to `dir`.
+    pub fn expand(mut self, args: Vec<String>, dir: &Path) -> Result<Vec<String>, Error> {
+        let args = self.bind_params(args)?;
+        self.expand_tokens(args, dir)
+    }
+
+    /// Remove `--param` directives from `tokens`, binding any name that is not already bound.
+    fn bind_params(&mut self, tokens: Vec<String>) -> Result<Vec<String>, Error> {
+        let mut rest = vec![];
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            // The path following `--file` is an operand, even if it looks like a directive.
+            if token == format!("--{FILE}") {
+                rest.push(token);
+                rest.extend(tokens.next());
+                continue;
+            }
+
+            if token != format!("--{PARAM}") {
+                rest.push(token);
+                continue;
+            }
+
+            let (Some(name), Some(value)) = (tokens.next(), tokens.next()) else {
+                bail!("Expected a name and a value after --{PARAM}");
+            };
+            ensure!(
+                is_param_name(&name),
+                "Invalid parameter name '{name}'. Parameter names must start with a letter or \
+                 '_' and only contain alphanumeric characters or '_'"
+            );
+            self.params.entry(name).or_insert(value);
+        }
+        Ok(rest)
+    }
+
+    fn expand_tokens(&mut self, tokens: Vec<String>, dir: &Path) -> Result<Vec<String>, Error> {
+        let mut expanded = vec![];
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            if token != format!("--{FILE}") {
+                expanded.push(self.substitute(&token)?);
+                continue;
+            }
+
+            let Some(path) = tokens.next() else {
+                bail!("Expected a file path after --{FILE}");
+            };
+            let path = dir.join(self.substitute(&path)?);
+            expanded.extend(self.include(&path)?);
+        }
+        Ok(expanded)
+    }
+
+    fn include(&mut self, path: &Path) -> Result<Vec<String>, Error> {
+        let canonical = path
+            .canonicalize()
+            .with_context(|| format!("Cannot find PTB file {}", path.display()))?;
+        ensure!(
+            !self.include_stack.contains(&canonical),
+            "PTB file {} includes itself",
+            path.display()
+        );
+
+        let contents = std::fs::read_to_string(&canonical)
+            .with_context(|| format!("Cannot read PTB file {}", path.display()))?;
+        let tokens = shlex::split(&contents)
+            .ok_or_else(|| anyhow!("Unterminated quote in PTB file {}", path.display()))?;
+        let tokens = self.bind_params(tokens)?;
+
+        let dir = canonical.parent().unwrap_or(Path::new("/")).to_path_buf();
+        self.include_stack.push(canonical);
+        let expanded = self.expand_tokens(tokens, &dir);
+        self.include_stack.pop();
+        expanded
+    }
+
+    /// Replace every parameter reference in `token` with its value, outside of string literals.
+    fn substitute(&self, token: &str) -> Result<String, Error> {
+        let mut result = String::with_capacity(token.len());
+        let mut quote = None;
+        let mut escaped = false;
+        let mut rest = token;
+        while let Some(c) = rest.chars().next() {
+            if c != '$' || quote.is_some() {
+                // Track string literals the same way the lexer does, so that their contents are
+                // passed through verbatim.
+                match quote {
+                    _ if escaped => escaped = false,
+                    Some(_) if c == '\\' => escaped = true,
+                    Some(q) if c == q => quote = None,
+                    None if c == '"' || c == '\'' => quote = Some(c),
+                    _ => (),
+                }
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            rest = &rest[1..];
+
+            if let Some(tail) = rest.strip_prefix('$') {
+                result.push('$');
+                rest = tail;
+                continue;
+            }
+
+            let name = if let Some(braced) = rest.strip_prefix('{') {
+                let Some(end) = braced.find('}') else {
+                    bail!("Unterminated parameter reference in '{token}'");
+                };
+                rest = &braced[end + 1..];
+                &braced[..end]
+            } else {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                let name = &rest[..end];
+                rest = &rest[end..];
+                name
+            };
+
+            ensure!(
+                is_param_name(name),
+                "Invalid parameter reference '${name}' in '{token}'"
+            );
+            result.push_str(&self.lookup(name)?);
+        }
+        Ok(result)
+    }
+
+    fn lookup(&self, name: &str) -> Result<String, Error> {
+        if let Some(value) = self.params.get(name) {
+            return Ok(value.clone());
+        }
+
+        std::env::var(name).map_err(|_| {
+            anyhow!(
+                "Parameter '{name}' is not bound. Provide it with --{PARAM} {name} <VALUE> or set \
+                 the environment variable {name}"
+            )
+        })
+    }
+}
+
+impl Default for ScriptExpander {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_param_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        shlex::split(s).unwrap()
+    }
+
+    fn expand_in(dir: &Path, s: &str) -> Result<Vec<String>, Error> {
+        ScriptExpander::new().expand(args(s), dir)
+    }
+
+    #[test]
+    fn substitute_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let expanded = expand_in(
+            dir.path(),
+            "--split-coins gas [$AMOUNT, ${AMOUNT}0] --param AMOUNT 100 --assign price$$",
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            args("--split-coins gas [100, 1000] --assign price$")
+        );
+    }
+
+    #[test]
+    fn quoted_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let expanded = expand_in(
+            dir.path(),
+            r#"--make-move-vec <vector<u8>> ['"$AMOUNT"', "'\'$AMOUNT'", $AMOUNT] --param AMOUNT 1"#,
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            args(r#"--make-move-vec <vector<u8>> ['"$AMOUNT"', "'\'$AMOUNT'", 1]"#)
+        );
+    }
+
+    #[test]
+    fn directive_operands() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("--param"), "--assign x $VALUE").unwrap();
+
+        // The value bound by `--param` and the path included by `--file` are never directives.
+        let expanded = expand_in(dir.path(), "--param VALUE --file --file --param").unwrap();
+        assert_eq!(expanded, args("--assign x --file"));
+    }
+
+    #[test]
+    fn unbound_param() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = expand_in(dir.path(), "--split-coins gas [$PTB_TEST_UNBOUND_PARAM]");
+        assert!(err.is_err());
+
+        let err = expand_in(dir.path(), "--split-coins gas [${AMOUNT]");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn include_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("lib")).unwrap();
+        std::fs::write(
+            dir.path().join("lib/split.ptb"),
+            "# Split and bind the result\n--param AMOUNT 5\n--split-coins gas [$AMOUNT]\n--assign coins\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("main.ptb"),
+            "--file lib/split.ptb\n--transfer-objects [coins.0] @$RECIPIENT # send it\n",
+        )
+        .unwrap();
+
+        let expanded = expand_in(
+            dir.path(),
+            "--param RECIPIENT 0x6 --param AMOUNT 10 --file main.ptb --gas-budget 1000",
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            args(
+                "--split-coins gas [10] --assign coins --transfer-objects [coins.0] @0x6 \
+                 --gas-budget 1000"
+            )
+        );
+
+        // Defaults declared in files apply when the parameter is not bound on the command line.
+        let expanded = expand_in(dir.path(), "--param RECIPIENT 0x6 --file main.ptb").unwrap();
+        assert_eq!(expanded[2], "[5]");
+    }
+
+    #[test]
+    fn cyclic_include() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.ptb"), "--file b.ptb").unwrap();
+        std::fs::write(dir.path().join("b.ptb"), "--file a.ptb").unwrap();
+        assert!(expand_in(dir.path(), "--file a.ptb").is_err());
+    }
+}
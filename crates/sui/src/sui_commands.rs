@@ -765,6 +765,7 @@ async fn start(
             port: faucet_address.port(),
             num_coins: DEFAULT_FAUCET_NUM_COINS,
             amount: DEFAULT_FAUCET_MIST_AMOUNT,
+            write_ahead_log: config_dir.join("faucet.wal"),
             ..Default::default()
         };
 
@@ -790,20 +791,16 @@ async fn start(
             .save()
             .unwrap();
         }
-        let faucet_wal = config_dir.join("faucet.wal");
         let simple_faucet = SimpleFaucet::new(
             create_wallet_context(config.wallet_client_timeout_secs, config_dir)?,
             &prometheus_registry,
-            faucet_wal.as_path(),
+            &config.write_ahead_log,
             config.clone(),
         )
         .await
         .unwrap();
 
-        let app_state = Arc::new(AppState {
-            faucet: simple_faucet,
-            config,
-        });
+        let app_state = Arc::new(AppState::new(simple_faucet, config));
 
         start_faucet(app_state, CONCURRENCY_LIMIT, &prometheus_registry).await?;
     }
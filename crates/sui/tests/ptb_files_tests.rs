@@ -12,7 +12,7 @@ const TEST_DIR: &str = "tests";
 #[cfg(not(msim))]
 #[tokio::main]
 async fn test_ptb_files(path: &Path) -> datatest_stable::Result<()> {
-    use sui::client_ptb::ptb::{to_source_string, PTB};
+    use sui::client_ptb::ptb::PTB;
     use sui::client_ptb::{error::build_error_reports, ptb::PTBPreview};
     use test_cluster::TestClusterBuilder;
 
@@ -28,10 +28,9 @@ async fn test_ptb_files(path: &Path) -> datatest_stable::Result<()> {
     let fname = || path.file_name().unwrap().to_string_lossy().to_string();
     let file_contents = std::fs::read_to_string(path).unwrap();
     let shlexed = shlex::split(&file_contents).unwrap();
-    let file_contents = to_source_string(shlexed.clone());
 
-    // Parsing
-    let program = PTB::parse_ptb_commands(shlexed);
+    // Parsing, with files included relative to the PTB file. Errors refer to the expanded commands.
+    let (file_contents, program) = PTB::parse_ptb_commands(shlexed, path.parent().unwrap());
     let (program, program_meta) = match program {
         Ok(program) => program,
         Err(errors) => {